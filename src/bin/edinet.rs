@@ -4,7 +4,7 @@ use anyhow::Result;
 use tracing::{info, error};
 
 // Reference the main library crate
-use fast10k::{edinet_indexer, storage, models, downloader, config::Config, edinet::reader};
+use fast10k::{edinet_indexer, storage, style, models, downloader, config::Config, edinet::{reader, EdinetIndexFilter}, edinet_tui::ui::truncate_string};
 
 #[derive(Parser)]
 #[command(name = "edinet")]
@@ -13,6 +13,10 @@ use fast10k::{edinet_indexer, storage, models, downloader, config::Config, edine
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Disable colored output (also off with NO_COLOR set or when output is not a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
 }
 
 #[derive(Subcommand)]
@@ -24,23 +28,39 @@ pub enum Commands {
     },
     /// Search for documents
     Search {
-        /// Company ticker symbol
+        /// Company ticker symbol or fund code (e.g. G01234)
         #[arg(long)]
         sym: String,
         
         /// Output format: table (human-readable) or tsv (tab-separated)
         #[arg(long, default_value = "table")]
         format: String,
+        
+        /// Hide filings superseded by a later amendment
+        #[arg(long)]
+        latest_only: bool,
+
+        /// Include withdrawn filings, which are hidden by default
+        #[arg(long)]
+        include_withdrawn: bool,
     },
     /// Download documents
     Download {
-        /// Company ticker symbol
+        /// Company ticker symbol or fund code (e.g. G01234)
         #[arg(long)]
         sym: String,
 
-        /// Maximum number of documents to download
+        /// Maximum number of documents to download (0 for all)
         #[arg(long, default_value = "5")]
         limit: usize,
+
+        /// Print the resolved EDINET code and each download request (API key redacted) without downloading
+        #[arg(long, alias = "print-url")]
+        dry_run: bool,
+
+        /// Also download each document's attachments as `{doc_id}-{date}-attachments.zip`
+        #[arg(long)]
+        attachments: bool,
     },
     /// Load static EDINET data from CSV
     LoadStatic {
@@ -53,7 +73,7 @@ pub enum Commands {
         /// Search query (company name, symbol, or EDINET code)
         query: String,
         
-        /// Maximum number of results
+        /// Maximum number of results (0 for all)
         #[arg(long, default_value = "20")]
         limit: usize,
     },
@@ -78,7 +98,16 @@ pub enum IndexCommands {
     /// Show index statistics
     Stats,
     /// Update EDINET index from last date to current date
-    Update,
+    Update {
+        /// Fetch only dates since the last successful index run instead of the last 7 days
+        #[arg(long)]
+        since_last_run: bool,
+    },
+    /// Index EDINET ZIP files already on disk, e.g. shared by a colleague, without API access
+    Import {
+        /// ZIP file or directory searched recursively for ZIP files
+        path: String,
+    },
     /// Build EDINET index from/to date
     Build {
         /// Start date (YYYY-MM-DD)
@@ -88,6 +117,26 @@ pub enum IndexCommands {
         /// End date (YYYY-MM-DD)
         #[arg(long)]
         to: NaiveDate,
+
+        /// Only index these docTypeCodes, comma separated (e.g. 120,140)
+        #[arg(long)]
+        doc_types: Option<String>,
+
+        /// Only index these formCodes, comma separated (e.g. 030000)
+        #[arg(long)]
+        form_codes: Option<String>,
+
+        /// Skip documents without XBRL data
+        #[arg(long)]
+        xbrl_only: bool,
+
+        /// Also fetch Saturdays and Sundays
+        #[arg(long)]
+        include_weekends: bool,
+
+        /// Skip Japanese national holidays and the year-end market closure
+        #[arg(long)]
+        skip_holidays: bool,
     },
 }
 
@@ -98,10 +147,15 @@ async fn main() -> Result<()> {
         std::env::set_var("RUST_LOG", "edinet=info,fast10k=info");
     }
     
+    let cli = Cli::parse();
+
     // Initialize logging
-    tracing_subscriber::fmt::init();
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_ansi(style::should_color(cli.no_color, &std::io::stderr()))
+        .init();
+    style::set_color_enabled(style::should_color(cli.no_color, &std::io::stdout()));
 
-    let cli = Cli::parse();
     let config = Config::from_env()?;
     config.validate()?;
 
@@ -113,9 +167,14 @@ async fn main() -> Result<()> {
                     error!("Failed to get index statistics: {}", e);
                 }
             }
-            IndexCommands::Update => {
+            IndexCommands::Update { since_last_run } => {
                 info!("Updating EDINET index...");
-                match edinet_indexer::update_edinet_index(config.database_path_str(), 7).await {
+                let result = if *since_last_run {
+                    edinet_indexer::update_edinet_index_since_last_run(config.database_path_str(), 7).await
+                } else {
+                    edinet_indexer::update_edinet_index(config.database_path_str(), 7).await
+                };
+                match result {
                     Ok(count) => {
                         info!("Successfully updated index with {} EDINET documents", count);
                         if let Err(e) = edinet_indexer::get_edinet_index_stats(config.database_path_str()).await {
@@ -125,9 +184,20 @@ async fn main() -> Result<()> {
                     Err(e) => error!("EDINET index update failed: {}", e),
                 }
             }
-            IndexCommands::Build { from, to } => {
+            IndexCommands::Import { path } => {
+                match edinet_indexer::index_edinet_archives(std::path::Path::new(path), config.database_path_str()).await {
+                    Ok(count) => info!("Successfully indexed {} EDINET ZIP files from {}", count, path),
+                    Err(e) => error!("EDINET ZIP import failed: {}", e),
+                }
+            }
+            IndexCommands::Build { from, to, doc_types, form_codes, xbrl_only, include_weekends, skip_holidays } => {
                 info!("Building EDINET index from {} to {}...", from, to);
-                match edinet_indexer::build_edinet_index_by_date(config.database_path_str(), *from, *to).await {
+                let filter = EdinetIndexFilter::from_code_lists(doc_types.as_deref(), form_codes.as_deref())
+                    .with_xbrl_only(*xbrl_only);
+                let mut index_config = config.clone();
+                index_config.indexing.include_weekends |= *include_weekends;
+                index_config.indexing.skip_holidays |= *skip_holidays;
+                match edinet_indexer::build_edinet_index_by_date_with_config(config.database_path_str(), *from, *to, &index_config, &filter).await {
                     Ok(count) => {
                         info!("Successfully indexed {} EDINET documents", count);
                         if let Err(e) = edinet_indexer::get_edinet_index_stats(config.database_path_str()).await {
@@ -138,21 +208,18 @@ async fn main() -> Result<()> {
                 }
             }
         },
-        Commands::Search { sym, format } => {
+        Commands::Search { sym, format, latest_only, include_withdrawn } => {
             // Check if index needs updating before searching
             if let Err(e) = check_and_update_index_if_needed(&config).await {
                 error!("Failed to check/update index: {}", e);
             }
             
-            let search_query = models::SearchQuery {
-                ticker: Some(sym.clone()),
-                company_name: None,
-                filing_type: None,
-                source: Some(models::Source::Edinet),
-                date_from: None,
-                date_to: None,
-                text_query: None,
-            };
+            let search_query = models::SearchQuery::builder()
+                .ticker(sym.as_str())
+                .source(models::Source::Edinet)
+                .latest_only(*latest_only)
+                .include_withdrawn(*include_withdrawn)
+                .build()?;
             
             match storage::search_documents(&search_query, config.database_path_str(), 100).await {
                 Ok(documents) => {
@@ -179,12 +246,12 @@ async fn main() -> Result<()> {
                         
                         for doc in &documents {
                             let ticker = &doc.ticker;
-                            let company = truncate_string(&doc.company_name, 38);
+                            let company = truncate_string(&doc.company_name, 40);
                             let filing_type = doc.filing_type.as_str();
                             let date = doc.date.format("%Y-%m-%d").to_string();
                             let path = doc.content_path.display().to_string();
                             
-                            println!("{:<12} {:<40} {:<15} {:<12} {:<20}", 
+                            println!("{:<12} {} {:<15} {:<12} {:<20}", 
                                 ticker, company, filing_type, date, path);
                         }
                         
@@ -195,7 +262,7 @@ async fn main() -> Result<()> {
                 Err(e) => error!("Search failed: {}", e),
             }
         }
-        Commands::Download { sym, limit } => {
+        Commands::Download { sym, limit, dry_run, attachments } => {
             info!("Downloading {} documents for symbol: {}", limit, sym);
             let download_request = models::DownloadRequest {
                 source: models::Source::Edinet,
@@ -205,17 +272,27 @@ async fn main() -> Result<()> {
                 date_to: None,
                 limit: *limit,
                 format: models::DocumentFormat::Complete,
+                document_id: None,
+                skip_existing: false,
+                dry_run: *dry_run,
+                include_attachments: *attachments,
+                output_template: None,
             };
             
             match downloader::download_documents(&download_request, config.download_dir_str()).await {
-                Ok(count) => info!("Successfully downloaded {} documents", count),
+                Ok(report) => downloader::print_report(&report, *dry_run),
                 Err(e) => error!("Download failed: {}", e),
             }
         }
         Commands::LoadStatic { csv_path } => {
             info!("Loading EDINET static data from: {}", csv_path);
             match storage::load_edinet_static_data(config.database_path_str(), csv_path).await {
-                Ok(count) => info!("Successfully loaded {} EDINET static records", count),
+                Ok(summary) => {
+                    println!("Loaded EDINET static data: {}", summary);
+                    if summary.rejected > 0 {
+                        println!("{} rows could not be parsed; see the log for their line numbers", summary.rejected);
+                    }
+                }
                 Err(e) => error!("Failed to load static data: {}", e),
             }
         }
@@ -244,7 +321,7 @@ async fn main() -> Result<()> {
                             section.section_type, 
                             section.full_length
                         );
-                        println!("   📂 {}", section.filename);
+                        println!("   📂 {}", section.display_path());
                         
                         if !section.content.trim().is_empty() {
                             println!("   📝 Content preview:");
@@ -276,15 +353,6 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Truncate string to specified length with ellipsis
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
-    }
-}
-
 async fn check_and_update_index_if_needed(config: &Config) -> Result<()> {
     use chrono::{NaiveDate, Utc};
     
@@ -56,12 +56,8 @@ async fn handle_command(command: Commands, config: &Config) -> Result<()> {
             // Set up the search query
             let search_query = SearchQuery {
                 ticker: Some(sym.clone()),
-                company_name: None,
-                filing_type: None,
                 source: Some(Source::Edinet),
-                date_from: None,
-                date_to: None,
-                text_query: None,
+                ..Default::default()
             };
             
             // Execute the search
@@ -76,14 +72,11 @@ async fn handle_command(command: Commands, config: &Config) -> Result<()> {
                         println!("{}", "-".repeat(100));
                         
                         for doc in &documents {
-                            let ticker = doc.ticker.as_deref().unwrap_or("N/A");
                             let company = truncate_string(&doc.company_name, 38);
-                            let filing_type = doc.filing_type.map_or("N/A".to_string(), |ft| format!("{:?}", ft));
                             let date = doc.date.format("%Y-%m-%d").to_string();
-                            let path = doc.content_path.as_deref().unwrap_or("N/A");
                             
                             println!("{:<12} {:<40} {:<15} {:<12} {:<20}", 
-                                ticker, company, filing_type, date, path);
+                                doc.ticker, company, doc.filing_type.as_str(), date, doc.content_path.display());
                         }
                         
                         println!();
@@ -16,7 +16,7 @@ use tracing::{info, error};
 
 use fast10k::{
     config::Config,
-    edinet_tui::App,
+    edinet_tui::{app::Screen, ui::truncate_string, App, ResultsScreen, SearchScreen},
     models::{SearchQuery, Source},
 };
 
@@ -30,6 +30,22 @@ pub struct Cli {
     /// Run in CLI mode (print output and exit, no interactive TUI)
     #[arg(long, global = true)]
     pub cli: bool,
+    /// Source to search, download and view (edinet, edgar, or any to merge both)
+    #[arg(long, global = true, default_value = "edinet")]
+    pub source: String,
+}
+
+/// Parse `--source`, where `any` means no source filter
+fn parse_source(value: &str) -> Result<Option<Source>> {
+    if value.eq_ignore_ascii_case("any") {
+        return Ok(None);
+    }
+    Source::all()
+        .iter()
+        .find(|source| source.as_str().eq_ignore_ascii_case(value))
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| anyhow::anyhow!("Unknown source '{}'. Use edinet, edgar, tdnet or any", value))
 }
 
 #[derive(Subcommand)]
@@ -74,11 +90,12 @@ async fn main() -> Result<()> {
     // Load configuration
     let config = Config::from_env()?;
     config.validate()?;
+    let source = parse_source(&cli.source)?;
 
     // Handle CLI mode - print output and exit without TUI
     if cli.cli {
         if let Some(command) = cli.command {
-            return handle_cli_command(command, &config).await;
+            return handle_cli_command(command, &config, source).await;
         } else {
             eprintln!("Error: CLI mode requires a command");
             std::process::exit(1);
@@ -94,10 +111,11 @@ async fn main() -> Result<()> {
 
     // Create and run the application
     let mut app = App::new(config)?;
+    app.register_screen(Screen::Search, SearchScreen::new().with_default_source(source.clone()));
     
     // Handle command line arguments for TUI mode
     if let Some(command) = cli.command {
-        handle_startup_command(&mut app, command).await?;
+        handle_startup_command(&mut app, command, source).await?;
     }
     
     let result = run_app(&mut terminal, &mut app).await;
@@ -126,25 +144,28 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Search the chosen source, or merge every source for `--source any`
+async fn search_documents(query: &SearchQuery, database_path: &str) -> Result<Vec<fast10k::models::Document>> {
+    match query.source {
+        Some(_) => fast10k::storage::search_documents(query, database_path, 100).await,
+        None => fast10k::storage::search_all_sources(query, database_path, 100).await,
+    }
+}
+
 /// Handle CLI mode commands - print output and exit
-async fn handle_cli_command(command: Commands, config: &Config) -> Result<()> {
+async fn handle_cli_command(command: Commands, config: &Config, source: Option<Source>) -> Result<()> {
     use fast10k::{storage, models::Document};
     
     match command {
         Commands::Search { sym } | Commands::S { sym } => {
             // Set up the search query
-            let search_query = SearchQuery {
-                ticker: Some(sym.clone()),
-                company_name: None,
-                filing_type: None,
-                source: Some(Source::Edinet),
-                date_from: None,
-                date_to: None,
-                text_query: None,
-            };
+            let search_query = SearchQuery::builder()
+                .ticker(sym.as_str())
+                .source(source.clone())
+                .build()?;
             
             // Execute the search
-            match storage::search_documents(&search_query, config.database_path_str(), 100).await {
+            match search_documents(&search_query, config.database_path_str()).await {
                 Ok(documents) => {
                     if documents.is_empty() {
                         println!("No documents found for symbol: {}", sym);
@@ -156,12 +177,12 @@ async fn handle_cli_command(command: Commands, config: &Config) -> Result<()> {
                         
                         for doc in &documents {
                             let ticker = &doc.ticker;
-                            let company = truncate_string(&doc.company_name, 38);
+                            let company = truncate_string(&doc.company_name, 40);
                             let filing_type = doc.filing_type.as_str();
                             let date = doc.date.format("%Y-%m-%d").to_string();
                             let path = doc.content_path.to_str().unwrap_or("N/A");
                             
-                            println!("{:<12} {:<40} {:<15} {:<12} {:<20}", 
+                            println!("{:<12} {} {:<15} {:<12} {:<20}", 
                                 ticker, company, filing_type, date, path);
                         }
                         
@@ -180,46 +201,36 @@ async fn handle_cli_command(command: Commands, config: &Config) -> Result<()> {
     Ok(())
 }
 
-/// Truncate string to specified length with ellipsis
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
-    }
-}
-
 /// Handle startup commands from command line arguments
-async fn handle_startup_command(app: &mut App, command: Commands) -> Result<()> {
-    use fast10k::{storage, edinet_tui::app::Screen};
-    
+async fn handle_startup_command(app: &mut App, command: Commands, source: Option<Source>) -> Result<()> {
     match command {
         Commands::Search { sym } | Commands::S { sym } => {
             info!("Executing search for symbol: {}", sym);
             
             // Set up the search query
-            let search_query = SearchQuery {
-                ticker: Some(sym.clone()),
-                company_name: None,
-                filing_type: None,
-                source: Some(Source::Edinet),
-                date_from: None,
-                date_to: None,
-                text_query: None,
-            };
+            let search_query = SearchQuery::builder()
+                .ticker(sym.as_str())
+                .source(source.clone())
+                .build()?;
             
             // Pre-populate the search form
-            app.search.ticker_input.value = sym.clone();
+            if let Some(search) = app.screen_mut::<SearchScreen>() {
+                search.ticker_input.value = sym.clone();
+            }
             
             // Execute the search
-            match storage::search_documents(&search_query, app.config.database_path_str(), 100).await {
+            match search_documents(&search_query, app.config.database_path_str()).await {
                 Ok(documents) => {
                     info!("Found {} documents for symbol {}", documents.len(), sym);
                     app.set_status(format!("Found {} documents for {}", documents.len(), sym));
                     
                     // Store results and navigate to results screen
-                    app.results.set_documents(documents);
-                    app.search.last_query = Some(search_query);
+                    if let Some(results) = app.screen_mut::<ResultsScreen>() {
+                        results.set_documents(documents);
+                    }
+                    if let Some(search) = app.screen_mut::<SearchScreen>() {
+                        search.last_query = Some(search_query);
+                    }
                     app.navigate_to_screen(Screen::Results);
                 }
                 Err(e) => {
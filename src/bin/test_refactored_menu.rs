@@ -19,7 +19,7 @@ use fast10k::{
     config::Config,
     edinet_tui::{
         screens::MainMenuScreenRefactored,
-        traits::{Screen, ScreenAction},
+        traits::{ActionScreen, ScreenAction},
     },
 };
 
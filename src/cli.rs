@@ -9,6 +9,51 @@ use crate::models::{FilingType, Source, DocumentFormat};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    
+    /// Only log errors
+    #[arg(long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    
+    /// Log debug details
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+    
+    /// Also write logs to this file (or set FAST10K_LOG_FILE)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<String>,
+    
+    /// Do not write a log file, even if FAST10K_LOG_FILE is set
+    #[arg(long, global = true, conflicts_with = "log_file")]
+    pub no_log_file: bool,
+    
+    /// Disable colored output (also off with NO_COLOR set or when output is not a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+}
+
+impl Cli {
+    /// Log filter for the console and log file: `--quiet`/`--verbose` win over `RUST_LOG`
+    pub fn log_filter(&self) -> String {
+        if self.quiet {
+            "fast10k=error".to_string()
+        } else if self.verbose {
+            "fast10k=debug".to_string()
+        } else {
+            std::env::var("RUST_LOG").unwrap_or_else(|_| "fast10k=info".to_string())
+        }
+    }
+    
+    /// Log file to write, if any: `--log-file`, else `FAST10K_LOG_FILE`, unless `--no-log-file`
+    pub fn log_file_path(&self) -> Option<std::path::PathBuf> {
+        if self.no_log_file {
+            return None;
+        }
+        self.log_file
+            .clone()
+            .or_else(|| std::env::var("FAST10K_LOG_FILE").ok())
+            .filter(|path| !path.trim().is_empty())
+            .map(std::path::PathBuf::from)
+    }
 }
 
 #[derive(Subcommand)]
@@ -19,7 +64,7 @@ pub enum Commands {
         #[arg(short, long)]
         source: String,
         
-        /// Company ticker symbol
+        /// Company ticker symbol (or EDINET fund code)
         #[arg(short, long)]
         ticker: String,
         
@@ -39,13 +84,98 @@ pub enum Commands {
         #[arg(short, long, default_value = "./downloads")]
         output: String,
         
-        /// Maximum number of documents to download
+        /// Maximum number of documents to download (0 for all)
         #[arg(short, long, default_value = "5")]
         limit: usize,
         
-        /// Document format to download (txt, html, xbrl, ixbrl, complete)
+        /// Document format to download (txt, html, xbrl, ixbrl, complete, pdf, summary, csv)
+        #[arg(long, default_value = "txt")]
+        format: String,
+        
+        /// Print the resolved company code and each download request (API key redacted) without downloading
+        #[arg(long, alias = "print-url")]
+        dry_run: bool,
+        
+        /// Also download each EDINET document's attachments as `{doc_id}-{date}-attachments.zip`
+        #[arg(long)]
+        attachments: bool,
+        
+        /// EDGAR file name template using {form}, {date}, {accession}, {ticker} and {cik} (default: {form}-{date}-{accession})
+        #[arg(long)]
+        output_template: Option<String>,
+    },
+    
+    /// Download a single document by EDINET doc ID or EDGAR accession number
+    Get {
+        /// EDINET doc ID (e.g. S100ABCD) or EDGAR accession number (e.g. 0000320193-24-000123)
+        id: String,
+        
+        /// Source of the document (edgar, edinet); detected from the id when omitted
+        #[arg(short, long)]
+        source: Option<String>,
+        
+        /// Ticker to file the download under when the document is not indexed
+        #[arg(short, long)]
+        ticker: Option<String>,
+        
+        /// Output directory
+        #[arg(short, long, default_value = "./downloads")]
+        output: String,
+        
+        /// Document format to download (txt, html, xbrl, ixbrl, complete, pdf, summary, csv)
+        #[arg(long, default_value = "txt")]
+        format: String,
+        
+        /// Print the download request (API key redacted) without downloading
+        #[arg(long, alias = "print-url")]
+        dry_run: bool,
+        
+        /// Also download the EDINET document's attachments as `{doc_id}-{date}-attachments.zip`
+        #[arg(long)]
+        attachments: bool,
+        
+        /// EDGAR file name template using {form}, {date}, {accession}, {ticker} and {cik} (default: {form}-{date}-{accession})
+        #[arg(long)]
+        output_template: Option<String>,
+    },
+    
+    /// Download filings for every ticker in a file, resuming where a previous run stopped
+    BulkDownload {
+        /// File with one ticker per line (blank lines and # comments are ignored)
+        #[arg(long)]
+        tickers_file: String,
+        
+        /// Source to download from (edgar, edinet, tdnet)
+        #[arg(short, long)]
+        source: String,
+        
+        /// Filing type to download
+        #[arg(short, long)]
+        filing_type: Option<String>,
+        
+        /// Start date (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        
+        /// End date (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        
+        /// Output directory
+        #[arg(short, long, default_value = "./downloads")]
+        output: String,
+        
+        /// Maximum number of documents to download per ticker (0 for all)
+        #[arg(short, long, default_value = "100")]
+        limit: usize,
+        
+        /// Document format to download (txt, html, xbrl, ixbrl, complete, pdf, summary, csv)
         #[arg(long, default_value = "txt")]
         format: String,
+        
+        /// State file tracking completed tickers [default: <output>/bulk-download-state.json]
+        #[arg(long)]
+        state_file: Option<String>,
     },
     
     /// Index downloaded documents into SQLite or Parquet
@@ -61,7 +191,66 @@ pub enum Commands {
     
     /// Search indexed filings
     Search {
-        /// Company ticker symbol
+        /// Company ticker symbol (or EDINET fund code)
+        #[arg(short, long)]
+        ticker: Option<String>,
+        
+        /// Company name
+        #[arg(short, long)]
+        company: Option<String>,
+        
+        /// Filing type
+        #[arg(short, long)]
+        filing_type: Option<String>,
+        
+        /// Source (edgar, edinet, tdnet, or any to merge EDGAR and EDINET results)
+        #[arg(short, long)]
+        source: Option<String>,
+        
+        /// Start date (YYYY-MM-DD)
+        #[arg(long)]
+        from_date: Option<NaiveDate>,
+        
+        /// End date (YYYY-MM-DD)
+        #[arg(long)]
+        to_date: Option<NaiveDate>,
+        
+        /// Text query
+        #[arg(short, long)]
+        query: Option<String>,
+        
+        /// Hide filings superseded by a later amendment
+        #[arg(long)]
+        latest_only: bool,
+        
+        /// Include withdrawn EDINET filings, which are hidden by default
+        #[arg(long)]
+        include_withdrawn: bool,
+        
+        /// Only documents with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        
+        /// Database file path; repeat to search several databases and merge the results
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: Vec<String>,
+        
+        /// Maximum number of results (0 for all)
+        #[arg(long, default_value = "10")]
+        limit: usize,
+        
+        /// Page through every result, --limit rows at a time (plain output when stdout is not a terminal)
+        #[arg(long)]
+        interactive: bool,
+        
+        /// Run the search once per ticker in this file (one per line, # comments ignored), --limit results each
+        #[arg(long, conflicts_with_all = ["ticker", "interactive"])]
+        tickers_file: Option<String>,
+    },
+    
+    /// Export search results to a CSV or JSON file
+    Export {
+        /// Company ticker symbol (or EDINET fund code)
         #[arg(short, long)]
         ticker: Option<String>,
         
@@ -73,7 +262,7 @@ pub enum Commands {
         #[arg(short, long)]
         filing_type: Option<String>,
         
-        /// Source
+        /// Source (edgar, edinet, tdnet, or any to merge EDGAR and EDINET results)
         #[arg(short, long)]
         source: Option<String>,
         
@@ -89,13 +278,188 @@ pub enum Commands {
         #[arg(short, long)]
         query: Option<String>,
         
+        /// Hide filings superseded by a later amendment
+        #[arg(long)]
+        latest_only: bool,
+        
+        /// Include withdrawn EDINET filings, which are hidden by default
+        #[arg(long)]
+        include_withdrawn: bool,
+        
+        /// Only documents with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        
         /// Database file path
         #[arg(short, long, default_value = "./fast10k.db")]
         database: String,
         
-        /// Maximum number of results
-        #[arg(long, default_value = "10")]
+        /// Maximum number of results (0 for all)
+        #[arg(long, default_value = "1000")]
         limit: usize,
+        
+        /// Output file (defaults to a timestamped file in FAST10K_EXPORT_DIR, ./exports by default)
+        #[arg(short, long)]
+        output: Option<String>,
+        
+        /// Export format (csv, json)
+        #[arg(long, default_value = "csv")]
+        format: String,
+        
+        /// Add documents not yet in the output file instead of overwriting it
+        #[arg(long, requires = "output")]
+        append: bool,
+    },
+    
+    /// Show details for a single indexed document
+    Show {
+        /// Document id
+        id: String,
+        
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+        
+        /// Directory containing downloaded documents
+        #[arg(long, default_value = "./downloads")]
+        download_dir: String,
+        
+        /// Open the downloaded file with the system default application
+        #[arg(long)]
+        open: bool,
+    },
+    
+    /// Update the EDINET index with recent filings
+    Update {
+        /// Fetch only dates since the latest indexed filing instead of a fixed window
+        #[arg(long)]
+        incremental: bool,
+        
+        /// Fetch only dates since the last successful index run
+        #[arg(long, conflicts_with = "incremental")]
+        since_last_run: bool,
+        
+        /// Number of days to re-fetch (or to build when the index is empty)
+        #[arg(long, default_value = "7")]
+        days: i64,
+        
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+    },
+    
+    /// Fetch and index a single EDINET date again, e.g. one that failed during a build
+    IndexDate {
+        /// Date to index (YYYY-MM-DD)
+        #[arg(long)]
+        date: NaiveDate,
+        
+        /// Only index these docTypeCodes, comma separated (e.g. 120,140)
+        #[arg(long)]
+        doc_types: Option<String>,
+        
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+    },
+    
+    /// Poll EDINET for today's new filings and print them as they appear
+    Watch {
+        /// Seconds between polls
+        #[arg(long, default_value = "300")]
+        interval: u64,
+        
+        /// Only report these tickers or EDINET codes, comma separated
+        #[arg(long)]
+        watchlist: Option<String>,
+        
+        /// Only index these docTypeCodes, comma separated (e.g. 120,140)
+        #[arg(long)]
+        doc_types: Option<String>,
+        
+        /// Skip documents without XBRL data
+        #[arg(long)]
+        xbrl_only: bool,
+        
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+    },
+    
+    /// Show indexed document counts per source and filing type
+    Stats {
+        /// Only show this source (edgar, edinet, tdnet)
+        #[arg(short, long)]
+        source: Option<String>,
+        
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+    },
+    
+    /// Write a consistent snapshot of the database, e.g. before clearing the index
+    Backup {
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+        
+        /// Backup file [default: <database>-backup-<timestamp>.db next to the database]
+        #[arg(long)]
+        to: Option<String>,
+    },
+    
+    /// Replace the database with a backup, keeping a copy of the current one
+    Restore {
+        /// Backup file written by `backup`
+        backup: String,
+        
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+    },
+    
+    /// Report EDINET ZIPs and EDGAR filings that are not indexed and indexed documents whose file is gone
+    Reconcile {
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+        
+        /// Download directory to check
+        #[arg(long, default_value = "./downloads")]
+        downloads: String,
+        
+        /// Index the unindexed files and clear the content paths of missing files
+        #[arg(long)]
+        fix: bool,
+    },
+    
+    /// Re-derive the filing types of indexed EDINET documents from their stored metadata
+    Reclassify {
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+    },
+    
+    /// Compute and store content hashes for indexed documents that have a file but no hash yet
+    Hash {
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+        
+        /// Recompute every hash, e.g. after files were replaced in place
+        #[arg(long)]
+        rehash: bool,
+    },
+    
+    /// Check configuration, database and API connectivity
+    Doctor {
+        /// Database file path
+        #[arg(short, long, default_value = "./fast10k.db")]
+        database: String,
+        
+        /// Directory for downloaded documents
+        #[arg(long, default_value = "./downloads")]
+        download_dir: String,
     },
     
     /// Launch terminal UI to monitor downloads & search
@@ -110,12 +474,20 @@ pub enum Commands {
 
 impl Commands {
     pub fn parse_source(source: &str) -> Result<Source, anyhow::Error> {
-        match source.to_lowercase().as_str() {
-            "edgar" => Ok(Source::Edgar),
-            "edinet" => Ok(Source::Edinet),
-            "tdnet" => Ok(Source::Tdnet),
-            other => Ok(Source::Other(other.to_string())),
-        }
+        Source::all()
+            .iter()
+            .find(|s| s.as_str().eq_ignore_ascii_case(source))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unsupported source: {}. Supported sources: {}",
+                source,
+                Source::all().iter().map(|s| s.as_str().to_lowercase()).collect::<Vec<_>>().join(", ")
+            ))
+    }
+    
+    /// Whether the source filter asks for a merged EDGAR + EDINET search
+    pub fn is_any_source(source: Option<&str>) -> bool {
+        source.is_some_and(|s| matches!(s.to_lowercase().as_str(), "any" | "all"))
     }
     
     pub fn parse_filing_type(filing_type: &str) -> Result<FilingType, anyhow::Error> {
@@ -125,7 +497,12 @@ impl Commands {
             "8-k" | "8k" => Ok(FilingType::EightK),
             "transcript" => Ok(FilingType::Transcript),
             "press-release" | "press_release" => Ok(FilingType::PressRelease),
-            other => Ok(FilingType::Other(other.to_string())),
+            "fund" | "fund-report" | "fund_report" => Ok(FilingType::FundReport),
+            other => Ok(FilingType::all()
+                .iter()
+                .find(|ft| ft.as_str().eq_ignore_ascii_case(other))
+                .cloned()
+                .unwrap_or_else(|| FilingType::Other(other.to_string()))),
         }
     }
     
@@ -136,7 +513,10 @@ impl Commands {
             "xbrl" | "xml" => Ok(DocumentFormat::Xbrl),
             "ixbrl" | "inline-xbrl" | "inlinexbrl" => Ok(DocumentFormat::Ixbrl),
             "complete" | "all" => Ok(DocumentFormat::Complete),
-            other => Err(anyhow::anyhow!("Unsupported document format: {}. Supported formats: txt, html, xbrl, ixbrl, complete", other)),
+            "pdf" => Ok(DocumentFormat::Pdf),
+            "summary" | "metadata" => Ok(DocumentFormat::Summary),
+            "csv" => Ok(DocumentFormat::Csv),
+            other => Err(anyhow::anyhow!("Unsupported document format: {}. Supported formats: txt, html, xbrl, ixbrl, complete, pdf, summary, csv", other)),
         }
     }
 }
\ No newline at end of file
@@ -1,8 +1,15 @@
 //! Centralized configuration management for fast10k
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use anyhow::{Result, Context};
+use crate::locale::{validate_date_pattern, DateFormat, DisplayLocale};
+
+/// Contact used in the EDGAR User-Agent when none is configured
+pub const EDGAR_PLACEHOLDER_CONTACT: &str = "your.email@example.com";
+
+/// Failures in a row that stop a bulk operation when `FAST10K_MAX_CONSECUTIVE_FAILURES` is not set
+pub const DEFAULT_MAX_CONSECUTIVE_FAILURES: usize = 10;
 
 /// Application configuration
 #[derive(Debug, Clone)]
@@ -11,12 +18,34 @@ pub struct Config {
     pub database_path: PathBuf,
     /// Directory for downloaded documents
     pub download_dir: PathBuf,
+    /// Per-source download directories used instead of `download_dir/<source>`
+    pub source_download_dirs: SourceDownloadDirs,
+    /// Directory for exported search results
+    pub export_dir: PathBuf,
     /// EDINET API key (optional)
     pub edinet_api_key: Option<String>,
     /// Rate limiting configuration
     pub rate_limits: RateLimits,
     /// HTTP client configuration
     pub http: HttpConfig,
+    /// Display formatting configuration
+    pub display: DisplayConfig,
+    /// EDINET indexing configuration
+    pub indexing: IndexingConfig,
+    /// Failures in a row after which index builds and bulk downloads stop (0 never stops)
+    pub max_consecutive_failures: usize,
+    /// Base URLs of the EDGAR and EDINET APIs
+    pub endpoints: ApiEndpoints,
+}
+
+/// Download directories for single sources, e.g. to keep EDGAR and EDINET filings on different disks
+///
+/// A set directory replaces `<download dir>/<source>`, so files go to `<directory>/<ticker>/`.
+#[derive(Debug, Clone, Default)]
+pub struct SourceDownloadDirs {
+    pub edgar: Option<PathBuf>,
+    pub edinet: Option<PathBuf>,
+    pub tdnet: Option<PathBuf>,
 }
 
 /// Rate limiting configuration for different APIs
@@ -33,10 +62,137 @@ pub struct RateLimits {
 /// HTTP client configuration
 #[derive(Debug, Clone)]
 pub struct HttpConfig {
-    /// Request timeout in seconds
+    /// Timeout in seconds for list and metadata requests (EDINET document lists, EDGAR submissions)
     pub timeout_seconds: u64,
+    /// Timeout in seconds for document downloads, which can be large ZIP archives
+    pub download_timeout_seconds: u64,
     /// User agent string
     pub user_agent: String,
+    /// Contact name sent to SEC EDGAR in the User-Agent
+    pub contact_name: Option<String>,
+    /// Contact email sent to SEC EDGAR in the User-Agent (required by SEC fair access policy)
+    pub contact_email: Option<String>,
+    /// HTTP(S) proxy URL used for all outbound requests
+    pub proxy_url: Option<String>,
+    /// Extra root CA certificate (PEM or DER) trusted in addition to the system roots
+    pub ca_cert_path: Option<PathBuf>,
+}
+
+/// Display formatting configuration
+#[derive(Debug, Clone)]
+pub struct DisplayConfig {
+    /// Locale used for dates and numbers in the TUI
+    pub locale: DisplayLocale,
+    /// Custom strftime date format overriding the locale's, e.g. `%Y/%m/%d`
+    pub date_format: Option<String>,
+    /// Number of rows per page in the TUI results table
+    pub items_per_page: usize,
+    /// Maximum number of documents a TUI search loads
+    pub max_search_results: usize,
+    /// Preload downloaded documents on the visible results page in the background
+    pub preload_content: bool,
+    /// Maximum number of sections the viewer lists for a document
+    pub viewer_max_sections: usize,
+    /// Maximum characters of each section's content shown in the viewer
+    pub viewer_max_content_length: usize,
+}
+
+/// Which dates EDINET indexing fetches, how many at once, and what is stored
+#[derive(Debug, Clone)]
+pub struct IndexingConfig {
+    /// Fetch Saturdays and Sundays too, for the occasional weekend filing
+    pub include_weekends: bool,
+    /// Skip Japanese national holidays and the year-end market closure
+    pub skip_holidays: bool,
+    /// Number of dates whose document lists are fetched at the same time
+    pub concurrency: usize,
+    /// Store the full text of downloaded and imported EDINET documents in the database
+    pub store_content: bool,
+    /// Bytes of text kept as a document's content preview when indexing, or 0 for no preview
+    pub content_preview_length: usize,
+    /// Number of downloaded files whose text `fast10k index` extracts at the same time
+    pub file_concurrency: usize,
+}
+
+impl Default for IndexingConfig {
+    fn default() -> Self {
+        Self {
+            include_weekends: false,
+            skip_holidays: false,
+            concurrency: 4,
+            store_content: false,
+            content_preview_length: 2000,
+            file_concurrency: 4,
+        }
+    }
+}
+
+/// Base URLs of the remote APIs, overridable to point at a mirror or a mock server
+#[derive(Debug, Clone)]
+pub struct ApiEndpoints {
+    /// SEC website hosting `company_tickers.json` and the filing archives
+    pub edgar_base_url: String,
+    /// SEC data API hosting company submissions
+    pub edgar_data_url: String,
+    /// EDINET API
+    pub edinet_base_url: String,
+    /// Path of the EDINET API version below `edinet_base_url`, e.g. `/api/v2`
+    pub edinet_api_path: String,
+}
+
+impl Default for ApiEndpoints {
+    fn default() -> Self {
+        Self {
+            edgar_base_url: "https://www.sec.gov".to_string(),
+            edgar_data_url: "https://data.sec.gov".to_string(),
+            edinet_base_url: crate::edinet::types::EdinetApi::BASE_URL.to_string(),
+            edinet_api_path: crate::edinet::types::EdinetApi::API_PATH.to_string(),
+        }
+    }
+}
+
+impl ApiEndpoints {
+    /// Point every API at `base_url`, as used by tests against a single mock server
+    pub fn all(base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Self {
+            edgar_base_url: base_url.clone(),
+            edgar_data_url: base_url.clone(),
+            edinet_base_url: base_url,
+            ..Self::default()
+        }
+    }
+    
+    /// EDINET document list for a date, `documents.json`
+    pub fn edinet_documents_url(&self) -> String {
+        format!("{}{}/documents.json", self.edinet_base_url, self.edinet_api_path)
+    }
+    
+    /// EDINET download of a single document
+    pub fn edinet_document_url(&self, doc_id: &str) -> String {
+        format!("{}{}/documents/{}", self.edinet_base_url, self.edinet_api_path, doc_id)
+    }
+}
+
+impl DisplayConfig {
+    /// Date format shared by the TUI and CLI output
+    pub fn date_format(&self) -> DateFormat {
+        DateFormat::new(self.locale, self.date_format.clone())
+    }
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            locale: DisplayLocale::default(),
+            date_format: None,
+            items_per_page: 20,
+            max_search_results: 100,
+            preload_content: false,
+            viewer_max_sections: 200,
+            viewer_max_content_length: 500_000,
+        }
+    }
 }
 
 impl Default for RateLimits {
@@ -53,7 +209,12 @@ impl Default for HttpConfig {
     fn default() -> Self {
         Self {
             timeout_seconds: 30,
+            download_timeout_seconds: 300,
             user_agent: "fast10k/0.1.0".to_string(),
+            contact_name: None,
+            contact_email: None,
+            proxy_url: None,
+            ca_cert_path: None,
         }
     }
 }
@@ -69,6 +230,17 @@ impl Config {
             .unwrap_or_else(|_| "./downloads".to_string())
             .into();
 
+        let source_dir = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty()).map(PathBuf::from);
+        let source_download_dirs = SourceDownloadDirs {
+            edgar: source_dir("FAST10K_EDGAR_DOWNLOAD_DIR"),
+            edinet: source_dir("FAST10K_EDINET_DOWNLOAD_DIR"),
+            tdnet: source_dir("FAST10K_TDNET_DOWNLOAD_DIR"),
+        };
+
+        let export_dir = std::env::var("FAST10K_EXPORT_DIR")
+            .unwrap_or_else(|_| "./exports".to_string())
+            .into();
+
         let edinet_api_key = std::env::var("EDINET_API_KEY").ok();
 
         let rate_limits = RateLimits {
@@ -79,16 +251,76 @@ impl Config {
 
         let http = HttpConfig {
             timeout_seconds: parse_env_var("FAST10K_HTTP_TIMEOUT_SECONDS")?.unwrap_or(30),
+            download_timeout_seconds: parse_env_var("FAST10K_DOWNLOAD_TIMEOUT_SECONDS")?.unwrap_or(300),
             user_agent: std::env::var("FAST10K_USER_AGENT")
                 .unwrap_or_else(|_| "fast10k/0.1.0".to_string()),
+            contact_name: std::env::var("FAST10K_CONTACT_NAME").ok().filter(|v| !v.trim().is_empty()),
+            contact_email: std::env::var("FAST10K_CONTACT_EMAIL").ok().filter(|v| !v.trim().is_empty()),
+            proxy_url: std::env::var("FAST10K_HTTP_PROXY").ok(),
+            ca_cert_path: std::env::var("FAST10K_CA_CERT").ok().map(PathBuf::from),
+        };
+
+        let display = DisplayConfig {
+            locale: match std::env::var("FAST10K_DISPLAY_LOCALE") {
+                Ok(val) => val.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+                Err(_) => DisplayLocale::default(),
+            },
+            date_format: match std::env::var("FAST10K_DATE_FORMAT") {
+                Ok(val) if !val.is_empty() => {
+                    validate_date_pattern(&val).map_err(|e| anyhow::anyhow!("FAST10K_DATE_FORMAT: {}", e))?;
+                    Some(val)
+                }
+                _ => None,
+            },
+            items_per_page: parse_env_var("FAST10K_ITEMS_PER_PAGE")?.unwrap_or(20),
+            max_search_results: parse_env_var("FAST10K_MAX_SEARCH_RESULTS")?.unwrap_or(100),
+            preload_content: parse_env_var("FAST10K_PRELOAD_CONTENT")?.unwrap_or(false),
+            viewer_max_sections: parse_env_var("FAST10K_VIEWER_MAX_SECTIONS")?.unwrap_or(200),
+            viewer_max_content_length: parse_env_var("FAST10K_VIEWER_MAX_CONTENT_LENGTH")?.unwrap_or(500_000),
+        };
+
+        let indexing = IndexingConfig {
+            include_weekends: parse_env_var("FAST10K_EDINET_INCLUDE_WEEKENDS")?.unwrap_or(false),
+            skip_holidays: parse_env_var("FAST10K_EDINET_SKIP_HOLIDAYS")?.unwrap_or(false),
+            concurrency: parse_env_var("FAST10K_EDINET_INDEX_CONCURRENCY")?.unwrap_or(4),
+            store_content: parse_env_var("FAST10K_STORE_CONTENT")?.unwrap_or(false),
+            content_preview_length: parse_env_var("FAST10K_CONTENT_PREVIEW_LENGTH")?.unwrap_or(2000),
+            file_concurrency: parse_env_var("FAST10K_INDEX_FILE_CONCURRENCY")?.unwrap_or(4),
+        };
+        let max_consecutive_failures = parse_env_var("FAST10K_MAX_CONSECUTIVE_FAILURES")?.unwrap_or(DEFAULT_MAX_CONSECUTIVE_FAILURES);
+
+        let defaults = ApiEndpoints::default();
+        let base_url = |name: &str, default: String| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .map(|v| v.trim_end_matches('/').to_string())
+                .unwrap_or(default)
+        };
+        let endpoints = ApiEndpoints {
+            edgar_base_url: base_url("FAST10K_EDGAR_BASE_URL", defaults.edgar_base_url),
+            edgar_data_url: base_url("FAST10K_EDGAR_DATA_URL", defaults.edgar_data_url),
+            edinet_base_url: base_url("FAST10K_EDINET_BASE_URL", defaults.edinet_base_url),
+            edinet_api_path: std::env::var("FAST10K_EDINET_API_PATH")
+                .ok()
+                .map(|v| v.trim().trim_end_matches('/').to_string())
+                .filter(|v| !v.is_empty())
+                .map(|v| if v.starts_with('/') { v } else { format!("/{}", v) })
+                .unwrap_or(defaults.edinet_api_path),
         };
 
         Ok(Config {
             database_path,
             download_dir,
+            source_download_dirs,
+            export_dir,
             edinet_api_key,
             rate_limits,
             http,
+            display,
+            indexing,
+            max_consecutive_failures,
+            endpoints,
         })
     }
 
@@ -102,6 +334,26 @@ impl Config {
         self.download_dir.to_str().unwrap_or("./downloads")
     }
 
+    /// Directory holding one source's downloads (`edgar`, `edinet` or `tdnet`, case-insensitive)
+    ///
+    /// This is `<download_dir>/<source>` unless a directory is configured for the source, which
+    /// then takes precedence over `download_dir` (e.g. a `--output` directory).
+    pub fn source_dir(&self, download_dir: impl AsRef<Path>, source: &str) -> PathBuf {
+        let source = source.to_lowercase();
+        let configured = match source.as_str() {
+            "edgar" => &self.source_download_dirs.edgar,
+            "edinet" => &self.source_download_dirs.edinet,
+            "tdnet" => &self.source_download_dirs.tdnet,
+            _ => &None,
+        };
+        configured.clone().unwrap_or_else(|| download_dir.as_ref().join(source))
+    }
+
+    /// Directory holding one source's downloads under the configured download directory
+    pub fn source_download_dir(&self, source: &str) -> PathBuf {
+        self.source_dir(&self.download_dir, source)
+    }
+
     /// Get EDINET API delay as Duration
     pub fn edinet_api_delay(&self) -> Duration {
         Duration::from_millis(self.rate_limits.edinet_api_delay_ms)
@@ -113,10 +365,64 @@ impl Config {
     }
 
     /// Get HTTP timeout as Duration
+    ///
+    /// This is the client default and covers list and metadata requests.
     pub fn http_timeout(&self) -> Duration {
         Duration::from_secs(self.http.timeout_seconds)
     }
 
+    /// Get document download timeout as Duration, set per request on download calls
+    pub fn download_timeout(&self) -> Duration {
+        Duration::from_secs(self.http.download_timeout_seconds)
+    }
+
+    /// User-Agent for SEC EDGAR requests, e.g. `fast10k/0.1.0 (Jane Doe jane@example.com)`
+    ///
+    /// Falls back to a placeholder contact when `FAST10K_CONTACT_EMAIL` is not set.
+    pub fn edgar_user_agent(&self) -> String {
+        let contact = match (&self.http.contact_name, &self.http.contact_email) {
+            (Some(name), Some(email)) => format!("{} {}", name, email),
+            (None, Some(email)) => email.clone(),
+            (_, None) => EDGAR_PLACEHOLDER_CONTACT.to_string(),
+        };
+        format!("{} ({})", self.http.user_agent, contact)
+    }
+
+    /// Whether a real contact email is configured for EDGAR requests
+    pub fn has_edgar_contact(&self) -> bool {
+        self.http.contact_email.is_some()
+    }
+
+    /// HTTP client builder with the configured user agent, timeout, proxy and CA certificate
+    ///
+    /// Responses are requested compressed (gzip, brotli, deflate) and decompressed transparently,
+    /// which matters for large JSON such as `company_tickers.json` and EDGAR submissions.
+    pub fn http_client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(&self.http.user_agent)
+            .timeout(self.http_timeout())
+            .gzip(true)
+            .brotli(true)
+            .deflate(true);
+
+        if let Some(proxy_url) = &self.http.proxy_url {
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .with_context(|| format!("Invalid FAST10K_HTTP_PROXY: {}", proxy_url))?;
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(ca_cert_path) = &self.http.ca_cert_path {
+            let bytes = std::fs::read(ca_cert_path)
+                .with_context(|| format!("Cannot read CA certificate: {}", ca_cert_path.display()))?;
+            let certificate = reqwest::Certificate::from_pem(&bytes)
+                .or_else(|_| reqwest::Certificate::from_der(&bytes))
+                .with_context(|| format!("Invalid CA certificate: {}", ca_cert_path.display()))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(builder)
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<()> {
         // Check if parent directory of database exists
@@ -129,6 +435,22 @@ impl Config {
             }
         }
 
+        if self.http.timeout_seconds == 0 || self.http.download_timeout_seconds == 0 {
+            return Err(anyhow::anyhow!("FAST10K_HTTP_TIMEOUT_SECONDS and FAST10K_DOWNLOAD_TIMEOUT_SECONDS must be greater than 0"));
+        }
+
+        if self.display.items_per_page == 0 {
+            return Err(anyhow::anyhow!("FAST10K_ITEMS_PER_PAGE must be greater than 0"));
+        }
+
+        if self.display.max_search_results == 0 {
+            return Err(anyhow::anyhow!("FAST10K_MAX_SEARCH_RESULTS must be greater than 0"));
+        }
+
+        if self.display.viewer_max_sections == 0 {
+            return Err(anyhow::anyhow!("FAST10K_VIEWER_MAX_SECTIONS must be greater than 0"));
+        }
+
         // Check if download directory can be created
         std::fs::create_dir_all(&self.download_dir)
             .with_context(|| format!("Cannot create download directory: {}", self.download_dir.display()))?;
@@ -162,6 +484,31 @@ mod tests {
         assert_eq!(config.download_dir_str(), "./downloads");
         assert_eq!(config.rate_limits.edinet_api_delay_ms, 100);
         assert_eq!(config.http.timeout_seconds, 30);
+        assert_eq!(config.http.download_timeout_seconds, 300);
+        assert_eq!(config.download_timeout(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_edinet_endpoint_urls() {
+        let mut endpoints = ApiEndpoints::default();
+        assert_eq!(endpoints.edinet_documents_url(), "https://api.edinet-fsa.go.jp/api/v2/documents.json");
+        assert_eq!(endpoints.edinet_document_url("S100ABCD"), "https://api.edinet-fsa.go.jp/api/v2/documents/S100ABCD");
+
+        endpoints = ApiEndpoints { edinet_api_path: "/api/v3".to_string(), ..ApiEndpoints::all("http://localhost:8080/") };
+        assert_eq!(endpoints.edinet_documents_url(), "http://localhost:8080/api/v3/documents.json");
+    }
+
+    #[test]
+    fn test_source_download_dirs() {
+        let mut config = Config::from_env().unwrap();
+        config.source_download_dirs = SourceDownloadDirs::default();
+        assert_eq!(config.source_download_dir("EDINET"), Path::new("./downloads/edinet"));
+        assert_eq!(config.source_dir("/tmp/out", "edgar"), Path::new("/tmp/out/edgar"));
+
+        config.source_download_dirs.edinet = Some(PathBuf::from("/mnt/jp/filings"));
+        assert_eq!(config.source_download_dir("EDINET"), Path::new("/mnt/jp/filings"));
+        assert_eq!(config.source_dir("/tmp/out", "edinet"), Path::new("/mnt/jp/filings"));
+        assert_eq!(config.source_dir("/tmp/out", "edgar"), Path::new("/tmp/out/edgar"));
     }
 
     #[test]
@@ -170,4 +517,55 @@ mod tests {
         // Should not fail for default paths
         config.validate().unwrap();
     }
+
+    #[test]
+    fn test_edgar_user_agent() {
+        let mut config = Config::from_env().unwrap();
+        config.http.contact_name = None;
+        config.http.contact_email = None;
+        assert!(!config.has_edgar_contact());
+        assert_eq!(config.edgar_user_agent(), "fast10k/0.1.0 (your.email@example.com)");
+
+        config.http.contact_name = Some("Jane Doe".to_string());
+        config.http.contact_email = Some("jane@corp.example".to_string());
+        assert!(config.has_edgar_contact());
+        assert_eq!(config.edgar_user_agent(), "fast10k/0.1.0 (Jane Doe jane@corp.example)");
+    }
+
+    #[test]
+    fn test_http_client_builder() {
+        let mut config = Config::from_env().unwrap();
+        config.http.proxy_url = Some("http://proxy.example.com:8080".to_string());
+        config.http_client_builder().unwrap().build().unwrap();
+
+        config.http.ca_cert_path = Some(PathBuf::from("./does-not-exist.pem"));
+        assert!(config.http_client_builder().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_client_requests_compression() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_lowercase()
+        });
+
+        let config = Config::from_env().unwrap();
+        let client = config.http_client_builder().unwrap().no_proxy().build().unwrap();
+        client.get(format!("http://{}/", addr)).send().await.unwrap();
+
+        let request = server.join().unwrap();
+        let accept_encoding = request
+            .lines()
+            .find_map(|line| line.strip_prefix("accept-encoding:"))
+            .expect("accept-encoding header sent");
+        assert!(accept_encoding.contains("gzip"));
+        assert!(accept_encoding.contains("br"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,408 @@
+//! Resumable bulk download of many companies
+//!
+//! Completed tickers are recorded in a JSON state file after each ticker, keyed by the source, filing type,
+//! format and date range they were downloaded for, so an interrupted run picks up where it stopped and
+//! never re-downloads finished tickers.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::download_documents;
+use crate::models::{DownloadReport, DownloadRequest};
+use crate::style;
+
+/// Read tickers from a file with one ticker per line; blank lines and `#` comments are ignored
+pub fn read_tickers_file(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read tickers file {}", path.display()))?;
+    Ok(parse_tickers(&content))
+}
+
+fn parse_tickers(content: &str) -> Vec<String> {
+    let mut tickers: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let ticker = line.split('#').next().unwrap_or_default().trim();
+        if !ticker.is_empty() && !tickers.iter().any(|t| t == ticker) {
+            tickers.push(ticker.to_string());
+        }
+    }
+    tickers
+}
+
+/// Stops a long run of requests once too many fail in a row, e.g. during an API outage
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    threshold: usize,
+    consecutive_failures: usize,
+}
+
+impl CircuitBreaker {
+    /// Trip after `threshold` failures in a row; a threshold of 0 never trips
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold, consecutive_failures: 0 }
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// Count a failure, returning whether the breaker has now tripped
+    pub fn record_failure(&mut self) -> bool {
+        self.consecutive_failures += 1;
+        self.is_tripped()
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.threshold > 0 && self.consecutive_failures >= self.threshold
+    }
+
+    pub fn consecutive_failures(&self) -> usize {
+        self.consecutive_failures
+    }
+}
+
+/// A finished ticker in the state file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedTicker {
+    pub downloaded: usize,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Progress of a bulk download, persisted between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BulkDownloadState {
+    pub completed: BTreeMap<String, CompletedTicker>,
+}
+
+impl BulkDownloadState {
+    /// Load the state file, starting fresh if it does not exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid state file {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write then rename so an interruption never leaves a truncated state file
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Whether `ticker` was completed with the same source, filing type, format and date range as `request`
+    pub fn is_completed(&self, ticker: &str, request: &DownloadRequest) -> bool {
+        self.completed.contains_key(&state_key(ticker, request))
+    }
+
+    pub fn mark_completed(&mut self, ticker: &str, request: &DownloadRequest, downloaded: usize) {
+        self.completed.insert(
+            state_key(ticker, request),
+            CompletedTicker { downloaded, completed_at: Utc::now() },
+        );
+    }
+}
+
+fn state_key(ticker: &str, request: &DownloadRequest) -> String {
+    let date = |d: Option<NaiveDate>| d.map(|d| d.to_string()).unwrap_or_else(|| "*".to_string());
+    format!(
+        "{}|{}|{}|{}|{}|{}",
+        request.source.as_str(),
+        ticker,
+        request.filing_type.as_ref().map(|t| t.as_str()).unwrap_or("*"),
+        request.format.as_str(),
+        date(request.date_from),
+        date(request.date_to)
+    )
+}
+
+/// What happened to a single ticker in a bulk run
+#[derive(Debug, Clone, PartialEq)]
+pub enum TickerOutcome {
+    /// Downloaded now, with what happened to each of the ticker's documents
+    Downloaded(DownloadReport),
+    /// Already completed by a previous run
+    Skipped,
+    Failed(String),
+}
+
+#[derive(Debug, Default)]
+pub struct BulkDownloadSummary {
+    pub results: Vec<(String, TickerOutcome)>,
+}
+
+impl BulkDownloadSummary {
+    pub fn documents_downloaded(&self) -> usize {
+        self.results
+            .iter()
+            .map(|(_, outcome)| match outcome {
+                TickerOutcome::Downloaded(report) => report.downloaded,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Documents of downloaded tickers that could not be downloaded
+    pub fn documents_failed(&self) -> usize {
+        self.results
+            .iter()
+            .map(|(_, outcome)| match outcome {
+                TickerOutcome::Downloaded(report) => report.failed.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.results.iter().filter(|(_, o)| *o == TickerOutcome::Skipped).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|(_, o)| matches!(o, TickerOutcome::Failed(_))).count()
+    }
+}
+
+/// Download every ticker using `template` for the source, filters and limits
+///
+/// Tickers already completed for the same source, filing type, format and date range are skipped, documents already on disk
+/// are not downloaded again, and failed tickers, or tickers with documents that failed, are left out of the state so the
+/// next run retries them.
+/// After `max_consecutive_failures` failed tickers in a row (0 for no limit) the run stops with an
+/// error, keeping the state of the tickers completed so far.
+pub async fn bulk_download(
+    tickers: &[String],
+    template: &DownloadRequest,
+    output_dir: &str,
+    state_path: &Path,
+    max_consecutive_failures: usize,
+) -> Result<BulkDownloadSummary> {
+    let mut state = BulkDownloadState::load(state_path)?;
+    let mut summary = BulkDownloadSummary::default();
+    let mut breaker = CircuitBreaker::new(max_consecutive_failures);
+    let total = tickers.len();
+
+    for (index, ticker) in tickers.iter().enumerate() {
+        let progress = format!("[{}/{}]", index + 1, total);
+
+        if state.is_completed(ticker, template) {
+            println!("{}", style::dim(format!("⏭️  {} {} already completed, skipping", progress, ticker)));
+            summary.results.push((ticker.clone(), TickerOutcome::Skipped));
+            continue;
+        }
+
+        println!("📥 {} Downloading {}", progress, ticker);
+        let request = DownloadRequest {
+            ticker: ticker.clone(),
+            document_id: None,
+            skip_existing: true,
+            dry_run: false,
+            ..template.clone()
+        };
+
+        match download_documents(&request, output_dir).await {
+            Ok(report) => {
+                println!("{}", style::success(format!("✅ {} {}: {} documents downloaded", progress, ticker, report.downloaded)));
+                for (id, error) in &report.failed {
+                    println!("{}", style::failure(format!("    ✗ {}: {}", id, error)));
+                }
+                if report.failed.is_empty() {
+                    state.mark_completed(ticker, template, report.downloaded);
+                    state.save(state_path)?;
+                }
+                summary.results.push((ticker.clone(), TickerOutcome::Downloaded(report)));
+                breaker.record_success();
+            }
+            Err(e) => {
+                warn!("Bulk download failed for {}: {}", ticker, e);
+                println!("{}", style::failure(format!("❌ {} {}: {}", progress, ticker, e)));
+                summary.results.push((ticker.clone(), TickerOutcome::Failed(e.to_string())));
+                if breaker.record_failure() {
+                    anyhow::bail!(
+                        "Stopped bulk download after {} failed tickers in a row (last error: {}). {} of {} tickers are done; \
+                         progress is saved in {}, so run the same command again to resume",
+                        breaker.consecutive_failures(),
+                        e,
+                        summary.results.len() - summary.failed(),
+                        total,
+                        state_path.display()
+                    );
+                }
+            }
+        }
+    }
+
+    info!(
+        "Bulk download finished: {} documents, {} tickers skipped, {} failed",
+        summary.documents_downloaded(),
+        summary.skipped(),
+        summary.failed()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DocumentFormat, FilingType, Source};
+    use tempfile::TempDir;
+
+    fn template() -> DownloadRequest {
+        DownloadRequest {
+            source: Source::Edgar,
+            ticker: String::new(),
+            filing_type: None,
+            date_from: NaiveDate::from_ymd_opt(2024, 1, 1),
+            date_to: None,
+            limit: 10,
+            format: DocumentFormat::Txt,
+            document_id: None,
+            skip_existing: false,
+            dry_run: false,
+            include_attachments: false,
+            output_template: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_tickers() {
+        let tickers = parse_tickers("AAPL\n\n# big tech\nMSFT  # comment\n  7203 \nAAPL\n");
+        assert_eq!(tickers, vec!["AAPL", "MSFT", "7203"]);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("state.json");
+        let request = template();
+
+        let mut state = BulkDownloadState::load(&path).unwrap();
+        assert!(state.completed.is_empty());
+        state.mark_completed("AAPL", &request, 3);
+        state.save(&path).unwrap();
+
+        let loaded = BulkDownloadState::load(&path).unwrap();
+        assert!(loaded.is_completed("AAPL", &request));
+        assert!(!loaded.is_completed("AAPL", &DownloadRequest { date_from: None, ..template() }));
+        assert!(!loaded.is_completed("AAPL", &DownloadRequest { format: DocumentFormat::Html, ..template() }));
+        assert!(!loaded.is_completed("AAPL", &DownloadRequest { source: Source::Edinet, ..template() }));
+        assert!(!loaded.is_completed("MSFT", &request));
+        assert_eq!(loaded.completed["EDGAR|AAPL|*|txt|2024-01-01|*"].downloaded, 3);
+    }
+
+    #[tokio::test]
+    async fn test_completed_tickers_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_path = temp_dir.path().join("state.json");
+        let request = template();
+
+        let mut state = BulkDownloadState::default();
+        state.mark_completed("AAPL", &request, 2);
+        state.mark_completed("MSFT", &request, 0);
+        state.save(&state_path).unwrap();
+
+        let tickers = vec!["AAPL".to_string(), "MSFT".to_string()];
+        let summary = bulk_download(&tickers, &request, temp_dir.path().to_str().unwrap(), &state_path, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.skipped(), 2);
+        assert_eq!(summary.failed(), 0);
+        assert_eq!(summary.documents_downloaded(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_other_filing_type_is_downloaded_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_path = temp_dir.path().join("state.json");
+        // An unsupported source fails every download, which shows whether the ticker was attempted
+        let annual = DownloadRequest {
+            source: Source::Other("unknown".to_string()),
+            filing_type: Some(FilingType::TenK),
+            ..template()
+        };
+        let quarterly = DownloadRequest { filing_type: Some(FilingType::TenQ), ..annual.clone() };
+
+        let mut state = BulkDownloadState::default();
+        state.mark_completed("AAPL", &annual, 2);
+        state.save(&state_path).unwrap();
+
+        let tickers = vec!["AAPL".to_string()];
+        let output_dir = temp_dir.path().to_str().unwrap();
+        let summary = bulk_download(&tickers, &annual, output_dir, &state_path, 0).await.unwrap();
+        assert_eq!(summary.skipped(), 1);
+
+        let summary = bulk_download(&tickers, &quarterly, output_dir, &state_path, 0).await.unwrap();
+        assert_eq!(summary.skipped(), 0);
+        assert_eq!(summary.failed(), 1);
+    }
+
+    #[test]
+    fn test_summary_counts_failed_documents() {
+        let partial = DownloadReport {
+            downloaded: 2,
+            skipped: 1,
+            failed: vec![("doc-3".to_string(), "timed out".to_string())],
+        };
+        let summary = BulkDownloadSummary {
+            results: vec![
+                ("AAPL".to_string(), TickerOutcome::Downloaded(partial)),
+                ("MSFT".to_string(), TickerOutcome::Downloaded(DownloadReport { downloaded: 3, ..Default::default() })),
+                ("GOOG".to_string(), TickerOutcome::Skipped),
+                ("AMZN".to_string(), TickerOutcome::Failed("no filings".to_string())),
+            ],
+        };
+
+        assert_eq!(summary.documents_downloaded(), 5);
+        assert_eq!(summary.documents_failed(), 1);
+        assert_eq!(summary.skipped(), 1);
+        assert_eq!(summary.failed(), 1);
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let mut breaker = CircuitBreaker::new(3);
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.consecutive_failures(), 3);
+
+        let mut never = CircuitBreaker::new(0);
+        assert!((0..100).all(|_| !never.record_failure()));
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_failures_stop_the_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_path = temp_dir.path().join("state.json");
+        let request = DownloadRequest { source: Source::Other("unknown".to_string()), ..template() };
+
+        let mut state = BulkDownloadState::default();
+        state.mark_completed("AAPL", &request, 2);
+        state.save(&state_path).unwrap();
+
+        let tickers: Vec<String> = ["AAPL", "MSFT", "GOOG", "AMZN"].iter().map(|t| t.to_string()).collect();
+        let err = bulk_download(&tickers, &request, temp_dir.path().to_str().unwrap(), &state_path, 2)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("after 2 failed tickers in a row"), "{}", err);
+        assert!(err.contains("1 of 4 tickers are done"), "{}", err);
+        let state = BulkDownloadState::load(&state_path).unwrap();
+        assert_eq!(state.completed.len(), 1);
+    }
+}
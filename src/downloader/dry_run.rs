@@ -0,0 +1,88 @@
+//! Request previews printed by `--dry-run` instead of downloading
+
+use std::fmt;
+
+/// Shown in place of API keys and other credentials
+const REDACTED: &str = "<redacted>";
+
+/// A request a downloader would send, printed with credentials redacted
+#[derive(Debug, Clone)]
+pub struct RequestPreview {
+    url: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestPreview {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self { url: url.into(), query: Vec::new(), headers: Vec::new() }
+    }
+
+    pub fn with_query(mut self, name: &str, value: &str) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Full URL including the query string
+    pub fn full_url(&self) -> String {
+        match reqwest::Url::parse_with_params(&self.url, &self.query) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.url.clone(),
+        }
+    }
+}
+
+impl fmt::Display for RequestPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GET {}", self.full_url())?;
+        for (name, value) in &self.query {
+            write!(f, "\n    param  {}={}", name, value)?;
+        }
+        for (name, value) in &self.headers {
+            let value = if is_sensitive_header(name) { REDACTED } else { value };
+            write!(f, "\n    header {}: {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Headers carrying credentials, e.g. EDINET's `Ocp-Apim-Subscription-Key`
+fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["key", "authorization", "token", "cookie"].iter().any(|word| name.contains(word))
+}
+
+/// Print a line of dry-run output, e.g. a resolved company code
+pub fn note(message: impl fmt::Display) {
+    println!("[dry-run] {}", message);
+}
+
+/// Print a request that would be sent, and the file it would be saved to
+pub fn print_request(request: &RequestPreview, output_path: &std::path::Path) {
+    println!("[dry-run] {}\n    -> {}", request, output_path.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_redacts_api_key() {
+        let preview = RequestPreview::get("https://api.edinet-fsa.go.jp/api/v2/documents/S100ABCD")
+            .with_query("type", "1")
+            .with_header("Ocp-Apim-Subscription-Key", "secret-key")
+            .with_header("Accept", "application/json");
+
+        assert_eq!(preview.full_url(), "https://api.edinet-fsa.go.jp/api/v2/documents/S100ABCD?type=1");
+        let printed = preview.to_string();
+        assert!(printed.contains("param  type=1"));
+        assert!(printed.contains("header Ocp-Apim-Subscription-Key: <redacted>"));
+        assert!(printed.contains("header Accept: application/json"));
+        assert!(!printed.contains("secret-key"));
+    }
+}
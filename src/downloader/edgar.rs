@@ -1,11 +1,27 @@
-use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 use tokio::fs;
 use tracing::{debug, error, info, warn};
-use crate::models::DownloadRequest;
+use crate::config::{ApiEndpoints, Config};
+use crate::models::{DownloadReport, DownloadRequest};
+use super::dry_run::{self, RequestPreview};
+use super::{is_existing_download, remove_partial_downloads, write_download, DownloaderError};
+
+/// Service name reported in rate-limit and API errors
+const SERVICE: &str = "EDGAR";
+
+/// Attempts made to fetch an older submissions file that keeps failing with a retryable error
+const SUBMISSIONS_FILE_ATTEMPTS: u32 = 3;
+/// Wait before retrying an older submissions file, multiplied by the attempt number
+const SUBMISSIONS_FILE_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
+/// Accept header sent with filing document requests
+const FILING_ACCEPT: &str = "text/html,text/plain,*/*";
+/// File name of a downloaded filing before the format's extension, e.g. `10-K-2024-11-01-000032019324000123`
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{form}-{date}-{accession}";
+/// Fields that can be interpolated into a file name template
+const FILENAME_FIELDS: [&str; 5] = ["form", "date", "accession", "ticker", "cik"];
 
 #[derive(Debug, Deserialize)]
 struct CompanyTicker {
@@ -53,7 +69,20 @@ struct CompanySubmissions {
 #[derive(Debug, Deserialize)]
 struct FilingsData {
     pub recent: RecentFilings,
-    pub files: Vec<serde_json::Value>,
+    /// Older filings beyond the recent window, split into further JSON files, newest first
+    pub files: Vec<SubmissionsFile>,
+}
+
+/// An overflow file of older filings, e.g. `CIK0000320193-submissions-001.json`
+#[derive(Debug, Clone, Deserialize)]
+struct SubmissionsFile {
+    pub name: String,
+    #[serde(rename = "filingCount", default)]
+    pub filing_count: usize,
+    #[serde(rename = "filingFrom")]
+    pub filing_from: chrono::NaiveDate,
+    #[serde(rename = "filingTo")]
+    pub filing_to: chrono::NaiveDate,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +113,28 @@ struct RecentFilings {
     pub primary_doc_description: Vec<String>,
 }
 
+impl RecentFilings {
+    /// Combine the parallel arrays into one entry per filing
+    fn entries(&self) -> Vec<FilingEntry> {
+        (0..self.accession_number.len())
+            .map(|i| FilingEntry {
+                accession_number: self.accession_number[i].clone(),
+                filing_date: self.filing_date[i].clone(),
+                report_date: self.report_date.get(i).cloned().unwrap_or_default(),
+                form: self.form[i].clone(),
+                primary_document: self.primary_document.get(i).cloned().unwrap_or_default(),
+                primary_doc_description: self.primary_doc_description.get(i).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// A company's recent filings and the overflow files holding its older ones
+struct CompanyFilings {
+    recent: Vec<FilingEntry>,
+    older_files: Vec<SubmissionsFile>,
+}
+
 #[derive(Debug)]
 struct FilingEntry {
     pub accession_number: String,
@@ -94,99 +145,328 @@ struct FilingEntry {
     pub primary_doc_description: String,
 }
 
-pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<usize> {
-    info!("Starting EDGAR download for ticker: {}", request.ticker);
+pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<DownloadReport, DownloaderError> {
+    let config = Config::from_env()?;
+    download_with_config(request, output_dir, &config).await
+}
+
+pub async fn download_with_config(
+    request: &DownloadRequest,
+    output_dir: &str,
+    config: &Config,
+) -> Result<DownloadReport, DownloaderError> {
+    let client = edgar_client(config)?;
+    let endpoints = &config.endpoints;
+    let template = request.output_template.as_deref().unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+    validate_filename_template(template)?;
+    if matches!(request.format, crate::models::DocumentFormat::Csv | crate::models::DocumentFormat::Pdf) {
+        return Err(DownloaderError::Config(format!(
+            "The {} format is only available for EDINET documents",
+            request.format.as_str()
+        )));
+    }
     
-    let client = Client::builder()
-        .user_agent("fast10k/0.1.0 (your.email@example.com)")
-        .build()?;
+    // A known accession number skips the ticker lookup entirely
+    if let Some(accession_number) = &request.document_id {
+        return download_by_accession_number(&client, request, accession_number, output_dir, config).await;
+    }
+    
+    info!("Starting EDGAR download for ticker: {}", request.ticker);
     
     // Step 1: Find CIK for the ticker
-    let cik = search_company_by_ticker(&client, &request.ticker).await?;
+    let cik = search_company_by_ticker(&client, endpoints, &request.ticker).await?;
     info!("Found CIK {} for ticker {}", cik, request.ticker);
+    if request.dry_run {
+        dry_run::note(format!("Resolved CIK {} for ticker {}", cik, request.ticker));
+    }
     
-    // Step 2: Get company filings
-    let filings = get_company_filings(&client, &cik).await?;
-    info!("Found {} filings for CIK {}", filings.len(), cik);
+    // Step 2: Get company filings; older overflow files are only fetched if still needed
+    let CompanyFilings { recent, older_files } = get_company_filings(&client, endpoints, &cik).await?;
+    info!("Found {} recent filings and {} older submission files for CIK {}", recent.len(), older_files.len(), cik);
+    let mut filings = VecDeque::from(recent);
+    let mut older_files = older_files_in_range(older_files, request.date_from, request.date_to).into_iter();
     
-    let company_dir = Path::new(output_dir).join("edgar").join(&request.ticker);
-    fs::create_dir_all(&company_dir).await?;
+    let company_dir = config.source_dir(output_dir, "edgar").join(&request.ticker);
+    if !request.dry_run {
+        fs::create_dir_all(&company_dir).await?;
+        remove_partial_downloads(&company_dir);
+    }
     
-    let mut download_count = 0;
+    let mut report = DownloadReport::default();
+    let mut first_error = None;
     
-    // Step 3: Download matching filings (limited by request.limit)
-    for filing in filings {
-        // Stop if we've reached the download limit
-        if download_count >= request.limit {
-            break;
-        }
+    // Step 3: Download matching filings (limited by request.limit, counting skipped ones; 0 downloads all)
+    while request.limit == 0 || report.downloaded + report.skipped < request.limit {
+        let Some(filing) = filings.pop_front() else {
+            // Page in the next older submissions file once the ones fetched so far are used up
+            let Some(file) = older_files.next() else {
+                break;
+            };
+            tokio::time::sleep(std::time::Duration::from_millis(config.rate_limits.edgar_api_delay_ms)).await;
+            filings.extend(get_submissions_file(&client, endpoints, &file).await?);
+            continue;
+        };
+        
         // Filter by filing type if specified
         if let Some(ref filing_type) = request.filing_type {
-            if !matches_filing_type(&filing.form, filing_type) {
+            if !matches_filing_type(&filing, filing_type) {
                 continue;
             }
         }
         
         // Filter by date range if specified
-        if let Some(date_from) = request.date_from {
-            let filing_date = chrono::NaiveDate::parse_from_str(&filing.filing_date, "%Y-%m-%d")?;
-            if filing_date < date_from {
+        if request.date_from.is_some() || request.date_to.is_some() {
+            let filing_date = chrono::NaiveDate::parse_from_str(&filing.filing_date, "%Y-%m-%d")
+                .map_err(|e| DownloaderError::InvalidResponse(format!("Filing date '{}': {}", filing.filing_date, e)))?;
+            if request.date_from.is_some_and(|date_from| filing_date < date_from)
+                || request.date_to.is_some_and(|date_to| filing_date > date_to)
+            {
                 continue;
             }
         }
         
-        if let Some(date_to) = request.date_to {
-            let filing_date = chrono::NaiveDate::parse_from_str(&filing.filing_date, "%Y-%m-%d")?;
-            if filing_date > date_to {
-                continue;
-            }
+        let accession_clean = filing.accession_number.replace("-", "");
+        let fields = FilenameFields {
+            form: &filing.form,
+            date: &filing.filing_date,
+            accession: &accession_clean,
+            ticker: &request.ticker,
+            cik: &cik,
+        };
+        let file_path = company_dir.join(render_filename(template, &fields, request.format.file_extension())?);
+        
+        if request.skip_existing && is_existing_download(&file_path) {
+            debug!("Skipping existing filing: {}", file_path.display());
+            report.skipped += 1;
+            continue;
         }
         
-        let filename = format!("{}-{}-{}.{}", 
-            filing.form.replace("/", "-"), 
-            filing.filing_date, 
-            filing.accession_number.replace("-", ""),
-            request.format.file_extension());
-        let file_path = company_dir.join(filename);
+        if request.dry_run {
+            print_filing_requests(config, &filing.accession_number, Some(&filing.primary_document), &file_path, &request.format)?;
+            report.downloaded += 1;
+            continue;
+        }
         
-        match download_filing(&client, &filing.accession_number, &file_path, &request.format).await {
+        match download_filing(&client, config, &filing.accession_number, Some(&filing.primary_document), &file_path, &request.format).await {
             Ok(_) => {
                 info!("Downloaded filing: {}", file_path.display());
-                download_count += 1;
+                report.downloaded += 1;
             }
             Err(e) => {
                 warn!("Failed to download filing {}: {}", filing.accession_number, e);
+                report.failed.push((filing.accession_number.clone(), e.to_string()));
+                first_error.get_or_insert(e);
             }
         }
     }
     
-    info!("Downloaded {} filings for ticker {}", download_count, request.ticker);
-    Ok(download_count)
+    // Report why nothing could be downloaded, so callers can tell e.g. rate limiting apart
+    if let (0, Some(e)) = (report.downloaded, first_error) {
+        return Err(e);
+    }
+    
+    info!("Downloaded {} filings for ticker {} ({} already present)", report.downloaded, request.ticker, report.skipped);
+    Ok(report)
 }
 
-fn matches_filing_type(form: &str, filing_type: &crate::models::FilingType) -> bool {
+/// Download a single filing by accession number, named like ticker-based downloads when it is indexed
+async fn download_by_accession_number(
+    client: &Client,
+    request: &DownloadRequest,
+    accession_number: &str,
+    output_dir: &str,
+    config: &Config,
+) -> Result<DownloadReport, DownloaderError> {
+    info!("Starting EDGAR download for accession number: {}", accession_number);
+    
+    let indexed = crate::storage::get_document_by_id(accession_number, config.database_path_str())
+        .await
+        .unwrap_or_else(|e| {
+            debug!("Could not look up {} in the index: {}", accession_number, e);
+            None
+        });
+    
+    let template = request.output_template.as_deref().unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+    let accession_clean = accession_number.replace("-", "");
+    let (ticker, filename) = match &indexed {
+        Some(document) => {
+            let date = document.date.to_string();
+            let fields = FilenameFields {
+                form: document.filing_type.as_str(),
+                date: &date,
+                accession: &accession_clean,
+                ticker: &document.ticker,
+                // Accession numbers start with the filer's zero-padded CIK
+                cik: accession_clean.get(..10).unwrap_or_default(),
+            };
+            (document.ticker.clone(), render_filename(template, &fields, request.format.file_extension())?)
+        }
+        // Without the index there is no form or date to name the file by
+        None => (
+            if request.ticker.is_empty() { "unknown".to_string() } else { request.ticker.clone() },
+            format!("{}.{}", accession_clean, request.format.file_extension()),
+        ),
+    };
+    
+    let company_dir = config.source_dir(output_dir, "edgar").join(&ticker);
+    let file_path = company_dir.join(filename);
+    
+    if request.skip_existing && is_existing_download(&file_path) {
+        info!("Filing already downloaded: {}", file_path.display());
+        return Ok(DownloadReport { skipped: 1, ..Default::default() });
+    }
+    
+    if request.dry_run {
+        dry_run::note(format!(
+            "{} {} in the index at {}",
+            accession_number,
+            if indexed.is_some() { "found" } else { "not found" },
+            config.database_path_str()
+        ));
+        print_filing_requests(config, accession_number, None, &file_path, &request.format)?;
+        return Ok(DownloadReport { downloaded: 1, ..Default::default() });
+    }
+    
+    fs::create_dir_all(&company_dir).await?;
+    
+    download_filing(client, config, accession_number, None, &file_path, &request.format).await?;
+    info!("Downloaded filing: {}", file_path.display());
+    Ok(DownloadReport { downloaded: 1, ..Default::default() })
+}
+
+/// Values interpolated into a file name template
+struct FilenameFields<'a> {
+    form: &'a str,
+    date: &'a str,
+    /// Accession number without dashes
+    accession: &'a str,
+    ticker: &'a str,
+    /// Zero-padded 10-digit CIK
+    cik: &'a str,
+}
+
+impl FilenameFields<'_> {
+    fn get(&self, name: &str) -> Option<&str> {
+        match name {
+            "form" => Some(self.form),
+            "date" => Some(self.date),
+            "accession" => Some(self.accession),
+            "ticker" => Some(self.ticker),
+            "cik" => Some(self.cik),
+            _ => None,
+        }
+    }
+}
+
+/// Check that a file name template only uses known `{field}`s and names a file, not a path
+pub fn validate_filename_template(template: &str) -> Result<(), DownloaderError> {
+    let fields = FilenameFields { form: "", date: "", accession: "", ticker: "", cik: "" };
+    render_filename(template, &fields, "").map(|_| ())
+}
+
+/// Render a file name template such as `{ticker}_{form}_{date}` and append the extension;
+/// `/` in values (e.g. `10-K/A`) becomes `-` so they cannot create directories
+fn render_filename(template: &str, fields: &FilenameFields, extension: &str) -> Result<String, DownloaderError> {
+    let invalid = |reason: String| DownloaderError::Config(format!("Output template '{}': {}", template, reason));
+    if template.contains(['/', '\\']) {
+        return Err(invalid("must be a file name without path separators".to_string()));
+    }
+
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| invalid("unclosed '{'".to_string()))? + start;
+        let field = &rest[start + 1..end];
+        let value = fields.get(field).ok_or_else(|| {
+            invalid(format!("unknown field {{{}}}, expected one of {}", field, FILENAME_FIELDS.join(", ")))
+        })?;
+        name.push_str(&value.replace('/', "-"));
+        rest = &rest[end + 1..];
+    }
+    name.push_str(rest);
+
+    if name.trim().is_empty() {
+        return Err(invalid("renders an empty file name".to_string()));
+    }
+    Ok(format!("{}.{}", name, extension))
+}
+
+/// Whether a filing is of the requested type; transcripts are recognized by their description,
+/// since EDGAR files them under other forms (usually 8-K or 425)
+fn matches_filing_type(filing: &FilingEntry, filing_type: &crate::models::FilingType) -> bool {
     use crate::models::FilingType;
+    let form = filing.form.as_str();
     match filing_type {
         FilingType::TenK => form.starts_with("10-K"),
         FilingType::TenQ => form.starts_with("10-Q"),
         FilingType::EightK => form.starts_with("8-K"),
+        FilingType::Transcript => FilingType::is_transcript_title(&filing.primary_doc_description),
         FilingType::Other(form_type) => form == form_type,
         _ => false,
     }
 }
 
-async fn search_company_by_ticker(client: &Client, ticker: &str) -> Result<String> {
-    let url = "https://www.sec.gov/files/company_tickers.json";
+/// HTTP client for SEC requests; SEC requires a user agent with contact details
+fn edgar_client(config: &Config) -> Result<Client, DownloaderError> {
+    if !config.has_edgar_contact() {
+        warn!(
+            "FAST10K_CONTACT_EMAIL is not set; EDGAR requests use the placeholder contact {} and may be rate-limited or blocked",
+            crate::config::EDGAR_PLACEHOLDER_CONTACT
+        );
+    }
+
+    Ok(config
+        .http_client_builder()?
+        .user_agent(config.edgar_user_agent())
+        .build()?)
+}
+
+/// Check that the SEC EDGAR endpoints are reachable, returning the negotiated content-encoding
+///
+/// The regular client decompresses transparently and strips the header, so this check
+/// asks for the same encodings with decompression turned off to see what SEC actually sends.
+pub async fn check_connectivity(config: &Config) -> Result<Option<String>, DownloaderError> {
+    let client = config
+        .http_client_builder()?
+        .user_agent(config.edgar_user_agent())
+        .no_gzip()
+        .no_brotli()
+        .no_deflate()
+        .build()?;
+    
+    let response = client
+        .get(format!("{}/files/company_tickers.json", config.endpoints.edgar_base_url))
+        .header(reqwest::header::ACCEPT_ENCODING, "gzip, br, deflate")
+        .send()
+        .await?;
+    
+    if !response.status().is_success() {
+        return Err(DownloaderError::from_status(SERVICE, response.status(), "Failed to reach EDGAR"));
+    }
+    
+    let encoding = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    debug!("EDGAR content-encoding: {}", encoding.as_deref().unwrap_or("identity"));
+    
+    Ok(encoding)
+}
+
+async fn search_company_by_ticker(client: &Client, endpoints: &ApiEndpoints, ticker: &str) -> Result<String, DownloaderError> {
+    let url = format!("{}/files/company_tickers.json", endpoints.edgar_base_url);
     
     debug!("Fetching company tickers from: {}", url);
     let response = client
-        .get(url)
+        .get(&url)
         .header("Accept", "application/json")
         .send()
         .await?;
     
     if !response.status().is_success() {
-        return Err(anyhow!("Failed to fetch company tickers: HTTP {}", response.status()));
+        return Err(DownloaderError::from_status(SERVICE, response.status(), "Failed to fetch company tickers"));
     }
     
     let tickers: HashMap<String, CompanyTicker> = response.json().await?;
@@ -201,11 +481,11 @@ async fn search_company_by_ticker(client: &Client, ticker: &str) -> Result<Strin
         }
     }
     
-    Err(anyhow!("Ticker {} not found in EDGAR database", ticker))
+    Err(DownloaderError::NotFound(format!("Ticker {} not found in EDGAR database", ticker)))
 }
 
-async fn get_company_filings(client: &Client, cik: &str) -> Result<Vec<FilingEntry>> {
-    let url = format!("https://data.sec.gov/submissions/CIK{}.json", cik);
+async fn get_company_filings(client: &Client, endpoints: &ApiEndpoints, cik: &str) -> Result<CompanyFilings, DownloaderError> {
+    let url = format!("{}/submissions/CIK{}.json", endpoints.edgar_data_url, cik);
     
     debug!("Fetching company submissions from: {}", url);
     let response = client
@@ -215,54 +495,200 @@ async fn get_company_filings(client: &Client, cik: &str) -> Result<Vec<FilingEnt
         .await?;
     
     if !response.status().is_success() {
-        return Err(anyhow!("Failed to fetch company submissions: HTTP {}", response.status()));
+        return Err(DownloaderError::from_status(
+            SERVICE,
+            response.status(),
+            format!("No submissions found for CIK {}", cik),
+        ));
     }
     
     let submissions: CompanySubmissions = response.json().await?;
-    let recent = &submissions.filings.recent;
-    
-    let mut filings = Vec::new();
-    
-    // Combine all the parallel arrays into FilingEntry structs
-    let len = recent.accession_number.len();
-    for i in 0..len {
-        filings.push(FilingEntry {
-            accession_number: recent.accession_number[i].clone(),
-            filing_date: recent.filing_date[i].clone(),
-            report_date: recent.report_date.get(i).cloned().unwrap_or_default(),
-            form: recent.form[i].clone(),
-            primary_document: recent.primary_document.get(i).cloned().unwrap_or_default(),
-            primary_doc_description: recent.primary_doc_description.get(i).cloned().unwrap_or_default(),
-        });
+    let recent = submissions.filings.recent.entries();
+    
+    info!("Retrieved {} recent filings for CIK {}", recent.len(), cik);
+    Ok(CompanyFilings { recent, older_files: submissions.filings.files })
+}
+
+/// Older submissions files that can hold filings in the date range, newest first
+///
+/// Files are listed newest first, so everything after the first file ending before `date_from` is dropped.
+fn older_files_in_range(
+    files: Vec<SubmissionsFile>,
+    date_from: Option<chrono::NaiveDate>,
+    date_to: Option<chrono::NaiveDate>,
+) -> Vec<SubmissionsFile> {
+    files
+        .into_iter()
+        .take_while(|file| date_from.is_none_or(|from| file.filing_to >= from))
+        .filter(|file| date_to.is_none_or(|to| file.filing_from <= to))
+        .collect()
+}
+
+/// Fetch the filings in an older submissions file, retrying rate limits and network errors
+async fn get_submissions_file(
+    client: &Client,
+    endpoints: &ApiEndpoints,
+    file: &SubmissionsFile,
+) -> Result<Vec<FilingEntry>, DownloaderError> {
+    let url = format!("{}/submissions/{}", endpoints.edgar_data_url, file.name);
+    
+    let mut attempt = 1;
+    loop {
+        debug!("Fetching {} older filings from: {} (attempt {})", file.filing_count, url, attempt);
+        match fetch_submissions_file(client, &url).await {
+            Ok(filings) => {
+                let entries = filings.entries();
+                info!("Retrieved {} filings from {} to {} in {}", entries.len(), file.filing_from, file.filing_to, file.name);
+                return Ok(entries);
+            }
+            Err(e) if e.is_retryable() && attempt < SUBMISSIONS_FILE_ATTEMPTS => {
+                warn!("Failed to fetch {} (attempt {}): {}, retrying", file.name, attempt, e);
+                tokio::time::sleep(SUBMISSIONS_FILE_BACKOFF * attempt).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn fetch_submissions_file(client: &Client, url: &str) -> Result<RecentFilings, DownloaderError> {
+    let response = client
+        .get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await?;
+    
+    if !response.status().is_success() {
+        return Err(DownloaderError::from_status(
+            SERVICE,
+            response.status(),
+            format!("Submissions file not found: {}", url),
+        ));
     }
     
-    info!("Retrieved {} recent filings for CIK {}", filings.len(), cik);
-    Ok(filings)
+    Ok(response.json().await?)
+}
+
+/// Print the requests `download_filing` would try for a filing, in fallback order
+fn print_filing_requests(
+    config: &Config,
+    accession_number: &str,
+    primary_document: Option<&str>,
+    output_path: &Path,
+    format: &crate::models::DocumentFormat,
+) -> Result<(), DownloaderError> {
+    let primary_document = primary_document.filter(|name| !name.is_empty());
+    if primary_document.is_none() && uses_primary_document(format) {
+        let url = format!("{}/index.json", filing_base_url(&config.endpoints, accession_number)?);
+        let request = RequestPreview::get(url).with_header("User-Agent", &config.edgar_user_agent());
+        dry_run::print_request(&request, output_path);
+    }
+    for url in filing_urls(&config.endpoints, accession_number, primary_document, format)? {
+        let request = RequestPreview::get(url)
+            .with_header("User-Agent", &config.edgar_user_agent())
+            .with_header("Accept", FILING_ACCEPT);
+        dry_run::print_request(&request, output_path);
+    }
+    Ok(())
+}
+
+/// Filing index listing the documents of a filing, served as `index.json` in its directory
+#[derive(Debug, Deserialize)]
+struct FilingIndex {
+    directory: FilingDirectory,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilingDirectory {
+    #[serde(default)]
+    item: Vec<FilingIndexItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilingIndexItem {
+    name: String,
+    /// Size in bytes; EDGAR sends it as a string, empty for subdirectories
+    #[serde(default)]
+    size: serde_json::Value,
 }
 
-async fn download_filing(client: &Client, accession_number: &str, output_path: &Path, format: &crate::models::DocumentFormat) -> Result<()> {
+impl FilingIndex {
+    /// The filing's main document: its largest HTML file that is not an index page
+    fn primary_document(&self) -> Option<&str> {
+        self.directory
+            .item
+            .iter()
+            .filter(|item| {
+                let name = item.name.to_ascii_lowercase();
+                (name.ends_with(".htm") || name.ends_with(".html")) && !name.contains("-index")
+            })
+            .max_by_key(|item| item.size.as_u64().or_else(|| item.size.as_str()?.parse().ok()).unwrap_or(0))
+            .map(|item| item.name.as_str())
+    }
+}
+
+/// Formats served by a filing's primary document rather than a file named after its accession number
+fn uses_primary_document(format: &crate::models::DocumentFormat) -> bool {
+    matches!(format, crate::models::DocumentFormat::Html | crate::models::DocumentFormat::Ixbrl)
+}
+
+/// Name of a filing's primary document from its `index.json`, or None if the index can't be read
+async fn find_primary_document(client: &Client, config: &Config, accession_number: &str) -> Option<String> {
+    let url = format!("{}/index.json", filing_base_url(&config.endpoints, accession_number).ok()?);
+    let index = async {
+        let response = client.get(&url).timeout(config.download_timeout()).send().await?.error_for_status()?;
+        response.json::<FilingIndex>().await
+    };
+    match index.await {
+        Ok(index) => index.primary_document().map(str::to_string),
+        Err(e) => {
+            debug!("Could not read filing index {}: {}", url, e);
+            None
+        }
+    }
+}
+
+/// Directory holding a filing's documents
+fn filing_base_url(endpoints: &ApiEndpoints, accession_number: &str) -> Result<String, DownloaderError> {
     // Format the accession number for the URL (remove dashes)
     let accession_clean = accession_number.replace("-", "");
     
     // Extract CIK from accession number (first 10 digits)
     if accession_clean.len() < 10 {
-        return Err(anyhow!("Invalid accession number format: {}", accession_number));
+        return Err(DownloaderError::InvalidResponse(format!("Invalid accession number format: {}", accession_number)));
     }
     
     let cik = &accession_clean[0..10];
     let cik_num = cik.parse::<u64>()
-        .map_err(|_| anyhow!("Invalid CIK in accession number: {}", accession_number))?;
+        .map_err(|_| DownloaderError::InvalidResponse(format!("Invalid CIK in accession number: {}", accession_number)))?;
     
     // EDGAR filing URLs follow the pattern:
     // https://www.sec.gov/Archives/edgar/data/{CIK}/{accession_clean}/{primary_document}
-    let base_url = format!(
-        "https://www.sec.gov/Archives/edgar/data/{}/{}",
+    Ok(format!(
+        "{}/Archives/edgar/data/{}/{}",
+        endpoints.edgar_base_url,
         cik_num, // Use numeric CIK without leading zeros for URL
         accession_clean
-    );
+    ))
+}
+
+/// Candidate URLs for a filing document, tried in order until one succeeds
+fn filing_urls(
+    endpoints: &ApiEndpoints,
+    accession_number: &str,
+    primary_document: Option<&str>,
+    format: &crate::models::DocumentFormat,
+) -> Result<Vec<String>, DownloaderError> {
+    let base_url = filing_base_url(endpoints, accession_number)?;
+    let accession_clean = accession_number.replace("-", "");
+    let mut urls: Vec<String> = primary_document
+        .filter(|_| uses_primary_document(format))
+        .map(|name| format!("{}/{}", base_url, name))
+        .into_iter()
+        .collect();
     
-    // Try different document name patterns with retry logic based on format
-    let document_urls = match format {
+    // Fall back to common document name patterns based on format
+    urls.extend(match format {
         crate::models::DocumentFormat::Txt => vec![
             format!("{}/{}.txt", base_url, accession_number),
             format!("{}/{}-index.html", base_url, accession_number),
@@ -280,31 +706,60 @@ async fn download_filing(client: &Client, accession_number: &str, output_path: &
             format!("{}/{}.htm", base_url, accession_number),
             format!("{}/{}-index.html", base_url, accession_number),
         ],
+        crate::models::DocumentFormat::Summary => vec![
+            // Filing index only: lists the documents without downloading them
+            format!("{}/index.json", base_url),
+        ],
         crate::models::DocumentFormat::Complete => vec![
             format!("{}/complete-submission.zip", base_url),
             format!("{}/{}-complete.zip", base_url, accession_number),
         ],
+        crate::models::DocumentFormat::Csv | crate::models::DocumentFormat::Pdf => {
+            return Err(DownloaderError::Config(format!("The {} format is only available for EDINET documents", format.as_str())))
+        }
         crate::models::DocumentFormat::Other(_) => vec![
             format!("{}/complete-submission.zip", base_url),
             format!("{}/{}-complete.zip", base_url, accession_number),
         ],
+    });
+    Ok(urls)
+}
+
+/// Download a filing, looking up its primary document in the filing index when it isn't known
+async fn download_filing(
+    client: &Client,
+    config: &Config,
+    accession_number: &str,
+    primary_document: Option<&str>,
+    output_path: &Path,
+    format: &crate::models::DocumentFormat,
+) -> Result<(), DownloaderError> {
+    let looked_up;
+    let primary_document = match primary_document.filter(|name| !name.is_empty()) {
+        Some(name) => Some(name),
+        None if uses_primary_document(format) => {
+            looked_up = find_primary_document(client, config, accession_number).await;
+            looked_up.as_deref()
+        }
+        None => None,
     };
     
-    for url in document_urls {
+    let mut last_error = None;
+    for url in filing_urls(&config.endpoints, accession_number, primary_document, format)? {
         for attempt in 1..=3 {
             debug!("Attempting to download from: {} (attempt {})", url, attempt);
             
             let response = match client
                 .get(&url)
-                .header("Accept", "text/html,text/plain,*/*")
-                .header("User-Agent", "fast10k/0.1.0 (your.email@example.com)")
-                .timeout(std::time::Duration::from_secs(30))
+                .header("Accept", FILING_ACCEPT)
+                .timeout(config.download_timeout())
                 .send()
                 .await
             {
                 Ok(resp) => resp,
                 Err(e) => {
                     warn!("Request failed for {} (attempt {}): {}", url, attempt, e);
+                    last_error = Some(DownloaderError::Network(e));
                     if attempt < 3 {
                         tokio::time::sleep(std::time::Duration::from_millis(1000 * attempt as u64)).await;
                         continue;
@@ -317,15 +772,16 @@ async fn download_filing(client: &Client, accession_number: &str, output_path: &
             if response.status().is_success() {
                 match response.text().await {
                     Ok(content) => {
-                        if let Err(e) = fs::write(output_path, content).await {
+                        if let Err(e) = write_download(output_path, content) {
                             error!("Failed to write file {}: {}", output_path.display(), e);
-                            return Err(anyhow!("Failed to write downloaded content: {}", e));
+                            return Err(DownloaderError::Io(e));
                         }
                         info!("Successfully downloaded filing to: {}", output_path.display());
                         return Ok(());
                     }
                     Err(e) => {
                         warn!("Failed to read response content: {}", e);
+                        last_error = Some(DownloaderError::Network(e));
                         if attempt < 3 {
                             tokio::time::sleep(std::time::Duration::from_millis(1000 * attempt as u64)).await;
                             continue;
@@ -335,14 +791,357 @@ async fn download_filing(client: &Client, accession_number: &str, output_path: &
             } else if response.status().as_u16() == 429 {
                 // Rate limited - wait longer before retry
                 warn!("Rate limited, waiting before retry...");
+                last_error = Some(DownloaderError::RateLimited(SERVICE.to_string()));
                 tokio::time::sleep(std::time::Duration::from_secs(5)).await;
                 continue;
             } else {
                 debug!("HTTP {} for URL: {}", response.status(), url);
+                last_error = Some(DownloaderError::from_status(
+                    SERVICE,
+                    response.status(),
+                    format!("Filing {} not found at any attempted URL", accession_number),
+                ));
                 break; // Try next URL
             }
         }
     }
     
-    Err(anyhow!("Failed to download filing {} from any attempted URL after retries", accession_number))
-}
\ No newline at end of file
+    Err(last_error.unwrap_or_else(|| {
+        DownloaderError::NotFound(format!("Filing {} not found at any attempted URL", accession_number))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DocumentFormat, FilingType, Source};
+    use crate::test_support::{edgar_company_tickers, edgar_filings, edgar_submissions, mock_config};
+    use chrono::NaiveDate;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn request(ticker: &str) -> DownloadRequest {
+        DownloadRequest {
+            source: Source::Edgar,
+            ticker: ticker.to_string(),
+            filing_type: Some(FilingType::TenK),
+            date_from: None,
+            date_to: None,
+            limit: 5,
+            format: DocumentFormat::Txt,
+            document_id: None,
+            skip_existing: false,
+            dry_run: false,
+            include_attachments: false,
+            output_template: None,
+        }
+    }
+
+    async fn mount_company(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/files/company_tickers.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edgar_company_tickers(&[(320193, "AAPL", "Apple Inc.")])))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_render_filename() {
+        let fields = FilenameFields {
+            form: "10-K/A",
+            date: "2024-11-01",
+            accession: "000032019324000123",
+            ticker: "AAPL",
+            cik: "0000320193",
+        };
+
+        assert_eq!(
+            render_filename(DEFAULT_FILENAME_TEMPLATE, &fields, "txt").unwrap(),
+            "10-K-A-2024-11-01-000032019324000123.txt"
+        );
+        assert_eq!(render_filename("{ticker}_{cik}_{form}", &fields, "htm").unwrap(), "AAPL_0000320193_10-K-A.htm");
+
+        assert!(validate_filename_template("{ticker}-{year}").unwrap_err().to_string().contains("{year}"));
+        assert!(validate_filename_template("{ticker").is_err());
+        assert!(validate_filename_template("{ticker}/{form}").is_err());
+    }
+
+    #[test]
+    fn test_transcripts_match_by_description() {
+        let filing = |form: &str, description: &str| FilingEntry {
+            accession_number: "0000320193-24-000100".to_string(),
+            filing_date: "2024-10-31".to_string(),
+            report_date: String::new(),
+            form: form.to_string(),
+            primary_document: "transcript.htm".to_string(),
+            primary_doc_description: description.to_string(),
+        };
+
+        let call = filing("8-K", "Q4 2024 Earnings Call Transcript");
+        assert!(matches_filing_type(&call, &FilingType::Transcript));
+        assert!(matches_filing_type(&call, &FilingType::EightK));
+        assert!(!matches_filing_type(&filing("8-K", "Current report"), &FilingType::Transcript));
+        assert!(!matches_filing_type(&filing("10-K", "Annual report"), &FilingType::Transcript));
+    }
+
+    #[tokio::test]
+    async fn test_download_matching_filings() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edgar_submissions(
+                "0000320193",
+                "Apple Inc.",
+                &[
+                    ("0000320193-24-000123", "10-K", "2024-11-01"),
+                    ("0000320193-24-000100", "8-K", "2024-10-31"),
+                ],
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Archives/edgar/data/320193/000032019324000123/0000320193-24-000123.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ANNUAL REPORT"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let report = download_with_config(&request("aapl"), output_dir.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        let file = output_dir.join("edgar/aapl/10-K-2024-11-01-000032019324000123.txt");
+        assert_eq!(std::fs::read_to_string(file).unwrap(), "ANNUAL REPORT");
+    }
+
+    #[tokio::test]
+    async fn test_html_downloads_the_primary_document() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edgar_submissions(
+                "0000320193",
+                "Apple Inc.",
+                &[("0000320193-24-000123", "10-K", "2024-11-01")],
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Archives/edgar/data/320193/000032019324000123/primary.htm"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>ANNUAL REPORT</html>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest { format: DocumentFormat::Html, ..request("aapl") };
+        let report = download_with_config(&request, output_dir.to_str().unwrap(), &config).await.unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        let file = output_dir.join("edgar/aapl/10-K-2024-11-01-000032019324000123.htm");
+        assert_eq!(std::fs::read_to_string(file).unwrap(), "<html>ANNUAL REPORT</html>");
+    }
+
+    #[tokio::test]
+    async fn test_accession_download_finds_the_primary_document_in_the_filing_index() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/Archives/edgar/data/320193/000032019324000123/index.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "directory": {
+                    "name": "/Archives/edgar/data/320193/000032019324000123",
+                    "item": [
+                        { "name": "0000320193-24-000123-index.html", "type": "text.gif", "size": "" },
+                        { "name": "R1.htm", "type": "text.gif", "size": "3021" },
+                        { "name": "aapl-20240928.htm", "type": "text.gif", "size": "1520344" },
+                        { "name": "aapl-20240928_htm.xml", "type": "text.gif", "size": "2011452" },
+                    ]
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Archives/edgar/data/320193/000032019324000123/aapl-20240928.htm"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>ANNUAL REPORT</html>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest {
+            format: DocumentFormat::Html,
+            document_id: Some("0000320193-24-000123".to_string()),
+            ..request("aapl")
+        };
+        let report = download_with_config(&request, output_dir.to_str().unwrap(), &config).await.unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        let file = output_dir.join("edgar/aapl/000032019324000123.htm");
+        assert_eq!(std::fs::read_to_string(file).unwrap(), "<html>ANNUAL REPORT</html>");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_resolves_cik_without_downloading() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edgar_submissions(
+                "0000320193",
+                "Apple Inc.",
+                &[("0000320193-24-000123", "10-K", "2024-11-01")],
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Archives/edgar/data/320193/000032019324000123/0000320193-24-000123.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ANNUAL REPORT"))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest { dry_run: true, ..request("aapl") };
+        let report = download_with_config(&request, output_dir.to_str().unwrap(), &config).await.unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        assert!(!output_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_limit_zero_downloads_every_match() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+        let filings: Vec<(String, &str, String)> = (2015..2025)
+            .map(|year| (format!("0000320193-{}-000100", year % 100), "10-K", format!("{}-11-01", year)))
+            .collect();
+        let filings: Vec<(&str, &str, &str)> = filings.iter().map(|(id, form, date)| (id.as_str(), *form, date.as_str())).collect();
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edgar_submissions("0000320193", "Apple Inc.", &filings)))
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest { dry_run: true, limit: 0, ..request("AAPL") };
+        let report = download_with_config(&request, output_dir.to_str().unwrap(), &config).await.unwrap();
+
+        assert_eq!(report.downloaded, 10);
+    }
+
+    #[tokio::test]
+    async fn test_older_filings_are_paged_in() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+
+        let mut submissions = edgar_submissions(
+            "0000320193",
+            "Apple Inc.",
+            &[("0000320193-24-000123", "10-K", "2024-11-01")],
+        );
+        submissions["filings"]["files"] = json!([
+            { "name": "CIK0000320193-submissions-001.json", "filingCount": 1, "filingFrom": "2005-01-01", "filingTo": "2012-12-31" },
+            { "name": "CIK0000320193-submissions-002.json", "filingCount": 1, "filingFrom": "1994-01-01", "filingTo": "2004-12-31" },
+        ]);
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(submissions))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193-submissions-001.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edgar_filings(&[
+                ("0000320193-10-000012", "10-K", "2010-10-27"),
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Ends before date_from, so it is never fetched
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193-submissions-002.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Archives/edgar/data/320193/000032019310000012/0000320193-10-000012.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ANNUAL REPORT 2010"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut request = request("AAPL");
+        request.date_from = NaiveDate::from_ymd_opt(2008, 1, 1);
+        request.date_to = NaiveDate::from_ymd_opt(2012, 12, 31);
+        let output_dir = temp_dir.path().join("downloads");
+        let report = download_with_config(&request, output_dir.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        assert!(output_dir.join("edgar/AAPL/10-K-2010-10-27-000032019310000012.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_ticker_is_not_found() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+
+        let err = download_with_config(&request("MSFT"), temp_dir.path().to_str().unwrap(), &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DownloaderError::NotFound(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_pdf_is_rejected() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+
+        let request = DownloadRequest { format: DocumentFormat::Pdf, ..request("AAPL") };
+        let err = download_with_config(&request, temp_dir.path().to_str().unwrap(), &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DownloaderError::Config(ref message) if message.contains("pdf")), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_submissions() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        mount_company(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&server)
+            .await;
+
+        let err = download_with_config(&request("AAPL"), temp_dir.path().to_str().unwrap(), &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DownloaderError::RateLimited(_)), "{:?}", err);
+        assert!(err.is_retryable());
+    }
+}
@@ -3,11 +3,11 @@
 //! This module provides the interface for the downloader system to access
 //! EDINET functionality. The actual implementation is in the `edinet` module.
 
-use crate::models::DownloadRequest;
+use crate::models::{DownloadReport, DownloadRequest};
 use crate::edinet;
-use anyhow::Result;
+use super::DownloaderError;
 
 /// Download EDINET documents (delegated to edinet module)
-pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<usize> {
+pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<DownloadReport, DownloaderError> {
     edinet::downloader::download_documents(request, output_dir).await
 }
\ No newline at end of file
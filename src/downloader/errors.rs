@@ -0,0 +1,143 @@
+//! Downloader error types
+
+use crate::edinet::EdinetError;
+use reqwest::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DownloaderError {
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Rate limited by {0}, try again later")]
+    RateLimited(String),
+
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{service} API error (status {status_code}): {message}")]
+    ApiError {
+        service: String,
+        status_code: u16,
+        message: String,
+    },
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Unsupported source: {0}")]
+    UnsupportedSource(String),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+}
+
+impl DownloaderError {
+    /// Classify an unsuccessful HTTP status from `service`
+    pub fn from_status(service: &str, status: StatusCode, message: impl Into<String>) -> Self {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => DownloaderError::RateLimited(service.to_string()),
+            StatusCode::NOT_FOUND => DownloaderError::NotFound(message.into()),
+            _ => DownloaderError::ApiError {
+                service: service.to_string(),
+                status_code: status.as_u16(),
+                message: message.into(),
+            },
+        }
+    }
+
+    /// Plain-language explanation of what went wrong and what to do about it, for the TUI status bar
+    pub fn user_message(&self) -> String {
+        match self {
+            DownloaderError::NotFound(message) | DownloaderError::Config(message) => message.clone(),
+            DownloaderError::RateLimited(service) => {
+                format!("{} is limiting requests. Wait a minute and try again.", service)
+            }
+            DownloaderError::Network(_) => {
+                "Could not reach the server. Check your internet connection, or FAST10K_HTTP_PROXY behind a proxy.".to_string()
+            }
+            DownloaderError::Io(e) => format!("Could not save the download: {}. Check FAST10K_DOWNLOAD_DIR.", e),
+            DownloaderError::ApiError { service, status_code, .. } if service == "EDINET" => {
+                crate::edinet::errors::status_message(*status_code)
+            }
+            DownloaderError::ApiError { service, status_code, .. } => {
+                format!("{} returned an error (status {}). Try again later.", service, status_code)
+            }
+            DownloaderError::InvalidResponse(_) => {
+                "The server sent an unexpected response. Try again later; the log file has the details.".to_string()
+            }
+            DownloaderError::UnsupportedSource(_) => self.to_string(),
+        }
+    }
+
+    /// Whether retrying the same download later may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, DownloaderError::RateLimited(_) | DownloaderError::Network(_))
+    }
+}
+
+impl From<EdinetError> for DownloaderError {
+    fn from(err: EdinetError) -> Self {
+        match err {
+            EdinetError::CompanyNotFound(_) => DownloaderError::NotFound(err.to_string()),
+            EdinetError::ApiError { status_code, message } => {
+                match StatusCode::from_u16(status_code) {
+                    Ok(status) => DownloaderError::from_status("EDINET", status, message),
+                    Err(_) => DownloaderError::ApiError {
+                        service: "EDINET".to_string(),
+                        status_code,
+                        message,
+                    },
+                }
+            }
+            EdinetError::RateLimited { .. } => DownloaderError::RateLimited("EDINET".to_string()),
+            EdinetError::Http(e) => DownloaderError::Network(e),
+            EdinetError::Io(e) => DownloaderError::Io(e),
+            EdinetError::ApiResponseError { .. } | EdinetError::InvalidDate(_) => {
+                DownloaderError::InvalidResponse(err.to_string())
+            }
+            EdinetError::MissingApiKey => DownloaderError::Config(err.user_message()),
+            EdinetError::Database(_) | EdinetError::Config(_) => DownloaderError::Config(err.to_string()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for DownloaderError {
+    fn from(err: anyhow::Error) -> Self {
+        DownloaderError::Config(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edinet_errors_are_classified() {
+        let err = DownloaderError::from(EdinetError::CompanyNotFound("9999".to_string()));
+        assert!(matches!(err, DownloaderError::NotFound(_)));
+        assert!(err.to_string().contains("9999"));
+
+        let err = DownloaderError::from(EdinetError::ApiError { status_code: 429, message: String::new() });
+        assert!(matches!(err, DownloaderError::RateLimited(_)));
+        assert!(err.is_retryable());
+
+        let err = DownloaderError::from(EdinetError::ApiError { status_code: 500, message: "down".to_string() });
+        assert!(matches!(err, DownloaderError::ApiError { status_code: 500, .. }));
+        assert!(!err.is_retryable());
+
+        assert!(matches!(DownloaderError::from(EdinetError::MissingApiKey), DownloaderError::Config(_)));
+    }
+
+    #[test]
+    fn test_user_message() {
+        let err = DownloaderError::from(EdinetError::ApiError { status_code: 403, message: "認証エラー".to_string() });
+        assert!(err.user_message().contains("EDINET_API_KEY"), "{}", err.user_message());
+
+        let err = DownloaderError::from(EdinetError::MissingApiKey);
+        assert!(err.user_message().starts_with("No EDINET API key is configured"));
+    }
+}
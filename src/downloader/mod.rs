@@ -1,20 +1,112 @@
-use anyhow::Result;
-use crate::models::{DownloadRequest, Source};
+use std::path::{Path, PathBuf};
+use crate::models::{DownloadReport, DownloadRequest, Source};
+use crate::style;
 
+pub mod bulk;
+pub mod dry_run;
 pub mod edgar;
 pub mod edinet;
+pub mod errors;
 pub mod tdnet;
 
-pub async fn download_documents(request: &DownloadRequest, output_dir: &str) -> Result<usize> {
+pub use errors::DownloaderError;
+
+/// Suffix of a download still being written; it is renamed to its final name once complete
+pub const PARTIAL_DOWNLOAD_SUFFIX: &str = ".part";
+
+pub async fn download_documents(request: &DownloadRequest, output_dir: &str) -> Result<DownloadReport, DownloaderError> {
     // Create output directory if it doesn't exist
-    std::fs::create_dir_all(output_dir)?;
+    if !request.dry_run {
+        std::fs::create_dir_all(output_dir)?;
+    }
     
     match &request.source {
         Source::Edgar => edgar::download(request, output_dir).await,
         Source::Edinet => edinet::download(request, output_dir).await,
         Source::Tdnet => tdnet::download(request, output_dir).await,
-        Source::Other(name) => {
-            anyhow::bail!("Unsupported source: {}", name)
+        Source::Other(name) => Err(DownloaderError::UnsupportedSource(name.clone())),
+    }
+}
+
+/// Whether a previous run already left a non-empty download at `path`
+pub fn is_existing_download(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.len() > 0)
+}
+
+/// Write a download to a `.part` file and rename it into place, so an interrupted write never
+/// leaves a truncated file under the final name
+pub fn write_download(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(PARTIAL_DOWNLOAD_SUFFIX);
+    let partial = PathBuf::from(partial);
+
+    let result = std::fs::write(&partial, contents).and_then(|()| std::fs::rename(&partial, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Remove `.part` files left in `dir` and its subdirectories by interrupted downloads, returning how many
+pub fn remove_partial_downloads(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            removed += remove_partial_downloads(&path);
+        } else if path.to_string_lossy().ends_with(PARTIAL_DOWNLOAD_SUFFIX) && std::fs::remove_file(&path).is_ok() {
+            tracing::debug!("Removed partial download: {}", path.display());
+            removed += 1;
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_downloads_never_take_the_final_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S100TEST-2024-06-25.zip");
+
+        write_download(&path, b"complete").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"complete");
+        assert!(!dir.path().join("S100TEST-2024-06-25.zip.part").exists());
+
+        // A write that was killed halfway leaves only the .part file behind
+        let nested = dir.path().join("edinet").join("7203");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("S100HALF-2024-06-25.zip.part"), b"trunc").unwrap();
+        assert!(!is_existing_download(&nested.join("S100HALF-2024-06-25.zip")));
+
+        assert_eq!(remove_partial_downloads(dir.path()), 1);
+        assert!(std::fs::read_dir(&nested).unwrap().next().is_none());
+        assert!(path.exists());
+    }
+}
+
+/// Print the outcome of a download command, listing each document that failed
+pub fn print_report(report: &DownloadReport, dry_run: bool) {
+    let verb = if dry_run { "would be downloaded" } else { "downloaded" };
+    let summary = format!(
+        "{} requested: {} {}, {} skipped (already present), {} failed",
+        report.requested(),
+        report.downloaded,
+        verb,
+        report.skipped,
+        report.failed.len()
+    );
+    if report.failed.is_empty() {
+        println!("{}", style::success(summary));
+    } else {
+        println!("{}", style::warning(summary));
+        for (id, error) in &report.failed {
+            println!("{}", style::failure(format!("  ✗ {}: {}", id, error)));
         }
     }
-}
\ No newline at end of file
+}
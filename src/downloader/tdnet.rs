@@ -1,18 +1,21 @@
-use anyhow::Result;
-use reqwest::Client;
-use std::path::Path;
 use tracing::{info, warn};
-use crate::models::DownloadRequest;
+use crate::config::Config;
+use crate::models::{DownloadReport, DownloadRequest};
+use super::DownloaderError;
 
-pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<usize> {
+pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<DownloadReport, DownloaderError> {
     info!("Starting TDNet download for ticker: {}", request.ticker);
     
-    let _client = Client::builder()
-        .user_agent("fast10k/0.1.0")
-        .build()?;
+    if request.dry_run {
+        super::dry_run::note("TDNet downloads are a placeholder; no request would be sent");
+        return Ok(DownloadReport::default());
+    }
+    
+    let config = Config::from_env()?;
+    let _client = config.http_client_builder()?.build()?;
     
     // Create output directory structure
-    let company_dir = Path::new(output_dir).join("tdnet").join(&request.ticker);
+    let company_dir = config.source_dir(output_dir, "tdnet").join(&request.ticker);
     std::fs::create_dir_all(&company_dir)?;
     
     // Placeholder: Create a sample TDNet announcement
@@ -31,7 +34,7 @@ pub async fn download(request: &DownloadRequest, output_dir: &str) -> Result<usi
     // TDNet is the Tokyo Stock Exchange's Timely Disclosure Network
     warn!("TDNet downloader is currently a placeholder implementation");
     
-    Ok(1) // Return count of downloaded documents
+    Ok(DownloadReport { downloaded: 1, ..Default::default() })
 }
 
 // TODO: Implement TDNet functionality
@@ -1,16 +1,29 @@
 //! EDINET document downloading functionality
 
-use crate::edinet::{EdinetDocument, EdinetApi, EdinetError, EdinetErrorResponse};
-use crate::models::DownloadRequest;
+use crate::edinet::{indexer, is_fund_code, EdinetDocument, EdinetApi, EdinetError, EdinetErrorResponse};
+use crate::models::{DocumentFormat, DownloadReport, DownloadRequest};
 use crate::storage;
 use crate::config::Config;
+use crate::downloader::dry_run::{self, RequestPreview};
+use crate::downloader::{is_existing_download, remove_partial_downloads, write_download, DownloaderError};
 use anyhow::Result;
 use reqwest::Client;
 use std::path::Path;
 use tracing::{debug, info, warn};
 
+/// Suffix of attachment ZIPs downloaded alongside a document, so readers can tell them apart
+pub const ATTACHMENTS_FILE_SUFFIX: &str = "-attachments.zip";
+
+/// Suffix of the ZIPs of financial data CSVs downloaded with `--format csv`
+pub const CSV_FILE_SUFFIX: &str = "-csv.zip";
+
+/// Whether a downloaded file is an attachments or CSV ZIP rather than the document ZIP itself
+pub fn is_companion_download(file_name: &str) -> bool {
+    file_name.ends_with(ATTACHMENTS_FILE_SUFFIX) || file_name.ends_with(CSV_FILE_SUFFIX)
+}
+
 /// Download documents from EDINET using the provided request
-pub async fn download_documents(request: &DownloadRequest, output_dir: &str) -> Result<usize> {
+pub async fn download_documents(request: &DownloadRequest, output_dir: &str) -> Result<DownloadReport, DownloaderError> {
     let config = Config::from_env()?;
     download_documents_with_config(request, output_dir, &config).await
 }
@@ -20,37 +33,111 @@ pub async fn download_documents_with_config(
     request: &DownloadRequest,
     output_dir: &str,
     config: &Config,
-) -> Result<usize> {
-    info!("Starting EDINET download for ticker: {}", request.ticker);
+) -> Result<DownloadReport, DownloaderError> {
+    let client = config.http_client_builder()?.build()?;
+
+    let (ticker, documents) = match &request.document_id {
+        // A known document ID skips the company lookup entirely
+        Some(doc_id) => {
+            info!("Starting EDINET download for document: {}", doc_id);
+            let (ticker, document) = get_edinet_document_by_id(doc_id, request, config).await?;
+            (ticker, vec![document])
+        }
+        None => {
+            info!("Starting EDINET download for ticker: {}", request.ticker);
+
+            // Step 1: Search for company by ticker to get EDINET code. Funds are indexed under their
+            // fund code, which is not in the company database.
+            let edinet_code = if is_fund_code(&request.ticker) {
+                info!("Using fund code: {}", request.ticker);
+                request.ticker.clone()
+            } else {
+                if request.dry_run {
+                    dry_run::note(format!(
+                        "Looking up ticker {} in the company table of {}",
+                        request.ticker,
+                        config.database_path_str()
+                    ));
+                }
+                let edinet_code = search_edinet_company(&request.ticker, config).await?;
+                info!("Found EDINET code: {} for ticker: {}", edinet_code, request.ticker);
+                edinet_code
+            };
+            if request.dry_run {
+                dry_run::note(format!("Resolved EDINET code {} for ticker {}", edinet_code, request.ticker));
+            }
 
-    let client = Client::builder()
-        .user_agent(&config.http.user_agent)
-        .timeout(config.http_timeout())
-        .build()?;
+            // Step 2: Get list of available documents from local database
+            let documents = get_edinet_documents_from_db(&edinet_code, request, config).await?;
+            info!("Found {} documents for company", documents.len());
+            (request.ticker.clone(), documents)
+        }
+    };
 
     // Create output directory structure
-    let company_dir = Path::new(output_dir).join("edinet").join(&request.ticker);
+    let company_dir = config.source_dir(output_dir, "edinet").join(&ticker);
+    if request.dry_run {
+        return Ok(print_document_requests(&documents, &company_dir, request, config));
+    }
     std::fs::create_dir_all(&company_dir)?;
+    remove_partial_downloads(&company_dir);
 
-    // Step 1: Search for company by ticker to get EDINET code
-    let edinet_code = search_edinet_company(&request.ticker, config).await?;
-    info!("Found EDINET code: {} for ticker: {}", edinet_code, request.ticker);
+    let mut report = DownloadReport::default();
 
-    // Step 2: Get list of available documents from local database
-    let documents = get_edinet_documents_from_db(&edinet_code, request, config).await?;
-    info!("Found {} documents for company", documents.len());
+    // Summary downloads only store the indexed metadata, no API calls needed
+    if matches!(request.format, DocumentFormat::Summary) {
+        for document in &documents {
+            let output_path = company_dir.join(summary_file_name(document));
+            if request.skip_existing && is_existing_download(&output_path) {
+                debug!("Skipping existing summary: {}", output_path.display());
+                report.skipped += 1;
+                continue;
+            }
+            match write_edinet_summary(document, &output_path) {
+                Ok(()) => {
+                    report.downloaded += 1;
+                    info!("✓ Saved summary: {}", output_path.display());
+                }
+                Err(e) => {
+                    let doc_id = document.doc_id.as_deref().unwrap_or("unknown");
+                    warn!("✗ Failed to save summary {}: {}", doc_id, e);
+                    report.failed.push((doc_id.to_string(), e.to_string()));
+                }
+            }
+        }
 
-    let mut downloaded_count = 0;
+        info!("Saved {} EDINET document summaries", report.downloaded);
+        return Ok(report);
+    }
 
     // Step 3: Download each document
+    let is_csv = matches!(request.format, DocumentFormat::Csv);
+    let is_pdf = matches!(request.format, DocumentFormat::Pdf);
+    let mut first_error = None;
     for (index, document) in documents.iter().enumerate() {
-        let file_name = format!(
-            "{}-{}.zip",
-            document.doc_id.as_deref().unwrap_or("unknown"),
-            document.submit_date.as_deref().unwrap_or("unknown")
-        );
+        let (download_type, file_name) = document_download(document, &request.format);
         let output_path = company_dir.join(file_name);
 
+        if is_csv && !has_csv(document) {
+            let doc_id = document.doc_id.as_deref().unwrap_or("unknown");
+            warn!("✗ Document {} has no CSV data", doc_id);
+            report.failed.push((doc_id.to_string(), "EDINET has no CSV data for this document".to_string()));
+            continue;
+        }
+
+        if is_pdf && !has_pdf(document) {
+            let doc_id = document.doc_id.as_deref().unwrap_or("unknown");
+            warn!("✗ Document {} has no PDF", doc_id);
+            report.failed.push((doc_id.to_string(), "EDINET has no PDF for this document".to_string()));
+            continue;
+        }
+
+        if request.skip_existing && is_existing_download(&output_path) {
+            debug!("Skipping existing document: {}", output_path.display());
+            report.skipped += 1;
+            continue;
+        }
+
         // Log document details before downloading
         info!(
             "Downloading document {}/{}: {} - {} ({})",
@@ -64,26 +151,197 @@ pub async fn download_documents_with_config(
             document.submit_date.as_deref().unwrap_or("unknown date")
         );
 
-        match download_edinet_document(&client, document, &output_path, config).await {
+        match download_edinet_document(&client, document, download_type, &output_path, config).await {
             Ok(()) => {
-                downloaded_count += 1;
+                report.downloaded += 1;
                 info!("✓ Successfully downloaded: {}", output_path.display());
+                // CSV ZIPs hold no text sections to store
+                if let (true, false, Some(doc_id)) = (config.indexing.store_content, is_csv, document.doc_id.as_deref()) {
+                    if let Err(e) = indexer::store_zip_content(&output_path, doc_id, config.database_path_str()).await {
+                        warn!("Could not store the content of {}: {}", doc_id, e);
+                    }
+                }
             }
             Err(e) => {
-                warn!(
-                    "✗ Failed to download document {}: {}",
-                    document.doc_id.as_deref().unwrap_or("unknown"),
-                    e
-                );
+                let doc_id = document.doc_id.as_deref().unwrap_or("unknown");
+                warn!("✗ Failed to download document {}: {}", doc_id, e);
+                report.failed.push((doc_id.to_string(), e.to_string()));
+                first_error.get_or_insert(e);
             }
         }
 
         // Rate limiting - EDINET API has usage limits
         tokio::time::sleep(config.edinet_download_delay()).await;
+
+        if request.include_attachments && has_attachments(document) {
+            download_attachments(&client, document, &company_dir, request, config).await;
+        }
+    }
+
+    // Report why nothing could be downloaded, so callers can tell e.g. rate limiting apart
+    if let (0, Some(e)) = (report.downloaded, first_error) {
+        return Err(e.into());
+    }
+
+    info!("Downloaded {} EDINET documents", report.downloaded);
+    Ok(report)
+}
+
+/// Download a document's attachments next to its ZIP; failures are only logged since the main
+/// document was already handled
+async fn download_attachments(
+    client: &Client,
+    document: &EdinetDocument,
+    company_dir: &Path,
+    request: &DownloadRequest,
+    config: &Config,
+) {
+    let output_path = company_dir.join(attachments_file_name(document));
+    if request.skip_existing && is_existing_download(&output_path) {
+        debug!("Skipping existing attachments: {}", output_path.display());
+        return;
+    }
+
+    match download_edinet_document(client, document, EdinetApi::DOWNLOAD_TYPE_ATTACHMENTS, &output_path, config).await {
+        Ok(()) => info!("✓ Downloaded attachments: {}", output_path.display()),
+        Err(e) => warn!(
+            "✗ Failed to download attachments for {}: {}",
+            document.doc_id.as_deref().unwrap_or("unknown"),
+            e
+        ),
+    }
+
+    tokio::time::sleep(config.edinet_download_delay()).await;
+}
+
+/// Whether a document may have attachments; unindexed documents have no flag and are tried anyway
+fn has_attachments(document: &EdinetDocument) -> bool {
+    document.attach_doc_flag.as_deref() != Some("0")
+}
+
+/// Whether a document may have CSV financial data; unindexed documents are tried anyway
+fn has_csv(document: &EdinetDocument) -> bool {
+    document.csv_flag.as_deref() != Some("0")
+}
+
+/// Whether a document may have a PDF; unindexed documents are tried anyway
+fn has_pdf(document: &EdinetDocument) -> bool {
+    document.pdf_flag.as_deref() != Some("0")
+}
+
+/// Download `type` and file name of a document in the requested format
+fn document_download(document: &EdinetDocument, format: &DocumentFormat) -> (&'static str, String) {
+    match format {
+        DocumentFormat::Csv => (EdinetApi::DOWNLOAD_TYPE_CSV, csv_file_name(document)),
+        DocumentFormat::Pdf => (EdinetApi::DOWNLOAD_TYPE_PDF, pdf_file_name(document)),
+        _ => (EdinetApi::DOWNLOAD_TYPE_ZIP, zip_file_name(document)),
+    }
+}
+
+/// Print the requests a download would send for each document, reporting how many would be downloaded
+fn print_document_requests(
+    documents: &[EdinetDocument],
+    company_dir: &Path,
+    request: &DownloadRequest,
+    config: &Config,
+) -> DownloadReport {
+    let is_summary = matches!(request.format, DocumentFormat::Summary);
+    if !is_summary && config.edinet_api_key.is_none() {
+        dry_run::note("EDINET_API_KEY is not set; these requests would fail");
+    }
+
+    let mut report = DownloadReport::default();
+    for document in documents {
+        let (download_type, file_name) = document_download(document, &request.format);
+        let output_path = company_dir.join(if is_summary { summary_file_name(document) } else { file_name });
+        if request.skip_existing && is_existing_download(&output_path) {
+            report.skipped += 1;
+            continue;
+        }
+        if is_summary {
+            // Summaries are written from the index without any API request
+            dry_run::note(format!("Write indexed metadata -> {}", output_path.display()));
+        } else {
+            dry_run::print_request(&document_request(document, download_type, config), &output_path);
+            if request.include_attachments && has_attachments(document) {
+                let attachments_path = company_dir.join(attachments_file_name(document));
+                dry_run::print_request(
+                    &document_request(document, EdinetApi::DOWNLOAD_TYPE_ATTACHMENTS, config),
+                    &attachments_path,
+                );
+            }
+        }
+        report.downloaded += 1;
     }
+    report
+}
 
-    info!("Downloaded {} EDINET documents", downloaded_count);
-    Ok(downloaded_count)
+/// The document download request, with a placeholder key when none is configured
+fn document_request(document: &EdinetDocument, download_type: &str, config: &Config) -> RequestPreview {
+    let url = config.endpoints.edinet_document_url(document.doc_id.as_deref().unwrap_or("unknown"));
+    RequestPreview::get(url)
+        .with_query("type", download_type)
+        .with_header("Ocp-Apim-Subscription-Key", config.edinet_api_key.as_deref().unwrap_or_default())
+}
+
+/// File name of a downloaded document ZIP, e.g. `S100ABCD-2024-06-25.zip`
+fn zip_file_name(document: &EdinetDocument) -> String {
+    format!(
+        "{}-{}.zip",
+        document.doc_id.as_deref().unwrap_or("unknown"),
+        document.submit_date.as_deref().unwrap_or("unknown")
+    )
+}
+
+/// File name of a downloaded document PDF, e.g. `S100ABCD-2024-06-25.pdf`
+fn pdf_file_name(document: &EdinetDocument) -> String {
+    format!(
+        "{}-{}.pdf",
+        document.doc_id.as_deref().unwrap_or("unknown"),
+        document.submit_date.as_deref().unwrap_or("unknown")
+    )
+}
+
+/// File name of a document's attachments ZIP, e.g. `S100ABCD-2024-06-25-attachments.zip`
+fn attachments_file_name(document: &EdinetDocument) -> String {
+    format!(
+        "{}-{}{}",
+        document.doc_id.as_deref().unwrap_or("unknown"),
+        document.submit_date.as_deref().unwrap_or("unknown"),
+        ATTACHMENTS_FILE_SUFFIX
+    )
+}
+
+/// File name of a document's financial data CSVs, e.g. `S100ABCD-2024-06-25-csv.zip`
+fn csv_file_name(document: &EdinetDocument) -> String {
+    format!(
+        "{}-{}{}",
+        document.doc_id.as_deref().unwrap_or("unknown"),
+        document.submit_date.as_deref().unwrap_or("unknown"),
+        CSV_FILE_SUFFIX
+    )
+}
+
+/// File name of the metadata summary for a document, e.g. `S100ABCD-2024-06-25-summary.json`
+fn summary_file_name(document: &EdinetDocument) -> String {
+    format!(
+        "{}-{}-summary.{}",
+        document.doc_id.as_deref().unwrap_or("unknown"),
+        document.submit_date.as_deref().unwrap_or("unknown"),
+        DocumentFormat::Summary.file_extension()
+    )
+}
+
+/// Write the document metadata as JSON so it can be previewed without the full ZIP
+fn write_edinet_summary(document: &EdinetDocument, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let json = serde_json::to_string_pretty(document)?;
+    write_download(output_path, json)?;
+
+    Ok(())
 }
 
 /// Search for EDINET company code by ticker symbol
@@ -99,7 +357,10 @@ async fn search_edinet_company(ticker: &str, config: &Config) -> Result<String,
             );
             Ok(edinet_code)
         }
-        Ok(None) => Err(EdinetError::CompanyNotFound(ticker.to_string())),
+        Ok(None) => {
+            warn!("Ticker {} not found in the company table of {}", ticker, config.database_path_str());
+            Err(EdinetError::CompanyNotFound(ticker.to_string()))
+        }
         Err(e) => Err(EdinetError::Config(e.to_string())),
     }
 }
@@ -111,15 +372,14 @@ async fn get_edinet_documents_from_db(
     config: &Config,
 ) -> Result<Vec<EdinetDocument>, EdinetError> {
     // Query local database instead of scanning API
-    let search_query = crate::models::SearchQuery {
-        ticker: Some(request.ticker.clone()),
-        company_name: None,
-        filing_type: request.filing_type.clone(),
-        source: Some(crate::models::Source::Edinet),
-        date_from: request.date_from,
-        date_to: request.date_to,
-        text_query: None,
-    };
+    let search_query = crate::models::SearchQuery::builder()
+        .ticker(request.ticker.as_str())
+        .filing_type(request.filing_type.clone())
+        .source(crate::models::Source::Edinet)
+        .date_from(request.date_from)
+        .date_to(request.date_to)
+        .build()
+        .map_err(|e| EdinetError::Config(e.to_string()))?;
 
     info!("Querying documents database for documents...");
     let documents = storage::search_documents(
@@ -133,85 +393,111 @@ async fn get_edinet_documents_from_db(
     info!("Found {} documents in documents database", documents.len());
 
     // Convert Document to EdinetDocument for downloading
-    let mut edinet_documents = Vec::new();
-    for doc in documents {
-        // Extract document ID from metadata if available, otherwise use the document ID
-        let doc_id = doc
+    Ok(documents.iter().map(to_edinet_document).collect())
+}
+
+/// Look up a single document by its EDINET doc ID, returning the ticker to file it under
+///
+/// Documents missing from the index are still downloadable; they are filed under the
+/// request's ticker, or `unknown` when none was given.
+async fn get_edinet_document_by_id(
+    doc_id: &str,
+    request: &DownloadRequest,
+    config: &Config,
+) -> Result<(String, EdinetDocument), EdinetError> {
+    let indexed = storage::get_document_by_id(doc_id, config.database_path_str())
+        .await
+        .map_err(|e| EdinetError::Config(e.to_string()))?;
+
+    match indexed {
+        Some(doc) => Ok((doc.ticker.clone(), to_edinet_document(&doc))),
+        None => {
+            info!("Document {} is not indexed, downloading without metadata", doc_id);
+            let document: EdinetDocument = serde_json::from_value(serde_json::json!({
+                "seqNumber": 0,
+                "docID": doc_id,
+            }))
+            .map_err(|e| EdinetError::Config(e.to_string()))?;
+            let ticker = if request.ticker.is_empty() { "unknown" } else { &request.ticker };
+            Ok((ticker.to_string(), document))
+        }
+    }
+}
+
+/// Convert an indexed Document back into the EDINET API shape used for downloading
+fn to_edinet_document(doc: &crate::models::Document) -> EdinetDocument {
+    // Extract document ID from metadata if available, otherwise use the document ID
+    let doc_id = doc
+        .metadata
+        .get("doc_id")
+        .or_else(|| doc.metadata.get("document_id"))
+        .unwrap_or(&doc.id)
+        .clone();
+
+    EdinetDocument {
+        seq_number: 0, // Not used for download
+        doc_id: Some(doc_id),
+        edinet_code: doc.metadata.get("edinet_code").cloned(),
+        sec_code: (!is_fund_code(&doc.ticker)).then(|| doc.ticker.clone()),
+        jcn: doc.metadata.get("jcn").cloned(),
+        filer_name: Some(doc.company_name.clone()),
+        fund_code: doc.metadata.get("fund_code").cloned(),
+        ordinance_code: doc.metadata.get("ordinance_code").cloned(),
+        form_code: doc.metadata.get("form_code").cloned(),
+        doc_type_code: doc.metadata.get("doc_type_code").cloned(),
+        period_start: doc.metadata.get("period_start").cloned(),
+        period_end: doc.metadata.get("period_end").cloned(),
+        submit_date: Some(doc.date.format("%Y-%m-%d").to_string()),
+        doc_description: doc
             .metadata
-            .get("doc_id")
-            .or_else(|| doc.metadata.get("document_id"))
-            .unwrap_or(&doc.id)
-            .clone();
-
-        let edinet_doc = EdinetDocument {
-            seq_number: 0, // Not used for download
-            doc_id: Some(doc_id),
-            edinet_code: doc.metadata.get("edinet_code").cloned(),
-            sec_code: Some(doc.ticker.clone()),
-            jcn: doc.metadata.get("jcn").cloned(),
-            filer_name: Some(doc.company_name.clone()),
-            fund_code: None,
-            ordinance_code: doc.metadata.get("ordinance_code").cloned(),
-            form_code: doc.metadata.get("form_code").cloned(),
-            doc_type_code: doc.metadata.get("doc_type_code").cloned(),
-            period_start: doc.metadata.get("period_start").cloned(),
-            period_end: doc.metadata.get("period_end").cloned(),
-            submit_date: Some(doc.date.format("%Y-%m-%d").to_string()),
-            doc_description: doc
-                .metadata
-                .get("doc_description")
-                .or_else(|| doc.metadata.get("description"))
-                .cloned(),
-            issuer_edinet_code: doc.metadata.get("issuer_edinet_code").cloned(),
-            subject_edinet_code: doc.metadata.get("subject_edinet_code").cloned(),
-            subsidiary_edinet_code: doc.metadata.get("subsidiary_edinet_code").cloned(),
-            current_report_reason: doc.metadata.get("current_report_reason").cloned(),
-            parent_doc_id: doc.metadata.get("parent_doc_id").cloned(),
-            ope_date_time: doc.metadata.get("ope_date_time").cloned(),
-            withdrawal_status: doc.metadata.get("withdrawal_status").cloned(),
-            doc_info_edit_status: doc.metadata.get("doc_info_edit_status").cloned(),
-            disclosure_request_status: doc.metadata.get("disclosure_request_status").cloned(),
-            xbrl_flag: doc.metadata.get("xbrl_flag").cloned(),
-            pdf_flag: doc.metadata.get("pdf_flag").cloned(),
-            attach_doc_flag: doc.metadata.get("attach_doc_flag").cloned(),
-            english_flag: doc.metadata.get("english_flag").cloned(),
-            csv_flag: doc.metadata.get("csv_flag").cloned(),
-            legal_status: doc.metadata.get("legal_status").cloned(),
-        };
-
-        edinet_documents.push(edinet_doc);
-    }
-
-    Ok(edinet_documents)
-}
-
-/// Download a single EDINET document
+            .get("doc_description")
+            .or_else(|| doc.metadata.get("description"))
+            .cloned(),
+        issuer_edinet_code: doc.metadata.get("issuer_edinet_code").cloned(),
+        subject_edinet_code: doc.metadata.get("subject_edinet_code").cloned(),
+        subsidiary_edinet_code: doc.metadata.get("subsidiary_edinet_code").cloned(),
+        current_report_reason: doc.metadata.get("current_report_reason").cloned(),
+        parent_doc_id: doc.metadata.get("parent_doc_id").cloned(),
+        ope_date_time: doc.metadata.get("ope_date_time").cloned(),
+        withdrawal_status: doc.metadata.get("withdrawal_status").cloned(),
+        doc_info_edit_status: doc.metadata.get("doc_info_edit_status").cloned(),
+        disclosure_request_status: doc.metadata.get("disclosure_request_status").cloned(),
+        xbrl_flag: doc.metadata.get("xbrl_flag").cloned(),
+        pdf_flag: doc.metadata.get("pdf_flag").cloned(),
+        attach_doc_flag: doc.metadata.get("attach_doc_flag").cloned(),
+        english_flag: doc.metadata.get("english_flag").cloned(),
+        csv_flag: doc.metadata.get("csv_flag").cloned(),
+        legal_status: doc.metadata.get("legal_status").cloned(),
+    }
+}
+
+/// Download a single EDINET document in the given download `type`
 async fn download_edinet_document(
     client: &Client,
     document: &EdinetDocument,
+    download_type: &str,
     output_path: &Path,
     config: &Config,
 ) -> Result<(), EdinetError> {
     let api_key = config.edinet_api_key.as_ref().ok_or(EdinetError::MissingApiKey)?;
 
-    let url = format!(
-        "{}{}/{}",
-        EdinetApi::BASE_URL,
-        EdinetApi::DOCUMENT_DOWNLOAD_ENDPOINT,
-        document.doc_id.as_deref().unwrap_or("unknown")
-    );
+    let url = config.endpoints.edinet_document_url(document.doc_id.as_deref().unwrap_or("unknown"));
 
     debug!("Downloading document from: {}", url);
 
     let response = client
         .get(&url)
-        .query(&[("type", "1")]) // type=1 for ZIP format
+        .query(&[("type", download_type)])
         .header("Ocp-Apim-Subscription-Key", api_key)
+        .timeout(config.download_timeout())
         .send()
         .await?;
 
     let status = response.status();
 
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(EdinetError::rate_limited(response.headers()));
+    }
     if !status.is_success() {
         let response_text = response.text().await?;
         if let Ok(error_response) = serde_json::from_str::<EdinetErrorResponse>(&response_text) {
@@ -234,7 +520,168 @@ async fn download_edinet_document(
         std::fs::create_dir_all(parent)?;
     }
 
-    std::fs::write(output_path, content)?;
+    write_download(output_path, content)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+    use crate::test_support::{edinet_error, mock_config, zip_payload, EDINET_TEST_API_KEY};
+    use tempfile::TempDir;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn request(doc_id: &str) -> DownloadRequest {
+        DownloadRequest {
+            source: Source::Edinet,
+            ticker: "7203".to_string(),
+            filing_type: None,
+            date_from: None,
+            date_to: None,
+            limit: 1,
+            format: DocumentFormat::Other("zip".to_string()),
+            document_id: Some(doc_id.to_string()),
+            skip_existing: false,
+            dry_run: false,
+            include_attachments: false,
+            output_template: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_document_zip() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        let payload = zip_payload(&[("XBRL/PublicDoc/report.htm", "<html>有価証券報告書</html>")]);
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100TEST"))
+            .and(query_param("type", "1"))
+            .and(header("Ocp-Apim-Subscription-Key", EDINET_TEST_API_KEY))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(payload.clone(), "application/octet-stream"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let report = download_documents_with_config(&request("S100TEST"), output_dir.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        let saved = std::fs::read(output_dir.join("edinet/7203/S100TEST-unknown.zip")).unwrap();
+        assert_eq!(saved, payload);
+    }
+
+    #[tokio::test]
+    async fn test_download_document_pdf() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        let payload = b"%PDF-1.7 report".to_vec();
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100TEST"))
+            .and(query_param("type", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(payload.clone(), "application/pdf"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest { format: DocumentFormat::Pdf, ..request("S100TEST") };
+        let report = download_documents_with_config(&request, output_dir.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        let saved = std::fs::read(output_dir.join("edinet/7203/S100TEST-unknown.pdf")).unwrap();
+        assert_eq!(saved, payload);
+    }
+
+    #[tokio::test]
+    async fn test_download_includes_attachments_when_requested() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100TEST"))
+            .and(query_param("type", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(zip_payload(&[("main.htm", "main")]), "application/octet-stream"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let attachments = zip_payload(&[("attach.pdf", "定款")]);
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100TEST"))
+            .and(query_param("type", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(attachments.clone(), "application/octet-stream"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest { include_attachments: true, ..request("S100TEST") };
+        let report = download_documents_with_config(&request, output_dir.to_str().unwrap(), &config).await.unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        assert!(output_dir.join("edinet/7203/S100TEST-unknown.zip").exists());
+        let saved = std::fs::read(output_dir.join("edinet/7203/S100TEST-unknown-attachments.zip")).unwrap();
+        assert_eq!(saved, attachments);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_sends_no_document_request() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100TEST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().join("downloads");
+        let request = DownloadRequest { dry_run: true, ..request("S100TEST") };
+        let report = download_documents_with_config(&request, output_dir.to_str().unwrap(), &config).await.unwrap();
+
+        assert_eq!(report.downloaded, 1);
+        assert!(!output_dir.exists());
+        let document: EdinetDocument =
+            serde_json::from_value(serde_json::json!({ "seqNumber": 0, "docID": "S100TEST" })).unwrap();
+        let preview = document_request(&document, EdinetApi::DOWNLOAD_TYPE_ZIP, &config).to_string();
+        assert!(preview.contains("/api/v2/documents/S100TEST?type=1"));
+        assert!(!preview.contains(EDINET_TEST_API_KEY));
+    }
+
+    #[tokio::test]
+    async fn test_error_responses_are_classified() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100GONE"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(edinet_error(404, "Not Found")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents/S100BUSY"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Too Many Requests"))
+            .mount(&server)
+            .await;
+
+        let output_dir = temp_dir.path().to_str().unwrap();
+        let err = download_documents_with_config(&request("S100GONE"), output_dir, &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DownloaderError::NotFound(_)), "{:?}", err);
+
+        let err = download_documents_with_config(&request("S100BUSY"), output_dir, &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DownloaderError::RateLimited(_)), "{:?}", err);
+    }
+}
@@ -1,5 +1,6 @@
 //! EDINET-specific error types
 
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,6 +24,11 @@ pub enum EdinetError {
         message: String,
     },
     
+    #[error("EDINET API rate limit exceeded (status 429){}", retry_after.map(|d| format!(", retry after {}s", d.as_secs())).unwrap_or_default())]
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
     
@@ -39,8 +45,79 @@ pub enum EdinetError {
     Config(String),
 }
 
+impl EdinetError {
+    /// Plain-language explanation of what went wrong and what to do about it, for the TUI status bar
+    pub fn user_message(&self) -> String {
+        match self {
+            EdinetError::MissingApiKey => {
+                "No EDINET API key is configured. Set the EDINET_API_KEY environment variable (see README, EDINET Configuration) and restart.".to_string()
+            }
+            EdinetError::CompanyNotFound(ticker) => format!(
+                "Ticker {} is not in the company list. Load the EDINET static data (L on the main menu) and try again.",
+                ticker
+            ),
+            EdinetError::ApiResponseError { date, .. } => format!(
+                "EDINET sent an unexpected response for {}. Try again later; the log file has the details.",
+                date
+            ),
+            EdinetError::ApiError { status_code, .. } => status_message(*status_code),
+            EdinetError::RateLimited { retry_after } => match retry_after {
+                Some(wait) => format!("EDINET is limiting requests. Wait {}s and try again.", wait.as_secs()),
+                None => "EDINET is limiting requests. Wait a minute and try again.".to_string(),
+            },
+            EdinetError::Database(_) => {
+                "Could not access the database. Check FAST10K_DB_PATH and that no other program is writing to it.".to_string()
+            }
+            EdinetError::Http(_) => {
+                "Could not reach EDINET. Check your internet connection, or FAST10K_HTTP_PROXY behind a proxy.".to_string()
+            }
+            EdinetError::Io(e) => format!("Could not read or write a file: {}", e),
+            EdinetError::InvalidDate(_) => "A date could not be read. Use the YYYY-MM-DD format.".to_string(),
+            EdinetError::Config(message) => message.clone(),
+        }
+    }
+
+    /// A 429 response, keeping the wait it asks for in `Retry-After` (in seconds)
+    pub fn rate_limited(headers: &reqwest::header::HeaderMap) -> Self {
+        let retry_after = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        EdinetError::RateLimited { retry_after }
+    }
+}
+
+/// What an EDINET API error status means for the user, instead of the raw (often Japanese) response body
+pub fn status_message(status_code: u16) -> String {
+    match status_code {
+        400 => "EDINET rejected the request as invalid. Check the dates and document ID.".to_string(),
+        401 | 403 => "EDINET rejected the API key. Check that EDINET_API_KEY is set to a valid key.".to_string(),
+        404 => "EDINET has no such document. It may have been withdrawn or be past its disclosure period.".to_string(),
+        429 => "EDINET is limiting requests. Wait a minute and try again.".to_string(),
+        500..=599 => format!("EDINET is unavailable right now (status {}). Try again later.", status_code),
+        _ => format!("EDINET returned an error (status {}).", status_code),
+    }
+}
+
 impl From<anyhow::Error> for EdinetError {
     fn from(err: anyhow::Error) -> Self {
         EdinetError::Config(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message_hides_raw_api_body() {
+        let err = EdinetError::ApiError { status_code: 401, message: "アクセス拒否".to_string() };
+        assert!(err.user_message().contains("EDINET_API_KEY"));
+        assert!(!err.user_message().contains("アクセス拒否"));
+
+        assert!(EdinetError::MissingApiKey.user_message().contains("EDINET_API_KEY"));
+        let err = EdinetError::RateLimited { retry_after: Some(Duration::from_secs(30)) };
+        assert!(err.user_message().contains("30s"));
+    }
+}
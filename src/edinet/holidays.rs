@@ -0,0 +1,158 @@
+//! Japanese market holiday calendar used to skip non-business days when indexing EDINET
+//!
+//! Covers national holidays under the current law (2007 onwards), including substitute
+//! holidays, citizens' holidays and the 2019/2020/2021 one-off changes, plus the Tokyo
+//! Stock Exchange year-end closure (December 31 to January 3).
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// Whether the Japanese market is closed on `date` for a holiday (weekends are not included)
+pub fn is_market_holiday(date: NaiveDate) -> bool {
+    is_year_end_closure(date) || is_national_holiday(date)
+}
+
+/// Whether `date` is a Japanese national holiday, including substitute and citizens' holidays
+pub fn is_national_holiday(date: NaiveDate) -> bool {
+    is_base_holiday(date) || is_substitute_holiday(date) || is_citizens_holiday(date)
+}
+
+/// Tokyo Stock Exchange year-end closure
+fn is_year_end_closure(date: NaiveDate) -> bool {
+    matches!((date.month(), date.day()), (12, 31) | (1, 1..=3))
+}
+
+/// A holiday falling on a Sunday moves to the next day that is not itself a holiday
+fn is_substitute_holiday(date: NaiveDate) -> bool {
+    if is_base_holiday(date) {
+        return false;
+    }
+    let mut previous = date - Duration::days(1);
+    while is_base_holiday(previous) {
+        if previous.weekday() == Weekday::Sun {
+            return true;
+        }
+        previous -= Duration::days(1);
+    }
+    false
+}
+
+/// A day sandwiched between two holidays is itself a holiday
+fn is_citizens_holiday(date: NaiveDate) -> bool {
+    date.weekday() != Weekday::Sun
+        && !is_base_holiday(date)
+        && is_base_holiday(date - Duration::days(1))
+        && is_base_holiday(date + Duration::days(1))
+}
+
+fn is_base_holiday(date: NaiveDate) -> bool {
+    base_holidays(date.year()).contains(&date)
+}
+
+/// Holidays defined directly by law for a year, before substitute and citizens' holidays
+fn base_holidays(year: i32) -> Vec<NaiveDate> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).expect("valid holiday date");
+    let monday = |month, n| {
+        NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Mon, n).expect("valid Happy Monday")
+    };
+
+    let mut holidays = vec![
+        date(1, 1),
+        monday(1, 2), // Coming of Age Day
+        date(2, 11),
+        date(3, vernal_equinox_day(year)),
+        date(4, 29),
+        date(5, 3),
+        date(5, 4),
+        date(5, 5),
+        monday(9, 3), // Respect for the Aged Day
+        date(9, autumnal_equinox_day(year)),
+        date(11, 3),
+        date(11, 23),
+    ];
+
+    // Marine Day, Mountain Day and Sports Day moved around the Tokyo Olympics
+    match year {
+        2020 => holidays.extend([date(7, 23), date(7, 24), date(8, 10)]),
+        2021 => holidays.extend([date(7, 22), date(7, 23), date(8, 8)]),
+        _ => {
+            holidays.push(monday(7, 3));
+            holidays.push(monday(10, 2));
+            if year >= 2016 {
+                holidays.push(date(8, 11));
+            }
+        }
+    }
+
+    // Emperor's Birthday
+    if year <= 2018 {
+        holidays.push(date(12, 23));
+    } else if year >= 2020 {
+        holidays.push(date(2, 23));
+    }
+
+    // Enthronement of Emperor Naruhito
+    if year == 2019 {
+        holidays.extend([date(5, 1), date(10, 22)]);
+    }
+
+    holidays
+}
+
+/// Day of March of the vernal equinox (valid 1980-2099)
+fn vernal_equinox_day(year: i32) -> u32 {
+    equinox_day(20.8431, year)
+}
+
+/// Day of September of the autumnal equinox (valid 1980-2099)
+fn autumnal_equinox_day(year: i32) -> u32 {
+    equinox_day(23.2488, year)
+}
+
+fn equinox_day(base: f64, year: i32) -> u32 {
+    let years = year - 1980;
+    (base + 0.242194 * years as f64 - (years / 4) as f64).floor() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_national_holidays() {
+        let holidays_2024 = [
+            (1, 1), (1, 8), (2, 11), (2, 12), (2, 23), (3, 20), (4, 29), (5, 3), (5, 4), (5, 5), (5, 6),
+            (7, 15), (8, 11), (8, 12), (9, 16), (9, 22), (9, 23), (10, 14), (11, 3), (11, 4), (11, 23),
+        ];
+        let actual: Vec<(u32, u32)> = date(2024, 1, 1)
+            .iter_days()
+            .take_while(|d| d.year() == 2024)
+            .filter(|d| is_national_holiday(*d))
+            .map(|d| (d.month(), d.day()))
+            .collect();
+        assert_eq!(actual, holidays_2024);
+    }
+
+    #[test]
+    fn test_special_holidays() {
+        // Citizens' holidays around the 2019 enthronement and in 2026's Silver Week
+        assert!(is_national_holiday(date(2019, 4, 30)));
+        assert!(is_national_holiday(date(2019, 5, 2)));
+        assert!(is_national_holiday(date(2026, 9, 22)));
+        // Olympic moves, including the substitute for Mountain Day on a Sunday
+        assert!(is_national_holiday(date(2021, 7, 22)));
+        assert!(is_national_holiday(date(2021, 8, 9)));
+        assert!(!is_national_holiday(date(2021, 10, 11)));
+    }
+
+    #[test]
+    fn test_market_holidays() {
+        assert!(is_market_holiday(date(2024, 12, 31)));
+        assert!(is_market_holiday(date(2025, 1, 3)));
+        assert!(!is_market_holiday(date(2024, 12, 30)));
+        assert!(!is_market_holiday(date(2024, 6, 20)));
+    }
+}
@@ -1,17 +1,88 @@
 //! EDINET document indexing functionality
 
-use crate::edinet::{EdinetDocument, EdinetIndexResponse, EdinetApi, EdinetError};
-use crate::models::{Document, FilingType, Source, DocumentFormat};
+use crate::edinet::{downloader, holidays, reader, EdinetDocument, EdinetIndexResponse, EdinetError};
+use crate::models::{edinet_viewer_url, Document, FilingType, Source, DocumentFormat};
 use crate::storage;
-use crate::config::Config;
+use crate::config::{Config, IndexingConfig};
+use crate::downloader::bulk::CircuitBreaker;
 use anyhow::Result;
 use chrono::{NaiveDate, Utc, Duration as ChronoDuration, Weekday, Datelike};
 use reqwest::Client;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
 use tracing::{debug, info, warn};
 
+/// Bytes of an imported ZIP's first section read to find the report type on its cover page
+const COVER_PAGE_LENGTH: usize = 2000;
+
+/// Attempts per date when EDINET answers 429, before the date is recorded as failed
+const RATE_LIMIT_ATTEMPTS: u32 = 5;
+
+/// Wait after a 429 without `Retry-After`, multiplied by the number of retries so far
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Filter selecting which EDINET documents are inserted into the index
+#[derive(Debug, Clone, Default)]
+pub struct EdinetIndexFilter {
+    /// Only index documents with one of these `docTypeCode` values (e.g. 120, 140)
+    pub doc_type_codes: Vec<String>,
+    /// Only index documents with one of these `formCode` values (e.g. 030000)
+    pub form_codes: Vec<String>,
+    /// Only index documents with XBRL data (`xbrlFlag` of "1")
+    pub xbrl_only: bool,
+}
+
+impl EdinetIndexFilter {
+    /// Build a filter from comma-separated code lists
+    pub fn from_code_lists(doc_type_codes: Option<&str>, form_codes: Option<&str>) -> Self {
+        Self {
+            doc_type_codes: parse_code_list(doc_type_codes),
+            form_codes: parse_code_list(form_codes),
+            xbrl_only: false,
+        }
+    }
+
+    /// Also require XBRL data when `xbrl_only` is set
+    pub fn with_xbrl_only(mut self, xbrl_only: bool) -> Self {
+        self.xbrl_only = xbrl_only;
+        self
+    }
+
+    /// Whether the filter accepts every document
+    pub fn is_empty(&self) -> bool {
+        self.doc_type_codes.is_empty() && self.form_codes.is_empty() && !self.xbrl_only
+    }
+
+    /// Check whether a document passes the filter
+    pub fn matches(&self, doc: &EdinetDocument) -> bool {
+        let matches_code = |codes: &[String], value: Option<&str>| {
+            codes.is_empty() || value.is_some_and(|v| codes.iter().any(|c| c == v))
+        };
+
+        matches_code(&self.doc_type_codes, doc.doc_type_code.as_deref())
+            && matches_code(&self.form_codes, doc.form_code.as_deref())
+            && (!self.xbrl_only || doc.xbrl_flag.as_deref() == Some("1"))
+    }
+}
+
+/// Split a comma-separated list of codes, ignoring blanks
+fn parse_code_list(codes: Option<&str>) -> Vec<String> {
+    codes
+        .unwrap_or("")
+        .split(',')
+        .map(|code| code.trim())
+        .filter(|code| !code.is_empty())
+        .map(|code| code.to_string())
+        .collect()
+}
+
+/// `run_state` key holding the last date covered without gaps by a successful EDINET index run
+pub const EDINET_INDEX_RUN_STATE_KEY: &str = "edinet_index_last_date";
+
 /// Build EDINET index for the specified number of days back from today
 pub async fn build_edinet_index(database_path: &str, days_back: i64) -> Result<usize> {
     let end_date = Utc::now();
@@ -29,9 +100,19 @@ pub async fn build_edinet_index_by_date(
     database_path: &str,
     start_date: NaiveDate,
     end_date: NaiveDate,
+) -> Result<usize> {
+    build_edinet_index_by_date_filtered(database_path, start_date, end_date, &EdinetIndexFilter::default()).await
+}
+
+/// Build EDINET index between the specified dates, indexing only documents matching `filter`
+pub async fn build_edinet_index_by_date_filtered(
+    database_path: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    filter: &EdinetIndexFilter,
 ) -> Result<usize> {
     let config = Config::from_env()?;
-    build_edinet_index_by_date_with_config(database_path, start_date, end_date, &config).await
+    build_edinet_index_by_date_with_config(database_path, start_date, end_date, &config, filter).await
 }
 
 /// Build EDINET index with custom configuration
@@ -40,8 +121,12 @@ pub async fn build_edinet_index_by_date_with_config(
     start_date: NaiveDate,
     end_date: NaiveDate,
     config: &Config,
+    filter: &EdinetIndexFilter,
 ) -> Result<usize> {
     println!("🚀 Starting EDINET index build from {} to {}", start_date, end_date);
+    if !filter.is_empty() {
+        println!("🔎 Filtering by docTypeCode {:?} and formCode {:?}", filter.doc_type_codes, filter.form_codes);
+    }
 
     // Check for API key
     if config.edinet_api_key.is_none() {
@@ -53,68 +138,367 @@ pub async fn build_edinet_index_by_date_with_config(
     let start_time = Instant::now();
     info!("Indexing EDINET documents from {} to {}", start_date, end_date);
 
-    let client = Client::builder()
-        .user_agent(&config.http.user_agent)
-        .timeout(config.http_timeout())
-        .build()?;
+    let client = config.http_client_builder()?.build()?;
 
     let mut total_indexed = 0;
+    let mut first_failed_date = None;
+    let mut breaker = CircuitBreaker::new(config.max_consecutive_failures);
+    let mut last_error = None;
+    let mut indexed_dates = HashSet::new();
     let total_days = (end_date - start_date).num_days() + 1;
-    let weekdays: Vec<NaiveDate> = (0..total_days)
+    let dates: Vec<NaiveDate> = (0..total_days)
         .map(|i| start_date + ChronoDuration::days(i))
-        .filter(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+        .filter(|date| should_index_date(*date, &config.indexing))
         .collect();
 
-    info!("Will process {} weekdays out of {} total days (skipping weekends)", weekdays.len(), total_days);
+    let concurrency = config.indexing.concurrency.max(1);
+    info!(
+        "Will process {} of {} total days (weekends {}, holidays {}, {} at a time)",
+        dates.len(),
+        total_days,
+        if config.indexing.include_weekends { "included" } else { "skipped" },
+        if config.indexing.skip_holidays { "skipped" } else { "included" },
+        concurrency,
+    );
 
-    for (index, date) in weekdays.iter().enumerate() {
-        let date_str = date.format("%Y-%m-%d").to_string();
-        
-        match get_edinet_documents_for_date(&client, &date_str, config).await {
+    // Fetch several dates at once, while the pacer keeps requests `edinet_api_delay` apart
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let pacer = Arc::new(RequestPacer::new(config.edinet_api_delay()));
+    let shared_config = Arc::new(config.clone());
+    let mut fetches = JoinSet::new();
+    for date in dates.iter().copied() {
+        let (semaphore, pacer, client, config) =
+            (Arc::clone(&semaphore), Arc::clone(&pacer), client.clone(), Arc::clone(&shared_config));
+        fetches.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("indexing semaphore is never closed");
+            let (result, retries) = fetch_documents_with_retries(&client, date, &config, &pacer).await;
+            (date, result, retries)
+        });
+    }
+
+    // Documents are inserted one date at a time, in the order the fetches finish
+    let mut processed = 0;
+    let mut rate_limit_retries = 0;
+    while let Some(fetched) = fetches.join_next().await {
+        let (date, result, retries) = fetched?;
+        processed += 1;
+        rate_limit_retries += retries;
+
+        match result {
             Ok(documents) => {
                 if !documents.is_empty() {
-                    info!("Processing {} EDINET documents for {}", documents.len(), date_str);
+                    info!("Processing {} EDINET documents for {}", documents.len(), date);
                     
-                    let indexed_count = index_documents(&documents, database_path).await?;
+                    let indexed_count = index_documents(&documents, database_path, filter).await?;
                     total_indexed += indexed_count;
                     
-                    let progress = ((index + 1) as f64 / weekdays.len() as f64 * 100.0) as u32;
-                    println!("🗓️  Processing date {} ({}/{} weekdays, {}% complete) - ✅ Indexed {} documents (total: {})", 
-                        date_str, index + 1, weekdays.len(), progress, indexed_count, total_indexed);
+                    let progress = (processed as f64 / dates.len() as f64 * 100.0) as u32;
+                    println!("🗓️  Processing date {} ({}/{} days, {}% complete) - ✅ Indexed {} documents (total: {})", 
+                        date, processed, dates.len(), progress, indexed_count, total_indexed);
                 } else {
-                    debug!("No documents found for {}", date_str);
+                    debug!("No documents found for {}", date);
                 }
             }
             Err(e) => {
-                warn!("Failed to get documents for {}: {}", date_str, e);
+                warn!("Failed to get documents for {}: {}", date, e);
+                first_failed_date = Some(first_failed_date.map_or(date, |first: NaiveDate| first.min(date)));
+                last_error = Some(e);
+                if breaker.record_failure() {
+                    break;
+                }
                 continue;
             }
         }
+        indexed_dates.insert(date);
+        breaker.record_success();
+    }
 
-        // Rate limiting
-        tokio::time::sleep(config.edinet_api_delay()).await;
+    if breaker.is_tripped() {
+        // Dates not fetched yet count as failed, so the next run starts at the first of them
+        fetches.abort_all();
+        let resume_from = dates.iter().copied().find(|date| !indexed_dates.contains(date)).unwrap_or(start_date);
+        record_edinet_index_run(database_path, start_date, resume_from - ChronoDuration::days(1), filter).await?;
+        anyhow::bail!(
+            "Stopped EDINET indexing after {} failed dates in a row (last error: {}); EDINET may be down. \
+             Indexed {} documents; dates from {} on were not indexed, run `fast10k update --since-last-run` to resume",
+            breaker.consecutive_failures(),
+            last_error.map(|e| e.to_string()).unwrap_or_default(),
+            total_indexed,
+            resume_from
+        );
     }
 
     let elapsed = start_time.elapsed();
     info!("🎉 EDINET indexing complete!");
     info!("📈 Total documents indexed: {}", total_indexed);
     info!("⏱️  Total time: {} minutes {} seconds", elapsed.as_secs() / 60, elapsed.as_secs() % 60);
-    info!("📅 Processed {} weekdays from {} to {}", weekdays.len(), start_date, end_date);
+    info!("📅 Processed {} days from {} to {}", dates.len(), start_date, end_date);
+    info!("🔁 Rate-limit retries: {}", rate_limit_retries);
 
     println!("🎉 EDINET indexing complete!");
     println!("📈 Total documents indexed: {}", total_indexed);
     println!("⏱️  Total time: {} minutes {} seconds", elapsed.as_secs() / 60, elapsed.as_secs() % 60);
-    println!("📅 Processed {} weekdays from {} to {}", weekdays.len(), start_date, end_date);
+    println!("📅 Processed {} days from {} to {}", dates.len(), start_date, end_date);
+    if rate_limit_retries > 0 {
+        println!("🔁 Retried {} requests after EDINET rate limiting (HTTP 429)", rate_limit_retries);
+    }
+
+    // Dates from the first failed one onwards are fetched again by the next --since-last-run
+    let covered_until = first_failed_date
+        .map(|date| date - ChronoDuration::days(1))
+        .unwrap_or(end_date);
+    record_edinet_index_run(database_path, start_date, covered_until, filter).await?;
 
     Ok(total_indexed)
 }
 
+/// Fetch a date's document list, retrying up to `RATE_LIMIT_ATTEMPTS` times while EDINET answers 429
+///
+/// Returns the number of retries along with the result.
+async fn fetch_documents_with_retries(
+    client: &Client,
+    date: NaiveDate,
+    config: &Config,
+    pacer: &RequestPacer,
+) -> (Result<Vec<EdinetDocument>, EdinetError>, u32) {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let mut retries = 0;
+    loop {
+        pacer.wait().await;
+        match get_edinet_documents_for_date(client, &date_str, config).await {
+            Err(EdinetError::RateLimited { retry_after }) if retries + 1 < RATE_LIMIT_ATTEMPTS => {
+                retries += 1;
+                let delay = retry_after.unwrap_or(RATE_LIMIT_BACKOFF * retries);
+                warn!("EDINET rate limited the request for {}, retrying in {}s (retry {})", date, delay.as_secs(), retries);
+                // Every fetch waits, not just this one, so the API gets a real break
+                pacer.pause(delay).await;
+            }
+            result => return (result, retries),
+        }
+    }
+}
+
+/// Documents of a single date indexed by [`index_edinet_date`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateIndexReport {
+    /// Documents EDINET lists for the date
+    pub listed: usize,
+    /// Documents passing the filter that were inserted or refreshed
+    pub indexed: usize,
+    /// Indexed documents that were not in the index before
+    pub added: usize,
+}
+
+/// Fetch and index exactly one date, e.g. to fill the gap left by a date that failed in a range build
+///
+/// Rate limits are retried as in a range build, and the high-water mark moves past the date
+/// when it directly follows the mark and no filter is set.
+pub async fn index_edinet_date(
+    database_path: &str,
+    date: NaiveDate,
+    config: &Config,
+    filter: &EdinetIndexFilter,
+) -> Result<DateIndexReport> {
+    let client = config.http_client_builder()?.build()?;
+    let pacer = RequestPacer::new(config.edinet_api_delay());
+    let (result, _) = fetch_documents_with_retries(&client, date, config, &pacer).await;
+    let documents = result?;
+
+    let storage = storage::Storage::new(database_path).await?;
+    let mut known = 0;
+    for doc in documents.iter().filter(|doc| doc.filer_name.is_some() && filter.matches(doc)) {
+        if let Some(doc_id) = &doc.doc_id {
+            if storage.get_document_by_id(doc_id).await?.is_some() {
+                known += 1;
+            }
+        }
+    }
+
+    let indexed = index_documents(&documents, database_path, filter).await?;
+    record_edinet_index_run(database_path, date, date, filter).await?;
+    info!("Indexed {} of {} EDINET documents for {} ({} new)", indexed, documents.len(), date, indexed.saturating_sub(known));
+    Ok(DateIndexReport { listed: documents.len(), indexed, added: indexed.saturating_sub(known) })
+}
+
+/// Spaces out the start of requests made by concurrent tasks
+struct RequestPacer {
+    interval: Duration,
+    next_start: Mutex<tokio::time::Instant>,
+}
+
+impl RequestPacer {
+    fn new(interval: Duration) -> Self {
+        Self { interval, next_start: Mutex::new(tokio::time::Instant::now()) }
+    }
+
+    /// Wait for this caller's turn, at least `interval` after the previous caller's
+    async fn wait(&self) {
+        let start = {
+            let mut next_start = self.next_start.lock().await;
+            let start = (*next_start).max(tokio::time::Instant::now());
+            *next_start = start + self.interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+
+    /// Hold back every caller until `delay` from now, e.g. after a rate limit response
+    async fn pause(&self, delay: Duration) {
+        let mut next_start = self.next_start.lock().await;
+        *next_start = (*next_start).max(tokio::time::Instant::now() + delay);
+    }
+}
+
+/// Whether a date should be fetched from EDINET under the indexing configuration
+fn should_index_date(date: NaiveDate, indexing: &IndexingConfig) -> bool {
+    let is_weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+    (indexing.include_weekends || !is_weekend) && !(indexing.skip_holidays && holidays::is_market_holiday(date))
+}
+
+/// Last date covered by a successful EDINET index run, if any
+pub async fn get_edinet_last_run_date(database_path: &str) -> Result<Option<NaiveDate>> {
+    storage::get_run_state(EDINET_INDEX_RUN_STATE_KEY, database_path)
+        .await?
+        .map(|value| NaiveDate::parse_from_str(&value, "%Y-%m-%d"))
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Advance the persisted high-water mark after indexing `start_date..=covered_until`
+///
+/// A filtered run left the other documents of those dates unindexed, so it keeps the mark
+/// where it is for the next unfiltered `--since-last-run`.
+async fn record_edinet_index_run(
+    database_path: &str,
+    start_date: NaiveDate,
+    covered_until: NaiveDate,
+    filter: &EdinetIndexFilter,
+) -> Result<()> {
+    if !filter.is_empty() {
+        debug!("Filtered EDINET index run; keeping the high-water mark");
+        return Ok(());
+    }
+    let current = get_edinet_last_run_date(database_path).await?;
+    if let Some(mark) = next_high_water_mark(current, start_date, covered_until) {
+        storage::set_run_state(EDINET_INDEX_RUN_STATE_KEY, &mark.format("%Y-%m-%d").to_string(), database_path).await?;
+        debug!("EDINET index high-water mark is now {}", mark);
+    }
+    Ok(())
+}
+
+/// New high-water mark after a run, or `None` if it should stay as it is
+///
+/// The mark only moves forward, and only when the run starts at or before the day after
+/// the current mark so that a later `--since-last-run` never skips a gap.
+fn next_high_water_mark(
+    current: Option<NaiveDate>,
+    start_date: NaiveDate,
+    covered_until: NaiveDate,
+) -> Option<NaiveDate> {
+    if covered_until < start_date {
+        return None;
+    }
+    match current {
+        None => Some(covered_until),
+        Some(mark) if start_date <= mark + ChronoDuration::days(1) && covered_until > mark => Some(covered_until),
+        Some(_) => None,
+    }
+}
+
 /// Update EDINET index from the last indexed date to today
 pub async fn update_edinet_index(database_path: &str, days_back: i64) -> Result<usize> {
     info!("Updating EDINET index with documents from last {} days", days_back);
     build_edinet_index(database_path, days_back).await
 }
 
+/// Update EDINET index from the latest indexed date to today
+///
+/// The latest indexed date itself is fetched again, since documents filed later that
+/// day may have been published after the previous run. Falls back to the last
+/// `days_back` days when the index is empty.
+pub async fn update_edinet_index_incremental(database_path: &str, days_back: i64) -> Result<usize> {
+    if storage::count_documents_by_source(&Source::Edinet, database_path).await? == 0 {
+        info!("EDINET index is empty, building the last {} days", days_back);
+        return build_edinet_index(database_path, days_back).await;
+    }
+
+    let (_, last_date) = storage::get_date_range_for_source(&Source::Edinet, database_path).await?;
+    let start_date = NaiveDate::parse_from_str(&last_date, "%Y-%m-%d")?;
+    let end_date = Utc::now().date_naive();
+
+    info!("Incrementally updating EDINET index from {} to {}", start_date, end_date);
+    build_edinet_index_by_date(database_path, start_date, end_date).await
+}
+
+/// Update EDINET index from the date of the last successful run to today
+///
+/// The last run's date is fetched again, since documents filed later that day may have
+/// been published after the run. Falls back to the last `days_back` days when no run has
+/// been recorded yet.
+pub async fn update_edinet_index_since_last_run(database_path: &str, days_back: i64) -> Result<usize> {
+    let Some(start_date) = get_edinet_last_run_date(database_path).await? else {
+        info!("No previous EDINET index run recorded, building the last {} days", days_back);
+        return build_edinet_index(database_path, days_back).await;
+    };
+    let end_date = Utc::now().date_naive();
+
+    info!("Updating EDINET index since last run from {} to {}", start_date, end_date);
+    build_edinet_index_by_date(database_path, start_date, end_date).await
+}
+
+/// Fetch today's EDINET documents and index those not already in the database
+///
+/// Returns the newly indexed documents so callers can report them.
+pub async fn index_new_edinet_documents(
+    database_path: &str,
+    config: &Config,
+    filter: &EdinetIndexFilter,
+) -> Result<Vec<Document>> {
+    let client = config.http_client_builder()?.build()?;
+
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let documents = get_edinet_documents_for_date(&client, &today, config).await?;
+    let storage = storage::Storage::new(database_path).await?;
+
+    let mut new_documents = Vec::new();
+    for doc in &documents {
+        let (Some(doc_id), Some(_)) = (&doc.doc_id, &doc.filer_name) else {
+            continue;
+        };
+        if !filter.matches(doc) || storage.get_document_by_id(doc_id).await?.is_some() {
+            continue;
+        }
+
+        let document = match edinet_to_document(doc) {
+            Ok(document) => document,
+            Err(e) => {
+                warn!("Skipping EDINET document {}: {}", doc_id, e);
+                continue;
+            }
+        };
+        if let Err(e) = storage.insert_document(&document).await {
+            warn!("Failed to insert document {}: {}", document.id, e);
+            continue;
+        }
+        new_documents.push(document);
+    }
+
+    debug!("Indexed {} new of {} EDINET documents for {}", new_documents.len(), documents.len(), today);
+    Ok(new_documents)
+}
+
+/// Check that the EDINET API accepts the configured key, returning today's document count
+pub async fn check_edinet_api(config: &Config) -> Result<usize, EdinetError> {
+    let client = config
+        .http_client_builder()
+        .map_err(|e| EdinetError::Config(e.to_string()))?
+        .build()?;
+
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let documents = get_edinet_documents_for_date(&client, &today, config).await?;
+    Ok(documents.len())
+}
+
 /// Get EDINET documents for a specific date
 async fn get_edinet_documents_for_date(
     client: &Client,
@@ -123,7 +507,7 @@ async fn get_edinet_documents_for_date(
 ) -> Result<Vec<EdinetDocument>, EdinetError> {
     let api_key = config.edinet_api_key.as_ref().ok_or(EdinetError::MissingApiKey)?;
     
-    let url = format!("{}{}", EdinetApi::BASE_URL, EdinetApi::DOCUMENTS_ENDPOINT);
+    let url = config.endpoints.edinet_documents_url();
     
     debug!("Fetching EDINET documents for date: {}", date);
     
@@ -135,6 +519,9 @@ async fn get_edinet_documents_for_date(
         .await?;
 
     let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(EdinetError::rate_limited(response.headers()));
+    }
     let response_text = response.text().await?;
 
     if !status.is_success() {
@@ -144,17 +531,26 @@ async fn get_edinet_documents_for_date(
         });
     }
 
-    let edinet_response: EdinetIndexResponse = serde_json::from_str(&response_text)
+    let mut edinet_response: EdinetIndexResponse = serde_json::from_str(&response_text)
         .map_err(|e| EdinetError::ApiResponseError {
             date: date.to_string(),
             source: e,
         })?;
 
+    // The listing only holds documents submitted on `date`, so it stands in for a missing submission time
+    for document in &mut edinet_response.results {
+        document.submit_date.get_or_insert_with(|| date.to_string());
+    }
+
     Ok(edinet_response.results)
 }
 
 /// Index EDINET documents into the database
-async fn index_documents(documents: &[EdinetDocument], database_path: &str) -> Result<usize> {
+async fn index_documents(
+    documents: &[EdinetDocument],
+    database_path: &str,
+    filter: &EdinetIndexFilter,
+) -> Result<usize> {
     let mut indexed_count = 0;
 
     for doc in documents {
@@ -163,68 +559,396 @@ async fn index_documents(documents: &[EdinetDocument], database_path: &str) -> R
             continue;
         }
 
-        let filing_type = map_edinet_form_to_filing_type(doc.form_code.as_deref());
-        let format = determine_document_format(doc);
+        if !filter.matches(doc) {
+            continue;
+        }
+
+        let document = match edinet_to_document(doc) {
+            Ok(document) => document,
+            Err(e) => {
+                warn!("Skipping EDINET document {}: {}", doc.doc_id.as_deref().unwrap_or_default(), e);
+                continue;
+            }
+        };
 
-        // Create metadata HashMap
-        let mut metadata = HashMap::new();
-        
-        // Store all EDINET-specific fields in metadata
-        if let Some(ref edinet_code) = doc.edinet_code {
-            metadata.insert("edinet_code".to_string(), edinet_code.clone());
+        // Insert document into database
+        if let Err(e) = storage::insert_document(&document, database_path).await {
+            warn!("Failed to insert document {}: {}", document.id, e);
+            continue;
         }
-        if let Some(ref form_code) = doc.form_code {
-            metadata.insert("form_code".to_string(), form_code.clone());
+
+        indexed_count += 1;
+    }
+
+    Ok(indexed_count)
+}
+
+/// Filing type of an EDINET document from its fund code, form code and description
+fn edinet_filing_type(fund_code: Option<&str>, form_code: Option<&str>, doc_description: Option<&str>) -> FilingType {
+    if fund_code.is_some_and(|code| !code.is_empty()) {
+        FilingType::FundReport
+    } else if doc_description.is_some_and(FilingType::is_transcript_title) {
+        FilingType::Transcript
+    } else {
+        match map_edinet_form_to_filing_type(form_code) {
+            FilingType::Other(label) => doc_description
+                .and_then(FilingType::classify_text)
+                .unwrap_or(FilingType::Other(label)),
+            filing_type => filing_type,
         }
-        if let Some(ref doc_type_code) = doc.doc_type_code {
-            metadata.insert("doc_type_code".to_string(), doc_type_code.clone());
+    }
+}
+
+/// Convert an EDINET API document into our Document model
+///
+/// Callers must ensure `doc_id` and `filer_name` are present.
+fn edinet_to_document(doc: &EdinetDocument) -> Result<Document> {
+    let filing_type = edinet_filing_type(doc.fund_code.as_deref(), doc.form_code.as_deref(), doc.doc_description.as_deref());
+    let format = determine_document_format(doc);
+
+    // Create metadata HashMap
+    let mut metadata = HashMap::new();
+    
+    // Store all EDINET-specific fields in metadata
+    if let Some(ref edinet_code) = doc.edinet_code {
+        metadata.insert("edinet_code".to_string(), edinet_code.clone());
+    }
+    if let Some(ref fund_code) = doc.fund_code {
+        metadata.insert("fund_code".to_string(), fund_code.clone());
+    }
+    if let Some(ref form_code) = doc.form_code {
+        metadata.insert("form_code".to_string(), form_code.clone());
+    }
+    if let Some(ref doc_type_code) = doc.doc_type_code {
+        metadata.insert("doc_type_code".to_string(), doc_type_code.clone());
+    }
+    if let Some(ref period_start) = doc.period_start {
+        metadata.insert("period_start".to_string(), period_start.clone());
+    }
+    if let Some(ref period_end) = doc.period_end {
+        metadata.insert("period_end".to_string(), period_end.clone());
+    }
+    if let Some(ref doc_description) = doc.doc_description {
+        metadata.insert("doc_description".to_string(), doc_description.clone());
+    }
+    if let Some(ref parent_doc_id) = doc.parent_doc_id {
+        metadata.insert("parent_doc_id".to_string(), parent_doc_id.clone());
+    }
+    if let Some(ref xbrl_flag) = doc.xbrl_flag {
+        metadata.insert("xbrl_flag".to_string(), xbrl_flag.clone());
+    }
+    if let Some(ref pdf_flag) = doc.pdf_flag {
+        metadata.insert("pdf_flag".to_string(), pdf_flag.clone());
+    }
+    if let Some(ref withdrawal_status) = doc.withdrawal_status {
+        metadata.insert("withdrawal_status".to_string(), withdrawal_status.clone());
+    }
+    if let Some(ref attach_doc_flag) = doc.attach_doc_flag {
+        metadata.insert("attach_doc_flag".to_string(), attach_doc_flag.clone());
+    }
+
+    // Kept so the viewer page can be linked without recomputing it, e.g. in exports
+    let doc_id = doc.doc_id.as_ref().unwrap();
+    metadata.insert("source_url".to_string(), edinet_viewer_url(doc_id));
+
+    Ok(Document {
+        id: doc_id.clone(),
+        ticker: document_identifier(doc),
+        company_name: doc.filer_name.as_ref().unwrap().clone(),
+        filing_type,
+        source: Source::Edinet,
+        date: parse_submit_date(doc.submit_date.as_deref())?,
+        content_path: PathBuf::from(""), // Will be set when document is downloaded
+        metadata,
+        format,
+        tags: Vec::new(),
+    })
+}
+
+/// Index EDINET ZIPs obtained out of band, e.g. shared by a colleague, without calling the API
+///
+/// `input` may be a single ZIP or a directory searched recursively. Filing details come from
+/// the XBRL file names inside each ZIP and company details from the static data when loaded.
+/// The ZIP itself becomes the document's `content_path`.
+pub async fn index_edinet_archives(input: &Path, database_path: &str) -> Result<usize> {
+    let config = Config::from_env()?;
+    index_edinet_archives_with_config(input, database_path, &config).await
+}
+
+/// Index EDINET ZIP files with custom configuration
+pub async fn index_edinet_archives_with_config(input: &Path, database_path: &str, config: &Config) -> Result<usize> {
+    let mut archives = Vec::new();
+    collect_zip_files(input, &mut archives)?;
+    archives.sort();
+
+    let indexed_count = index_archive_files(&archives, database_path, config).await?;
+    info!("Indexed {} of {} EDINET ZIP files from {}", indexed_count, archives.len(), input.display());
+    Ok(indexed_count)
+}
+
+/// Index each ZIP in `archives`, skipping (and logging) files that are not EDINET filings
+async fn index_archive_files(archives: &[PathBuf], database_path: &str, config: &Config) -> Result<usize> {
+    let mut indexed_count = 0;
+    for archive in archives {
+        match edinet_document_from_zip(archive, database_path, config.indexing.content_preview_length).await {
+            Ok(document) => {
+                storage::insert_document(&document, database_path).await?;
+                if config.indexing.store_content {
+                    store_zip_content(archive, &document.id, database_path).await?;
+                }
+                debug!("Indexed {} from {}", document.id, archive.display());
+                indexed_count += 1;
+            }
+            Err(e) => warn!("Skipping {}: {}", archive.display(), e),
         }
-        if let Some(ref period_start) = doc.period_start {
-            metadata.insert("period_start".to_string(), period_start.clone());
+    }
+    Ok(indexed_count)
+}
+
+/// Where the download directory and the index disagree
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    /// EDINET ZIPs and EDGAR filings on disk that no indexed document refers to
+    pub unindexed_files: Vec<PathBuf>,
+    /// Indexed documents whose `content_path` file no longer exists, as (id, path)
+    pub missing_files: Vec<(String, PathBuf)>,
+}
+
+impl ReconcileReport {
+    pub fn is_consistent(&self) -> bool {
+        self.unindexed_files.is_empty() && self.missing_files.is_empty()
+    }
+}
+
+/// Compare the EDINET ZIPs under `edinet_dir` and the EDGAR filings under `edgar_dir` with the indexed documents
+///
+/// A file counts as indexed when a document's `content_path` points at it or when its name
+/// matches an indexed document id: `{doc_id}-{date}.zip` for EDINET, the accession number in
+/// `{form}-{date}-{accession}` for EDGAR.
+pub async fn reconcile_downloads(edinet_dir: &Path, edgar_dir: &Path, database_path: &str) -> Result<ReconcileReport> {
+    let documents = storage::list_content_paths(database_path).await?;
+    let ids: HashSet<&str> = documents.iter().map(|(id, _)| id.as_str()).collect();
+    let mut content_paths = HashSet::new();
+    let mut report = ReconcileReport::default();
+
+    for (id, path) in &documents {
+        if path.as_os_str().is_empty() {
+            continue;
         }
-        if let Some(ref period_end) = doc.period_end {
-            metadata.insert("period_end".to_string(), period_end.clone());
+        match path.canonicalize() {
+            Ok(path) => {
+                content_paths.insert(path);
+            }
+            Err(_) => report.missing_files.push((id.clone(), path.clone())),
         }
-        if let Some(ref doc_description) = doc.doc_description {
-            metadata.insert("doc_description".to_string(), doc_description.clone());
+    }
+
+    let mut archives = Vec::new();
+    if edinet_dir.exists() {
+        collect_zip_files(edinet_dir, &mut archives)?;
+    }
+    archives.sort();
+
+    for archive in archives {
+        let stem = archive.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let doc_id = stem.split('-').next().unwrap_or(stem);
+        let referenced = archive.canonicalize().is_ok_and(|path| content_paths.contains(&path));
+        if !referenced && !ids.contains(doc_id) && !ids.contains(stem) {
+            report.unindexed_files.push(archive);
         }
-        if let Some(ref xbrl_flag) = doc.xbrl_flag {
-            metadata.insert("xbrl_flag".to_string(), xbrl_flag.clone());
+    }
+
+    for filing in crate::indexer::find_filings(edgar_dir) {
+        let referenced = filing.path.canonicalize().is_ok_and(|path| content_paths.contains(&path));
+        if !referenced && !ids.contains(filing.name.accession_number.as_str()) {
+            report.unindexed_files.push(filing.path);
         }
-        if let Some(ref pdf_flag) = doc.pdf_flag {
-            metadata.insert("pdf_flag".to_string(), pdf_flag.clone());
+    }
+
+    info!(
+        "Reconciled {} and {} with {}: {} unindexed files, {} missing files",
+        edinet_dir.display(),
+        edgar_dir.display(),
+        database_path,
+        report.unindexed_files.len(),
+        report.missing_files.len()
+    );
+    Ok(report)
+}
+
+/// Index the unindexed ZIPs and filings in `report` and clear the content paths of missing files
+///
+/// Returns the number of files indexed and of content paths cleared.
+pub async fn fix_reconcile_report(report: &ReconcileReport, database_path: &str, config: &Config) -> Result<(usize, usize)> {
+    let (archives, filings): (Vec<PathBuf>, Vec<PathBuf>) = report
+        .unindexed_files
+        .iter()
+        .cloned()
+        .partition(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")));
+    let filings = filings.into_iter().filter_map(crate::indexer::filing_file).collect();
+    let indexed = index_archive_files(&archives, database_path, config).await?
+        + crate::indexer::index_filing_files(filings, database_path, config).await?;
+
+    let mut cleared = 0;
+    for (id, path) in &report.missing_files {
+        if storage::clear_content_path(id, database_path).await? {
+            debug!("Cleared missing content path {} of {}", path.display(), id);
+            cleared += 1;
         }
+    }
+    Ok((indexed, cleared))
+}
 
-        let document = Document {
-            id: doc.doc_id.as_ref().unwrap().clone(),
-            ticker: extract_ticker_from_sec_code(doc.sec_code.as_deref()),
-            company_name: doc.filer_name.as_ref().unwrap().clone(),
-            filing_type,
-            source: Source::Edinet,
-            date: parse_submit_date(doc.submit_date.as_deref())?,
-            content_path: PathBuf::from(""), // Will be set when document is downloaded
-            metadata,
-            format,
-        };
+/// Outcome of `reclassify_edinet_documents`
+#[derive(Debug, Default)]
+pub struct ReclassifyReport {
+    /// EDINET documents checked
+    pub checked: usize,
+    /// Documents whose filing type changed, as (id, old type, new type)
+    pub changed: Vec<(String, FilingType, FilingType)>,
+}
 
-        // Insert document into database
-        if let Err(e) = storage::insert_document(&document, database_path).await {
-            warn!("Failed to insert document {}: {}", document.id, e);
+/// Re-derive the filing type of every indexed EDINET document from its stored metadata
+///
+/// Fixes documents indexed before a mapping change without fetching them again. Documents
+/// whose metadata maps to no known type keep theirs, which may have come from their text,
+/// except that the EDGAR types older versions gave EDINET reports become their EDINET equivalents.
+pub async fn reclassify_edinet_documents(database_path: &str) -> Result<ReclassifyReport> {
+    let query = crate::models::SearchQuery { source: Some(Source::Edinet), include_withdrawn: true, ..Default::default() };
+    let documents = storage::search_documents(&query, database_path, 0).await?;
+    let mut report = ReclassifyReport { checked: documents.len(), ..Default::default() };
+
+    for document in documents {
+        let field = |key: &str| document.metadata.get(key).map(String::as_str);
+        let filing_type = match edinet_filing_type(field("fund_code"), field("form_code"), field("doc_description")) {
+            FilingType::Other(_) => match document.filing_type {
+                FilingType::TenK => FilingType::AnnualSecuritiesReport,
+                FilingType::TenQ => FilingType::QuarterlySecuritiesReport,
+                FilingType::EightK => FilingType::ExtraordinaryReport,
+                _ => continue,
+            },
+            filing_type => filing_type,
+        };
+        if filing_type.as_str() == document.filing_type.as_str() {
             continue;
         }
 
-        indexed_count += 1;
+        storage::update_filing_type(&document.id, &filing_type, database_path).await?;
+        debug!("Reclassified {} from {} to {}", document.id, document.filing_type.as_str(), filing_type.as_str());
+        report.changed.push((document.id, document.filing_type, filing_type));
     }
 
-    Ok(indexed_count)
+    info!("Reclassified {} of {} EDINET documents in {}", report.changed.len(), report.checked, database_path);
+    Ok(report)
+}
+
+fn collect_zip_files(path: &Path, archives: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        // Attachment and CSV ZIPs share their document's ID and would overwrite its content
+        let is_companion = downloader::is_companion_download(&path.to_string_lossy());
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")) && !is_companion {
+            archives.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(path)? {
+        collect_zip_files(&entry?.path(), archives)?;
+    }
+    Ok(())
+}
+
+/// Build a Document for an EDINET ZIP, named `{doc_id}-{submit date}.zip` by our downloader
+///
+/// The first `preview_length` bytes of text are kept as the content preview, or none with 0.
+async fn edinet_document_from_zip(path: &Path, database_path: &str, preview_length: usize) -> Result<Document> {
+    let path_str = path.to_string_lossy();
+    let info = reader::read_edinet_zip_filing_info(&path_str)?;
+    let edinet_code = info
+        .edinet_code
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("no EDINET XBRL files found"))?;
+
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let doc_id = stem.split('-').next().unwrap_or(stem);
+    let doc_id = match Source::from_document_id(doc_id) {
+        Some(Source::Edinet) => doc_id.to_string(),
+        _ => stem.to_string(),
+    };
+
+    let (filer_name, sec_code) = match storage::get_edinet_static_company(database_path, &edinet_code).await? {
+        Some((name, sec_code)) => (name, Some(sec_code).filter(|code| !code.is_empty())),
+        None => (edinet_code.clone(), None),
+    };
+
+    let edinet_doc: EdinetDocument = serde_json::from_value(serde_json::json!({
+        "seqNumber": 0,
+        "docID": doc_id,
+        "edinetCode": edinet_code,
+        "secCode": sec_code,
+        "filerName": filer_name,
+        "formCode": info.form_code,
+        "periodEnd": info.period_end,
+        "submitDateTime": info.submit_date,
+        "xbrlFlag": if info.has_xbrl { "1" } else { "0" },
+        "pdfFlag": if info.has_pdf { "1" } else { "0" },
+    }))?;
+
+    let mut document = edinet_to_document(&edinet_doc)?;
+    if FilingType::is_transcript_title(stem) {
+        document.filing_type = FilingType::Transcript;
+    }
+    if sec_code.is_none() {
+        // Without static data the EDINET code is the best identifier available
+        document.ticker = edinet_code;
+    }
+    document.content_path = path.to_path_buf();
+    if let Some(text) = zip_content_preview(&path_str, preview_length.max(COVER_PAGE_LENGTH)) {
+        if let (FilingType::Other(_), Some(filing_type)) = (&document.filing_type, FilingType::classify_text(&text)) {
+            debug!("Classified {} as {} from its content", document.id, filing_type.as_str());
+            document.filing_type = filing_type;
+        }
+        if preview_length > 0 {
+            let preview = reader::truncate_preview(&text, preview_length);
+            document.metadata.insert("content_preview".to_string(), preview);
+        }
+    }
+    Ok(document)
+}
+
+/// Store the full text of an EDINET ZIP so text searches match it without the file
+///
+/// A document indexed with an unknown filing type gets the type named on its cover page, if any.
+pub(crate) async fn store_zip_content(path: &Path, document_id: &str, database_path: &str) -> Result<()> {
+    let content = reader::read_edinet_full_text(&path.to_string_lossy())?;
+    storage::store_document_content(document_id, &content, database_path).await?;
+    debug!("Stored {} characters of content for {}", content.chars().count(), document_id);
+
+    let is_unknown = storage::get_document_by_id(document_id, database_path)
+        .await?
+        .is_some_and(|document| matches!(document.filing_type, FilingType::Other(_)));
+    if let Some(filing_type) = FilingType::classify_text(&content).filter(|_| is_unknown) {
+        storage::update_filing_type(document_id, &filing_type, database_path).await?;
+        info!("Classified {} as {} from its content", document_id, filing_type.as_str());
+    }
+    Ok(())
+}
+
+/// Text of the first main content section, used for full-text search
+fn zip_content_preview(path: &str, preview_length: usize) -> Option<String> {
+    reader::read_edinet_zip(path, 5, preview_length)
+        .ok()?
+        .into_iter()
+        .find(|section| section.filename.contains("honbun") && !section.content.trim().is_empty())
+        .map(|section| section.content)
 }
 
 /// Map EDINET form code to our FilingType enum
 fn map_edinet_form_to_filing_type(form_code: Option<&str>) -> FilingType {
     match form_code {
-        Some(code) if code.starts_with("030") => FilingType::TenK, // Annual securities report
-        Some(code) if code.starts_with("043") => FilingType::TenQ, // Quarterly securities report
-        Some(code) if code.starts_with("120") => FilingType::EightK, // Extraordinary report
+        Some(code) if code.starts_with("030") => FilingType::AnnualSecuritiesReport,
+        Some(code) if code.starts_with("043") => FilingType::QuarterlySecuritiesReport,
+        Some(code) if code.starts_with("050") => FilingType::SemiAnnualSecuritiesReport,
+        Some(code) if code.starts_with("120") => FilingType::ExtraordinaryReport,
         Some(code) => FilingType::Other(format!("EDINET Form {}", code)),
         None => FilingType::Other("Unknown EDINET Form".to_string()),
     }
@@ -238,11 +962,20 @@ fn determine_document_format(doc: &EdinetDocument) -> DocumentFormat {
     match (has_xbrl, has_pdf) {
         (true, true) => DocumentFormat::Complete,
         (true, false) => DocumentFormat::Xbrl,
-        (false, true) => DocumentFormat::Html, // PDF in EDINET is often HTML-based
+        (false, true) => DocumentFormat::Pdf,
         (false, false) => DocumentFormat::Txt,
     }
 }
 
+/// Identifier stored as the ticker: the securities code for listed companies, the fund code
+/// for investment funds that have no securities code
+fn document_identifier(doc: &EdinetDocument) -> String {
+    match (doc.sec_code.as_deref(), doc.fund_code.as_deref()) {
+        (None, Some(fund_code)) if doc.is_fund() => fund_code.to_string(),
+        (sec_code, _) => extract_ticker_from_sec_code(sec_code),
+    }
+}
+
 /// Extract ticker symbol from securities code
 fn extract_ticker_from_sec_code(sec_code: Option<&str>) -> String {
     sec_code
@@ -250,17 +983,25 @@ fn extract_ticker_from_sec_code(sec_code: Option<&str>) -> String {
         .unwrap_or_else(|| "UNKNOWN".to_string())
 }
 
-/// Parse EDINET submit date string to NaiveDate
+/// Date formats seen in EDINET submission dates, after any time of day is cut off
+const SUBMIT_DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+
+/// Parse the date of an EDINET `submitDateTime`, e.g. `2024-06-25 09:00`, `2024-06-25 09:00:00`,
+/// `2024-06-25T09:00:00` or a date on its own
+///
+/// A missing or unreadable date is an error rather than today's date, which would skew the
+/// indexed date ranges.
 fn parse_submit_date(submit_date: Option<&str>) -> Result<NaiveDate> {
-    match submit_date {
-        Some(date_str) => {
-            // EDINET date format is typically "YYYY-MM-DD HH:MM:SS"
-            let date_part = date_str.split_whitespace().next().unwrap_or(date_str);
-            NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
-                .map_err(|e| anyhow::anyhow!("Failed to parse date '{}': {}", date_str, e))
-        }
-        None => Ok(Utc::now().date_naive()),
-    }
+    let date_str = submit_date
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Missing submission date"))?;
+    let date_part = date_str.split(|c: char| c.is_whitespace() || c == 'T').next().unwrap_or(date_str);
+
+    SUBMIT_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_part, format).ok())
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse submission date '{}'", date_str))
 }
 
 /// Get statistics about the EDINET index
@@ -292,6 +1033,20 @@ pub async fn get_edinet_index_stats(database_path: &str) -> Result<()> {
         },
     }
     
+    // Get document counts per filing type
+    match storage::count_documents_by_filing_type(&Source::Edinet, database_path).await {
+        Ok(counts) => {
+            println!("Documents by filing type:");
+            for (filing_type, count) in counts {
+                println!("  {}: {} documents", filing_type.as_str(), count);
+            }
+        }
+        Err(e) => {
+            println!("Failed to get filing type counts: {}", e);
+            warn!("Failed to get filing type counts: {}", e);
+        }
+    }
+    
     // Get top companies by document count
     match storage::get_top_companies_for_source(&Source::Edinet, database_path, 10).await {
         Ok(companies) => {
@@ -309,4 +1064,597 @@ pub async fn get_edinet_index_stats(database_path: &str) -> Result<()> {
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edinet::is_fund_code;
+    use crate::test_support::{edinet_document, edinet_documents, edinet_error, mock_config, EDINET_TEST_API_KEY};
+    use tempfile::TempDir;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn document(doc_type_code: &str, form_code: &str) -> EdinetDocument {
+        serde_json::from_value(serde_json::json!({
+            "seqNumber": 1,
+            "docTypeCode": doc_type_code,
+            "formCode": form_code,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_index_filter_matches_codes() {
+        let filter = EdinetIndexFilter::from_code_lists(Some("120, 140,"), None);
+        assert_eq!(filter.doc_type_codes, vec!["120", "140"]);
+        assert!(filter.matches(&document("120", "030000")));
+        assert!(!filter.matches(&document("180", "030000")));
+
+        let filter = EdinetIndexFilter::from_code_lists(Some("120"), Some("043000"));
+        assert!(!filter.matches(&document("120", "030000")));
+
+        assert!(EdinetIndexFilter::default().matches(&document("350", "010000")));
+
+        let filter = EdinetIndexFilter::from_code_lists(Some("120"), None).with_xbrl_only(true);
+        assert!(!filter.is_empty());
+        assert!(!filter.matches(&document("120", "030000")));
+        let mut with_xbrl = document("120", "030000");
+        with_xbrl.xbrl_flag = Some("1".to_string());
+        assert!(filter.matches(&with_xbrl));
+    }
+
+    #[test]
+    fn test_should_index_date() {
+        let saturday = NaiveDate::from_ymd_opt(2024, 6, 22).unwrap();
+        let holiday = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let weekday = NaiveDate::from_ymd_opt(2024, 6, 20).unwrap();
+
+        let default = IndexingConfig::default();
+        assert!(!should_index_date(saturday, &default));
+        assert!(should_index_date(holiday, &default));
+
+        let calendar = IndexingConfig { include_weekends: true, skip_holidays: true, ..Default::default() };
+        assert!(should_index_date(saturday, &calendar));
+        assert!(!should_index_date(holiday, &calendar));
+        assert!(should_index_date(weekday, &calendar));
+    }
+
+    #[test]
+    fn test_next_high_water_mark() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+
+        assert_eq!(next_high_water_mark(None, date(3), date(7)), Some(date(7)));
+        // Contiguous or overlapping runs move the mark forward
+        assert_eq!(next_high_water_mark(Some(date(7)), date(7), date(10)), Some(date(10)));
+        assert_eq!(next_high_water_mark(Some(date(7)), date(8), date(10)), Some(date(10)));
+        // A run leaving a gap after the mark, or ending before it, keeps the mark
+        assert_eq!(next_high_water_mark(Some(date(7)), date(12), date(14)), None);
+        assert_eq!(next_high_water_mark(Some(date(7)), date(1), date(5)), None);
+        // Nothing covered when the first date already failed
+        assert_eq!(next_high_water_mark(None, date(3), date(2)), None);
+    }
+
+    #[test]
+    fn test_fund_documents_use_fund_code() {
+        let fund: EdinetDocument = serde_json::from_value(serde_json::json!({
+            "seqNumber": 1,
+            "docID": "S100FUND",
+            "filerName": "Example Asset Management",
+            "fundCode": "G01234",
+            "formCode": "07A000",
+            "submitDateTime": "2024-06-25 09:00",
+        }))
+        .unwrap();
+
+        let document = edinet_to_document(&fund).unwrap();
+        assert_eq!(document.ticker, "G01234");
+        assert!(matches!(document.filing_type, FilingType::FundReport));
+        assert_eq!(document.metadata.get("fund_code").map(String::as_str), Some("G01234"));
+        assert!(is_fund_code(&document.ticker));
+
+        let company: EdinetDocument = serde_json::from_value(serde_json::json!({
+            "seqNumber": 2,
+            "docID": "S100CORP",
+            "filerName": "Toyota",
+            "secCode": "72030",
+            "formCode": "030000",
+            "submitDate": "2024-06-25",
+        }))
+        .unwrap();
+        let company = edinet_to_document(&company).unwrap();
+        assert_eq!(company.ticker, "7203");
+        assert_eq!(
+            company.metadata.get("source_url").map(String::as_str),
+            Some("https://disclosure2.edinet-fsa.go.jp/WZEK0040.aspx?S100CORP,,")
+        );
+
+        let transcript: EdinetDocument = serde_json::from_value(serde_json::json!({
+            "seqNumber": 3,
+            "docID": "S100CALL",
+            "filerName": "Toyota",
+            "secCode": "72030",
+            "docDescription": "決算説明会 質疑応答 書き起こし",
+            "submitDateTime": "2024-06-25 10:00",
+        }))
+        .unwrap();
+        assert!(matches!(edinet_to_document(&transcript).unwrap().filing_type, FilingType::Transcript));
+    }
+
+    #[test]
+    fn test_format_is_inferred_from_flags() {
+        let format = |xbrl: &str, pdf: &str| {
+            let mut entry = edinet_document("S100FLAG", "72030", "Toyota", "2024-06-25 15:00");
+            entry["xbrlFlag"] = xbrl.into();
+            entry["pdfFlag"] = pdf.into();
+            let doc: EdinetDocument = serde_json::from_value(entry).unwrap();
+            determine_document_format(&doc).as_str().to_string()
+        };
+
+        assert_eq!(format("1", "1"), "complete");
+        assert_eq!(format("1", "0"), "xbrl");
+        assert_eq!(format("0", "1"), "pdf");
+        assert_eq!(format("0", "0"), "txt");
+    }
+
+    #[test]
+    fn test_parse_submit_date() {
+        let expected = NaiveDate::from_ymd_opt(2024, 6, 25).unwrap();
+        for input in ["2024-06-25 09:00", "2024-06-25 09:00:00", "2024-06-25T09:00:00", "2024-06-25", "2024/06/25", " 20240625 "] {
+            assert_eq!(parse_submit_date(Some(input)).unwrap(), expected, "{}", input);
+        }
+
+        assert!(parse_submit_date(None).is_err());
+        assert!(parse_submit_date(Some("")).is_err());
+        assert!(parse_submit_date(Some("25/06/2024")).is_err());
+
+        // Older responses name the field submitDate
+        let document: EdinetDocument =
+            serde_json::from_value(serde_json::json!({"seqNumber": 1, "submitDate": "2024-06-25"})).unwrap();
+        assert_eq!(document.submit_date.as_deref(), Some("2024-06-25"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_documents_for_date() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .and(query_param("date", "2024-06-25"))
+            .and(query_param("type", "2"))
+            .and(header("Ocp-Apim-Subscription-Key", EDINET_TEST_API_KEY))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                "2024-06-25",
+                vec![
+                    edinet_document("S100AAAA", "72030", "トヨタ自動車株式会社", "2024-06-25 15:00"),
+                    edinet_document("S100BBBB", "67580", "ソニーグループ株式会社", "2024-06-25 15:30"),
+                ],
+            )))
+            .mount(&server)
+            .await;
+
+        let client = config.http_client_builder().unwrap().build().unwrap();
+        let documents = get_edinet_documents_for_date(&client, "2024-06-25", &config).await.unwrap();
+        let ids: Vec<_> = documents.iter().filter_map(|d| d.doc_id.as_deref()).collect();
+        assert_eq!(ids, ["S100AAAA", "S100BBBB"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_build_records_first_failed_date() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = mock_config(&server, temp_dir.path());
+        config.indexing = IndexingConfig { concurrency: 3, ..Default::default() };
+        // Monday to Friday, with Wednesday failing
+        for (date, doc_id) in [("2024-06-24", "S100MON1"), ("2024-06-25", "S100TUE1"), ("2024-06-27", "S100THU1"), ("2024-06-28", "S100FRI1")] {
+            Mock::given(method("GET"))
+                .and(path("/api/v2/documents.json"))
+                .and(query_param("date", date))
+                .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                    date,
+                    vec![edinet_document(doc_id, "72030", "トヨタ自動車株式会社", &format!("{} 15:00", date))],
+                )))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .and(query_param("date", "2024-06-26"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(edinet_error(500, "Internal Server Error")))
+            .mount(&server)
+            .await;
+
+        let database_path = config.database_path_str().to_string();
+        let start = NaiveDate::from_ymd_opt(2024, 6, 24).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 6, 28).unwrap();
+        let indexed = build_edinet_index_by_date_with_config(&database_path, start, end, &config, &EdinetIndexFilter::default())
+            .await
+            .unwrap();
+
+        assert_eq!(indexed, 4);
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), NaiveDate::from_ymd_opt(2024, 6, 25));
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_failures_stop_the_build() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = mock_config(&server, temp_dir.path());
+        config.indexing = IndexingConfig { concurrency: 1, ..Default::default() };
+        config.max_consecutive_failures = 2;
+        config.rate_limits.edinet_api_delay_ms = 50;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .and(query_param("date", "2024-06-24"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                "2024-06-24",
+                vec![edinet_document("S100MON1", "72030", "トヨタ自動車株式会社", "2024-06-24 15:00")],
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .respond_with(ResponseTemplate::new(503).set_body_json(edinet_error(503, "Service Unavailable")))
+            .mount(&server)
+            .await;
+
+        let database_path = config.database_path_str().to_string();
+        let start = NaiveDate::from_ymd_opt(2024, 6, 24).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 7, 31).unwrap();
+        let err = build_edinet_index_by_date_with_config(&database_path, start, end, &config, &EdinetIndexFilter::default())
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("after 2 failed dates in a row"), "{}", err);
+        assert!(err.contains("dates from 2024-06-25 on"), "{}", err);
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), Some(start));
+        // The run stopped instead of requesting every remaining date
+        assert!(server.received_requests().await.unwrap().len() < 10);
+    }
+
+    #[tokio::test]
+    async fn test_index_single_date() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .and(query_param("date", "2024-06-26"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                "2024-06-26",
+                vec![
+                    edinet_document("S100OLD1", "72030", "トヨタ自動車株式会社", "2024-06-26 09:00"),
+                    edinet_document("S100NEW1", "67580", "ソニーグループ株式会社", "2024-06-26 15:00"),
+                ],
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let database_path = config.database_path_str().to_string();
+        let first = edinet_to_document(&serde_json::from_value(
+            edinet_document("S100OLD1", "72030", "トヨタ自動車株式会社", "2024-06-26 09:00"),
+        ).unwrap()).unwrap();
+        storage::insert_document(&first, &database_path).await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 6, 26).unwrap();
+        storage::set_run_state(EDINET_INDEX_RUN_STATE_KEY, "2024-06-25", &database_path).await.unwrap();
+
+        let report = index_edinet_date(&database_path, date, &config, &EdinetIndexFilter::default()).await.unwrap();
+
+        assert_eq!(report, DateIndexReport { listed: 2, indexed: 2, added: 1 });
+        assert!(storage::get_document_by_id("S100NEW1", &database_path).await.unwrap().is_some());
+        // The date directly follows the previous mark, so the gap is closed
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), Some(date));
+    }
+
+    #[tokio::test]
+    async fn test_filtered_run_keeps_high_water_mark() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .and(query_param("date", "2024-06-26"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                "2024-06-26",
+                vec![edinet_document("S100NEW1", "67580", "ソニーグループ株式会社", "2024-06-26 15:00")],
+            )))
+            .mount(&server)
+            .await;
+
+        let database_path = config.database_path_str().to_string();
+        let previous = NaiveDate::from_ymd_opt(2024, 6, 25).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 6, 26).unwrap();
+        storage::set_run_state(EDINET_INDEX_RUN_STATE_KEY, "2024-06-25", &database_path).await.unwrap();
+
+        let xbrl_only = EdinetIndexFilter::default().with_xbrl_only(true);
+        index_edinet_date(&database_path, date, &config, &xbrl_only).await.unwrap();
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), Some(previous));
+
+        let annual_only = EdinetIndexFilter::from_code_lists(Some("120"), None);
+        build_edinet_index_by_date_with_config(&database_path, date, date, &config, &annual_only).await.unwrap();
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), Some(previous));
+
+        index_edinet_date(&database_path, date, &config, &EdinetIndexFilter::default()).await.unwrap();
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), Some(date));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_date_is_retried() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                "2024-06-25",
+                vec![edinet_document("S100RATE", "72030", "トヨタ自動車株式会社", "2024-06-25 15:00")],
+            )))
+            .mount(&server)
+            .await;
+
+        let client = config.http_client_builder().unwrap().build().unwrap();
+        let err = get_edinet_documents_for_date(&client, "2024-06-25", &config).await.unwrap_err();
+        assert!(matches!(err, EdinetError::RateLimited { retry_after: Some(d) } if d.is_zero()), "{:?}", err);
+
+        // The second 429 is retried and the date still gets indexed
+        let database_path = config.database_path_str().to_string();
+        let date = NaiveDate::from_ymd_opt(2024, 6, 25).unwrap();
+        let indexed = build_edinet_index_by_date_with_config(&database_path, date, date, &config, &EdinetIndexFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(indexed, 1);
+        assert_eq!(get_edinet_last_run_date(&database_path).await.unwrap(), Some(date));
+    }
+
+    #[tokio::test]
+    async fn test_api_error_response() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(edinet_error(401, "Access denied due to invalid subscription key.")))
+            .mount(&server)
+            .await;
+
+        let err = check_edinet_api(&config).await.unwrap_err();
+        assert!(matches!(err, EdinetError::ApiError { status_code: 401, .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_index_new_documents_skips_known() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = mock_config(&server, temp_dir.path());
+        let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+        Mock::given(method("GET"))
+            .and(path("/api/v2/documents.json"))
+            .and(query_param("date", today.as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(edinet_documents(
+                &today,
+                vec![edinet_document("S100NEW1", "72030", "トヨタ自動車株式会社", &format!("{} 15:00", today))],
+            )))
+            .mount(&server)
+            .await;
+
+        let database_path = config.database_path_str().to_string();
+        let filter = EdinetIndexFilter::default();
+        let first = index_new_edinet_documents(&database_path, &config, &filter).await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].ticker, "7203");
+
+        let second = index_new_edinet_documents(&database_path, &config, &filter).await.unwrap();
+        assert!(second.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_index_edinet_archives() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let database_path = database_path.to_str().unwrap();
+        let shared = temp_dir.path().join("shared");
+        std::fs::create_dir_all(shared.join("nested")).unwrap();
+
+        let zip_path = shared.join("nested").join("S100TEST-2024-06-25.zip");
+        std::fs::write(&zip_path, crate::test_support::zip_payload(&[
+            ("XBRL/PublicDoc/jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.xbrl", "<xbrl/>"),
+            ("XBRL/PublicDoc/0101010_honbun_jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25_ixbrl.htm",
+                "<html><body><p>当社グループは自動車事業を行っています。</p></body></html>"),
+        ])).unwrap();
+        std::fs::write(shared.join("notes.txt"), "not an archive").unwrap();
+        std::fs::write(shared.join("broken.zip"), "not a zip").unwrap();
+
+        assert_eq!(index_edinet_archives(&shared, database_path).await.unwrap(), 1);
+
+        let document = storage::get_document_by_id("S100TEST", database_path).await.unwrap().unwrap();
+        assert_eq!(document.ticker, "E02144");
+        assert_eq!(document.filing_type.as_str(), "Annual Securities Report");
+        assert_eq!(document.date, NaiveDate::from_ymd_opt(2024, 6, 25).unwrap());
+        assert_eq!(document.content_path, zip_path);
+        assert_eq!(document.metadata.get("period_end").map(String::as_str), Some("2024-03-31"));
+        assert!(document.metadata["content_preview"].contains("自動車事業"));
+    }
+
+    #[tokio::test]
+    async fn test_reclassify_edinet_documents() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let database_path = database_path.to_str().unwrap();
+
+        let documents = [
+            // Indexed with EDGAR types by older mappings
+            ("S100ASR1", FilingType::TenQ, vec![("form_code", "030000")]),
+            ("S100ASR2", FilingType::TenK, vec![("form_code", "030000")]),
+            ("S100SSR1", FilingType::Other("EDINET Form 050000".to_string()), vec![("form_code", "050000")]),
+            ("S100OK01", FilingType::AnnualSecuritiesReport, vec![("form_code", "030000")]),
+            ("S100FUND", FilingType::Other("EDINET Form 07A000".to_string()), vec![("form_code", "07A000"), ("fund_code", "G01234")]),
+            // Classified from its text; the unknown form code must not undo that beyond the EDINET type
+            ("S100TEXT", FilingType::EightK, vec![("form_code", "999000")]),
+            ("S100RINJ", FilingType::ExtraordinaryReport, vec![("form_code", "999000")]),
+        ];
+        for (id, filing_type, metadata) in documents {
+            let document = Document {
+                id: id.to_string(),
+                ticker: "7203".to_string(),
+                company_name: "トヨタ自動車株式会社".to_string(),
+                filing_type,
+                source: Source::Edinet,
+                date: NaiveDate::from_ymd_opt(2024, 6, 25).unwrap(),
+                content_path: PathBuf::new(),
+                metadata: metadata.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                format: DocumentFormat::Complete,
+                tags: Vec::new(),
+            };
+            storage::insert_document(&document, database_path).await.unwrap();
+        }
+
+        let report = reclassify_edinet_documents(database_path).await.unwrap();
+        assert_eq!(report.checked, 7);
+        let mut changed: Vec<(&str, &str)> = report.changed.iter().map(|(id, _, new)| (id.as_str(), new.as_str())).collect();
+        changed.sort();
+        assert_eq!(
+            changed,
+            [
+                ("S100ASR1", "Annual Securities Report"),
+                ("S100ASR2", "Annual Securities Report"),
+                ("S100FUND", FilingType::FundReport.as_str()),
+                ("S100SSR1", "Semi-Annual Securities Report"),
+                ("S100TEXT", "Extraordinary Report"),
+            ]
+        );
+
+        let document = storage::get_document_by_id("S100TEXT", database_path).await.unwrap().unwrap();
+        assert_eq!(document.filing_type.as_str(), "Extraordinary Report");
+        assert!(reclassify_edinet_documents(database_path).await.unwrap().changed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_form_is_classified_from_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let database_path = database_path.to_str().unwrap();
+
+        let zip_path = temp_dir.path().join("S100RINJ-2024-06-25.zip");
+        std::fs::write(&zip_path, crate::test_support::zip_payload(&[
+            ("XBRL/PublicDoc/jpcrp999000-xyz-001_E02144-000_2024-06-25_01_2024-06-25.xbrl", "<xbrl/>"),
+            ("XBRL/PublicDoc/0000000_honbun_jpcrp999000-xyz-001_E02144-000_2024-06-25_01_2024-06-25_ixbrl.htm",
+                "<html><body><p>【表紙】</p><p>【提出書類】臨時報告書</p></body></html>"),
+        ])).unwrap();
+
+        assert_eq!(index_edinet_archives(&zip_path, database_path).await.unwrap(), 1);
+        let document = storage::get_document_by_id("S100RINJ", database_path).await.unwrap().unwrap();
+        assert_eq!(document.filing_type.as_str(), "Extraordinary Report");
+
+        // Documents indexed as unknown are classified once their full text is stored
+        storage::update_filing_type("S100RINJ", &FilingType::Other("EDINET Form 999000".to_string()), database_path).await.unwrap();
+        store_zip_content(&zip_path, "S100RINJ", database_path).await.unwrap();
+        let document = storage::get_document_by_id("S100RINJ", database_path).await.unwrap().unwrap();
+        assert_eq!(document.filing_type.as_str(), "Extraordinary Report");
+    }
+
+    #[tokio::test]
+    async fn test_content_preview_length() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let database_path = database_path.to_str().unwrap();
+        let mut config = Config::from_env().unwrap();
+
+        let zip_path = temp_dir.path().join("S100PREV-2024-06-25.zip");
+        let body = format!("<html><body><p>{}</p></body></html>", "自動車事業。".repeat(100));
+        std::fs::write(&zip_path, crate::test_support::zip_payload(&[
+            ("XBRL/PublicDoc/jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.xbrl", "<xbrl/>"),
+            ("XBRL/PublicDoc/0101010_honbun_jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25_ixbrl.htm", &body),
+        ])).unwrap();
+
+        config.indexing.content_preview_length = 30;
+        index_edinet_archives_with_config(&zip_path, database_path, &config).await.unwrap();
+        let document = storage::get_document_by_id("S100PREV", database_path).await.unwrap().unwrap();
+        assert!(document.metadata["content_preview"].len() <= 33);
+
+        // A length of 0 indexes the document without a preview
+        config.indexing.content_preview_length = 0;
+        index_edinet_archives_with_config(&zip_path, database_path, &config).await.unwrap();
+        let document = storage::get_document_by_id("S100PREV", database_path).await.unwrap().unwrap();
+        assert!(!document.metadata.contains_key("content_preview"));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_downloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let database_path = database_path.to_str().unwrap();
+        let downloads = temp_dir.path().join("downloads");
+        std::fs::create_dir_all(downloads.join("edinet").join("7203")).unwrap();
+        let config = Config::from_env().unwrap();
+
+        let payload = crate::test_support::zip_payload(&[
+            ("XBRL/PublicDoc/jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.xbrl", "<xbrl/>"),
+        ]);
+        let orphan = downloads.join("edinet").join("7203").join("S100ORPH-2024-06-25.zip");
+        std::fs::write(&orphan, &payload).unwrap();
+        std::fs::write(downloads.join("edinet").join("7203").join("S100KNOW-2024-06-25.zip"), &payload).unwrap();
+
+        let indexed = |doc_id| {
+            let doc: EdinetDocument = serde_json::from_value(edinet_document(doc_id, "72030", "トヨタ自動車株式会社", "2024-06-25 15:00")).unwrap();
+            edinet_to_document(&doc).unwrap()
+        };
+        storage::insert_document(&indexed("S100KNOW"), database_path).await.unwrap();
+        let mut stale = indexed("S100GONE");
+        stale.content_path = downloads.join("edinet").join("7203").join("S100GONE-2024-06-25.zip");
+        storage::insert_document(&stale, database_path).await.unwrap();
+
+        let edgar_dir = downloads.join("edgar");
+        std::fs::create_dir_all(edgar_dir.join("AAPL")).unwrap();
+        let filing = edgar_dir.join("AAPL").join("10-K-2024-11-01-000032019324000123.txt");
+        std::fs::write(&filing, "ANNUAL REPORT PURSUANT TO SECTION 13").unwrap();
+        std::fs::write(edgar_dir.join("AAPL").join("10-K-2024-11-01-000032019324000123.txt.part"), "").unwrap();
+
+        let edinet_dir = downloads.join("edinet");
+        let report = reconcile_downloads(&edinet_dir, &edgar_dir, database_path).await.unwrap();
+        assert_eq!(report.unindexed_files, vec![orphan, filing]);
+        assert_eq!(report.missing_files.len(), 1);
+        assert_eq!(report.missing_files[0].0, "S100GONE");
+
+        assert_eq!(fix_reconcile_report(&report, database_path, &config).await.unwrap(), (2, 1));
+        assert!(reconcile_downloads(&edinet_dir, &edgar_dir, database_path).await.unwrap().is_consistent());
+        assert!(storage::get_document_by_id("0000320193-24-000123", database_path).await.unwrap().is_some());
+        let gone = storage::get_document_by_id("S100GONE", database_path).await.unwrap().unwrap();
+        assert!(gone.content_path.as_os_str().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stored_content_is_searchable_without_the_file() {
+        let server = MockServer::start().await;
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = mock_config(&server, temp_dir.path());
+        config.indexing.store_content = true;
+        let database_path = config.database_path_str().to_string();
+
+        // The phrase sits beyond the content preview, so only the stored full text contains it
+        let body = format!("<html><body><p>{}</p><p>水素エンジンの開発</p></body></html>", "自動車事業。".repeat(1000));
+        let zip_path = temp_dir.path().join("S100FULL-2024-06-25.zip");
+        std::fs::write(&zip_path, crate::test_support::zip_payload(&[
+            ("XBRL/PublicDoc/jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.xbrl", "<xbrl/>"),
+            ("XBRL/PublicDoc/0101010_honbun_jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25_ixbrl.htm", &body),
+        ])).unwrap();
+
+        assert_eq!(index_edinet_archives_with_config(&zip_path, &database_path, &config).await.unwrap(), 1);
+        std::fs::remove_file(&zip_path).unwrap();
+
+        let query = crate::models::SearchQuery::builder().text_query("水素エンジン").build().unwrap();
+        let found = storage::search_documents(&query, &database_path, 10).await.unwrap();
+        assert_eq!(found.iter().map(|d| d.id.as_str()).collect::<Vec<_>>(), ["S100FULL"]);
+        assert_eq!(storage::count_matching_documents(&query, &database_path).await.unwrap(), 1);
+
+        let content = storage::get_document_content("S100FULL", &database_path).await.unwrap().unwrap();
+        assert!(content.ends_with("水素エンジンの開発"));
+    }
+}
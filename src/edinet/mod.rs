@@ -9,6 +9,7 @@ pub mod indexer;
 pub mod downloader;
 pub mod errors;
 pub mod reader;
+pub mod holidays;
 
 pub use types::*;
 pub use errors::EdinetError;
@@ -17,9 +18,25 @@ pub use errors::EdinetError;
 pub use indexer::{
     build_edinet_index,
     build_edinet_index_by_date,
+    build_edinet_index_by_date_filtered,
+    build_edinet_index_by_date_with_config,
+    check_edinet_api,
+    index_edinet_archives,
+    index_edinet_date,
+    index_new_edinet_documents,
+    DateIndexReport,
+    reconcile_downloads,
+    fix_reconcile_report,
+    ReconcileReport,
+    reclassify_edinet_documents,
+    ReclassifyReport,
+    EdinetIndexFilter,
     update_edinet_index,
+    update_edinet_index_incremental,
+    update_edinet_index_since_last_run,
+    get_edinet_last_run_date,
     get_edinet_index_stats,
 };
 
 pub use downloader::download_documents;
-pub use reader::{read_edinet_zip, DocumentSection};
\ No newline at end of file
+pub use reader::{read_edinet_zip, DocumentSection, NoTextSections};
\ No newline at end of file
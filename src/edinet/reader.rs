@@ -1,11 +1,14 @@
 //! EDINET document reader for ZIP file content extraction and preview
 
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
 use zip::ZipArchive;
 use scraper::{Html, Selector};
 use anyhow::{Result, Context};
 
+use crate::edinet::{downloader, EdinetDocument};
+
 /// Represents a section of an EDINET document
 #[derive(Debug, Clone)]
 pub struct DocumentSection {
@@ -13,10 +16,41 @@ pub struct DocumentSection {
     pub section_type: String,
     /// Raw filename within ZIP
     pub filename: String,
+    /// Nested archive the file came from (e.g. `attach.zip`), empty for the outer ZIP
+    pub origin: String,
     /// Extracted text content (preview)
     pub content: String,
-    /// Full content length before truncation
+    /// Full content length before truncation (raw entry size in bytes until loaded)
     pub full_length: usize,
+    /// Whether `content` has been read; lazily listed sections start out unloaded
+    pub loaded: bool,
+}
+
+impl DocumentSection {
+    /// Full path of the file including any nested archives, e.g. `attach.zip!/doc.htm`
+    pub fn display_path(&self) -> String {
+        if self.origin.is_empty() {
+            self.filename.clone()
+        } else {
+            format!("{}!/{}", self.origin, self.filename)
+        }
+    }
+}
+
+/// Maximum depth of ZIP archives nested inside an EDINET ZIP that will be read
+const MAX_NESTED_ZIP_DEPTH: usize = 3;
+
+/// Extensions of binary attachments that are never shown as text
+const BINARY_EXTENSIONS: [&str; 7] = ["gif", "jpg", "jpeg", "png", "bmp", "pdf", "xlsx"];
+
+/// A ZIP that opened fine but holds no readable text, e.g. a PDF-only filing
+///
+/// Callers can `downcast_ref` the returned `anyhow::Error` to tell this apart from a ZIP that failed to open.
+#[derive(Debug, thiserror::Error)]
+#[error("No text sections, only binaries/images ({file_count} files)")]
+pub struct NoTextSections {
+    /// Number of files in the archive
+    pub file_count: usize,
 }
 
 /// File type mapping based on EDINET document structure
@@ -65,7 +99,8 @@ pub fn get_section_type(filename: &str) -> String {
         "Research & Development".to_string()
     } else if base_name.contains("honbun") {
         "Content Section".to_string()
-    } else if base_name.contains("fuzoku") {
+    } else if filename.contains("fuzoku") {
+        // Attachments may be recognized by their directory rather than their own name
         "Attachment".to_string()
     } else if base_name.ends_with(".xbrl") {
         "XBRL Data".to_string()
@@ -124,7 +159,7 @@ pub fn extract_text_from_html(html_content: &str, max_length: usize) -> Result<(
     Ok((text_content, full_length))
 }
 
-/// Read and parse EDINET ZIP file contents
+/// Read and parse EDINET ZIP file contents, including any nested ZIP archives
 pub fn read_edinet_zip(
     zip_path: &str, 
     section_limit: usize, 
@@ -137,87 +172,720 @@ pub fn read_edinet_zip(
         .with_context(|| format!("Failed to read ZIP archive: {}", zip_path))?;
     
     let mut sections = Vec::new();
-    let mut processed_count = 0;
+    read_archive_sections(&mut archive, "", 0, section_limit, Some(preview_length), &mut sections)?;
+    ensure_text_sections(&archive, &sections)?;
     
+    Ok(sections)
+}
+
+/// Text of every HTML section in an EDINET ZIP, as stored for offline full-text search
+///
+/// XBRL and other raw files are left out; their text duplicates the HTML sections.
+pub fn read_edinet_full_text(zip_path: &str) -> Result<String> {
+    let sections = read_edinet_zip(zip_path, usize::MAX, usize::MAX)?;
+    Ok(sections
+        .iter()
+        .filter(|section| has_extension(&section.filename, &["htm", "html"]))
+        .map(|section| section.content.trim())
+        .filter(|content| !content.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+/// List the sections of a downloaded EDINET document without reading their content
+///
+/// Section content is read on demand with `load_section_content`. Summary JSON files are
+/// small and returned fully loaded.
+pub fn list_edinet_sections(path: &str, section_limit: usize) -> Result<Vec<DocumentSection>> {
+    if has_extension(path, &["json"]) {
+        return read_edinet_summary(path);
+    }
+
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open ZIP file: {}", path))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read ZIP archive: {}", path))?;
+
+    let mut sections = Vec::new();
+    read_archive_sections(&mut archive, "", 0, section_limit, None, &mut sections)?;
+    ensure_text_sections(&archive, &sections)?;
+
+    Ok(sections)
+}
+
+/// Fail with [`NoTextSections`] if nothing readable was found in `archive`
+fn ensure_text_sections<R: Read + Seek>(archive: &ZipArchive<R>, sections: &[DocumentSection]) -> Result<()> {
+    if sections.is_empty() {
+        let file_count = archive.file_names().filter(|name| !name.ends_with('/')).count();
+        return Err(NoTextSections { file_count }.into());
+    }
+    Ok(())
+}
+
+/// Japanese headings of annual report items and the section types they are listed under
+const SECTION_ALIASES: [(&str, &str); 10] = [
+    ("事業等のリスク", "Risk Factors"),
+    ("リスク", "Risk Factors"),
+    ("事業の内容", "Business Overview"),
+    ("企業の概況", "Business Overview"),
+    ("経営者による", "Management Analysis"),
+    ("経理の状況", "Financial Statements"),
+    ("財務諸表", "Financial Statements"),
+    ("コーポレート・ガバナンス", "Corporate Governance"),
+    ("役員の状況", "Board of Directors"),
+    ("研究開発", "Research & Development"),
+];
+
+/// Index of the first listed section whose type or file name matches `query`
+///
+/// Matching is case-insensitive and partial, so `risk` finds `Risk Factors`; Japanese headings
+/// such as `事業等のリスク` are matched by the section type they belong to.
+pub fn find_section(sections: &[DocumentSection], query: &str) -> Option<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    let section_type = SECTION_ALIASES
+        .iter()
+        .find(|(heading, _)| query.contains(heading))
+        .map(|(_, section_type)| section_type.to_lowercase());
+
+    sections
+        .iter()
+        .position(|section| section_type.as_deref() == Some(section.section_type.to_lowercase().as_str()))
+        .or_else(|| sections.iter().position(|section| section.section_type.to_lowercase().contains(&query)))
+        .or_else(|| sections.iter().position(|section| section.display_path().to_lowercase().contains(&query)))
+}
+
+/// An entry of a document's table of contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocEntry {
+    pub title: String,
+    /// 0 for report chapters and files outside them, 1 for the sections of a chapter
+    pub level: usize,
+    /// Section shown when the entry is selected; a chapter shows its first section
+    pub section_index: usize,
+}
+
+/// Chapters of part 1 of an annual securities report (有価証券報告書), by the chapter digits of `honbun` file names
+const REPORT_CHAPTERS: [(&str, &str); 7] = [
+    ("01", "第1 企業の概況"),
+    ("02", "第2 事業の状況"),
+    ("03", "第3 設備の状況"),
+    ("04", "第4 提出会社の状況"),
+    ("05", "第5 経理の状況"),
+    ("06", "第6 提出会社の株式事務の概要"),
+    ("07", "第7 提出会社の参考情報"),
+];
+
+/// Report chapter of a `honbun` file, from the part and chapter digits of names like `0105010_honbun_...htm`
+fn report_chapter(filename: &str) -> Option<String> {
+    let base_name = filename.rsplit('/').next().unwrap_or(filename);
+    if !base_name.contains("honbun") {
+        return None;
+    }
+    let code = base_name.get(..7).filter(|code| code.bytes().all(|b| b.is_ascii_digit()))?;
+    let (part, chapter) = (&code[..2], &code[2..4]);
+    match part {
+        "01" => Some(
+            REPORT_CHAPTERS
+                .iter()
+                .find(|(digits, _)| *digits == chapter)
+                .map(|(_, title)| title.to_string())
+                .unwrap_or_else(|| format!("第{}", chapter.trim_start_matches('0'))),
+        ),
+        "02" => Some("第二部 提出会社の保証会社等の情報".to_string()),
+        _ => None,
+    }
+}
+
+/// Table of contents of listed sections, grouping `honbun` files under the report chapter they belong to
+///
+/// Sections without a more specific type are listed by file name.
+pub fn table_of_contents(sections: &[DocumentSection]) -> Vec<TocEntry> {
+    let mut entries = Vec::new();
+    let mut current_chapter: Option<String> = None;
+    for (index, section) in sections.iter().enumerate() {
+        let title = match section.section_type.as_str() {
+            "Content Section" | "Other" => section.filename.rsplit('/').next().unwrap_or(&section.filename).to_string(),
+            section_type => section_type.to_string(),
+        };
+        match report_chapter(&section.filename) {
+            Some(chapter) => {
+                if current_chapter.as_ref() != Some(&chapter) {
+                    entries.push(TocEntry { title: chapter.clone(), level: 0, section_index: index });
+                    current_chapter = Some(chapter);
+                }
+                entries.push(TocEntry { title, level: 1, section_index: index });
+            }
+            None => {
+                current_chapter = None;
+                entries.push(TocEntry { title, level: 0, section_index: index });
+            }
+        }
+    }
+    entries
+}
+
+/// Index of the first section whose text contains `keyword`, loading sections one at a time until found
+///
+/// Sections loaded while searching keep their content, so a later jump to them is instant.
+pub fn find_section_by_content(
+    path: &str,
+    sections: &mut [DocumentSection],
+    keyword: &str,
+    preview_length: usize,
+) -> Result<Option<usize>> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Ok(None);
+    }
+    for (index, section) in sections.iter_mut().enumerate() {
+        load_section_content(path, section, preview_length)?;
+        if section.content.contains(keyword) {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// Extract the single section matching `query` from a downloaded EDINET document
+///
+/// Sections are matched by type or file name with [`find_section`] so only that section's content is
+/// read; failing that, section text is searched for `query`. Returns `Ok(None)` when nothing matches.
+pub fn read_edinet_section(
+    path: &str,
+    query: &str,
+    section_limit: usize,
+    preview_length: usize,
+) -> Result<Option<DocumentSection>> {
+    let mut sections = list_edinet_sections(path, section_limit)?;
+    let index = match find_section(&sections, query) {
+        Some(index) => Some(index),
+        None => find_section_by_content(path, &mut sections, query, preview_length)?,
+    };
+    let Some(index) = index else {
+        return Ok(None);
+    };
+
+    let mut section = sections.swap_remove(index);
+    load_section_content(path, &mut section, preview_length)?;
+    Ok(Some(section))
+}
+
+/// Read the content of a section listed by `list_edinet_sections`
+pub fn load_section_content(zip_path: &str, section: &mut DocumentSection, preview_length: usize) -> Result<()> {
+    if section.loaded {
+        return Ok(());
+    }
+
+    let file = File::open(zip_path)
+        .with_context(|| format!("Failed to open ZIP file: {}", zip_path))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read ZIP archive: {}", zip_path))?;
+
+    // Walk through nested archives, e.g. `attach.zip!/inner.zip` then the file itself
+    let mut entry_path: Vec<&str> = section.origin.split("!/").filter(|p| !p.is_empty()).collect();
+    entry_path.push(&section.filename);
+    let bytes = read_nested_entry(&mut archive, &entry_path)?;
+
+    let (content, full_length) = section_text(&section.filename, bytes, preview_length)?
+        .unwrap_or_else(|| ("(binary content)".to_string(), 0));
+    section.content = content;
+    section.full_length = full_length;
+    section.loaded = true;
+
+    Ok(())
+}
+
+/// Read an entry by path, where all but the last element name nested ZIP archives
+fn read_nested_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, entry_path: &[&str]) -> Result<Vec<u8>> {
+    let (name, rest) = entry_path
+        .split_first()
+        .context("Empty ZIP entry path")?;
+
+    let mut bytes = Vec::new();
+    archive.by_name(name)
+        .with_context(|| format!("File not found in ZIP: {}", name))?
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read file from ZIP: {}", name))?;
+
+    if rest.is_empty() {
+        return Ok(bytes);
+    }
+
+    let mut nested = ZipArchive::new(Cursor::new(bytes))
+        .with_context(|| format!("Failed to read nested ZIP: {}", name))?;
+    read_nested_entry(&mut nested, rest)
+}
+
+/// Extract preview text from a ZIP entry, or `None` if it is not UTF-8 text
+fn section_text(filename: &str, bytes: Vec<u8>, preview_length: usize) -> Result<Option<(String, usize)>> {
+    let Ok(contents) = String::from_utf8(bytes) else {
+        return Ok(None);
+    };
+
+    if filename.ends_with(".htm") || filename.ends_with(".html") {
+        extract_text_from_html(&contents, preview_length).map(Some)
+    } else {
+        // For XBRL and other files, just show a sample of the raw content
+        Ok(Some((truncate_preview(&contents, preview_length), contents.len())))
+    }
+}
+
+/// Read a single downloaded file, e.g. an EDGAR filing, as one section of type `section_type`
+///
+/// HTML is reduced to its text; other files are shown as they are. Binary files such as PDFs
+/// fail with [`NoTextSections`].
+pub fn read_text_file(path: &Path, section_type: &str, preview_length: usize) -> Result<Vec<DocumentSection>> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+    let Some((content, full_length)) = section_text(&filename.to_lowercase(), bytes, preview_length)? else {
+        return Err(NoTextSections { file_count: 1 }.into());
+    };
+
+    Ok(vec![DocumentSection {
+        section_type: section_type.to_string(),
+        filename,
+        origin: String::new(),
+        content,
+        full_length,
+        loaded: true,
+    }])
+}
+
+/// Read a downloaded EDINET document, either a full ZIP or a metadata summary JSON
+pub fn read_edinet_document(
+    path: &str,
+    section_limit: usize,
+    preview_length: usize,
+) -> Result<Vec<DocumentSection>> {
+    if has_extension(path, &["json"]) {
+        read_edinet_summary(path)
+    } else {
+        read_edinet_zip(path, section_limit, preview_length)
+    }
+}
+
+/// Read a metadata summary written by `fast10k download --format summary` as an outline section
+pub fn read_edinet_summary(summary_path: &str) -> Result<Vec<DocumentSection>> {
+    let contents = std::fs::read_to_string(summary_path)
+        .with_context(|| format!("Failed to open summary file: {}", summary_path))?;
+    let document: EdinetDocument = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse summary file: {}", summary_path))?;
+
+    let fields = [
+        ("Document ID", &document.doc_id),
+        ("Description", &document.doc_description),
+        ("Filer", &document.filer_name),
+        ("EDINET Code", &document.edinet_code),
+        ("Securities Code", &document.sec_code),
+        ("Document Type Code", &document.doc_type_code),
+        ("Form Code", &document.form_code),
+        ("Period Start", &document.period_start),
+        ("Period End", &document.period_end),
+        ("Submitted", &document.submit_date),
+        ("Parent Document", &document.parent_doc_id),
+        ("XBRL", &document.xbrl_flag),
+        ("PDF", &document.pdf_flag),
+        ("Attachments", &document.attach_doc_flag),
+        ("English", &document.english_flag),
+        ("CSV", &document.csv_flag),
+    ];
+    let outline = fields
+        .iter()
+        .filter_map(|(label, value)| value.as_deref().map(|v| format!("{}: {}", label, v)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let filename = Path::new(summary_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| summary_path.to_string());
+
+    Ok(vec![DocumentSection {
+        section_type: "Document Summary".to_string(),
+        filename,
+        origin: String::new(),
+        full_length: outline.len(),
+        content: outline,
+        loaded: true,
+    }])
+}
+
+/// Filing details encoded in the file names of an EDINET ZIP
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArchiveFilingInfo {
+    pub edinet_code: Option<String>,
+    pub form_code: Option<String>,
+    pub period_end: Option<String>,
+    pub submit_date: Option<String>,
+    pub has_xbrl: bool,
+    pub has_pdf: bool,
+}
+
+/// Read filing details from the entry names of an EDINET ZIP without extracting it
+///
+/// XBRL and inline XBRL files are named like
+/// `jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.xbrl`, i.e. form code,
+/// EDINET code, period end and submission date.
+pub fn read_edinet_zip_filing_info(zip_path: &str) -> Result<ArchiveFilingInfo> {
+    let file = File::open(zip_path)
+        .with_context(|| format!("Failed to open ZIP file: {}", zip_path))?;
+    let archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read ZIP archive: {}", zip_path))?;
+
+    let mut info = ArchiveFilingInfo::default();
+    for name in archive.file_names() {
+        info.has_xbrl |= has_extension(name, &["xbrl"]);
+        info.has_pdf |= has_extension(name, &["pdf"]);
+        if info.edinet_code.is_none() {
+            if let Some(parsed) = parse_filing_file_name(name) {
+                info = ArchiveFilingInfo { has_xbrl: info.has_xbrl, has_pdf: info.has_pdf, ..parsed };
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// Parse `jp{ns}{form}-{report}-{seq}_{edinet}-{seq}_{period end}_{n}_{submit date}` from a file name
+fn parse_filing_file_name(name: &str) -> Option<ArchiveFilingInfo> {
+    let base_name = name.rsplit('/').next().unwrap_or(name);
+    let start = base_name.find("jp")?;
+    let stem = base_name[start..].split('.').next()?;
+    let parts: Vec<&str> = stem.split('_').collect();
+    if parts.len() < 5 {
+        return None;
+    }
+
+    let is_date = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok();
+    let form_code: String = parts[0]
+        .split('-')
+        .next()?
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .collect();
+    let edinet_code = parts[1].split('-').next()?;
+    if form_code.len() != 6 || !edinet_code.starts_with('E') || !is_date(parts[2]) || !is_date(parts[4]) {
+        return None;
+    }
+
+    Some(ArchiveFilingInfo {
+        edinet_code: Some(edinet_code.to_string()),
+        form_code: Some(form_code),
+        period_end: Some(parts[2].to_string()),
+        submit_date: Some(parts[4].to_string()),
+        has_xbrl: false,
+        has_pdf: false,
+    })
+}
+
+/// Find the downloaded file for `doc_id` in `dir`, preferring the full ZIP over a summary.
+/// Attachment and CSV ZIPs downloaded alongside the document are skipped.
+pub fn find_downloaded_document(dir: &Path, doc_id: &str) -> Option<PathBuf> {
+    let mut summary = None;
+
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        // Only consider files that exactly match the document ID
+        if !filename.contains(doc_id) || downloader::is_companion_download(filename) {
+            continue;
+        }
+        if has_extension(filename, &["zip"]) {
+            return Some(path);
+        }
+        if has_extension(filename, &["json"]) {
+            summary = Some(path);
+        }
+    }
+
+    summary
+}
+
+/// Find the financial data CSV ZIP downloaded for `doc_id` with `--format csv`
+pub fn find_downloaded_financials(dir: &Path, doc_id: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?.flatten().map(|entry| entry.path()).find(|path| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| name.contains(doc_id) && name.ends_with(downloader::CSV_FILE_SUFFIX))
+    })
+}
+
+/// One value from EDINET's financial data CSVs
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FinancialLine {
+    /// XBRL element, e.g. `jppfs_cor:NetSales`
+    pub element_id: String,
+    /// Japanese item name, e.g. `売上高`
+    pub label: String,
+    /// XBRL context, e.g. `CurrentYearDuration`
+    pub context_id: String,
+    /// Relative period, e.g. `当期` or `前期`
+    pub period: String,
+    /// Unit name, e.g. `円`; empty for text values
+    pub unit: String,
+    /// Value as written in the CSV; `－` marks a nil value
+    pub value: String,
+}
+
+/// Column headers of EDINET's CSV files
+const CSV_ELEMENT_ID: &str = "要素ID";
+const CSV_LABEL: &str = "項目名";
+const CSV_CONTEXT_ID: &str = "コンテキストID";
+const CSV_PERIOD: &str = "相対年度";
+const CSV_UNIT: &str = "単位";
+const CSV_VALUE: &str = "値";
+
+/// Read the financial data lines of every CSV file in a ZIP downloaded with `--format csv`
+///
+/// EDINET writes the CSVs tab-separated in UTF-16LE under `XBRL_TO_CSV/`, one per XBRL instance
+/// (e.g. the report and its audit reports).
+pub fn read_edinet_financials(zip_path: &str) -> Result<Vec<FinancialLine>> {
+    let file = File::open(zip_path)
+        .with_context(|| format!("Failed to open ZIP file: {}", zip_path))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read ZIP archive: {}", zip_path))?;
+
+    let csv_names: Vec<String> = archive
+        .file_names()
+        .filter(|name| has_extension(name, &["csv"]))
+        .map(str::to_string)
+        .collect();
+    if csv_names.is_empty() {
+        anyhow::bail!("No CSV files in {}", zip_path);
+    }
+
+    let mut lines = Vec::new();
+    for name in csv_names {
+        let mut bytes = Vec::new();
+        archive.by_name(&name)?.read_to_end(&mut bytes)?;
+        lines.extend(parse_financial_csv(&decode_csv_text(&bytes)).with_context(|| format!("Failed to parse {}", name))?);
+    }
+    Ok(lines)
+}
+
+/// Decode CSV bytes, which EDINET writes as UTF-16LE with a byte order mark
+fn decode_csv_text(bytes: &[u8]) -> String {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn parse_financial_csv(text: &str) -> Result<Vec<FinancialLine>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header == name)
+            .with_context(|| format!("Missing column {}", name))
+    };
+    let (element_id, label, value) = (column(CSV_ELEMENT_ID)?, column(CSV_LABEL)?, column(CSV_VALUE)?);
+    let (context_id, period, unit) = (column(CSV_CONTEXT_ID).ok(), column(CSV_PERIOD).ok(), column(CSV_UNIT).ok());
+
+    let mut lines = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or_default().to_string();
+        lines.push(FinancialLine {
+            element_id: field(Some(element_id)),
+            label: field(Some(label)),
+            context_id: field(context_id),
+            period: field(period),
+            unit: field(unit),
+            value: field(Some(value)),
+        });
+    }
+    Ok(lines)
+}
+
+/// Write financial lines to a CSV file (UTF-8, comma-separated), returning the number of lines written
+pub fn export_financials(lines: &[FinancialLine], path: &Path) -> Result<usize> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Cannot create export directory: {}", parent.display()))?;
+    }
+
+    let mut writer = csv::Writer::from_path(path)
+        .with_context(|| format!("Cannot create export file: {}", path.display()))?;
+    for line in lines {
+        writer.serialize(line)?;
+    }
+    writer.flush()?;
+    Ok(lines.len())
+}
+
+/// Section type of the viewer section built by [`financials_section`]
+pub const FINANCIALS_SECTION_TYPE: &str = "Financial Data (CSV)";
+
+/// Present financial lines as a viewer section, one `label (period): value unit` line each
+pub fn financials_section(lines: &[FinancialLine], zip_path: &Path) -> DocumentSection {
+    let content = lines
+        .iter()
+        .map(|line| {
+            let value = if line.unit.is_empty() { line.value.clone() } else { format!("{} {}", line.value, line.unit) };
+            format!("{} ({}): {}", line.label, line.period, value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    DocumentSection {
+        section_type: FINANCIALS_SECTION_TYPE.to_string(),
+        filename: zip_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+        origin: String::new(),
+        full_length: content.chars().count(),
+        content,
+        loaded: true,
+    }
+}
+
+/// Append the content sections of `archive` to `sections`, recursing into nested ZIPs
+///
+/// With `preview_length` of `None` the sections are only listed and their content is left unloaded.
+fn read_archive_sections<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    origin: &str,
+    depth: usize,
+    section_limit: usize,
+    preview_length: Option<usize>,
+    sections: &mut Vec<DocumentSection>,
+) -> Result<()> {
     // Collect and sort file entries - prioritize main content files
     let mut file_entries: Vec<(usize, String)> = (0..archive.len())
-        .map(|i| {
-            let file = archive.by_index(i).unwrap();
-            (i, file.name().to_string())
+        .filter_map(|i| {
+            let file = archive.by_index(i).ok()?;
+            (!file.is_dir()).then(|| (i, file.name().to_string()))
         })
         .collect();
     
     // Sort to prioritize important sections
-    file_entries.sort_by(|a, b| {
-        let priority_a = get_file_priority(&a.1);
-        let priority_b = get_file_priority(&b.1);
-        priority_a.cmp(&priority_b)
-    });
+    file_entries.sort_by_key(|(_, filename)| get_file_priority(filename));
+    
+    let mut nested_archives = Vec::new();
     
     for (index, filename) in file_entries {
-        if processed_count >= section_limit {
-            break;
+        if sections.len() >= section_limit {
+            return Ok(());
         }
         
-        // Skip non-content files
-        if filename.contains("fuzoku/") || 
-           (!filename.contains("honbun") && !filename.contains("header") && !filename.ends_with(".xbrl")) {
+        if has_extension(&filename, &["zip"]) {
+            nested_archives.push((index, filename));
+            continue;
+        }
+        
+        // Skip binary and non-content files
+        if has_extension(&filename, &BINARY_EXTENSIONS) || !is_content_file(&filename, !origin.is_empty()) {
             continue;
         }
         
         let mut file = archive.by_index(index)
             .with_context(|| format!("Failed to read file from ZIP: {}", filename))?;
         
-        let mut contents = String::new();
-        match file.read_to_string(&mut contents) {
-            Ok(_) => {
-                let section_type = get_section_type(&filename);
-                
-                let (extracted_text, full_length) = if filename.ends_with(".htm") {
-                    extract_text_from_html(&contents, preview_length)?
-                } else if filename.ends_with(".xbrl") {
-                    // For XBRL files, just show a sample of the raw content
-                    let preview = if contents.len() > preview_length {
-                        let mut truncate_pos = preview_length;
-                        while truncate_pos > 0 && !contents.is_char_boundary(truncate_pos) {
-                            truncate_pos -= 1;
-                        }
-                        format!("{}...", &contents[..truncate_pos])
-                    } else {
-                        contents.clone()
-                    };
-                    (preview, contents.len())
-                } else {
-                    // For other files, show raw content preview
-                    let preview = if contents.len() > preview_length {
-                        let mut truncate_pos = preview_length;
-                        while truncate_pos > 0 && !contents.is_char_boundary(truncate_pos) {
-                            truncate_pos -= 1;
-                        }
-                        format!("{}...", &contents[..truncate_pos])
-                    } else {
-                        contents.clone()
-                    };
-                    (preview, contents.len())
-                };
-                
-                sections.push(DocumentSection {
-                    section_type,
-                    filename: filename.clone(),
-                    content: extracted_text,
-                    full_length,
-                });
-                
-                processed_count += 1;
-            }
-            Err(_) => {
-                // Skip binary files or files that can't be read as text
-                continue;
-            }
+        let Some(preview_length) = preview_length else {
+            sections.push(DocumentSection {
+                section_type: get_section_type(&filename),
+                full_length: file.size() as usize,
+                filename,
+                origin: origin.to_string(),
+                content: String::new(),
+                loaded: false,
+            });
+            continue;
+        };
+        
+        let mut bytes = Vec::new();
+        if file.read_to_end(&mut bytes).is_err() {
+            continue;
         }
+        
+        // Skip binary files or files that can't be read as text
+        let Some((extracted_text, full_length)) = section_text(&filename, bytes, preview_length)? else {
+            continue;
+        };
+        
+        sections.push(DocumentSection {
+            section_type: get_section_type(&filename),
+            filename,
+            origin: origin.to_string(),
+            content: extracted_text,
+            full_length,
+            loaded: true,
+        });
     }
     
-    Ok(sections)
+    if depth >= MAX_NESTED_ZIP_DEPTH {
+        return Ok(());
+    }
+    
+    for (index, filename) in nested_archives {
+        if sections.len() >= section_limit {
+            break;
+        }
+        
+        let mut bytes = Vec::new();
+        archive.by_index(index)
+            .with_context(|| format!("Failed to read file from ZIP: {}", filename))?
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("Failed to read nested ZIP: {}", filename))?;
+        
+        // Entries named .zip that are not valid archives are skipped like other binaries
+        let Ok(mut nested) = ZipArchive::new(Cursor::new(bytes)) else {
+            continue;
+        };
+        
+        let nested_origin = if origin.is_empty() {
+            filename
+        } else {
+            format!("{}!/{}", origin, filename)
+        };
+        read_archive_sections(&mut nested, &nested_origin, depth + 1, section_limit, preview_length, sections)?;
+    }
+    
+    Ok(())
+}
+
+/// Whether a ZIP entry holds document content worth previewing
+fn is_content_file(filename: &str, nested: bool) -> bool {
+    let is_html = has_extension(filename, &["htm", "html"]);
+    
+    filename.contains("honbun")
+        || filename.contains("header")
+        || filename.ends_with(".xbrl")
+        // HTML attachments and HTML files inside nested archives
+        || (is_html && (nested || filename.contains("fuzoku/")))
+}
+
+/// Case-insensitive check of a filename's extension
+fn has_extension(filename: &str, extensions: &[&str]) -> bool {
+    filename
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Truncate raw content for preview without breaking UTF-8 character boundaries
+pub(crate) fn truncate_preview(contents: &str, preview_length: usize) -> String {
+    if contents.len() > preview_length {
+        let mut truncate_pos = preview_length;
+        while truncate_pos > 0 && !contents.is_char_boundary(truncate_pos) {
+            truncate_pos -= 1;
+        }
+        format!("{}...", &contents[..truncate_pos])
+    } else {
+        contents.to_string()
+    }
 }
 
 /// Get file priority for sorting (lower number = higher priority)
@@ -246,10 +914,234 @@ mod tests {
         assert_eq!(get_section_type("test.xbrl"), "XBRL Data");
     }
 
+    fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            std::io::Write::write_all(&mut writer, data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_read_named_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S100TEST.zip");
+        std::fs::write(
+            &path,
+            build_zip(&[
+                (
+                    "XBRL/PublicDoc/0000000_header_test.htm",
+                    "<html><body><p>有価証券報告書 表紙</p></body></html>".as_bytes(),
+                ),
+                (
+                    "XBRL/PublicDoc/0101010_honbun_test.htm",
+                    "<html><body><p>事業の内容 自動車の製造販売</p></body></html>".as_bytes(),
+                ),
+                (
+                    "XBRL/PublicDoc/0102010_honbun_test.htm",
+                    "<html><body><p>事業等のリスク 為替変動の影響</p></body></html>".as_bytes(),
+                ),
+            ]),
+        )
+        .unwrap();
+        let path = path.to_string_lossy();
+
+        let section = read_edinet_section(&path, "risk", 10, 1000).unwrap().unwrap();
+        assert_eq!(section.section_type, "Risk Factors");
+        assert!(section.loaded && section.content.contains("為替変動"));
+
+        let section = read_edinet_section(&path, "事業等のリスク", 10, 1000).unwrap().unwrap();
+        assert_eq!(section.section_type, "Risk Factors");
+
+        // Falls back to searching the text when no type or file name matches
+        let section = read_edinet_section(&path, "自動車", 10, 1000).unwrap().unwrap();
+        assert_eq!(section.section_type, "Business Overview");
+
+        assert!(read_edinet_section(&path, "存在しない", 10, 1000).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_binary_only_zip_reports_no_text_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S100PDF0.zip");
+        std::fs::write(&path, build_zip(&[
+            ("PDF/S100PDF0.pdf", b"%PDF-1.4"),
+            ("PDF/fuzoku/image.png", b"\x89PNG"),
+        ])).unwrap();
+        let path = path.to_string_lossy();
+
+        for err in [read_edinet_zip(&path, 10, 100).unwrap_err(), list_edinet_sections(&path, 10).unwrap_err()] {
+            let no_text = err.downcast_ref::<NoTextSections>().expect("no text sections error");
+            assert_eq!(no_text.file_count, 2);
+        }
+
+        // A file that is not a ZIP at all is a different failure
+        std::fs::write(dir.path().join("broken.zip"), b"not a zip").unwrap();
+        let err = read_edinet_zip(&dir.path().join("broken.zip").to_string_lossy(), 10, 100).unwrap_err();
+        assert!(err.downcast_ref::<NoTextSections>().is_none());
+    }
+
+    #[test]
+    fn test_zip_filing_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S100TEST-2024-06-25.zip");
+        std::fs::write(&path, build_zip(&[
+            ("XBRL/PublicDoc/0000000_header_jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25_ixbrl.htm", b"<html></html>"),
+            ("XBRL/PublicDoc/jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.xbrl", b"<xbrl/>"),
+            ("XBRL/PublicDoc/manifest_PublicDoc.xml", b"<manifest/>"),
+        ])).unwrap();
+
+        let info = read_edinet_zip_filing_info(path.to_str().unwrap()).unwrap();
+        assert_eq!(info.edinet_code.as_deref(), Some("E02144"));
+        assert_eq!(info.form_code.as_deref(), Some("030000"));
+        assert_eq!(info.period_end.as_deref(), Some("2024-03-31"));
+        assert_eq!(info.submit_date.as_deref(), Some("2024-06-25"));
+        assert!(info.has_xbrl);
+        assert!(!info.has_pdf);
+
+        assert_eq!(parse_filing_file_name("XBRL/PublicDoc/0101010_honbun.htm"), None);
+    }
+
+    #[test]
+    fn test_nested_zip_sections() {
+        let html = b"<html><body><p>Nested attachment paragraph text</p></body></html>";
+        let nested = build_zip(&[("attachment.htm", html), ("logo.png", &[0x89, 0x50, 0x4e, 0x47])]);
+        let outer = build_zip(&[
+            ("XBRL/PublicDoc/0101010_honbun_test.htm", html),
+            ("XBRL/PublicDoc/images/chart.gif", b"GIF89a"),
+            ("XBRL/AttachDoc/attach.zip", &nested),
+        ]);
+
+        let mut archive = ZipArchive::new(Cursor::new(outer)).unwrap();
+        let mut sections = Vec::new();
+        read_archive_sections(&mut archive, "", 0, 10, Some(500), &mut sections).unwrap();
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].section_type, "Business Overview");
+        assert_eq!(sections[0].origin, "");
+        assert_eq!(sections[1].display_path(), "XBRL/AttachDoc/attach.zip!/attachment.htm");
+    }
+
+    #[test]
+    fn test_lazy_section_loading() {
+        let html = b"<html><body><p>Nested attachment paragraph text</p></body></html>";
+        let nested = build_zip(&[("attachment.htm", html)]);
+        let outer = build_zip(&[
+            ("XBRL/PublicDoc/0101010_honbun_test.htm", html),
+            ("XBRL/AttachDoc/attach.zip", &nested),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S100TEST.zip");
+        std::fs::write(&path, outer).unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut sections = list_edinet_sections(path, 10).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert!(sections.iter().all(|s| !s.loaded && s.content.is_empty()));
+
+        load_section_content(path, &mut sections[1], 10).unwrap();
+        assert!(sections[1].loaded);
+        assert_eq!(sections[1].display_path(), "XBRL/AttachDoc/attach.zip!/attachment.htm");
+        assert_eq!(sections[1].content, "Nested att...");
+        assert_eq!(sections[1].full_length, "Nested attachment paragraph text\n".len());
+    }
+
+    #[test]
+    fn test_find_downloaded_document_prefers_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let summary_path = dir.path().join("S100TEST-2024-06-25-summary.json");
+        std::fs::write(
+            &summary_path,
+            r#"{"seqNumber": 1, "docID": "S100TEST", "filerName": "Test Corp", "docDescription": "Annual Report", "xbrlFlag": null}"#,
+        )
+        .unwrap();
+
+        assert_eq!(find_downloaded_document(dir.path(), "S100TEST"), Some(summary_path.clone()));
+        let sections = read_edinet_document(summary_path.to_str().unwrap(), 10, 500).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].section_type, "Document Summary");
+        assert_eq!(
+            sections[0].content,
+            "Document ID: S100TEST\nDescription: Annual Report\nFiler: Test Corp"
+        );
+
+        let zip_path = dir.path().join("S100TEST-2024-06-25.zip");
+        std::fs::write(&zip_path, build_zip(&[("0101010_honbun.htm", b"<p>Body</p>")])).unwrap();
+        assert_eq!(find_downloaded_document(dir.path(), "S100TEST"), Some(zip_path));
+        assert_eq!(find_downloaded_document(dir.path(), "S100OTHER"), None);
+    }
+
+    #[test]
+    fn test_table_of_contents_groups_chapters() {
+        let section = |filename: &str| DocumentSection {
+            section_type: get_section_type(filename),
+            filename: filename.to_string(),
+            origin: String::new(),
+            content: String::new(),
+            full_length: 0,
+            loaded: false,
+        };
+        let sections = [
+            section("XBRL/PublicDoc/0000000_header_test.htm"),
+            section("XBRL/PublicDoc/0102010_honbun_test.htm"),
+            section("XBRL/PublicDoc/0102020_honbun_test.htm"),
+            section("XBRL/PublicDoc/0105010_honbun_test.htm"),
+        ];
+
+        let toc = table_of_contents(&sections);
+        let outline: Vec<(usize, &str, usize)> =
+            toc.iter().map(|entry| (entry.level, entry.title.as_str(), entry.section_index)).collect();
+        assert_eq!(
+            outline,
+            [
+                (0, "Document Header", 0),
+                (0, "第2 事業の状況", 1),
+                (1, "Risk Factors", 1),
+                (1, "0102020_honbun_test.htm", 2),
+                (0, "第5 経理の状況", 3),
+                (1, "Board of Directors", 3),
+            ]
+        );
+    }
+
     #[test]
     fn test_file_priority() {
         assert!(get_file_priority("0000000_header.htm") < get_file_priority("0101010_honbun.htm"));
         assert!(get_file_priority("0101010_honbun.htm") < get_file_priority("0104010_honbun.htm"));
         assert!(get_file_priority("test.xbrl") < get_file_priority("fuzoku/image.gif"));
     }
+
+    #[test]
+    fn test_read_financials_from_utf16_csv() {
+        let csv = "\"要素ID\"\t\"項目名\"\t\"コンテキストID\"\t\"相対年度\"\t\"連結・個別\"\t\"期間・時点\"\t\"ユニットID\"\t\"単位\"\t\"値\"\n\
+                   \"jppfs_cor:NetSales\"\t\"売上高\"\t\"CurrentYearDuration\"\t\"当期\"\t\"連結\"\t\"期間\"\t\"JPY\"\t\"円\"\t\"45095325000000\"\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(csv.encode_utf16().flat_map(u16::to_le_bytes));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S100TEST-2024-06-25-csv.zip");
+        std::fs::write(
+            &path,
+            build_zip(&[("XBRL_TO_CSV/jpcrp030000-asr-001_E02144-000_2024-03-31_01_2024-06-25.csv", &bytes)]),
+        )
+        .unwrap();
+
+        assert_eq!(find_downloaded_financials(dir.path(), "S100TEST"), Some(path.clone()));
+        assert_eq!(find_downloaded_document(dir.path(), "S100TEST"), None);
+
+        let lines = read_edinet_financials(&path.to_string_lossy()).unwrap();
+        assert_eq!(
+            lines,
+            vec![FinancialLine {
+                element_id: "jppfs_cor:NetSales".to_string(),
+                label: "売上高".to_string(),
+                context_id: "CurrentYearDuration".to_string(),
+                period: "当期".to_string(),
+                unit: "円".to_string(),
+                value: "45095325000000".to_string(),
+            }]
+        );
+        assert_eq!(financials_section(&lines, &path).content, "売上高 (当期): 45095325000000 円");
+    }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 //! Shared EDINET types and data structures
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// EDINET API response containing metadata and document results
 #[derive(Debug, Deserialize)]
@@ -40,7 +40,7 @@ pub struct EdinetResultSet {
 }
 
 /// Individual EDINET document metadata
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EdinetDocument {
     /// Sequence number in the response
     #[serde(rename = "seqNumber")]
@@ -90,8 +90,8 @@ pub struct EdinetDocument {
     #[serde(rename = "periodEnd")]
     pub period_end: Option<String>,
     
-    /// Document submission date and time
-    #[serde(rename = "submitDateTime")]
+    /// Document submission date and time; older responses and fixtures call it `submitDate`
+    #[serde(rename = "submitDateTime", alias = "submitDate")]
     pub submit_date: Option<String>,
     
     /// Document description
@@ -159,6 +159,18 @@ pub struct EdinetDocument {
     pub legal_status: Option<String>,
 }
 
+impl EdinetDocument {
+    /// Whether this is an investment fund filing (投資信託), identified by its fund code
+    pub fn is_fund(&self) -> bool {
+        self.fund_code.as_deref().is_some_and(|code| !code.is_empty())
+    }
+}
+
+/// Whether an identifier is an EDINET fund code (e.g. `G01234`) rather than a securities code
+pub fn is_fund_code(code: &str) -> bool {
+    code.len() == 6 && code.starts_with('G') && code[1..].chars().all(|c| c.is_ascii_digit())
+}
+
 /// EDINET API error response structure
 #[derive(Debug, Deserialize)]
 pub struct EdinetErrorResponse {
@@ -175,8 +187,14 @@ pub struct EdinetApi;
 impl EdinetApi {
     /// Base URL for EDINET API
     pub const BASE_URL: &'static str = "https://api.edinet-fsa.go.jp";
-    /// Documents listing endpoint
-    pub const DOCUMENTS_ENDPOINT: &'static str = "/api/v2/documents.json";
-    /// Document download endpoint (without document ID)
-    pub const DOCUMENT_DOWNLOAD_ENDPOINT: &'static str = "/api/v2/documents";
+    /// Path of the API version, under which the documents endpoints live
+    pub const API_PATH: &'static str = "/api/v2";
+    /// Download `type` for the submitted document and audit report as a ZIP
+    pub const DOWNLOAD_TYPE_ZIP: &'static str = "1";
+    /// Download `type` for the submitted document as a PDF (`pdfFlag`)
+    pub const DOWNLOAD_TYPE_PDF: &'static str = "2";
+    /// Download `type` for the attachments (`attachDocFlag`) as a ZIP
+    pub const DOWNLOAD_TYPE_ATTACHMENTS: &'static str = "3";
+    /// Download `type` for the XBRL financial data converted to CSV (`csvFlag`), as a ZIP
+    pub const DOWNLOAD_TYPE_CSV: &'static str = "5";
 }
\ No newline at end of file
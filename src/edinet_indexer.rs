@@ -3,7 +3,8 @@
 //! This module provides the interface for the main application to access
 //! EDINET indexing functionality. The actual implementation is in the `edinet` module.
 
-use crate::edinet;
+use crate::config::Config;
+use crate::edinet::{self, EdinetIndexFilter};
 use anyhow::Result;
 use chrono::NaiveDate;
 
@@ -21,11 +22,47 @@ pub async fn build_edinet_index_by_date(
     edinet::indexer::build_edinet_index_by_date(database_path, start_date, end_date).await
 }
 
+/// Build EDINET index between the specified dates, indexing only documents matching `filter`
+pub async fn build_edinet_index_by_date_filtered(
+    database_path: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    filter: &EdinetIndexFilter,
+) -> Result<usize> {
+    edinet::indexer::build_edinet_index_by_date_filtered(database_path, start_date, end_date, filter).await
+}
+
+/// Build EDINET index between the specified dates with custom configuration
+pub async fn build_edinet_index_by_date_with_config(
+    database_path: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    config: &Config,
+    filter: &EdinetIndexFilter,
+) -> Result<usize> {
+    edinet::indexer::build_edinet_index_by_date_with_config(database_path, start_date, end_date, config, filter).await
+}
+
+/// Index EDINET ZIPs from a file or directory without calling the EDINET API
+pub async fn index_edinet_archives(input: &std::path::Path, database_path: &str) -> Result<usize> {
+    edinet::indexer::index_edinet_archives(input, database_path).await
+}
+
 /// Update EDINET index from the last indexed date to today
 pub async fn update_edinet_index(database_path: &str, days_back: i64) -> Result<usize> {
     edinet::indexer::update_edinet_index(database_path, days_back).await
 }
 
+/// Update EDINET index from the latest indexed date to today
+pub async fn update_edinet_index_incremental(database_path: &str, days_back: i64) -> Result<usize> {
+    edinet::indexer::update_edinet_index_incremental(database_path, days_back).await
+}
+
+/// Update EDINET index from the date of the last successful run to today
+pub async fn update_edinet_index_since_last_run(database_path: &str, days_back: i64) -> Result<usize> {
+    edinet::indexer::update_edinet_index_since_last_run(database_path, days_back).await
+}
+
 /// Get statistics about the EDINET index
 pub async fn get_edinet_index_stats(database_path: &str) -> Result<()> {
     edinet::indexer::get_edinet_index_stats(database_path).await
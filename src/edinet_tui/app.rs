@@ -1,22 +1,32 @@
 //! Main TUI application state and logic
 
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame, Terminal,
 };
 
+use super::components::StatusDisplay;
+use super::operations::{ContentLoader, DatabaseHealthStatus, DatabaseManager, DownloadManager};
 use super::screens::*;
+use super::traits;
+use super::ui::{draw_too_small, is_too_small, key_legend_line};
 use crate::config::Config;
-use crate::models::{SearchQuery, Source};
 use crate::storage;
 
+/// How long the main loop waits for input before checking on background downloads
+const EVENT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+/// Shorter wait while an operation is in progress, so the status spinner keeps turning
+const BUSY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
 /// Application screens
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Screen {
     MainMenu,
     Database,
@@ -35,41 +45,101 @@ pub struct App {
     /// Application configuration
     pub config: Config,
 
-    // Screen states
-    pub main_menu: MainMenuScreen,
-    pub database: DatabaseScreen,
-    pub search: SearchScreen,
-    pub results: ResultsScreen,
-    pub viewer: ViewerScreen,
-    pub help: HelpScreen,
+    /// Screen states, dispatched through the `traits::Screen` trait
+    screens: HashMap<Screen, Box<dyn traits::Screen>>,
+
+    /// Cached document content, shared with background preload tasks
+    pub content_loader: Arc<tokio::sync::Mutex<ContentLoader>>,
+    /// Tracks downloads started from the results and viewer screens
+    pub download_manager: DownloadManager,
+    /// Runs index updates and builds started from the database screen
+    pub database_manager: DatabaseManager,
 
     // Global application state
     pub should_quit: bool,
     pub show_help_popup: bool,
-    pub status_message: Option<String>,
-    pub error_message: Option<String>,
+    pub show_debug_panel: bool,
+    /// Database health captured when the debug panel was opened
+    pub debug_health: Option<Result<DatabaseHealthStatus, String>>,
+    /// Onboarding guidance while the database has nothing to search
+    pub setup_hint: Option<String>,
+    pub show_setup_hint: bool,
+    /// Status bar message, shared by every screen
+    pub status: StatusDisplay,
 }
 
 impl App {
     /// Create a new TUI application
     pub fn new(config: Config) -> Result<Self> {
-        Ok(Self {
+        let mut app = Self {
             current_screen: Screen::MainMenu,
             previous_screen: None,
             config: config.clone(),
 
-            main_menu: MainMenuScreen::new(),
-            database: DatabaseScreen::new(config.clone()),
-            search: SearchScreen::new(),
-            results: ResultsScreen::new(),
-            viewer: ViewerScreen::new(),
-            help: HelpScreen::new(),
+            screens: HashMap::new(),
+
+            content_loader: Arc::new(tokio::sync::Mutex::new(ContentLoader::new(config.clone()))),
+            download_manager: DownloadManager::new(config.clone()),
+            database_manager: DatabaseManager::new(config.clone()),
 
             should_quit: false,
             show_help_popup: false,
-            status_message: None,
-            error_message: None,
-        })
+            show_debug_panel: false,
+            debug_health: None,
+            setup_hint: None,
+            show_setup_hint: false,
+            status: StatusDisplay::new().with_history(50),
+        };
+
+        app.register_screen(Screen::MainMenu, MainMenuScreen::new());
+        app.register_screen(Screen::Database, DatabaseScreen::new(config.clone()));
+        app.register_screen(Screen::Search, SearchScreen::new());
+        app.register_screen(
+            Screen::Results,
+            ResultsScreen::new()
+                .with_locale(config.display.locale)
+                .with_date_format(config.display.date_format())
+                .with_items_per_page(config.display.items_per_page),
+        );
+        app.register_screen(
+            Screen::Viewer,
+            ViewerScreen::new()
+                .with_locale(config.display.locale)
+                .with_date_format(config.display.date_format())
+                .with_content_limits(
+                    config.display.viewer_max_sections,
+                    config.display.viewer_max_content_length,
+                ),
+        );
+        app.register_screen(Screen::Help, HelpScreen::new());
+
+        let removed = app.download_manager.remove_partial_downloads();
+        if removed > 0 {
+            tracing::info!("Removed {} partial downloads left by an interrupted session", removed);
+        }
+
+        Ok(app)
+    }
+
+    /// Add or replace the screen shown for `id`
+    pub fn register_screen(&mut self, id: Screen, screen: impl traits::Screen + 'static) {
+        self.screens.insert(id, Box::new(screen));
+    }
+
+    /// Typed access to a registered screen, e.g. `app.screen_mut::<ResultsScreen>()`
+    ///
+    /// Returns `None` for the screen whose `handle_event` is currently running.
+    pub fn screen_mut<T: traits::Screen + 'static>(&mut self) -> Option<&mut T> {
+        self.screens
+            .values_mut()
+            .find_map(|screen| (**screen).as_any_mut().downcast_mut::<T>())
+    }
+
+    /// Shared counterpart of `screen_mut`
+    pub fn screen<T: traits::Screen + 'static>(&self) -> Option<&T> {
+        self.screens
+            .values()
+            .find_map(|screen| (**screen).as_any().downcast_ref::<T>())
     }
 
     /// Run the main application loop
@@ -81,12 +151,14 @@ impl App {
             // Draw the UI
             terminal.draw(|f| self.draw(f))?;
 
-            // Handle events
-            if let Ok(event) = crossterm::event::read() {
-                if let crossterm::event::Event::Key(key) = event {
+            // Handle events, waking up regularly to advance queued downloads and background work
+            let poll_interval = if self.is_busy() { BUSY_POLL_INTERVAL } else { EVENT_POLL_INTERVAL };
+            if crossterm::event::poll(poll_interval)? {
+                if let Ok(crossterm::event::Event::Key(key)) = crossterm::event::read() {
                     self.handle_key_event(key).await?;
                 }
             }
+            self.tick().await?;
 
             if self.should_quit {
                 break;
@@ -96,19 +168,62 @@ impl App {
         Ok(())
     }
 
+    /// Whether an operation is in progress, so the UI redraws often enough to animate the spinner
+    fn is_busy(&self) -> bool {
+        self.status.is_loading()
+            || self.download_manager.has_active_downloads()
+            || self.database_manager.is_operation_in_progress()
+    }
+
+    /// Advance background work after each input poll, whether or not a key was pressed
+    ///
+    /// Collects finished downloads and database operations, then lets screens report them.
+    pub async fn tick(&mut self) -> Result<()> {
+        self.download_manager.update_progress().await?;
+        self.database_manager.update_progress().await?;
+        self.tick_screens().await
+    }
+
+    /// Let every screen pick up finished background work, taking each out of the map like `handle_key_event`
+    async fn tick_screens(&mut self) -> Result<()> {
+        let ids: Vec<Screen> = self.screens.keys().cloned().collect();
+        for id in ids {
+            if let Some(mut screen) = self.screens.remove(&id) {
+                let result = screen.on_tick(self).await;
+                self.screens.insert(id, screen);
+                result?;
+            }
+        }
+        Ok(())
+    }
+
     /// Handle keyboard input events
     pub async fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
+        let capturing_text = self
+            .screens
+            .get(&self.current_screen)
+            .is_some_and(|screen| screen.is_capturing_text());
+
         // Global shortcuts
         match key.code {
+            KeyCode::Char('?') | KeyCode::Char('q') if capturing_text => {}
             KeyCode::F(1) | KeyCode::Char('?') => {
                 self.show_help_popup = !self.show_help_popup;
                 return Ok(());
             }
+            KeyCode::F(12) => {
+                self.toggle_debug_panel().await;
+                return Ok(());
+            }
             KeyCode::Esc => {
                 if self.show_help_popup {
                     self.show_help_popup = false;
                     return Ok(());
                 }
+                if self.show_debug_panel {
+                    self.show_debug_panel = false;
+                    return Ok(());
+                }
                 // ESC handling is now delegated to individual screen handlers
             }
             KeyCode::Char('q') => {
@@ -118,15 +233,23 @@ impl App {
             _ => {}
         }
 
-        // Screen-specific event handling
-        if !self.show_help_popup {
-            match self.current_screen {
-                Screen::MainMenu => self.handle_main_menu_event(key).await?,
-                Screen::Database => self.handle_database_event(key).await?,
-                Screen::Search => self.handle_search_event(key).await?,
-                Screen::Results => self.handle_results_event(key).await?,
-                Screen::Viewer => self.handle_viewer_event(key).await?,
-                Screen::Help => self.handle_help_event(key).await?,
+        if self.show_setup_hint {
+            // Any key dismisses the setup hint; D follows it to the Database screen
+            self.show_setup_hint = false;
+            if matches!(key.code, KeyCode::Char('d') | KeyCode::Char('D')) {
+                self.navigate_to_screen(Screen::Database);
+            }
+            return Ok(());
+        }
+
+        // Screen-specific event handling. The screen is taken out of the map while its handler
+        // runs so it can borrow itself and the app mutably at the same time.
+        if !self.show_help_popup && !self.show_debug_panel {
+            let id = self.current_screen.clone();
+            if let Some(mut screen) = self.screens.remove(&id) {
+                let result = screen.handle_event(key, self).await;
+                self.screens.insert(id, screen);
+                result?;
             }
         }
 
@@ -136,65 +259,89 @@ impl App {
     /// Draw the UI
     pub fn draw(&mut self, f: &mut Frame) {
         let size = f.size();
+        if is_too_small(size) {
+            draw_too_small(f, size);
+            return;
+        }
 
-        // Main layout: status bar at bottom, content area above
+        // Main layout: status bar at bottom, key legend above it, content area above
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .constraints([Constraint::Min(0), Constraint::Length(1), Constraint::Length(3)])
             .split(size);
 
         // Draw current screen content
-        match self.current_screen {
-            Screen::MainMenu => self.main_menu.draw(f, chunks[0]),
-            Screen::Database => self.database.draw(f, chunks[0]),
-            Screen::Search => self.search.draw(f, chunks[0]),
-            Screen::Results => self.results.draw(f, chunks[0]),
-            Screen::Viewer => self.viewer.draw(f, chunks[0]),
-            Screen::Help => self.help.draw(f, chunks[0]),
+        if let Some(screen) = self.screens.get_mut(&self.current_screen) {
+            screen.draw(f, chunks[0]);
         }
 
+        self.draw_key_legend(f, chunks[1]);
+
         // Draw status bar
-        self.draw_status_bar(f, chunks[1]);
+        self.draw_status_bar(f, chunks[2]);
+
+        if self.show_setup_hint {
+            self.draw_setup_hint(f, size);
+        }
 
         // Draw help popup if active
         if self.show_help_popup {
             self.draw_help_popup(f, size);
         }
+
+        if self.show_debug_panel {
+            self.draw_debug_panel(f, size);
+        }
     }
 
     /// Draw status bar with current screen info and shortcuts
     fn draw_status_bar(&self, f: &mut Frame, area: Rect) {
-        let status_text = if let Some(ref msg) = self.status_message {
-            format!("Status: {}", msg)
-        } else if let Some(ref err) = self.error_message {
-            format!("Error: {}", err)
-        } else {
-            format!(
-                "EDINET TUI - {} | ESC: Back | Q: Quit | F1/?:Help",
-                match self.current_screen {
-                    Screen::MainMenu => "Main Menu",
-                    Screen::Database => "Database Management",
-                    Screen::Search => "Search Documents",
-                    Screen::Results => "Search Results",
-                    Screen::Viewer => "Document Viewer",
-                    Screen::Help => "Help",
-                }
-            )
-        };
+        let idle_text = format!(
+            "EDINET TUI - {} | ESC: Back | Q: Quit | F1/?:Help",
+            self.screens
+                .get(&self.current_screen)
+                .map(|screen| screen.title())
+                .unwrap_or_default()
+        );
+        self.status.render_or(f, area, &idle_text);
+    }
 
-        let style = if self.error_message.is_some() {
-            Style::default().fg(Color::Red)
-        } else if self.status_message.is_some() {
-            Style::default().fg(Color::Green)
-        } else {
-            Style::default().fg(Color::Gray)
+    /// Draw the current screen's shortcuts, taken from the same help text as the F1 popup
+    fn draw_key_legend(&self, f: &mut Frame, area: Rect) {
+        let help = self
+            .screens
+            .get(&self.current_screen)
+            .map(|screen| screen.help())
+            .unwrap_or_default();
+        f.render_widget(Paragraph::new(key_legend_line(help, area.width as usize)), area);
+    }
+
+    /// Draw the onboarding popup shown when the database has nothing to search
+    fn draw_setup_hint(&self, f: &mut Frame, area: Rect) {
+        let Some(ref hint) = self.setup_hint else {
+            return;
         };
+        let popup_area = centered_rect(70, 40, area);
+
+        f.render_widget(Clear, popup_area);
 
-        let status_bar = Paragraph::new(status_text)
-            .style(style)
-            .block(Block::default().borders(Borders::ALL));
+        let text = format!(
+            "{}\n\n\
+            1. Load company data: Database Management → Load Static Data (l), or edinet load-static --csv-path static/EdinetcodeDlInfo.csv\n\
+            2. Build an index: Database Management → Build Index (b)\n\n\
+            Press D to open Database Management, any other key to dismiss",
+            hint
+        );
+        let popup = Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title("Getting Started")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Yellow)),
+            );
 
-        f.render_widget(status_bar, area);
+        f.render_widget(popup, popup_area);
     }
 
     /// Draw help popup with context-sensitive shortcuts
@@ -216,69 +363,98 @@ impl App {
         f.render_widget(help_popup, popup_area);
     }
 
+    /// Draw the debug panel with cache, download and database diagnostics
+    fn draw_debug_panel(&self, f: &mut Frame, area: Rect) {
+        let popup_area = centered_rect(70, 60, area);
+
+        f.render_widget(Clear, popup_area);
+
+        let mut lines = vec!["Content Cache:".to_string()];
+        match self.content_loader.try_lock() {
+            Ok(loader) => {
+                let stats = loader.get_cache_stats();
+                lines.push(format!(
+                    "  Entries: {} ({} valid, {} invalid, {} expired)",
+                    stats.total_entries, stats.valid_entries, stats.invalid_entries, stats.expired_entries
+                ));
+                lines.push(format!(
+                    "  Sections: {} ({:.1} per document) | Valid: {:.0}%",
+                    stats.total_sections,
+                    stats.average_sections_per_document(),
+                    stats.hit_rate()
+                ));
+            }
+            Err(_) => lines.push("  Busy (preloading results)".to_string()),
+        }
+
+        let downloads = self.download_manager.get_stats();
+        lines.push(String::new());
+        lines.push("Downloads:".to_string());
+        lines.push(format!(
+            "  Total: {} | Active: {} | Completed: {} | Failed: {} | Cancelled: {}",
+            downloads.total,
+            downloads.queued + downloads.in_progress,
+            downloads.completed,
+            downloads.failed,
+            downloads.cancelled
+        ));
+        for progress in self.download_manager.get_all_downloads() {
+            lines.push(format!(
+                "  {} {} [{:?}] {}",
+                progress.ticker, progress.document_id, progress.status, progress.message
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push("Database:".to_string());
+        match &self.debug_health {
+            Some(Ok(health)) => {
+                lines.push(format!("  {}", health.summary()));
+                lines.extend(health.issues.iter().map(|issue| format!("  - {}", issue)));
+                lines.extend(health.stale_static_warning().map(|warning| format!("  - {}", warning)));
+            }
+            Some(Err(e)) => lines.push(format!("  Health check failed: {}", e)),
+            None => lines.push("  Not checked".to_string()),
+        }
+
+        let debug_panel = Paragraph::new(lines.join("\n"))
+            .block(
+                Block::default()
+                    .title("Debug - F12/ESC to close")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Cyan)),
+            )
+            .style(Style::default().fg(Color::White));
+
+        f.render_widget(debug_panel, popup_area);
+    }
+
+    /// Toggle the debug panel, refreshing the database health check when opening it
+    async fn toggle_debug_panel(&mut self) {
+        self.show_debug_panel = !self.show_debug_panel;
+        if self.show_debug_panel {
+            self.debug_health = Some(
+                DatabaseManager::new(self.config.clone())
+                    .health_check()
+                    .await
+                    .map_err(|e| e.to_string()),
+            );
+        }
+    }
+
     /// Get context-sensitive help content
     fn get_context_help(&self) -> String {
         let global_help = "Global Shortcuts:\n\
             ESC - Go back\n\
             Q - Quit application\n\
-            F1 / ? - Toggle this help\n\n";
-
-        let screen_help = match self.current_screen {
-            Screen::MainMenu => {
-                "Main Menu:\n\
-                ↑/↓ - Navigate menu\n\
-                Enter - Select option\n\
-                S/s - Search Documents\n\
-                D/d - Database Management\n\
-                H/h - Help\n\
-                q - Quit"
-            }
-            Screen::Database => {
-                "Database Management:\n\
-                ↑/↓ - Navigate options\n\
-                Enter - Execute action\n\
-                s - Show statistics\n\
-                u - Update index\n\
-                b - Build index (date range)\n\
-                c - Clear/rebuild index"
-            }
-            Screen::Search => {
-                "Search Documents:\n\
-                Tab - Next field\n\
-                Shift+Tab - Previous field\n\
-                Enter - Execute search\n\
-                Type in text fields\n\
-                ↑/↓ - Navigate dropdowns\n\
-                Space - Toggle selections"
-            }
-            Screen::Results => {
-                "Search Results:\n\
-                ↑/↓ - Navigate documents\n\
-                Enter - View document\n\
-                d - Download document\n\
-                r - Refresh search\n\
-                / - New search\n\
-                Page Up/Down - Navigate pages"
-            }
-            Screen::Viewer => {
-                "Document Viewer:\n\
-                ↑/↓ - Scroll content up/down\n\
-                ←/→ - Change document sections\n\
-                Page Up/Down - Large scroll jumps\n\
-                Ctrl+U/D - Page scroll (vim-like)\n\
-                gg - Go to top (vim-like)\n\
-                G - Go to bottom (vim-like)\n\
-                Tab - Switch viewer modes\n\
-                d - Download document\n\
-                r - Reload content\n\
-                Enter - Load/Download content"
-            }
-            Screen::Help => {
-                "Help Screen:\n\
-                ↑/↓ - Scroll help content\n\
-                Tab - Switch help sections"
-            }
-        };
+            F1 / ? - Toggle this help\n\
+            F12 - Toggle debug panel\n\n";
+
+        let screen_help = self
+            .screens
+            .get(&self.current_screen)
+            .map(|screen| screen.help())
+            .unwrap_or_default();
 
         format!("{}{}", global_help, screen_help)
     }
@@ -292,748 +468,130 @@ impl App {
 
     /// Set status message
     pub fn set_status(&mut self, message: String) {
-        self.status_message = Some(message);
-        self.error_message = None;
+        self.status.set_info(message);
+    }
+
+    /// Set a message for a completed operation
+    pub fn set_success(&mut self, message: String) {
+        self.status.set_success(message);
+    }
+
+    /// Set a message for an operation in progress, shown with a spinner
+    pub fn set_loading(&mut self, message: String) {
+        self.status.set_loading(message);
     }
 
     /// Set error message
     pub fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.status_message = None;
+        self.status.set_error(message);
     }
 
     /// Clear status and error messages
     pub fn clear_messages(&mut self) {
-        self.status_message = None;
-        self.error_message = None;
+        self.status.clear();
     }
 
-    /// Check database status on startup
+    /// Check database status on startup, guiding new users when there is nothing to search
     async fn check_database_status(&mut self) {
-        // This will be implemented to check if database exists and has data
-        // For now, just set a status message
-        self.set_status("Ready - Database connection established".to_string());
-    }
-
-    // Event handlers for each screen
-    async fn handle_main_menu_event(&mut self, key: KeyEvent) -> Result<()> {
-        // Extract the required data before borrowing self
-        match key.code {
-            KeyCode::Up => {
-                let selected = self.main_menu.menu_state.selected().unwrap_or(0);
-                let new_selected = if selected == 0 {
-                    self.main_menu.menu_options.len() - 1
-                } else {
-                    selected - 1
-                };
-                self.main_menu.menu_state.select(Some(new_selected));
-            }
-            KeyCode::Down => {
-                let selected = self.main_menu.menu_state.selected().unwrap_or(0);
-                let new_selected = (selected + 1) % self.main_menu.menu_options.len();
-                self.main_menu.menu_state.select(Some(new_selected));
-            }
-            KeyCode::Enter => {
-                if let Some(selected) = self.main_menu.menu_state.selected() {
-                    if let Some(option) = self.main_menu.menu_options.get(selected) {
-                        self.navigate_to_screen(option.screen.clone());
-                    }
-                }
-            }
-            KeyCode::Char('q') => {
-                self.should_quit = true;
-            }
-            KeyCode::Char(c) => {
-                // Handle shortcut keys (case insensitive)
-                let upper_c = c.to_ascii_uppercase();
-                for option in &self.main_menu.menu_options {
-                    if option.shortcut == upper_c || option.shortcut == c {
-                        self.navigate_to_screen(option.screen.clone());
-                        break;
-                    }
-                }
+        let health = match DatabaseManager::new(self.config.clone()).health_check().await {
+            Ok(health) => health,
+            Err(e) => {
+                self.set_error(format!("Database check failed: {}", e));
+                return;
             }
-            _ => {}
-        }
-        Ok(())
-    }
+        };
 
-    async fn handle_database_event(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Up => {
-                let selected = self.database.operation_state.selected().unwrap_or(0);
-                let new_selected = if selected == 0 {
-                    self.database.operations.len() - 1
-                } else {
-                    selected - 1
-                };
-                self.database.operation_state.select(Some(new_selected));
-            }
-            KeyCode::Down => {
-                let selected = self.database.operation_state.selected().unwrap_or(0);
-                let new_selected = (selected + 1) % self.database.operations.len();
-                self.database.operation_state.select(Some(new_selected));
-            }
-            KeyCode::Enter => {
-                if let Some(selected) = self.database.operation_state.selected() {
-                    if selected == 0 {
-                        // Show Stats
-                        self.set_status("Database statistics - feature coming soon".to_string());
-                    } else if selected == 1 {
-                        // Update Index
-                        self.set_status("Index update - feature coming soon".to_string());
-                    } else if selected == 2 {
-                        // Build Index
-                        self.set_status("Index build - feature coming soon".to_string());
-                    } else if selected == 3 {
-                        // Clear Index
-                        self.set_status("Index clear - feature coming soon".to_string());
-                    }
-                }
-            }
-            KeyCode::Esc => {
-                // Database screen: ESC goes back to Main Menu
-                self.navigate_to_screen(Screen::MainMenu);
-            }
-            KeyCode::Char('s') => {
-                self.set_status("Database statistics - feature coming soon".to_string());
-            }
-            KeyCode::Char('u') => {
-                self.set_status("Index update - feature coming soon".to_string());
-            }
-            KeyCode::Char('b') => {
-                self.set_status("Index build - feature coming soon".to_string());
-            }
-            KeyCode::Char('c') => {
-                self.set_status("Index clear - feature coming soon".to_string());
-            }
-            _ => {}
-        }
-        Ok(())
-    }
+        self.setup_hint = setup_hint(&health);
+        self.show_setup_hint = self.setup_hint.is_some();
 
-    async fn handle_search_event(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Tab => {
-                self.search.current_field =
-                    (self.search.current_field + 1) % self.search.fields.len();
-                self.search.update_field_focus();
-                self.set_status(format!(
-                    "Focus: {}",
-                    self.search.fields[self.search.current_field].as_str()
-                ));
-            }
-            KeyCode::BackTab => {
-                self.search.current_field = if self.search.current_field == 0 {
-                    self.search.fields.len() - 1
-                } else {
-                    self.search.current_field - 1
-                };
-                self.search.update_field_focus();
-                self.set_status(format!(
-                    "Focus: {}",
-                    self.search.fields[self.search.current_field].as_str()
-                ));
-            }
-            KeyCode::Up => {
-                if self.search.current_field > 0 {
-                    self.search.current_field -= 1;
-                    self.search.update_field_focus();
-                }
-            }
-            KeyCode::Down => {
-                if self.search.current_field < self.search.fields.len() - 1 {
-                    self.search.current_field += 1;
-                    self.search.update_field_focus();
-                }
-            }
-            KeyCode::Enter => {
-                // Execute search
-                self.execute_search().await?;
-            }
-            KeyCode::Esc => {
-                // Search screen: ESC goes back to Main Menu
-                self.navigate_to_screen(Screen::MainMenu);
-            }
-            KeyCode::Char(c) => {
-                self.search.handle_char_input(c);
-            }
-            KeyCode::Backspace => {
-                self.search.handle_backspace();
-            }
-            KeyCode::Delete => {
-                self.search.handle_delete();
-            }
-            KeyCode::Left => {
-                self.search.handle_cursor_left();
-            }
-            KeyCode::Right => {
-                self.search.handle_cursor_right();
-            }
-            KeyCode::Home => {
-                self.search.handle_cursor_home();
-            }
-            KeyCode::End => {
-                self.search.handle_cursor_end();
-            }
-            _ => {}
+        if let Some(ref hint) = self.setup_hint {
+            self.set_error(format!("{} Press D on the main menu for Database Management.", hint));
+        } else if !health.issues.is_empty() {
+            self.set_error(format!("Database issues: {}", health.issues.join("; ")));
+        } else {
+            self.set_status(format!("Ready - {} documents indexed", health.documents_count));
         }
-        Ok(())
     }
 
-    async fn handle_results_event(&mut self, key: KeyEvent) -> Result<()> {
-        // Handle download cancellation
-        if self.results.is_downloading {
-            if let KeyCode::Esc = key.code {
-                self.results.is_downloading = false;
-                self.results.download_status = None;
-                self.set_status("Download cancelled".to_string());
-                return Ok(());
-            }
-            // Ignore all other keys during download
-            return Ok(());
-        }
-
-        match key.code {
-            KeyCode::Up => {
-                self.results.navigate_up();
-                self.set_status(
-                    "Navigate results with ↑/↓, Enter to view, d to download".to_string(),
-                );
-            }
-            KeyCode::Down => {
-                self.results.navigate_down();
-                self.set_status(
-                    "Navigate results with ↑/↓, Enter to view, d to download".to_string(),
-                );
-            }
-            KeyCode::Left | KeyCode::PageUp => {
-                self.results.previous_page();
-                self.set_status("Previous page".to_string());
-            }
-            KeyCode::Right | KeyCode::PageDown => {
-                self.results.next_page();
-                self.set_status("Next page".to_string());
-            }
-            KeyCode::Enter | KeyCode::Char('v') => {
-                if let Some(document) = self.results.get_selected_document() {
-                    self.viewer.set_document(document.clone());
+    /// Fetch a document from the database by id and open it in the viewer
+    pub async fn view_document_by_id(&mut self, id: &str) -> Result<()> {
+        match storage::get_document_by_id(id, self.config.database_path_str()).await {
+            Ok(Some(document)) => {
+                let config = self.config.clone();
+                if let Some(viewer) = self.screen_mut::<ViewerScreen>() {
+                    viewer.set_document(document);
                     // Check download status after setting document
-                    self.viewer.is_downloaded = self.viewer.is_document_downloaded(self);
-                    self.navigate_to_screen(Screen::Viewer);
-                } else {
-                    self.set_error("No document selected".to_string());
-                }
-            }
-            KeyCode::Esc => {
-                // Results screen: ESC goes back to Search
-                self.navigate_to_screen(Screen::Search);
-            }
-            KeyCode::Char('d') => {
-                // Download selected document
-                if let Some(document) = self.results.get_selected_document().cloned() {
-                    self.results.is_downloading = true;
-                    self.results.download_status =
-                        Some(format!("Downloading {}...", document.ticker));
-                    self.set_status(format!("Starting download for {}", document.ticker));
-
-                    let download_request = crate::models::DownloadRequest {
-                        source: crate::models::Source::Edinet,
-                        ticker: document.ticker.clone(),
-                        filing_type: Some(document.filing_type.clone()),
-                        date_from: Some(document.date),
-                        date_to: Some(document.date),
-                        limit: 1,
-                        format: crate::models::DocumentFormat::Complete,
-                    };
-
-                    match crate::downloader::download_documents(
-                        &download_request,
-                        self.config.download_dir_str(),
-                    )
-                    .await
-                    {
-                        Ok(count) => {
-                            self.set_status(format!(
-                                "Successfully downloaded {} document(s) to {}",
-                                count,
-                                self.config.download_dir_str()
-                            ));
-                        }
-                        Err(e) => {
-                            self.set_error(format!("Download failed: {}", e));
-                        }
-                    }
-
-                    self.results.is_downloading = false;
-                    self.results.download_status = None;
-                } else {
-                    self.set_error("No document selected".to_string());
+                    viewer.refresh_download_status(&config);
                 }
+                self.navigate_to_screen(Screen::Viewer);
             }
-            KeyCode::Char('/') => {
-                self.navigate_to_screen(Screen::Search);
-            }
-            KeyCode::Home => {
-                self.results.go_to_first_page();
-                self.set_status("First page".to_string());
+            Ok(None) => {
+                self.set_error(format!("Document {} not found", id));
             }
-            KeyCode::End => {
-                self.results.go_to_last_page();
-                self.set_status("Last page".to_string());
+            Err(e) => {
+                self.set_error(format!("Failed to load document {}: {}", id, e));
             }
-            _ => {}
         }
         Ok(())
     }
 
-    async fn handle_viewer_event(&mut self, key: KeyEvent) -> Result<()> {
-        // Handle download cancellation
-        if self.viewer.is_downloading {
-            if let KeyCode::Esc = key.code {
-                self.viewer.is_downloading = false;
-                self.viewer.download_status = None;
-                self.set_status("Download cancelled".to_string());
-                return Ok(());
-            }
-            // Ignore all other keys during download
-            return Ok(());
-        }
-
-        match key.code {
-            KeyCode::Tab => {
-                // Switch between Info and Content modes
-                self.viewer.mode = match self.viewer.mode {
-                    super::screens::viewer::ViewerMode::Info => {
-                        super::screens::viewer::ViewerMode::Content
-                    }
-                    super::screens::viewer::ViewerMode::Content => {
-                        super::screens::viewer::ViewerMode::Info
-                    }
-                };
-                self.viewer.scroll_offset = 0;
-            }
-            KeyCode::Up => {
-                // Scroll up in all modes
-                if self.viewer.scroll_offset > 0 {
-                    self.viewer.scroll_offset -= 1;
-                }
-            }
-            KeyCode::Down => {
-                // Scroll down in all modes with bounds checking
-                let max_scroll = self.calculate_max_scroll_offset();
-                if self.viewer.scroll_offset < max_scroll {
-                    self.viewer.scroll_offset += 1;
-                }
-            }
-            KeyCode::Left => {
-                // Previous section in Content mode
-                if self.viewer.mode == super::screens::viewer::ViewerMode::Content {
-                    if self.viewer.content_sections.is_some() && self.viewer.current_section > 0 {
-                        self.viewer.current_section -= 1;
-                        self.viewer.scroll_offset = 0;
-                    }
-                }
-            }
-            KeyCode::Right => {
-                // Next section in Content mode
-                if self.viewer.mode == super::screens::viewer::ViewerMode::Content {
-                    if let Some(ref sections) = self.viewer.content_sections {
-                        if self.viewer.current_section < sections.len() - 1 {
-                            self.viewer.current_section += 1;
-                            self.viewer.scroll_offset = 0;
-                        }
-                    }
-                }
-            }
-            KeyCode::PageUp => match self.viewer.mode {
-                super::screens::viewer::ViewerMode::Info => {
-                    self.viewer.scroll_offset = self.viewer.scroll_offset.saturating_sub(10);
-                }
-                super::screens::viewer::ViewerMode::Content => {
-                    self.viewer.scroll_offset = self.viewer.scroll_offset.saturating_sub(10);
-                }
-            },
-            KeyCode::PageDown => {
-                // Page down with bounds checking
-                let max_scroll = self.calculate_max_scroll_offset();
-                self.viewer.scroll_offset =
-                    std::cmp::min(self.viewer.scroll_offset + 10, max_scroll);
-            }
-            KeyCode::Home => {
-                self.viewer.scroll_offset = 0;
-                if self.viewer.mode == super::screens::viewer::ViewerMode::Content {
-                    self.viewer.current_section = 0;
-                }
-            }
-            KeyCode::End => {
-                if self.viewer.mode == super::screens::viewer::ViewerMode::Content {
-                    if let Some(ref sections) = self.viewer.content_sections {
-                        self.viewer.current_section = sections.len().saturating_sub(1);
-                    }
-                }
-                self.viewer.scroll_offset = 0;
-            }
-            KeyCode::Enter => {
-                match self.viewer.mode {
-                    super::screens::viewer::ViewerMode::Content => {
-                        // Load content if not already loaded
-                        self.load_viewer_content().await?;
-                    }
-                    super::screens::viewer::ViewerMode::Info => {
-                        // Switch to content view
-                        self.viewer.mode = super::screens::viewer::ViewerMode::Content;
-                        self.load_viewer_content().await?;
-                    }
-                }
-            }
-            KeyCode::Char('d') => {
-                // Check for Ctrl+D first (vim-like page down)
-                if key.modifiers.contains(KeyModifiers::CONTROL) {
-                    let page_size = self.calculate_page_size();
-                    let max_scroll = self.calculate_max_scroll_offset();
-                    self.viewer.scroll_offset =
-                        std::cmp::min(self.viewer.scroll_offset + page_size, max_scroll);
-                    self.set_status("Scroll down one page".to_string());
-                } else {
-                    // Regular 'd' - Download document
-                    self.download_viewer_document().await?;
-                }
-            }
-            KeyCode::Char('u') => {
-                // Check for Ctrl+U (vim-like page up)
-                if key.modifiers.contains(KeyModifiers::CONTROL) {
-                    let page_size = self.calculate_page_size();
-                    self.viewer.scroll_offset = self.viewer.scroll_offset.saturating_sub(page_size);
-                    self.set_status("Scroll up one page".to_string());
-                }
-                // Note: regular 'u' has no function in viewer, so we ignore it
-            }
-            KeyCode::Char('r') => {
-                // Reload/refresh content
-                if self.viewer.mode == super::screens::viewer::ViewerMode::Content {
-                    self.viewer.content_sections = None;
-                    self.load_viewer_content().await?;
-                }
-            }
-            KeyCode::Char('s') => {
-                // Save content to file (placeholder)
-                self.set_status("Save functionality not implemented yet".to_string());
-            }
-            KeyCode::Esc => {
-                // Viewer screen: ESC goes back to Results
-                // Also clear any pending vim commands
-                self.viewer.pending_g_key = false;
-                self.navigate_to_screen(Screen::Results);
-            }
-            KeyCode::Char('g') => {
-                // Vim-like "gg" command (go to top of content)
-                if self.viewer.pending_g_key {
-                    // Second 'g' - go to top of current section/content
-                    self.viewer.scroll_offset = 0;
-                    self.viewer.pending_g_key = false;
-                    self.set_status("Top of content".to_string());
-                } else {
-                    // First 'g' - set pending state
-                    self.viewer.pending_g_key = true;
-                    self.set_status("Press 'g' again to go to top".to_string());
-                }
-            }
-            KeyCode::Char('G') => {
-                // Vim-like "G" command (go to bottom of content)
-                self.viewer.pending_g_key = false;
-                let max_scroll = self.calculate_max_scroll_offset();
-                self.viewer.scroll_offset = max_scroll;
-                self.set_status("Bottom of content".to_string());
-            }
-            _ => {
-                // Clear pending vim commands on any other key
-                if self.viewer.pending_g_key {
-                    self.viewer.pending_g_key = false;
-                    self.set_status("Command cancelled".to_string());
-                }
-            }
+    /// Preload downloaded documents on the visible results page without blocking the UI
+    pub fn preload_visible_results(&self) {
+        if let Some(results) = self.screen::<ResultsScreen>() {
+            self.preload_documents(results.table.get_current_page_documents().to_vec());
         }
-        Ok(())
     }
 
-    /// Load document content for viewer
-    async fn load_viewer_content(&mut self) -> Result<()> {
-        if self.viewer.content_sections.is_some() {
-            return Ok(()); // Already loaded
+    /// Preload downloaded documents in a background task when preloading is enabled
+    pub fn preload_documents(&self, documents: Vec<crate::models::Document>) {
+        if !self.config.display.preload_content {
+            return;
         }
 
-        let document = match &self.viewer.current_document {
-            Some(doc) => doc.clone(),
-            None => return Ok(()),
-        };
-
-        self.viewer.is_loading = true;
-        self.set_status("Loading document content...".to_string());
-
-        // Get the document ID from metadata for precise matching
-        let doc_id = document
-            .metadata
-            .get("doc_id")
-            .or_else(|| document.metadata.get("document_id"))
-            .unwrap_or(&document.id);
-
-        // Construct expected download path
-        let download_dir = std::path::PathBuf::from(self.config.download_dir_str());
-        let edinet_dir = download_dir.join("edinet").join(&document.ticker);
-
-        // Look for the specific ZIP file matching this document's ID
-        if let Ok(entries) = std::fs::read_dir(&edinet_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        // Only load files that exactly match the document ID
-                        if filename.contains(doc_id) {
-                            match crate::edinet::reader::read_edinet_zip(
-                                path.to_str().unwrap(),
-                                usize::MAX,
-                                usize::MAX,
-                            ) {
-                                Ok(sections) => {
-                                    self.viewer.content_sections = Some(sections);
-                                    self.viewer.current_section = 0;
-                                    self.viewer.is_loading = false;
-                                    self.set_status("Document content loaded".to_string());
-                                    return Ok(());
-                                }
-                                Err(e) => {
-                                    self.set_error(format!(
-                                        "Failed to read document {}: {}",
-                                        doc_id, e
-                                    ));
-                                    self.viewer.is_loading = false;
-                                    return Ok(());
-                                }
-                            }
-                        }
-                    }
-                }
+        let content_loader = Arc::clone(&self.content_loader);
+        tokio::spawn(async move {
+            let mut content_loader = content_loader.lock().await;
+            if let Err(e) = content_loader.preload_documents(&documents).await {
+                tracing::debug!("Preloading results failed: {}", e);
             }
-        }
-
-        // If no downloaded file found, suggest downloading
-        self.set_error("Document not found locally. Use 'd' to download first.".to_string());
-        self.viewer.is_loading = false;
-        Ok(())
+        });
     }
 
-    /// Download document from viewer
-    async fn download_viewer_document(&mut self) -> Result<()> {
-        let document = match &self.viewer.current_document {
-            Some(doc) => doc.clone(),
-            None => return Ok(()),
-        };
-
-        self.viewer.is_downloading = true;
-        self.viewer.download_status = Some(format!("Downloading {}...", document.ticker));
-
-        self.set_status(format!("Starting download for {}", document.ticker));
-
-        let download_request = crate::models::DownloadRequest {
-            source: crate::models::Source::Edinet,
-            ticker: document.ticker.clone(),
-            filing_type: Some(document.filing_type.clone()),
-            date_from: Some(document.date),
-            date_to: Some(document.date),
-            limit: 1,
-            format: crate::models::DocumentFormat::Complete,
-        };
-
-        match crate::downloader::download_documents(
-            &download_request,
-            self.config.download_dir_str(),
-        )
-        .await
-        {
-            Ok(count) => {
-                self.set_status(format!("Successfully downloaded {} document(s)", count));
-                // Clear content sections to force reload
-                self.viewer.content_sections = None;
-                // Update download status
-                self.viewer.is_downloaded = self.viewer.is_document_downloaded(self);
-            }
-            Err(e) => {
-                self.set_error(format!("Download failed: {}", e));
-            }
-        }
-
-        self.viewer.is_downloading = false;
-        self.viewer.download_status = None;
-        Ok(())
-    }
-
-    async fn handle_help_event(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Up => {
-                if self.help.current_section > 0 {
-                    self.help.current_section -= 1;
-                    self.help
-                        .section_state
-                        .select(Some(self.help.current_section));
-                    self.help.scroll_offset = 0;
-                }
-            }
-            KeyCode::Down => {
-                if self.help.current_section < self.help.sections.len() - 1 {
-                    self.help.current_section += 1;
-                    self.help
-                        .section_state
-                        .select(Some(self.help.current_section));
-                    self.help.scroll_offset = 0;
-                }
-            }
-            KeyCode::PageUp => {
-                self.help.scroll_offset = self.help.scroll_offset.saturating_sub(10);
-            }
-            KeyCode::PageDown => {
-                self.help.scroll_offset += 10;
-            }
-            KeyCode::Home => {
-                self.help.scroll_offset = 0;
-            }
-            KeyCode::Esc => {
-                // Help screen: ESC goes back to Main Menu
-                self.navigate_to_screen(Screen::MainMenu);
-            }
-            _ => {}
-        }
-        Ok(())
+    /// Start downloading a document through the download manager so it shows up in the debug panel
+    ///
+    /// Returns the id to check with `DownloadManager::finished_download`; screens pick up the outcome in `on_tick`.
+    pub async fn download_document(&mut self, document: &crate::models::Document) -> Result<String> {
+        self.download_manager.download_document(document).await
     }
 
-    /// Execute search with current form values
-    async fn execute_search(&mut self) -> Result<()> {
-        use chrono::NaiveDate;
-
-        // Validate date inputs
-        if !self.search.date_from_input.is_empty() {
-            if NaiveDate::parse_from_str(&self.search.date_from_input.value, "%Y-%m-%d").is_err() {
-                self.set_error("Invalid 'Date From' format. Please use YYYY-MM-DD".to_string());
-                return Ok(());
-            }
+    /// Replace a document's tags and refresh the copies shown by the results and viewer screens
+    ///
+    /// Returns the tags as stored. The screen handling the key press is out of the screen map
+    /// while it runs, so it updates its own copy from the returned tags.
+    pub async fn set_document_tags(&mut self, id: &str, tags: &[String]) -> Result<Vec<String>> {
+        let database_path = self.config.database_path_str().to_string();
+        storage::set_tags(id, tags, &database_path).await?;
+        let tags = storage::get_tags(id, &database_path).await?;
+        if let Some(results) = self.screen_mut::<ResultsScreen>() {
+            results.update_document_tags(id, &tags);
         }
-
-        if !self.search.date_to_input.is_empty() {
-            if NaiveDate::parse_from_str(&self.search.date_to_input.value, "%Y-%m-%d").is_err() {
-                self.set_error("Invalid 'Date To' format. Please use YYYY-MM-DD".to_string());
-                return Ok(());
-            }
+        if let Some(viewer) = self.screen_mut::<ViewerScreen>() {
+            viewer.update_document_tags(id, &tags);
         }
-
-        // Build search query
-        let search_query = SearchQuery {
-            ticker: if self.search.ticker_input.is_empty() {
-                None
-            } else {
-                Some(self.search.ticker_input.value.clone())
-            },
-            company_name: if self.search.company_input.is_empty() {
-                None
-            } else {
-                Some(self.search.company_input.value.clone())
-            },
-            filing_type: self.search.filing_type_list.selected().cloned(),
-            source: Some(Source::Edinet),
-            date_from: if self.search.date_from_input.is_empty() {
-                None
-            } else {
-                NaiveDate::parse_from_str(&self.search.date_from_input.value, "%Y-%m-%d").ok()
-            },
-            date_to: if self.search.date_to_input.is_empty() {
-                None
-            } else {
-                NaiveDate::parse_from_str(&self.search.date_to_input.value, "%Y-%m-%d").ok()
-            },
-            text_query: if self.search.text_query_input.is_empty() {
-                None
-            } else {
-                Some(self.search.text_query_input.value.clone())
-            },
-        };
-
-        // Check if search has any criteria
-        if search_query.ticker.is_none()
-            && search_query.company_name.is_none()
-            && search_query.filing_type.is_none()
-            && search_query.date_from.is_none()
-            && search_query.date_to.is_none()
-            && search_query.text_query.is_none()
-        {
-            self.set_error("Please enter at least one search criteria".to_string());
-            return Ok(());
-        }
-
-        self.set_status("Searching documents...".to_string());
-
-        match storage::search_documents(&search_query, self.config.database_path_str(), 100).await {
-            Ok(documents) => {
-                self.set_status(format!("Found {} documents", documents.len()));
-
-                // Store results in the results screen
-                self.results.set_documents(documents);
-                self.search.last_query = Some(search_query);
-
-                // Navigate to results screen
-                self.navigate_to_screen(Screen::Results);
-            }
-            Err(e) => {
-                self.set_error(format!("Search failed: {}", e));
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Calculate page size for scrolling (approximates visible lines)
-    fn calculate_page_size(&self) -> usize {
-        // Conservative estimate for content area height
-        // This should roughly match the available display height in the viewer
-        20 // One full page ≈ 20 lines
+        Ok(tags)
     }
+}
 
-    /// Calculate maximum scroll offset to prevent scrolling past content end
-    fn calculate_max_scroll_offset(&self) -> usize {
-        match self.viewer.mode {
-            super::screens::viewer::ViewerMode::Content => {
-                if let Some(ref sections) = self.viewer.content_sections {
-                    if let Some(current_section) = sections.get(self.viewer.current_section) {
-                        // Calculate total lines: header lines + content lines
-                        let header_lines = 4; // Section, File, Size, blank line
-                        let content_lines = current_section.content.lines().count();
-                        let total_lines = header_lines + content_lines;
-
-                        // Estimate available display height (subtract borders and UI elements)
-                        // This is a rough estimate - in practice, the terminal height varies
-                        let available_height = 20; // Conservative estimate for content area
-
-                        if total_lines > available_height {
-                            total_lines - available_height
-                        } else {
-                            0
-                        }
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            }
-            super::screens::viewer::ViewerMode::Info => {
-                // Info mode has a variable number of lines (basic info + metadata + download info)
-                let info_lines = 25; // Increased to account for download status and ZIP contents
-                let available_height = 20;
-                if info_lines > available_height {
-                    info_lines - available_height
-                } else {
-                    0
-                }
-            }
-        }
+/// Onboarding message for a database that has no documents to search, if any
+fn setup_hint(health: &DatabaseHealthStatus) -> Option<String> {
+    if health.status == "Not Found" {
+        Some("No database found at the configured path.".to_string())
+    } else if health.documents_count > 0 {
+        None
+    } else if health.static_entries_count == 0 {
+        Some("The database is empty: no company data or documents are loaded yet.".to_string())
+    } else {
+        Some("No documents are indexed yet, so searches will return nothing.".to_string())
     }
 }
 
@@ -1057,3 +615,41 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[tokio::test]
+    async fn test_screen_dispatch() {
+        let mut app = App::new(Config::from_env().unwrap()).unwrap();
+        app.navigate_to_screen(Screen::Search);
+        assert_eq!(app.screens[&Screen::Search].title(), "Search Documents");
+
+        // Keys reach the boxed screen and it is put back afterwards
+        app.handle_key_event(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(app.screen::<SearchScreen>().unwrap().current_field, 1);
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(app.current_screen, Screen::MainMenu);
+        assert!(app.screen_mut::<ViewerScreen>().is_some());
+    }
+
+    #[test]
+    fn test_setup_hint() {
+        let health = |status: &str, documents_count, static_entries_count| DatabaseHealthStatus {
+            status: status.to_string(),
+            documents_count,
+            static_entries_count,
+            unmapped_edinet_codes: 0,
+            last_updated: None,
+            issues: Vec::new(),
+        };
+
+        assert!(setup_hint(&health("Not Found", 0, 0)).is_some());
+        assert!(setup_hint(&health("Empty", 0, 0)).unwrap().contains("empty"));
+        assert!(setup_hint(&health("Ready", 0, 3912)).unwrap().contains("No documents"));
+        assert!(setup_hint(&health("Healthy", 10, 3912)).is_none());
+    }
+}
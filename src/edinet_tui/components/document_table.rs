@@ -1,27 +1,79 @@
 //! Document table component for displaying search results
 
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Rect},
     style::Style,
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
     Frame,
 };
 
+use unicode_width::UnicodeWidthStr;
+
 use crate::{
-    edinet_tui::ui::Styles,
+    edinet_tui::ui::{truncate_string, Styles},
+    locale::{DateFormat, DisplayLocale},
     models::Document,
 };
 
+/// Blank columns between table cells
+const COLUMN_SPACING: usize = 2;
+
+/// Width of the row number column
+const ROW_NUMBER_WIDTH: usize = 4;
+
+/// Minimum width of the date column (fits both ISO and Japanese era short dates)
+const DATE_WIDTH: usize = 10;
+
+/// Width of the format column
+const FORMAT_WIDTH: usize = 10;
+
+/// Width of the download status column
+const DOWNLOAD_STATUS_WIDTH: usize = 1;
+
+/// Whether a document is available locally, shown as a glyph next to its row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DownloadState {
+    #[default]
+    NotDownloaded,
+    Downloading,
+    Downloaded,
+}
+
+impl DownloadState {
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            DownloadState::NotDownloaded => " ",
+            DownloadState::Downloading => "↓",
+            DownloadState::Downloaded => "✓",
+        }
+    }
+
+    fn style(&self) -> Style {
+        match self {
+            DownloadState::NotDownloaded => Style::default(),
+            DownloadState::Downloading => Styles::warning(),
+            DownloadState::Downloaded => Styles::success(),
+        }
+    }
+}
+
 /// Configuration for document table display
 #[derive(Debug, Clone)]
 pub struct DocumentTableConfig {
     pub title: String,
     pub show_borders: bool,
     pub show_header: bool,
+    pub show_row_numbers: bool,
+    /// Show a ✓/↓ column for documents that are downloaded or downloading
+    pub show_download_status: bool,
     pub max_ticker_len: usize,
-    pub max_company_len: usize,
+    /// Minimum company column width; the company column takes any remaining space
+    pub min_company_len: usize,
     pub max_type_len: usize,
+    pub date_format: DateFormat,
 }
 
 impl Default for DocumentTableConfig {
@@ -30,9 +82,12 @@ impl Default for DocumentTableConfig {
             title: "Documents".to_string(),
             show_borders: true,
             show_header: true,
+            show_row_numbers: false,
+            show_download_status: false,
             max_ticker_len: 8,
-            max_company_len: 15,  // reduced by 5 chars (from 20 to 15)
-            max_type_len: 16,     // increased by 8 chars (from 8 to 16)
+            min_company_len: 15,
+            max_type_len: 19,
+            date_format: DateFormat::default(),
         }
     }
 }
@@ -47,29 +102,88 @@ impl DocumentTableConfig {
 
     pub fn with_column_widths(mut self, ticker: usize, company: usize, type_len: usize) -> Self {
         self.max_ticker_len = ticker;
-        self.max_company_len = company;
+        self.min_company_len = company;
         self.max_type_len = type_len;
         self
     }
 
+    pub fn with_row_numbers(mut self) -> Self {
+        self.show_row_numbers = true;
+        self
+    }
+
+    pub fn with_download_status(mut self) -> Self {
+        self.show_download_status = true;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: DisplayLocale) -> Self {
+        self.date_format.locale = locale;
+        self
+    }
+
+    pub fn with_date_format(mut self, date_format: DateFormat) -> Self {
+        self.date_format = date_format;
+        self
+    }
+
+    /// Width of the date column, widened for custom date formats longer than the default
+    fn date_width(&self) -> usize {
+        let widest = NaiveDate::from_ymd_opt(2024, 12, 31).expect("valid date");
+        DATE_WIDTH.max(self.date_format.format_short(widest).width())
+    }
+
     pub fn without_header(mut self) -> Self {
         self.show_header = false;
         self
     }
 }
 
+/// Column widths resolved for a given render area
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnLayout {
+    company: usize,
+    show_type: bool,
+    show_format: bool,
+}
+
+impl ColumnLayout {
+    /// Table width constraints for the visible columns, with the company column filling leftover space
+    fn constraints(&self, config: &DocumentTableConfig) -> Vec<Constraint> {
+        let mut widths = Vec::new();
+        if config.show_row_numbers {
+            widths.push(Constraint::Length(ROW_NUMBER_WIDTH as u16));
+        }
+        if config.show_download_status {
+            widths.push(Constraint::Length(DOWNLOAD_STATUS_WIDTH as u16));
+        }
+        widths.push(Constraint::Length(config.date_width() as u16));
+        widths.push(Constraint::Length(config.max_ticker_len as u16));
+        widths.push(Constraint::Min(self.company as u16));
+        if self.show_type {
+            widths.push(Constraint::Length(config.max_type_len as u16));
+        }
+        if self.show_format {
+            widths.push(Constraint::Length(FORMAT_WIDTH as u16));
+        }
+        widths
+    }
+}
+
 /// Specialized component for displaying documents in a table format
 pub struct DocumentTable {
     pub documents: Vec<Document>,
-    pub state: ListState,
+    pub state: TableState,
     pub config: DocumentTableConfig,
     pub current_page: usize,
     pub items_per_page: usize,
+    /// Local availability by document id; documents without an entry are shown as not downloaded
+    pub download_states: HashMap<String, DownloadState>,
 }
 
 impl DocumentTable {
     pub fn new(documents: Vec<Document>, config: DocumentTableConfig) -> Self {
-        let mut state = ListState::default();
+        let mut state = TableState::default();
         if !documents.is_empty() {
             state.select(Some(0));
         }
@@ -80,17 +194,19 @@ impl DocumentTable {
             config,
             current_page: 0,
             items_per_page: 20,
+            download_states: HashMap::new(),
         }
     }
 
     pub fn with_pagination(mut self, items_per_page: usize) -> Self {
-        self.items_per_page = items_per_page;
+        self.items_per_page = items_per_page.max(1);
         self
     }
 
     /// Set new documents and reset selection
     pub fn set_documents(&mut self, documents: Vec<Document>) {
         self.documents = documents;
+        self.download_states.clear();
         self.current_page = 0;
         self.state.select(if self.documents.is_empty() {
             None
@@ -99,6 +215,29 @@ impl DocumentTable {
         });
     }
 
+    /// Change the page size, keeping the same document selected
+    pub fn set_items_per_page(&mut self, items_per_page: usize) {
+        let items_per_page = items_per_page.max(1);
+        if let Some(selected_local_idx) = self.state.selected() {
+            let global_idx = self.current_page * self.items_per_page + selected_local_idx;
+            self.current_page = global_idx / items_per_page;
+            self.state.select(Some(global_idx % items_per_page));
+        } else {
+            self.current_page = 0;
+        }
+        self.items_per_page = items_per_page;
+    }
+
+    /// Record whether a document is available locally
+    pub fn set_download_state(&mut self, document_id: &str, state: DownloadState) {
+        self.download_states.insert(document_id.to_string(), state);
+    }
+
+    /// Local availability of a document, as last recorded
+    pub fn download_state(&self, document_id: &str) -> DownloadState {
+        self.download_states.get(document_id).copied().unwrap_or_default()
+    }
+
     /// Get documents for current page
     pub fn get_current_page_documents(&self) -> &[Document] {
         let start_idx = self.current_page * self.items_per_page;
@@ -113,19 +252,19 @@ impl DocumentTable {
 
     /// Get currently selected document
     pub fn get_selected_document(&self) -> Option<&Document> {
-        self.state.selected().and_then(|idx| {
-            let page_start = self.current_page * self.items_per_page;
-            self.documents.get(page_start + idx)
-        })
+        self.selected_index().and_then(|idx| self.documents.get(idx))
+    }
+
+    /// Index of the selected document across all pages
+    pub fn selected_index(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .map(|idx| self.current_page * self.items_per_page + idx)
     }
 
-    /// Get total number of pages
+    /// Get total number of pages (0 when there are no documents)
     pub fn get_total_pages(&self) -> usize {
-        if self.documents.is_empty() {
-            1
-        } else {
-            (self.documents.len() + self.items_per_page - 1) / self.items_per_page
-        }
+        self.documents.len().div_ceil(self.items_per_page)
     }
 
     /// Navigate to next page
@@ -144,111 +283,162 @@ impl DocumentTable {
         }
     }
 
-    /// Navigate up within current page
+    /// Navigate to the first page
+    pub fn go_to_first_page(&mut self) {
+        self.current_page = 0;
+        self.state.select(if self.documents.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    /// Navigate to the last page
+    pub fn go_to_last_page(&mut self) {
+        if self.get_total_pages() > 0 {
+            self.current_page = self.get_total_pages() - 1;
+            self.state.select(Some(0));
+        }
+    }
+
+    /// Select the document at `index` across all pages, turning to its page; false when out of range
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index >= self.documents.len() {
+            return false;
+        }
+        self.current_page = index / self.items_per_page;
+        self.state.select(Some(index % self.items_per_page));
+        true
+    }
+
+    /// Index of the earliest document dated on or after `date`, or of the latest one when all are older
+    ///
+    /// Among documents with the same date the first row wins, whichever way the rows are sorted.
+    pub fn index_nearest_date(&self, date: chrono::NaiveDate) -> Option<usize> {
+        let rows = self.documents.iter().enumerate();
+        rows.clone()
+            .filter(|(_, document)| document.date >= date)
+            .min_by_key(|(index, document)| (document.date, *index))
+            .or_else(|| rows.max_by_key(|(index, document)| (document.date, std::cmp::Reverse(*index))))
+            .map(|(index, _)| index)
+    }
+
+    /// Navigate up, moving to the last item of the previous page at the top of a page
     pub fn navigate_up(&mut self) {
-        let page_documents = self.get_current_page_documents();
-        if page_documents.is_empty() {
+        if self.get_current_page_documents().is_empty() {
             return;
         }
 
         let selected = self.state.selected().unwrap_or(0);
-        let new_selected = if selected == 0 {
-            page_documents.len() - 1
-        } else {
-            selected - 1
-        };
-        self.state.select(Some(new_selected));
+        if selected > 0 {
+            self.state.select(Some(selected - 1));
+        } else if self.current_page > 0 {
+            self.current_page -= 1;
+            let page_len = self.get_current_page_documents().len();
+            self.state.select(Some(page_len.saturating_sub(1)));
+        }
     }
 
-    /// Navigate down within current page
+    /// Navigate down, moving to the first item of the next page at the bottom of a page
     pub fn navigate_down(&mut self) {
-        let page_documents = self.get_current_page_documents();
-        if page_documents.is_empty() {
+        let page_len = self.get_current_page_documents().len();
+        if page_len == 0 {
             return;
         }
 
         let selected = self.state.selected().unwrap_or(0);
-        let new_selected = (selected + 1) % page_documents.len();
-        self.state.select(Some(new_selected));
+        if selected + 1 < page_len {
+            self.state.select(Some(selected + 1));
+        } else if self.current_page + 1 < self.get_total_pages() {
+            self.current_page += 1;
+            self.state.select(Some(0));
+        }
     }
 
-    /// Render the document table
-    pub fn render(&mut self, f: &mut Frame, area: Rect) {
-        let page_documents: Vec<_> = self.get_current_page_documents().iter().cloned().collect();
-        
-        let mut items = Vec::new();
-
-        // Add header if configured
-        if self.config.show_header {
-            let header = Line::from(vec![
-                Span::styled(
-                    format!("{:<10}", "Date"),
-                    Styles::title(),
-                ),
-                Span::styled(" | ", Styles::title()),
-                Span::styled(
-                    format!("{:<width$}", "Symbol", width = self.config.max_ticker_len),
-                    Styles::title(),
-                ),
-                Span::styled(" | ", Styles::title()),
-                Span::styled(
-                    format!("{:<width$}", "Company", width = self.config.max_company_len),
-                    Styles::title(),
-                ),
-                Span::styled(" | ", Styles::title()),
-                Span::styled(
-                    format!("{:<width$}", "Type", width = self.config.max_type_len),
-                    Styles::title(),
-                ),
-                Span::styled(" | ", Styles::title()),
-                Span::styled("Format", Styles::title()),
-            ]);
-            items.push(ListItem::new(header));
+    /// Fit columns into `width`, dropping the format and then the type column on narrow terminals
+    fn column_layout(&self, width: usize) -> ColumnLayout {
+        let mut fixed = self.config.date_width() + COLUMN_SPACING + self.config.max_ticker_len + COLUMN_SPACING;
+        if self.config.show_row_numbers {
+            fixed += ROW_NUMBER_WIDTH + COLUMN_SPACING;
         }
-
-        // Add document rows
-        for (i, doc) in page_documents.iter().enumerate() {
-            let style = if Some(i) == self.state.selected() {
-                Styles::selected()
-            } else {
-                Style::default()
+        if self.config.show_download_status {
+            fixed += DOWNLOAD_STATUS_WIDTH + COLUMN_SPACING;
+        }
+        let type_width = COLUMN_SPACING + self.config.max_type_len;
+        let format_width = COLUMN_SPACING + FORMAT_WIDTH;
+
+        let mut layout = ColumnLayout { company: 0, show_type: true, show_format: true };
+        for (show_type, show_format) in [(true, true), (true, false), (false, false)] {
+            let used = fixed
+                + if show_type { type_width } else { 0 }
+                + if show_format { format_width } else { 0 };
+            layout = ColumnLayout {
+                company: width.saturating_sub(used).max(self.config.min_company_len),
+                show_type,
+                show_format,
             };
+            if width >= used + self.config.min_company_len {
+                break;
+            }
+        }
+        layout
+    }
 
-            let content = Line::from(vec![
-                Span::styled(format!("{:<10}", doc.date), style),
-                Span::styled(" | ", style),
-                Span::styled(
-                    format!(
-                        "{:<width$}",
-                        doc.ticker.chars().take(self.config.max_ticker_len).collect::<String>(),
-                        width = self.config.max_ticker_len
-                    ),
-                    style,
-                ),
-                Span::styled(" | ", style),
-                Span::styled(
-                    format!(
-                        "{:<width$}",
-                        doc.company_name.chars().take(self.config.max_company_len).collect::<String>(),
-                        width = self.config.max_company_len
-                    ),
-                    style,
-                ),
-                Span::styled(" | ", style),
-                Span::styled(
-                    format!(
-                        "{:<width$}",
-                        doc.filing_type.as_str().chars().take(self.config.max_type_len).collect::<String>(),
-                        width = self.config.max_type_len
-                    ),
-                    style,
-                ),
-                Span::styled(" | ", style),
-                Span::styled(doc.format.as_str(), style),
-            ]);
-
-            items.push(ListItem::new(content));
+    /// Build one table row from its cell values, truncated to the column widths
+    fn row_cells(
+        &self,
+        layout: &ColumnLayout,
+        row_number: &str,
+        download_state: DownloadState,
+        cells: [&str; 5],
+        type_style: Style,
+    ) -> Row<'static> {
+        let [date, ticker, company, filing_type, format] = cells;
+        let mut columns = Vec::new();
+        if self.config.show_row_numbers {
+            columns.push(Cell::from(format!("{:>width$}", row_number, width = ROW_NUMBER_WIDTH)));
+        }
+        if self.config.show_download_status {
+            columns.push(Cell::from(download_state.glyph()).style(download_state.style()));
+        }
+        columns.push(Cell::from(truncate_string(date, self.config.date_width())));
+        columns.push(Cell::from(truncate_string(ticker, self.config.max_ticker_len)));
+        columns.push(Cell::from(truncate_string(company, layout.company)));
+        if layout.show_type {
+            columns.push(Cell::from(truncate_string(filing_type, self.config.max_type_len)).style(type_style));
         }
+        if layout.show_format {
+            columns.push(Cell::from(truncate_string(format, FORMAT_WIDTH)));
+        }
+        Row::new(columns)
+    }
+
+    /// Render the document table
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let border_width = if self.config.show_borders { 2 } else { 0 };
+        let layout = self.column_layout((area.width as usize).saturating_sub(border_width));
+        let page_start = self.current_page * self.items_per_page;
+
+        let rows: Vec<Row> = self
+            .get_current_page_documents()
+            .iter()
+            .enumerate()
+            .map(|(i, doc)| {
+                self.row_cells(
+                    &layout,
+                    &(page_start + i + 1).to_string(),
+                    self.download_state(&doc.id),
+                    [
+                        &self.config.date_format.format_short(doc.date),
+                        &doc.ticker,
+                        &doc.company_name,
+                        doc.filing_type.as_str(),
+                        doc.format.as_str(),
+                    ],
+                    Styles::filing_type(&doc.filing_type),
+                )
+            })
+            .collect();
 
         // Add pagination info to title
         let title = if self.documents.is_empty() {
@@ -257,7 +447,7 @@ impl DocumentTable {
             format!(
                 "{} ({}/{} - Page {}/{})",
                 self.config.title,
-                page_documents.len(),
+                self.get_current_page_documents().len(),
                 self.documents.len(),
                 self.current_page + 1,
                 self.get_total_pages()
@@ -273,9 +463,20 @@ impl DocumentTable {
             Block::default()
         };
 
-        let list = List::new(items).block(block);
+        let mut table = Table::new(rows, layout.constraints(&self.config))
+            .block(block)
+            .column_spacing(COLUMN_SPACING as u16)
+            .highlight_style(Styles::selected());
 
-        f.render_stateful_widget(list, area, &mut self.state);
+        // Add header if configured
+        if self.config.show_header {
+            let header = self
+                .row_cells(&layout, "No.", DownloadState::NotDownloaded, ["Date", "Symbol", "Company", "Type", "Format"], Style::default())
+                .style(Styles::title());
+            table = table.header(header);
+        }
+
+        f.render_stateful_widget(table, area, &mut self.state);
     }
 
     /// Render with download status indicators
@@ -290,4 +491,178 @@ impl DocumentTable {
             self.render(f, area);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_index_turns_to_page() {
+        let document = |id: usize| Document {
+            id: format!("S100{:04}", id),
+            ticker: "7203".to_string(),
+            company_name: "Toyota".to_string(),
+            filing_type: crate::models::FilingType::AnnualSecuritiesReport,
+            source: crate::models::Source::Edinet,
+            date: chrono::NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+            content_path: std::path::PathBuf::new(),
+            metadata: std::collections::HashMap::new(),
+            format: crate::models::DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+        let mut table = DocumentTable::new((0..50).map(document).collect(), DocumentTableConfig::default())
+            .with_pagination(20);
+
+        assert!(table.select_index(46));
+        assert_eq!(table.current_page, 2);
+        assert_eq!(table.state.selected(), Some(6));
+        assert_eq!(table.get_selected_document().unwrap().id, "S1000046");
+
+        assert!(!table.select_index(50));
+        assert_eq!(table.selected_index(), Some(46));
+    }
+
+    #[test]
+    fn test_index_nearest_date() {
+        let document = |day: u32| Document {
+            id: format!("S1000{:03}", day),
+            ticker: "7203".to_string(),
+            company_name: "Toyota".to_string(),
+            filing_type: crate::models::FilingType::AnnualSecuritiesReport,
+            source: crate::models::Source::Edinet,
+            date: chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(day as i64),
+            content_path: std::path::PathBuf::new(),
+            metadata: std::collections::HashMap::new(),
+            format: crate::models::DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+        // Newest first, as searches return them
+        let table = DocumentTable::new([300, 200, 200, 100].map(document).to_vec(), DocumentTableConfig::default());
+        let date = |day: i64| chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(day);
+
+        assert_eq!(table.index_nearest_date(date(150)), Some(1));
+        assert_eq!(table.index_nearest_date(date(200)), Some(1));
+        assert_eq!(table.index_nearest_date(date(0)), Some(3));
+        assert_eq!(table.index_nearest_date(date(400)), Some(0));
+        assert_eq!(DocumentTable::new(Vec::new(), DocumentTableConfig::default()).index_nearest_date(date(0)), None);
+    }
+
+    #[test]
+    fn test_column_layout_drops_columns_when_narrow() {
+        let table = DocumentTable::new(Vec::new(), DocumentTableConfig::default().with_row_numbers());
+
+        let wide = table.column_layout(120);
+        assert!(wide.show_type && wide.show_format);
+        assert!(wide.company > table.config.min_company_len);
+
+        let medium = table.column_layout(70);
+        assert!(medium.show_type && !medium.show_format);
+
+        let narrow = table.column_layout(40);
+        assert!(!narrow.show_type && !narrow.show_format);
+        assert_eq!(narrow.company, table.config.min_company_len);
+    }
+
+    #[test]
+    fn test_render_adapts_to_width() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let document = Document {
+            id: "S100TEST".to_string(),
+            ticker: "7203".to_string(),
+            company_name: "トヨタ自動車株式会社".to_string(),
+            filing_type: crate::models::FilingType::AnnualSecuritiesReport,
+            source: crate::models::Source::Edinet,
+            date: chrono::NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+            content_path: std::path::PathBuf::new(),
+            metadata: std::collections::HashMap::new(),
+            format: crate::models::DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+        let mut table = DocumentTable::new(vec![document], DocumentTableConfig::new("Results").with_row_numbers());
+
+        let mut render = |width: u16| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 6)).unwrap();
+            terminal.draw(|f| table.render(f, f.size())).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            move |y: u16| (0..width).map(|x| buffer.get(x, y).symbol().to_string()).collect::<String>()
+        };
+
+        let wide = render(80);
+        assert!(wide(1).contains("Company") && wide(1).contains("Format"));
+        assert!(wide(2).contains("7203"));
+
+        let narrow = render(70);
+        assert!(narrow(1).contains("Type") && !narrow(1).contains("Format"));
+    }
+
+    #[test]
+    fn test_filing_type_is_color_coded() {
+        use ratatui::{backend::TestBackend, style::Color, Terminal};
+        use crate::models::FilingType;
+
+        let document = |id: &str, filing_type| Document {
+            id: id.to_string(),
+            ticker: "7203".to_string(),
+            company_name: "Toyota".to_string(),
+            filing_type,
+            source: crate::models::Source::Edinet,
+            date: chrono::NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+            content_path: std::path::PathBuf::new(),
+            metadata: std::collections::HashMap::new(),
+            format: crate::models::DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+        let mut table = DocumentTable::new(
+            vec![
+                document("S100A", FilingType::AnnualSecuritiesReport),
+                document("S100B", FilingType::ExtraordinaryReport),
+            ],
+            DocumentTableConfig::new("Results"),
+        );
+        table.state.select(None);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 6)).unwrap();
+        terminal.draw(|f| table.render(f, f.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let type_x = (0..100).find(|&x| buffer.get(x, 1).symbol() == "T").unwrap();
+
+        assert_eq!(buffer.get(type_x, 2).fg, Color::Green);
+        assert_eq!(buffer.get(type_x, 3).fg, Color::Red);
+    }
+
+    #[test]
+    fn test_download_status_column() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let document = |id: &str| Document {
+            id: id.to_string(),
+            ticker: "7203".to_string(),
+            company_name: "Toyota".to_string(),
+            filing_type: crate::models::FilingType::AnnualSecuritiesReport,
+            source: crate::models::Source::Edinet,
+            date: chrono::NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+            content_path: std::path::PathBuf::new(),
+            metadata: std::collections::HashMap::new(),
+            format: crate::models::DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+        let mut table = DocumentTable::new(
+            vec![document("S100A"), document("S100B"), document("S100C")],
+            DocumentTableConfig::new("Results").with_download_status(),
+        );
+        table.set_download_state("S100A", DownloadState::Downloaded);
+        table.set_download_state("S100B", DownloadState::Downloading);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 7)).unwrap();
+        terminal.draw(|f| table.render(f, f.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer.get(1, 2).symbol(), "✓");
+        assert_eq!(buffer.get(1, 3).symbol(), "↓");
+        assert_eq!(buffer.get(1, 4).symbol(), " ");
+
+        table.set_documents(vec![document("S100A")]);
+        assert_eq!(table.download_state("S100A"), DownloadState::NotDownloaded);
+    }
+}
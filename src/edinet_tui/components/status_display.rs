@@ -2,12 +2,18 @@
 
 use ratatui::{
     layout::Rect,
+    style::Style,
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
 use crate::edinet_tui::ui::Styles;
 
+/// Frames of the spinner shown before in-progress messages
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// How long each spinner frame is shown
+const SPINNER_FRAME_MILLIS: i64 = 100;
+
 /// Types of status messages
 #[derive(Debug, Clone, PartialEq)]
 pub enum StatusType {
@@ -18,6 +24,19 @@ pub enum StatusType {
     Loading,
 }
 
+impl StatusType {
+    /// Color used for messages of this type, shared by every screen
+    pub fn style(&self) -> Style {
+        match self {
+            StatusType::Info => Styles::info(),
+            StatusType::Success => Styles::success(),
+            StatusType::Warning => Styles::warning(),
+            StatusType::Error => Styles::error(),
+            StatusType::Loading => Styles::warning(),
+        }
+    }
+}
+
 /// Status message with type and content
 #[derive(Debug, Clone)]
 pub struct StatusMessage {
@@ -97,6 +116,18 @@ impl StatusDisplay {
         self
     }
 
+    /// A display showing only an in-progress message, e.g. for a loading placeholder
+    pub fn loading(message: &str) -> Self {
+        let mut display = Self::new();
+        display.set_loading(message.to_string());
+        display
+    }
+
+    /// Whether an in-progress message is shown
+    pub fn is_loading(&self) -> bool {
+        self.current_message.as_ref().is_some_and(|message| message.status_type == StatusType::Loading)
+    }
+
     /// Set current status message
     pub fn set_message(&mut self, message: StatusMessage) {
         // Add to history if we have a current message
@@ -172,65 +203,26 @@ impl StatusDisplay {
 
     /// Render the status display
     pub fn render(&self, f: &mut Frame, area: Rect) {
-        let content = if let Some(message) = &self.current_message {
-            self.format_message(message)
-        } else {
-            "Ready".to_string()
-        };
-
-        let style = if let Some(message) = &self.current_message {
-            match message.status_type {
-                StatusType::Info => Styles::info(),
-                StatusType::Success => Styles::success(),
-                StatusType::Warning => Styles::warning(),
-                StatusType::Error => Styles::error(),
-                StatusType::Loading => Styles::warning(),
-            }
-        } else {
-            Styles::default()
-        };
-
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Styles::inactive_border());
-
-        let paragraph = Paragraph::new(content)
-            .style(style)
-            .block(block);
+        self.render_or(f, area, "Ready");
+    }
 
-        f.render_widget(paragraph, area);
+    /// Render the current message, or `idle_text` in a neutral style when there is none
+    pub fn render_or(&self, f: &mut Frame, area: Rect, idle_text: &str) {
+        let block = Block::default().borders(Borders::ALL).border_style(Styles::inactive_border());
+        f.render_widget(self.paragraph(idle_text).block(block), area);
     }
 
     /// Render with custom title
     pub fn render_with_title(&self, f: &mut Frame, area: Rect, title: &str) {
-        let content = if let Some(message) = &self.current_message {
-            self.format_message(message)
-        } else {
-            "Ready".to_string()
-        };
-
-        let style = if let Some(message) = &self.current_message {
-            match message.status_type {
-                StatusType::Info => Styles::info(),
-                StatusType::Success => Styles::success(),
-                StatusType::Warning => Styles::warning(),
-                StatusType::Error => Styles::error(),
-                StatusType::Loading => Styles::warning(),
-            }
-        } else {
-            Styles::default()
-        };
-
-        let block = Block::default()
-            .title(title)
-            .borders(Borders::ALL)
-            .border_style(Styles::inactive_border());
-
-        let paragraph = Paragraph::new(content)
-            .style(style)
-            .block(block);
+        let block = Block::default().title(title).borders(Borders::ALL).border_style(Styles::inactive_border());
+        f.render_widget(self.paragraph("Ready").block(block), area);
+    }
 
-        f.render_widget(paragraph, area);
+    fn paragraph(&self, idle_text: &str) -> Paragraph<'static> {
+        match &self.current_message {
+            Some(message) => Paragraph::new(self.format_message(message)).style(message.status_type.style()),
+            None => Paragraph::new(idle_text.to_string()).style(Styles::inactive()),
+        }
     }
 
     /// Format message for display
@@ -240,7 +232,7 @@ impl StatusDisplay {
             StatusType::Success => "✓",
             StatusType::Warning => "⚠",
             StatusType::Error => "✗",
-            StatusType::Loading => "⟳",
+            StatusType::Loading => spinner_frame(message.timestamp),
         };
 
         if self.show_timestamp {
@@ -258,4 +250,31 @@ impl StatusDisplay {
             format!("{} {}", prefix, message.message)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Spinner frame for a message started at `started`, advancing as the screen is redrawn
+fn spinner_frame(started: Option<chrono::DateTime<chrono::Local>>) -> &'static str {
+    let elapsed = started.map(|started| (chrono::Local::now() - started).num_milliseconds()).unwrap_or(0);
+    SPINNER_FRAMES[(elapsed.max(0) / SPINNER_FRAME_MILLIS) as usize % SPINNER_FRAMES.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loading_message_shows_spinner() {
+        let mut status = StatusDisplay::loading("Downloading 7203...");
+        assert!(status.is_loading());
+        let message = status.get_current().unwrap().clone();
+        assert!(SPINNER_FRAMES.iter().any(|frame| status.format_message(&message).starts_with(frame)));
+
+        // The spinner advances with the time since the message was set and wraps around
+        let started = chrono::Local::now() - chrono::Duration::milliseconds(SPINNER_FRAME_MILLIS * 11 + 50);
+        assert_eq!(spinner_frame(Some(started)), SPINNER_FRAMES[1]);
+
+        status.set_error("Download failed".to_string());
+        assert!(!status.is_loading());
+        assert_eq!(status.get_history().len(), 1);
+    }
+}
@@ -6,10 +6,12 @@ use std::path::PathBuf;
 
 use crate::{
     config::Config,
-    edinet::reader::{read_edinet_zip, DocumentSection},
+    edinet::reader::DocumentSection,
     models::Document,
 };
 
+use super::local_files;
+
 /// Content cache entry
 #[derive(Debug, Clone)]
 pub struct ContentCache {
@@ -99,27 +101,14 @@ impl ContentLoader {
 
     /// Load content directly from file without caching
     async fn load_from_file(&self, document: &Document) -> Result<Vec<DocumentSection>> {
-        let document_id = self.get_document_id(document);
-        let download_dir = PathBuf::from(self.config.download_dir_str());
-        let edinet_dir = download_dir.join("edinet").join(&document.ticker);
-
-        // Look for the specific ZIP file matching this document's ID
-        if let Ok(entries) = std::fs::read_dir(&edinet_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        // Only load files that exactly match the document ID
-                        if filename.contains(&document_id) {
-                            return read_edinet_zip(
-                                path.to_str().unwrap(),
-                                usize::MAX, // No limit on sections
-                                usize::MAX, // No limit on content length
-                            );
-                        }
-                    }
-                }
-            }
+        // Look for the downloaded ZIP, filing or metadata summary matching this document's ID
+        if let Some(path) = local_files::find_downloaded_file(&self.config, document) {
+            return local_files::read_sections(
+                document,
+                &path,
+                self.config.display.viewer_max_sections,
+                self.config.display.viewer_max_content_length,
+            );
         }
 
         Err(anyhow::anyhow!("Document content not found locally. Download the document first."))
@@ -130,25 +119,12 @@ impl ContentLoader {
         let document_id = self.get_document_id(document);
         
         // Find the actual file path for cache validation
-        let download_dir = PathBuf::from(self.config.download_dir_str());
-        let edinet_dir = download_dir.join("edinet").join(&document.ticker);
-        
-        if let Ok(entries) = std::fs::read_dir(&edinet_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        if filename.contains(&document_id) {
-                            let cache_entry = ContentCache::new(document_id.clone(), sections, path);
-                            self.cache.insert(document_id, cache_entry);
-                            
-                            // Clean up cache if needed
-                            self.cleanup_cache().await;
-                            return;
-                        }
-                    }
-                }
-            }
+        if let Some(path) = local_files::find_downloaded_file(&self.config, document) {
+            let cache_entry = ContentCache::new(document_id.clone(), sections, path);
+            self.cache.insert(document_id, cache_entry);
+
+            // Clean up cache if needed
+            self.cleanup_cache().await;
         }
     }
 
@@ -169,12 +145,12 @@ impl ContentLoader {
         self.get_cached_content(document).is_some()
     }
 
-    /// Preload content for multiple documents
+    /// Preload content for multiple documents, skipping ones that are not downloaded
     pub async fn preload_documents(&mut self, documents: &[Document]) -> Result<usize> {
         let mut loaded_count = 0;
         
         for document in documents {
-            if !self.is_cached(document) {
+            if !self.is_cached(document) && self.is_document_available(document) {
                 match self.load_document_content(document).await {
                     Ok(_) => loaded_count += 1,
                     Err(_) => continue, // Skip documents that can't be loaded
@@ -238,28 +214,7 @@ impl ContentLoader {
 
     /// Check if a document is available locally (downloaded)
     pub fn is_document_available(&self, document: &Document) -> bool {
-        let document_id = self.get_document_id(document);
-        let download_dir = PathBuf::from(self.config.download_dir_str());
-        let edinet_dir = download_dir.join("edinet").join(&document.ticker);
-
-        if !edinet_dir.exists() {
-            return false;
-        }
-
-        if let Ok(entries) = std::fs::read_dir(&edinet_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        if filename.contains(&document_id) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-
-        false
+        local_files::find_downloaded_file(&self.config, document).is_some()
     }
 
     /// Generate document ID for cache keys
@@ -297,4 +252,35 @@ impl ContentCacheStats {
             self.total_sections as f32 / self.total_entries as f32
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_document;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn test_preload_only_downloaded_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let edinet_dir = dir.path().join("edinet").join("7203");
+        std::fs::create_dir_all(&edinet_dir).unwrap();
+
+        let file = std::fs::File::create(edinet_dir.join("S100LOCAL-2024-06-20.zip")).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        zip.start_file("XBRL/PublicDoc/0101010_honbun.htm", zip::write::FileOptions::default()).unwrap();
+        zip.write_all(b"<html><body><p>Business overview</p></body></html>").unwrap();
+        zip.finish().unwrap();
+
+        let mut config = Config::from_env().unwrap();
+        config.download_dir = dir.path().to_path_buf();
+        let mut loader = ContentLoader::new(config);
+
+        let documents = [test_document("S100LOCAL"), test_document("S100REMOTE")];
+        assert_eq!(loader.preload_documents(&documents).await.unwrap(), 1);
+        assert!(loader.is_cached(&documents[0]));
+        assert!(!loader.is_cached(&documents[1]));
+
+        // Already cached documents are not loaded again
+        assert_eq!(loader.preload_documents(&documents).await.unwrap(), 0);
+    }
+}
@@ -6,16 +6,21 @@ use tokio::task::JoinHandle;
 
 use crate::{
     config::Config,
-    edinet,
+    edinet::{self, EdinetIndexFilter},
+    edinet_indexer,
     storage,
 };
 
+/// Days looked back by an index update
+const UPDATE_INDEX_DAYS_BACK: i64 = 7;
+
 /// Database operation types
 #[derive(Debug, Clone)]
 pub enum DatabaseOperation {
     ShowStats,
     UpdateIndex,
-    BuildIndex { from: NaiveDate, to: NaiveDate },
+    BuildIndex { from: NaiveDate, to: NaiveDate, filter: EdinetIndexFilter },
+    IndexDate { date: NaiveDate },
     ClearIndex,
     LoadStaticData { csv_path: String },
 }
@@ -30,6 +35,8 @@ pub struct DatabaseProgress {
     pub started_at: chrono::DateTime<chrono::Local>,
     pub completed_at: Option<chrono::DateTime<chrono::Local>>,
     pub result: Option<String>,
+    /// Why the operation failed, if it did
+    pub error: Option<String>,
 }
 
 /// Database operation status
@@ -52,6 +59,7 @@ impl DatabaseProgress {
             started_at: chrono::Local::now(),
             completed_at: None,
             result: None,
+            error: None,
         }
     }
 
@@ -71,6 +79,7 @@ impl DatabaseProgress {
     pub fn set_failed(&mut self, error: String) {
         self.status = DatabaseStatus::Failed;
         self.message = format!("Failed: {}", error);
+        self.error = Some(error);
         self.completed_at = Some(chrono::Local::now());
     }
 
@@ -126,9 +135,14 @@ impl DatabaseManager {
                     Self::update_index_operation(config).await
                 })
             }
-            DatabaseOperation::BuildIndex { from, to } => {
+            DatabaseOperation::BuildIndex { from, to, filter } => {
+                tokio::spawn(async move {
+                    Self::build_index_operation(config, from, to, filter).await
+                })
+            }
+            DatabaseOperation::IndexDate { date } => {
                 tokio::spawn(async move {
-                    Self::build_index_operation(config, from, to).await
+                    Self::index_date_operation(config, date).await
                 })
             }
             DatabaseOperation::ClearIndex => {
@@ -180,7 +194,7 @@ impl DatabaseManager {
                             progress.set_completed("Operation completed successfully".to_string(), Some(message));
                         }
                         Err(e) => {
-                            progress.set_failed(e.to_string());
+                            progress.set_failed(super::user_message(&e));
                         }
                     }
                 }
@@ -209,6 +223,19 @@ impl DatabaseManager {
             .map(|p| p.message.clone())
     }
 
+    /// Take the outcome of a finished operation, its result message or why it failed
+    ///
+    /// Returns `None` while the operation is still running or when there is none.
+    pub fn take_finished_operation(&mut self) -> Option<Result<String, String>> {
+        let progress = self.current_operation.as_ref().filter(|progress| !progress.is_active())?;
+        let outcome = match progress.status {
+            DatabaseStatus::Completed => Ok(progress.result.clone().unwrap_or_else(|| progress.message.clone())),
+            _ => Err(progress.error.clone().unwrap_or_else(|| progress.message.clone())),
+        };
+        self.current_operation = None;
+        Some(outcome)
+    }
+
     /// Clear completed operation from history
     pub fn clear_completed_operation(&mut self) {
         if let Some(progress) = &self.current_operation {
@@ -241,25 +268,27 @@ impl DatabaseManager {
     }
 
     async fn update_index_operation(config: Config) -> Result<String> {
-        // This would use the edinet indexer - simplified for now
-        // let mut indexer = edinet::indexer::EdinetIndexer::new(config)?;
-        // let result = indexer.update_index().await?;
-        
-        // Placeholder implementation
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-        Ok("Index update completed (placeholder implementation)".to_string())
+        let count = edinet_indexer::update_edinet_index(config.database_path_str(), UPDATE_INDEX_DAYS_BACK).await?;
+        Ok(format!("Successfully updated index with {} documents", count))
     }
 
-    async fn build_index_operation(config: Config, from: NaiveDate, to: NaiveDate) -> Result<String> {
-        // This would use the edinet indexer - simplified for now
-        // let mut indexer = edinet::indexer::EdinetIndexer::new(config)?;
-        // let result = indexer.build_index(from, to).await?;
-        
-        // Placeholder implementation
-        tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+    async fn build_index_operation(
+        config: Config,
+        from: NaiveDate,
+        to: NaiveDate,
+        filter: EdinetIndexFilter,
+    ) -> Result<String> {
+        let count =
+            edinet_indexer::build_edinet_index_by_date_filtered(config.database_path_str(), from, to, &filter).await?;
+        Ok(format!("Successfully indexed {} documents", count))
+    }
+
+    async fn index_date_operation(config: Config, date: NaiveDate) -> Result<String> {
+        let report =
+            edinet::index_edinet_date(config.database_path_str(), date, &config, &EdinetIndexFilter::default()).await?;
         Ok(format!(
-            "Index build completed for {} to {} (placeholder implementation)",
-            from, to
+            "Indexed {}: {} documents added, {} indexed of {} listed",
+            date, report.added, report.indexed, report.listed
         ))
     }
 
@@ -270,10 +299,10 @@ impl DatabaseManager {
     }
 
     async fn load_static_data_operation(config: Config, csv_path: String) -> Result<String> {
-        let count = storage::load_edinet_static_data(config.database_path_str(), &csv_path).await
+        let summary = storage::load_edinet_static_data(config.database_path_str(), &csv_path).await
             .map_err(|e| anyhow::anyhow!("Failed to load static data: {}", e))?;
 
-        Ok(format!("Loaded {} static entries from {}", count, csv_path))
+        Ok(format!("Loaded static data from {}: {}", csv_path, summary))
     }
 
     /// Quick database health check
@@ -286,6 +315,7 @@ impl DatabaseManager {
                 status: "Not Found".to_string(),
                 documents_count: 0,
                 static_entries_count: 0,
+                unmapped_edinet_codes: 0,
                 last_updated: None,
                 issues: vec!["Database file does not exist".to_string()],
             });
@@ -293,14 +323,43 @@ impl DatabaseManager {
 
         let mut issues = Vec::new();
 
+        // Check schema version before opening the database migrates it
+        match storage::get_schema_version(db_path).await {
+            Ok(version) if version < storage::SCHEMA_VERSION => issues.push(format!(
+                "Schema version {} is older than {} (will be migrated on next open)",
+                version,
+                storage::SCHEMA_VERSION
+            )),
+            Ok(version) if version > storage::SCHEMA_VERSION => issues.push(format!(
+                "Schema version {} is newer than this build supports ({})",
+                version,
+                storage::SCHEMA_VERSION
+            )),
+            Ok(_) => {}
+            Err(e) => issues.push(format!("Cannot read schema version: {}", e)),
+        }
+
         // Check document count
         let documents_count = storage::count_documents_by_source(&crate::models::Source::Edinet, db_path).await.unwrap_or_else(|e| {
             issues.push(format!("Cannot count documents: {}", e));
             0
         }) as usize;
 
-        // Check static entries count - simplified for now
-        let static_entries_count = 0; // Would implement proper count function
+        // Check static entries count
+        let static_entries_count = storage::count_edinet_static(db_path).await.unwrap_or_else(|e| {
+            issues.push(format!("Cannot count static entries: {}", e));
+            0
+        }) as usize;
+
+        // Check for indexed companies the static data doesn't know about
+        let unmapped_edinet_codes = if static_entries_count > 0 {
+            storage::count_unmapped_edinet_codes(db_path).await.unwrap_or_else(|e| {
+                issues.push(format!("Cannot check static data coverage: {}", e));
+                0
+            }) as usize
+        } else {
+            0
+        };
 
         // Determine overall status
         let status = if issues.is_empty() {
@@ -319,6 +378,7 @@ impl DatabaseManager {
             status,
             documents_count,
             static_entries_count,
+            unmapped_edinet_codes,
             last_updated: None, // Could implement this by tracking in metadata table
             issues,
         })
@@ -331,6 +391,8 @@ pub struct DatabaseHealthStatus {
     pub status: String,
     pub documents_count: usize,
     pub static_entries_count: usize,
+    /// Distinct EDINET codes in indexed documents missing from the static data
+    pub unmapped_edinet_codes: usize,
     pub last_updated: Option<chrono::DateTime<chrono::Local>>,
     pub issues: Vec<String>,
 }
@@ -340,6 +402,16 @@ impl DatabaseHealthStatus {
         self.issues.is_empty() && self.status == "Healthy"
     }
 
+    /// Warning to show when the static data looks older than the indexed filings
+    pub fn stale_static_warning(&self) -> Option<String> {
+        (self.unmapped_edinet_codes > 0).then(|| {
+            format!(
+                "{} indexed EDINET codes are missing from static data; reload it with 'edinet load-static'",
+                self.unmapped_edinet_codes
+            )
+        })
+    }
+
     pub fn summary(&self) -> String {
         format!(
             "Status: {} | Documents: {} | Static: {} | Issues: {}",
@@ -1,16 +1,20 @@
 //! Download manager for handling document downloads
 
 use anyhow::Result;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
 use tokio::task::JoinHandle;
 
 use crate::{
     config::Config,
     models::{Document, DownloadRequest, DocumentFormat, Source},
-    downloader,
+    downloader::{self, DownloaderError},
 };
 
+/// Attempts made for a download that keeps getting rate limited
+const RATE_LIMIT_ATTEMPTS: u32 = 3;
+/// Wait before retrying a rate-limited download, multiplied by the attempt number
+const RATE_LIMIT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Download progress tracking
 #[derive(Debug, Clone)]
 pub struct DownloadProgress {
@@ -18,6 +22,8 @@ pub struct DownloadProgress {
     pub ticker: String,
     pub status: DownloadStatus,
     pub message: String,
+    /// Why the download failed, if it did
+    pub error: Option<String>,
     pub progress_percent: Option<f32>,
     pub started_at: chrono::DateTime<chrono::Local>,
     pub completed_at: Option<chrono::DateTime<chrono::Local>>,
@@ -40,6 +46,7 @@ impl DownloadProgress {
             ticker,
             status: DownloadStatus::Queued,
             message: "Queued for download".to_string(),
+            error: None,
             progress_percent: None,
             started_at: chrono::Local::now(),
             completed_at: None,
@@ -61,6 +68,7 @@ impl DownloadProgress {
     pub fn set_failed(&mut self, error: String) {
         self.status = DownloadStatus::Failed;
         self.message = format!("Failed: {}", error);
+        self.error = Some(error);
         self.completed_at = Some(chrono::Local::now());
     }
 
@@ -83,16 +91,27 @@ impl DownloadProgress {
 pub struct DownloadManager {
     config: Config,
     active_downloads: HashMap<String, DownloadProgress>,
-    download_handles: HashMap<String, JoinHandle<Result<usize>>>,
+    download_handles: HashMap<String, JoinHandle<Result<usize, DownloaderError>>>,
+    /// Documents waiting for a free download slot, in the order they were queued
+    queue: VecDeque<(String, Document)>,
     max_concurrent_downloads: usize,
 }
 
+/// Outcome of queueing a batch of documents
+#[derive(Debug, Default, PartialEq)]
+pub struct QueueSummary {
+    pub queued: usize,
+    /// Already downloaded, or already queued or downloading
+    pub skipped: usize,
+}
+
 impl DownloadManager {
     pub fn new(config: Config) -> Self {
         Self {
             config,
             active_downloads: HashMap::new(),
             download_handles: HashMap::new(),
+            queue: VecDeque::new(),
             max_concurrent_downloads: 3, // Reasonable default
         }
     }
@@ -102,6 +121,14 @@ impl DownloadManager {
         self
     }
 
+    /// Remove files left half-written by interrupted downloads of any source, returning how many
+    pub fn remove_partial_downloads(&self) -> usize {
+        Source::all()
+            .iter()
+            .map(|source| downloader::remove_partial_downloads(&self.config.source_download_dir(source.as_str())))
+            .sum()
+    }
+
     /// Start downloading a document
     pub async fn download_document(&mut self, document: &Document) -> Result<String> {
         let document_id = self.get_document_id(document);
@@ -114,29 +141,68 @@ impl DownloadManager {
         }
 
         // Check concurrent download limit
-        let active_count = self.active_downloads.values()
-            .filter(|p| p.is_active())
-            .count();
-        
-        if active_count >= self.max_concurrent_downloads {
+        if self.download_handles.len() >= self.max_concurrent_downloads {
             return Err(anyhow::anyhow!("Maximum concurrent downloads ({}) reached", self.max_concurrent_downloads));
         }
 
-        // Create progress tracker
+        self.start_download(document_id.clone(), document);
+        Ok(document_id)
+    }
+
+    /// Queue documents for download, skipping ones already on disk or already queued or downloading
+    ///
+    /// Queued documents start as download slots free up; call `update_progress` regularly to advance the queue.
+    pub fn queue_documents(&mut self, documents: &[Document]) -> QueueSummary {
+        let mut summary = QueueSummary::default();
+        for document in documents {
+            let document_id = self.get_document_id(document);
+            if self.is_downloading(&document_id) || self.is_document_downloaded(document) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            self.active_downloads.insert(
+                document_id.clone(),
+                DownloadProgress::new(document_id.clone(), document.ticker.clone()),
+            );
+            self.queue.push_back((document_id, document.clone()));
+            summary.queued += 1;
+        }
+
+        self.start_queued_downloads();
+        summary
+    }
+
+    /// Start queued downloads while there are free download slots
+    fn start_queued_downloads(&mut self) {
+        while self.download_handles.len() < self.max_concurrent_downloads {
+            let Some((document_id, document)) = self.queue.pop_front() else {
+                break;
+            };
+            self.start_download(document_id, &document);
+        }
+    }
+
+    /// Spawn the download task for a document, tracked under `document_id`
+    fn start_download(&mut self, document_id: String, document: &Document) {
         let mut progress = DownloadProgress::new(document_id.clone(), document.ticker.clone());
         progress.set_in_progress(format!("Starting download for {}", document.ticker));
-        
         self.active_downloads.insert(document_id.clone(), progress);
 
-        // Create download request
+        // Create download request; the document ID is an EDINET doc ID or an EDGAR accession number
         let download_request = DownloadRequest {
-            source: Source::Edinet,
+            source: document.source.clone(),
             ticker: document.ticker.clone(),
             filing_type: Some(document.filing_type.clone()),
             date_from: Some(document.date),
             date_to: Some(document.date),
             limit: 1,
             format: DocumentFormat::Complete,
+            document_id: Some(document.id.clone()),
+            skip_existing: false,
+            dry_run: false,
+            include_attachments: false,
+            output_template: None,
         };
 
         // Start async download
@@ -144,12 +210,39 @@ impl DownloadManager {
         let doc_id = document_id.clone();
         
         let handle = tokio::spawn(async move {
-            downloader::download_documents(&download_request, &download_dir).await
+            let mut attempt = 1;
+            loop {
+                match downloader::download_documents(&download_request, &download_dir).await {
+                    Err(DownloaderError::RateLimited(_)) if attempt < RATE_LIMIT_ATTEMPTS => {
+                        tokio::time::sleep(RATE_LIMIT_BACKOFF * attempt).await;
+                        attempt += 1;
+                    }
+                    result => return result.map(|report| report.downloaded),
+                }
+            }
         });
 
-        self.download_handles.insert(document_id.clone(), handle);
+        self.download_handles.insert(document_id, handle);
+    }
 
-        Ok(document_id)
+    /// Wait for a started download to finish, returning the number of documents downloaded
+    pub async fn wait_for_download(&mut self, document_id: &str) -> Result<usize> {
+        let handle = self.download_handles.remove(document_id)
+            .ok_or_else(|| anyhow::anyhow!("No download in progress for {}", document_id))?;
+
+        let result = match handle.await {
+            Ok(download_result) => download_result.map_err(Into::into),
+            Err(e) => Err(anyhow::anyhow!("Download task failed: {}", e)),
+        };
+
+        if let Some(progress) = self.active_downloads.get_mut(document_id) {
+            match &result {
+                Ok(count) => progress.set_completed(format!("Downloaded {} document(s)", count)),
+                Err(e) => progress.set_failed(super::user_message(e)),
+            }
+        }
+
+        result
     }
 
     /// Cancel a download
@@ -157,6 +250,7 @@ impl DownloadManager {
         if let Some(handle) = self.download_handles.remove(document_id) {
             handle.abort();
         }
+        self.queue.retain(|(id, _)| id != document_id);
 
         if let Some(progress) = self.active_downloads.get_mut(document_id) {
             progress.set_cancelled();
@@ -201,7 +295,7 @@ impl DownloadManager {
                             progress.set_completed(format!("Downloaded {} document(s)", count));
                         }
                         Err(e) => {
-                            progress.set_failed(e.to_string());
+                            progress.set_failed(e.user_message());
                         }
                     }
                 }
@@ -215,6 +309,7 @@ impl DownloadManager {
             self.download_handles.remove(&document_id);
         }
 
+        self.start_queued_downloads();
         Ok(())
     }
 
@@ -223,6 +318,18 @@ impl DownloadManager {
         self.active_downloads.get(document_id)
     }
 
+    /// Outcome of a download once it is no longer queued or running: its message, or why it failed
+    pub fn finished_download(&self, document_id: &str) -> Option<Result<String, String>> {
+        let progress = self.active_downloads.get(document_id)?;
+        match progress.status {
+            DownloadStatus::Queued | DownloadStatus::InProgress => None,
+            DownloadStatus::Completed => Some(Ok(progress.message.clone())),
+            DownloadStatus::Failed | DownloadStatus::Cancelled => {
+                Some(Err(progress.error.clone().unwrap_or_else(|| progress.message.clone())))
+            }
+        }
+    }
+
     /// Get all active downloads
     pub fn get_active_downloads(&self) -> Vec<&DownloadProgress> {
         self.active_downloads.values()
@@ -241,6 +348,11 @@ impl DownloadManager {
             .map_or(false, |p| p.is_active())
     }
 
+    /// Check if a document is queued or being downloaded
+    pub fn is_document_downloading(&self, document: &Document) -> bool {
+        self.is_downloading(&self.get_document_id(document))
+    }
+
     /// Check if any downloads are active
     pub fn has_active_downloads(&self) -> bool {
         self.active_downloads.values().any(|p| p.is_active())
@@ -271,30 +383,7 @@ impl DownloadManager {
 
     /// Check if a document is already downloaded locally
     pub fn is_document_downloaded(&self, document: &Document) -> bool {
-        let download_dir = PathBuf::from(self.config.download_dir_str());
-        let edinet_dir = download_dir.join("edinet").join(&document.ticker);
-
-        if !edinet_dir.exists() {
-            return false;
-        }
-
-        // Look for ZIP files that match this document
-        let doc_id = self.get_document_id(document);
-        
-        if let Ok(entries) = std::fs::read_dir(&edinet_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        if filename.contains(&doc_id) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-
-        false
+        super::local_files::find_downloaded_file(&self.config, document).is_some()
     }
 
     /// Generate a unique document ID for tracking
@@ -326,4 +415,29 @@ impl DownloadStats {
             self.completed as f32 / self.total as f32 * 100.0
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_document;
+
+    #[tokio::test]
+    async fn test_queue_skips_downloaded_and_queued_documents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::from_env().unwrap();
+        config.download_dir = temp_dir.path().to_path_buf();
+        let edinet_dir = temp_dir.path().join("edinet").join("7203");
+        std::fs::create_dir_all(&edinet_dir).unwrap();
+        std::fs::write(edinet_dir.join("S100DONE-2024-06-20.zip"), b"zip").unwrap();
+
+        // No free download slots, so everything stays queued and nothing hits the network
+        let mut manager = DownloadManager::new(config).with_max_concurrent(0);
+        let documents = [test_document("S100DONE"), test_document("S100NEW1"), test_document("S100NEW2")];
+        assert_eq!(manager.queue_documents(&documents), QueueSummary { queued: 2, skipped: 1 });
+        assert_eq!(manager.queue_documents(&documents), QueueSummary { queued: 0, skipped: 3 });
+        assert_eq!(manager.get_stats().queued, 2);
+
+        manager.cancel_download("S100NEW1");
+        assert_eq!(manager.queue.len(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+//! Downloaded files of documents from any source
+//!
+//! EDINET documents are ZIPs (or metadata summaries) named after their doc ID, while EDGAR
+//! filings are single files whose names include the accession number without dashes.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::Config,
+    downloader::PARTIAL_DOWNLOAD_SUFFIX,
+    edinet::reader::{find_downloaded_document, list_edinet_sections, read_edinet_document, read_text_file, DocumentSection},
+    models::{Document, Source},
+};
+
+/// Identifier the downloaded files of a document are named after
+pub fn file_key(document: &Document) -> String {
+    match document.source {
+        Source::Edinet => document
+            .metadata
+            .get("doc_id")
+            .or_else(|| document.metadata.get("document_id"))
+            .unwrap_or(&document.id)
+            .clone(),
+        _ => document.id.replace('-', ""),
+    }
+}
+
+/// Directory the downloads of a document's company are saved to
+pub fn company_dir(config: &Config, document: &Document) -> PathBuf {
+    config.source_download_dir(document.source.as_str()).join(&document.ticker)
+}
+
+/// The downloaded file of a document, if any
+pub fn find_downloaded_file(config: &Config, document: &Document) -> Option<PathBuf> {
+    let dir = company_dir(config, document);
+    let key = file_key(document);
+    match document.source {
+        Source::Edinet => find_downloaded_document(&dir, &key),
+        _ => find_downloaded_filing(&dir, &key),
+    }
+}
+
+/// A file named after `key`, preferring full filings over JSON summaries
+fn find_downloaded_filing(dir: &Path, key: &str) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| name.contains(key) && !name.ends_with(PARTIAL_DOWNLOAD_SUFFIX))
+        })
+        .collect();
+    files.sort_by_key(|path| path.extension().is_some_and(|ext| ext == "json"));
+    files.into_iter().next()
+}
+
+/// Sections of a downloaded file; EDINET ZIPs are listed without reading their text
+pub fn list_sections(
+    document: &Document,
+    path: &Path,
+    section_limit: usize,
+    preview_length: usize,
+) -> Result<Vec<DocumentSection>> {
+    match document.source {
+        Source::Edinet => list_edinet_sections(&path.to_string_lossy(), section_limit),
+        _ => read_text_file(path, document.filing_type.as_str(), preview_length),
+    }
+}
+
+/// Sections of a downloaded file with their text read
+pub fn read_sections(
+    document: &Document,
+    path: &Path,
+    section_limit: usize,
+    preview_length: usize,
+) -> Result<Vec<DocumentSection>> {
+    match document.source {
+        Source::Edinet => read_edinet_document(&path.to_string_lossy(), section_limit, preview_length),
+        _ => read_text_file(path, document.filing_type.as_str(), preview_length),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DocumentFormat, FilingType};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_edgar_filing_is_found_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::from_env().unwrap();
+        config.download_dir = dir.path().to_path_buf();
+        config.source_download_dirs = Default::default();
+
+        let document = Document {
+            id: "0000320193-24-000123".to_string(),
+            ticker: "AAPL".to_string(),
+            company_name: "Apple Inc.".to_string(),
+            filing_type: FilingType::TenK,
+            source: Source::Edgar,
+            date: chrono::NaiveDate::from_ymd_opt(2024, 11, 1).unwrap(),
+            content_path: PathBuf::new(),
+            metadata: HashMap::new(),
+            format: DocumentFormat::Html,
+            tags: Vec::new(),
+        };
+        assert!(find_downloaded_file(&config, &document).is_none());
+
+        let company_dir = dir.path().join("edgar").join("AAPL");
+        std::fs::create_dir_all(&company_dir).unwrap();
+        std::fs::write(company_dir.join("10-K-2024-11-01-000032019324000123.json"), "{}").unwrap();
+        std::fs::write(
+            company_dir.join("10-K-2024-11-01-000032019324000123.html"),
+            "<html><body><p>Risk Factors: supply chain</p></body></html>",
+        )
+        .unwrap();
+        std::fs::write(company_dir.join("10-K-2024-11-01-000032019324000999.html"), "other").unwrap();
+
+        let path = find_downloaded_file(&config, &document).unwrap();
+        assert!(path.ends_with("10-K-2024-11-01-000032019324000123.html"));
+
+        let sections = list_sections(&document, &path, 10, 1000).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].section_type, "10-K");
+        assert!(sections[0].loaded && sections[0].content.contains("supply chain"));
+    }
+}
@@ -6,7 +6,22 @@
 pub mod download_manager;
 pub mod content_loader;
 pub mod database_manager;
+pub mod local_files;
 
-pub use download_manager::{DownloadManager, DownloadProgress, DownloadStatus};
-pub use content_loader::{ContentLoader, ContentCache};
-pub use database_manager::{DatabaseManager, DatabaseOperation};
\ No newline at end of file
+pub use download_manager::{DownloadManager, DownloadProgress, DownloadStats, DownloadStatus, QueueSummary};
+pub use content_loader::{ContentLoader, ContentCache, ContentCacheStats};
+pub use database_manager::{DatabaseHealthStatus, DatabaseManager, DatabaseOperation};
+
+use crate::{downloader::DownloaderError, edinet::EdinetError};
+
+/// Plain-language message for a failed operation, using the guidance of an EDINET or
+/// downloader error when the failure came from one
+pub fn user_message(err: &anyhow::Error) -> String {
+    if let Some(err) = err.downcast_ref::<EdinetError>() {
+        err.user_message()
+    } else if let Some(err) = err.downcast_ref::<DownloaderError>() {
+        err.user_message()
+    } else {
+        err.to_string()
+    }
+}
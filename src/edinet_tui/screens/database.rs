@@ -5,7 +5,7 @@ use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Gauge},
     Frame,
@@ -14,10 +14,11 @@ use chrono::{NaiveDate, Local};
 
 use crate::{
     config::Config,
-    edinet_indexer,
+    edinet::EdinetIndexFilter,
     storage,
     models::Source,
-    edinet_tui::ui::{Styles, InputField},
+    edinet_tui::{app::Screen, components::StatusDisplay, traits, ui::{Styles, InputField}},
+    edinet_tui::operations::DatabaseOperation as IndexOperation,
 };
 
 /// Database management operations
@@ -26,15 +27,22 @@ pub enum DatabaseOperation {
     ShowStats,
     UpdateIndex,
     BuildIndex,
+    IndexDate,
+    LoadStaticData,
     ClearIndex,
 }
 
+/// Default location of EDINET's code list, as used in the README
+const DEFAULT_STATIC_CSV_PATH: &str = "static/EdinetcodeDlInfo.csv";
+
 impl DatabaseOperation {
     pub fn as_str(&self) -> &str {
         match self {
             DatabaseOperation::ShowStats => "Show Statistics",
             DatabaseOperation::UpdateIndex => "Update Index (last 7 days)",
             DatabaseOperation::BuildIndex => "Build Index (date range)",
+            DatabaseOperation::IndexDate => "Index Single Date",
+            DatabaseOperation::LoadStaticData => "Load Static Data",
             DatabaseOperation::ClearIndex => "Clear/Rebuild Index",
         }
     }
@@ -44,6 +52,8 @@ impl DatabaseOperation {
             DatabaseOperation::ShowStats => "Display current index statistics and status",
             DatabaseOperation::UpdateIndex => "Update index with recent documents",
             DatabaseOperation::BuildIndex => "Build index for a specific date range",
+            DatabaseOperation::IndexDate => "Fetch one date again, e.g. one that failed during a build",
+            DatabaseOperation::LoadStaticData => "Load company names and codes from EdinetcodeDlInfo.csv (needed for downloads)",
             DatabaseOperation::ClearIndex => "Clear all data and rebuild from scratch",
         }
     }
@@ -53,11 +63,30 @@ impl DatabaseOperation {
             DatabaseOperation::ShowStats => 's',
             DatabaseOperation::UpdateIndex => 'u',
             DatabaseOperation::BuildIndex => 'b',
+            DatabaseOperation::IndexDate => 'i',
+            DatabaseOperation::LoadStaticData => 'l',
             DatabaseOperation::ClearIndex => 'c',
         }
     }
 }
 
+/// Form shown while the screen is in input mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputForm {
+    BuildIndex,
+    IndexDate,
+    LoadStaticData,
+}
+
+impl InputForm {
+    fn field_count(&self) -> usize {
+        match self {
+            InputForm::BuildIndex => 3,
+            InputForm::IndexDate | InputForm::LoadStaticData => 1,
+        }
+    }
+}
+
 /// Current database statistics
 #[derive(Debug, Clone, Default)]
 pub struct DatabaseStats {
@@ -75,13 +104,17 @@ pub struct DatabaseScreen {
     pub operations: Vec<DatabaseOperation>,
     pub stats: DatabaseStats,
     pub is_loading: bool,
-    pub current_operation: Option<String>,
+    pub operation_status: StatusDisplay,
     pub progress: Option<f64>,
     
-    // For build index date range input
+    // For build index date range and static data path input
     pub input_mode: bool,
+    pub input_form: InputForm,
     pub from_date_input: InputField,
     pub to_date_input: InputField,
+    pub doc_types_input: InputField,
+    pub index_date_input: InputField,
+    pub csv_path_input: InputField,
     pub current_input_field: usize,
 }
 
@@ -91,6 +124,8 @@ impl DatabaseScreen {
             DatabaseOperation::ShowStats,
             DatabaseOperation::UpdateIndex,
             DatabaseOperation::BuildIndex,
+            DatabaseOperation::IndexDate,
+            DatabaseOperation::LoadStaticData,
             DatabaseOperation::ClearIndex,
         ];
 
@@ -103,70 +138,69 @@ impl DatabaseScreen {
             operations,
             stats: DatabaseStats::default(),
             is_loading: false,
-            current_operation: None,
+            operation_status: StatusDisplay::new(),
             progress: None,
             input_mode: false,
+            input_form: InputForm::BuildIndex,
             from_date_input: InputField::new("From Date (YYYY-MM-DD)")
                 .with_placeholder("2024-01-01"),
             to_date_input: InputField::new("To Date (YYYY-MM-DD)")
                 .with_placeholder(&Local::now().format("%Y-%m-%d").to_string()),
+            doc_types_input: InputField::new("Doc Type Codes (optional, e.g. 120,140)")
+                .with_placeholder("all"),
+            index_date_input: InputField::new("Date (YYYY-MM-DD)")
+                .with_placeholder(&Local::now().format("%Y-%m-%d").to_string()),
+            csv_path_input: InputField::new("EDINET Code List CSV (EdinetcodeDlInfo.csv)")
+                .with_value(DEFAULT_STATIC_CSV_PATH),
             current_input_field: 0,
         }
     }
 
-    /// Handle key events for the database screen
-    pub async fn handle_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) -> Result<()> {
-        if self.input_mode {
-            return self.handle_input_mode_event(key, app).await;
-        }
+    /// Open a form, focusing its first field
+    fn open_input_form(&mut self, form: InputForm) {
+        self.input_mode = true;
+        self.input_form = form;
+        self.current_input_field = 0;
+        self.update_input_focus();
+    }
 
-        match key.code {
-            KeyCode::Up => {
-                let selected = self.operation_state.selected().unwrap_or(0);
-                let new_selected = if selected == 0 {
-                    self.operations.len() - 1
-                } else {
-                    selected - 1
-                };
-                self.operation_state.select(Some(new_selected));
-            }
-            KeyCode::Down => {
-                let selected = self.operation_state.selected().unwrap_or(0);
-                let new_selected = (selected + 1) % self.operations.len();
-                self.operation_state.select(Some(new_selected));
-            }
-            KeyCode::Enter => {
-                if let Some(selected) = self.operation_state.selected() {
-                    if let Some(operation) = self.operations.get(selected) {
-                        self.execute_operation(operation.clone(), app).await?;
-                    }
-                }
-            }
-            KeyCode::Char(c) => {
-                // Handle shortcut keys
-                for operation in &self.operations {
-                    if operation.shortcut() == c {
-                        self.execute_operation(operation.clone(), app).await?;
-                        break;
-                    }
-                }
-            }
-            _ => {}
-        }
-        Ok(())
+    /// Ask for the static data CSV path; used by the Database screen and the main menu
+    pub fn open_static_data_form(&mut self) {
+        self.open_input_form(InputForm::LoadStaticData);
     }
 
-    /// Handle input mode events for date range input
+    /// Handle input mode events for the date range and static data forms
     async fn handle_input_mode_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) -> Result<()> {
+        let field_count = self.input_form.field_count();
         match key.code {
             KeyCode::Tab => {
-                self.current_input_field = (self.current_input_field + 1) % 2;
+                self.current_input_field = (self.current_input_field + 1) % field_count;
                 self.update_input_focus();
             }
             KeyCode::BackTab => {
-                self.current_input_field = if self.current_input_field == 0 { 1 } else { 0 };
+                self.current_input_field = (self.current_input_field + field_count - 1) % field_count;
                 self.update_input_focus();
             }
+            KeyCode::Enter if self.input_form == InputForm::LoadStaticData => {
+                let csv_path = self.csv_path_input.value.trim().to_string();
+                if std::path::Path::new(&csv_path).is_file() {
+                    self.input_mode = false;
+                    self.update_input_focus();
+                    self.execute_load_static_data(csv_path, app).await?;
+                } else {
+                    app.set_error(format!("CSV file not found: {}", csv_path));
+                }
+            }
+            KeyCode::Enter if self.input_form == InputForm::IndexDate => {
+                match NaiveDate::parse_from_str(self.index_date_input.value.trim(), "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.input_mode = false;
+                        self.update_input_focus();
+                        self.execute_index_date(date, app).await?;
+                    }
+                    Err(_) => app.set_error("Invalid date format. Please use YYYY-MM-DD".to_string()),
+                }
+            }
             KeyCode::Enter => {
                 // Validate and execute build index
                 if let (Ok(from_date), Ok(to_date)) = (
@@ -174,7 +208,8 @@ impl DatabaseScreen {
                     NaiveDate::parse_from_str(&self.to_date_input.value, "%Y-%m-%d"),
                 ) {
                     self.input_mode = false;
-                    self.execute_build_index(from_date, to_date, app).await?;
+                    let filter = EdinetIndexFilter::from_code_lists(Some(&self.doc_types_input.value), None);
+                    self.execute_build_index(from_date, to_date, &filter, app).await?;
                 } else {
                     app.set_error("Invalid date format. Please use YYYY-MM-DD".to_string());
                 }
@@ -210,20 +245,34 @@ impl DatabaseScreen {
     }
 
     fn update_input_focus(&mut self) {
-        self.from_date_input.set_focus(self.current_input_field == 0 && self.input_mode);
-        self.to_date_input.set_focus(self.current_input_field == 1 && self.input_mode);
+        let building = self.input_mode && self.input_form == InputForm::BuildIndex;
+        self.from_date_input.set_focus(self.current_input_field == 0 && building);
+        self.to_date_input.set_focus(self.current_input_field == 1 && building);
+        self.doc_types_input.set_focus(self.current_input_field == 2 && building);
+        self.index_date_input.set_focus(self.input_mode && self.input_form == InputForm::IndexDate);
+        self.csv_path_input.set_focus(self.input_mode && self.input_form == InputForm::LoadStaticData);
     }
 
     fn get_current_input_field(&mut self) -> &mut InputField {
+        match self.input_form {
+            InputForm::LoadStaticData => return &mut self.csv_path_input,
+            InputForm::IndexDate => return &mut self.index_date_input,
+            InputForm::BuildIndex => {}
+        }
         match self.current_input_field {
             0 => &mut self.from_date_input,
             1 => &mut self.to_date_input,
+            2 => &mut self.doc_types_input,
             _ => &mut self.from_date_input,
         }
     }
 
     /// Execute a database operation
     async fn execute_operation(&mut self, operation: DatabaseOperation, app: &mut super::super::app::App) -> Result<()> {
+        if app.database_manager.is_operation_in_progress() {
+            app.set_error("Indexing is still running; wait for it to finish".to_string());
+            return Ok(());
+        }
         match operation {
             DatabaseOperation::ShowStats => {
                 self.refresh_stats(app).await?;
@@ -232,11 +281,17 @@ impl DatabaseScreen {
                 self.execute_update_index(app).await?;
             }
             DatabaseOperation::BuildIndex => {
-                self.input_mode = true;
-                self.current_input_field = 0;
-                self.update_input_focus();
+                self.open_input_form(InputForm::BuildIndex);
                 app.set_status("Enter date range for index build".to_string());
             }
+            DatabaseOperation::IndexDate => {
+                self.open_input_form(InputForm::IndexDate);
+                app.set_status("Enter the date to index again".to_string());
+            }
+            DatabaseOperation::LoadStaticData => {
+                self.open_static_data_form();
+                app.set_status("Enter the path of EdinetcodeDlInfo.csv".to_string());
+            }
             DatabaseOperation::ClearIndex => {
                 self.execute_clear_index(app).await?;
             }
@@ -274,75 +329,102 @@ impl DatabaseScreen {
         Ok(())
     }
 
-    /// Execute index update
+    /// Start an index update in the background
     async fn execute_update_index(&mut self, app: &mut super::super::app::App) -> Result<()> {
         self.is_loading = true;
-        self.current_operation = Some("Updating index...".to_string());
+        self.operation_status.set_loading("Updating index...".to_string());
         
-        app.set_status("Updating EDINET index...".to_string());
+        app.set_loading("Updating EDINET index...".to_string());
         
-        match edinet_indexer::update_edinet_index(self.config.database_path_str(), 7).await {
-            Ok(count) => {
-                app.set_status(format!("Successfully updated index with {} documents", count));
-                self.refresh_stats(app).await?;
-            }
-            Err(e) => {
-                app.set_error(format!("Index update failed: {}", e));
-            }
-        }
+        self.start_index_operation(IndexOperation::UpdateIndex, app).await;
+        Ok(())
+    }
+
+    /// Start building the index for a date range in the background
+    async fn execute_build_index(
+        &mut self,
+        from_date: NaiveDate,
+        to_date: NaiveDate,
+        filter: &EdinetIndexFilter,
+        app: &mut super::super::app::App,
+    ) -> Result<()> {
+        self.is_loading = true;
+        self.operation_status.set_loading(format!("Building index from {} to {}...", from_date, to_date));
         
-        self.is_loading = false;
-        self.current_operation = None;
+        app.set_loading("Building EDINET index...".to_string());
+        
+        let operation = IndexOperation::BuildIndex { from: from_date, to: to_date, filter: filter.clone() };
+        self.start_index_operation(operation, app).await;
         Ok(())
     }
 
-    /// Execute build index for date range
-    async fn execute_build_index(&mut self, from_date: NaiveDate, to_date: NaiveDate, app: &mut super::super::app::App) -> Result<()> {
+    /// Start indexing a single date in the background
+    async fn execute_index_date(&mut self, date: NaiveDate, app: &mut super::super::app::App) -> Result<()> {
         self.is_loading = true;
-        self.current_operation = Some(format!("Building index from {} to {}...", from_date, to_date));
+        self.operation_status.set_loading(format!("Indexing {}...", date));
         
-        app.set_status("Building EDINET index...".to_string());
+        app.set_loading(format!("Indexing EDINET documents for {}...", date));
         
-        match edinet_indexer::build_edinet_index_by_date(self.config.database_path_str(), from_date, to_date).await {
-            Ok(count) => {
-                app.set_status(format!("Successfully indexed {} documents", count));
-                self.refresh_stats(app).await?;
-            }
-            Err(e) => {
-                app.set_error(format!("Index build failed: {}", e));
-            }
+        self.start_index_operation(IndexOperation::IndexDate { date }, app).await;
+        Ok(())
+    }
+
+    /// Start loading EDINET static data from a CSV file in the background
+    async fn execute_load_static_data(&mut self, csv_path: String, app: &mut super::super::app::App) -> Result<()> {
+        self.is_loading = true;
+        self.operation_status.set_loading(format!("Loading static data from {}...", csv_path));
+        
+        app.set_loading("Loading EDINET static data...".to_string());
+        
+        self.start_index_operation(IndexOperation::LoadStaticData { csv_path }, app).await;
+        Ok(())
+    }
+
+    /// Hand an index operation to the app's database manager, which runs it in the background
+    async fn start_index_operation(&mut self, operation: IndexOperation, app: &mut super::super::app::App) {
+        if let Err(e) = app.database_manager.start_operation(operation).await {
+            self.is_loading = false;
+            self.operation_status.clear();
+            app.set_error(format!("Failed to start operation: {}", e));
         }
+    }
+
+    /// Report the background index update or build once it has finished
+    async fn check_index_task(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        let Some(result) = app.database_manager.take_finished_operation() else {
+            return Ok(());
+        };
         
         self.is_loading = false;
-        self.current_operation = None;
+        self.operation_status.clear();
+        match result {
+            Ok(message) => {
+                // Refresh the statistics first so their status message does not replace the outcome
+                app.setup_hint = None;
+                self.refresh_stats(app).await?;
+                app.set_success(message);
+            }
+            Err(e) => app.set_error(e),
+        }
         Ok(())
     }
 
     /// Execute clear index
     async fn execute_clear_index(&mut self, app: &mut super::super::app::App) -> Result<()> {
         self.is_loading = true;
-        self.current_operation = Some("Clearing index...".to_string());
+        self.operation_status.set_loading("Clearing index...".to_string());
         
-        app.set_status("Clearing EDINET index...".to_string());
+        app.set_loading("Clearing EDINET index...".to_string());
         
         // For now, we'll just show a message. In a real implementation,
         // you'd want to add a confirmation dialog and actual clear functionality
         app.set_status("Clear index functionality not implemented yet".to_string());
         
         self.is_loading = false;
-        self.current_operation = None;
+        self.operation_status.clear();
         Ok(())
     }
 
-    /// Draw the database management screen
-    pub fn draw(&mut self, f: &mut Frame, area: Rect) {
-        if self.input_mode {
-            self.draw_input_mode(f, area);
-        } else {
-            self.draw_normal_mode(f, area);
-        }
-    }
-
     fn draw_normal_mode(&mut self, f: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -362,13 +444,72 @@ impl DatabaseScreen {
         self.draw_status(f, right_chunks[1]);
     }
 
+    fn draw_index_date_input(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Title
+                Constraint::Length(3),  // Date
+                Constraint::Length(3),  // Instructions
+                Constraint::Min(0),     // Statistics (smaller)
+            ])
+            .split(area);
+
+        let title = Paragraph::new("Index Single Date")
+            .style(Styles::title())
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(title, chunks[0]);
+
+        self.index_date_input.render(f, chunks[1]);
+
+        let instructions = Paragraph::new("Enter: Index | Esc: Cancel | Documents already indexed for the date are refreshed")
+            .style(Styles::info())
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(instructions, chunks[2]);
+
+        self.draw_statistics(f, chunks[3]);
+    }
+
+    fn draw_static_data_input(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Title
+                Constraint::Length(3),  // CSV path
+                Constraint::Length(3),  // Instructions
+                Constraint::Min(0),     // Statistics (smaller)
+            ])
+            .split(area);
+
+        let title = Paragraph::new("Load Static Data - EDINET Code List")
+            .style(Styles::title())
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(title, chunks[0]);
+
+        self.csv_path_input.render(f, chunks[1]);
+
+        let instructions = Paragraph::new("Enter: Load | Esc: Cancel | Download the CSV from the EDINET site (EDINETコードリスト)")
+            .style(Styles::info())
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(instructions, chunks[2]);
+
+        self.draw_statistics(f, chunks[3]);
+    }
+
     fn draw_input_mode(&mut self, f: &mut Frame, area: Rect) {
+        match self.input_form {
+            InputForm::LoadStaticData => return self.draw_static_data_input(f, area),
+            InputForm::IndexDate => return self.draw_index_date_input(f, area),
+            InputForm::BuildIndex => {}
+        }
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),  // Title
                 Constraint::Length(3),  // From date
                 Constraint::Length(3),  // To date
+                Constraint::Length(3),  // Doc type codes
                 Constraint::Length(3),  // Instructions
                 Constraint::Min(0),     // Statistics (smaller)
             ])
@@ -383,15 +524,16 @@ impl DatabaseScreen {
         // Input fields
         self.from_date_input.render(f, chunks[1]);
         self.to_date_input.render(f, chunks[2]);
+        self.doc_types_input.render(f, chunks[3]);
 
         // Instructions
         let instructions = Paragraph::new("Tab: Next field | Enter: Build | Esc: Cancel")
             .style(Styles::info())
             .block(Block::default().borders(Borders::ALL));
-        f.render_widget(instructions, chunks[3]);
+        f.render_widget(instructions, chunks[4]);
 
         // Statistics (smaller)
-        self.draw_statistics(f, chunks[4]);
+        self.draw_statistics(f, chunks[5]);
     }
 
     fn draw_operations(&mut self, f: &mut Frame, area: Rect) {
@@ -466,18 +608,7 @@ impl DatabaseScreen {
 
     fn draw_status(&self, f: &mut Frame, area: Rect) {
         if self.is_loading {
-            let status_text = self.current_operation
-                .as_ref()
-                .map(|s| s.as_str())
-                .unwrap_or("Working...");
-            
-            let status = Paragraph::new(status_text)
-                .style(Styles::info())
-                .block(Block::default()
-                    .title("Status")
-                    .borders(Borders::ALL));
-            
-            f.render_widget(status, area);
+            self.operation_status.render_with_title(f, area, "Status");
             
             // Show progress bar if available
             if let Some(progress) = self.progress {
@@ -494,7 +625,7 @@ impl DatabaseScreen {
         } else {
             let instructions = vec![
                 Line::from("↑/↓: Navigate | Enter: Execute"),
-                Line::from("s/u/b/c: Direct shortcuts"),
+                Line::from("s/u/b/l/c: Direct shortcuts"),
             ];
 
             let help = Paragraph::new(instructions)
@@ -507,4 +638,82 @@ impl DatabaseScreen {
             f.render_widget(help, area);
         }
     }
-}
\ No newline at end of file
+}
+
+#[async_trait::async_trait(?Send)]
+impl traits::Screen for DatabaseScreen {
+    /// Draw the database management screen
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        if self.input_mode {
+            self.draw_input_mode(f, area);
+        } else {
+            self.draw_normal_mode(f, area);
+        }
+    }
+
+    /// Handle key events for the database screen
+    async fn handle_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) -> Result<()> {
+        if self.input_mode {
+            return self.handle_input_mode_event(key, app).await;
+        }
+
+        match key.code {
+            KeyCode::Up => {
+                let selected = self.operation_state.selected().unwrap_or(0);
+                let new_selected = if selected == 0 {
+                    self.operations.len() - 1
+                } else {
+                    selected - 1
+                };
+                self.operation_state.select(Some(new_selected));
+            }
+            KeyCode::Down => {
+                let selected = self.operation_state.selected().unwrap_or(0);
+                let new_selected = (selected + 1) % self.operations.len();
+                self.operation_state.select(Some(new_selected));
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.operation_state.selected() {
+                    if let Some(operation) = self.operations.get(selected) {
+                        self.execute_operation(operation.clone(), app).await?;
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                // Database screen: ESC goes back to Main Menu
+                app.navigate_to_screen(Screen::MainMenu);
+            }
+            KeyCode::Char(c) => {
+                // Handle shortcut keys
+                for operation in &self.operations {
+                    if operation.shortcut() == c {
+                        self.execute_operation(operation.clone(), app).await?;
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn on_tick(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        self.check_index_task(app).await
+    }
+
+    fn title(&self) -> &str {
+        "Database Management"
+    }
+
+    fn help(&self) -> &str {
+        "Database Management:\n\
+        ↑/↓ - Navigate options\n\
+        Enter - Execute action\n\
+        s - Show statistics\n\
+        u - Update index\n\
+        b - Build index (date range)\n\
+        i - Index a single date again\n\
+        l - Load static data (EDINET code list CSV)\n\
+        c - Clear/rebuild index"
+    }
+}
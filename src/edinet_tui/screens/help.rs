@@ -11,7 +11,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::edinet_tui::ui::Styles;
+use crate::edinet_tui::{app::Screen, traits, ui::Styles};
 
 /// Help sections
 #[derive(Debug, Clone, PartialEq)]
@@ -70,41 +70,6 @@ impl HelpScreen {
         }
     }
 
-    /// Handle key events for the help screen
-    pub async fn handle_event(
-        &mut self,
-        key: KeyEvent,
-        _app: &mut super::super::app::App,
-    ) -> Result<()> {
-        match key.code {
-            KeyCode::Up => {
-                if self.current_section > 0 {
-                    self.current_section -= 1;
-                    self.section_state.select(Some(self.current_section));
-                    self.scroll_offset = 0;
-                }
-            }
-            KeyCode::Down => {
-                if self.current_section < self.sections.len() - 1 {
-                    self.current_section += 1;
-                    self.section_state.select(Some(self.current_section));
-                    self.scroll_offset = 0;
-                }
-            }
-            KeyCode::PageUp => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(10);
-            }
-            KeyCode::PageDown => {
-                self.scroll_offset += 10;
-            }
-            KeyCode::Home => {
-                self.scroll_offset = 0;
-            }
-            _ => {}
-        }
-        Ok(())
-    }
-
     /// Get content for current section
     fn get_section_content(&self) -> Vec<Line> {
         match self.sections[self.current_section] {
@@ -152,6 +117,7 @@ impl HelpScreen {
             Line::from("• ESC - Go back to previous screen or main menu"),
             Line::from("• q - Quit application from anywhere"),
             Line::from("• F1 or ? - Toggle help popup"),
+            Line::from("• F12 - Toggle debug panel (cache, downloads, database health)"),
             Line::from(""),
             Line::from(Span::styled("Screen Navigation:", Styles::info())),
             Line::from("• Arrow keys (↑/↓) - Navigate lists and menus"),
@@ -206,6 +172,7 @@ impl HelpScreen {
             Line::from("• Ticker Symbol - Company stock symbol (e.g., 7203, 6758)"),
             Line::from("• Company Name - Full or partial company name"),
             Line::from("• Filing Type - Document type (annual, quarterly, etc.)"),
+            Line::from("• Source - EDINET, EDGAR, or Any to merge both by date (←/→ to change)"),
             Line::from("• Date From/To - Date range (YYYY-MM-DD format)"),
             Line::from("• Text Search - Search within document content"),
             Line::from(""),
@@ -234,10 +201,13 @@ impl HelpScreen {
             Line::from("• ↑/↓ - Navigate through documents"),
             Line::from("• Page Up/Down - Navigate pages"),
             Line::from("• Home/End - Go to first/last page"),
+            Line::from("• +/- - Increase/decrease page size"),
             Line::from(""),
             Line::from(Span::styled("Actions:", Styles::info())),
             Line::from("• Enter or v - View selected document"),
             Line::from("• d - Download selected document"),
+            Line::from("• D - Download every result in the background (progress in the F12 debug panel)"),
+            Line::from("• e/E - Export results to CSV/JSON"),
             Line::from("• / - Start new search"),
             Line::from("• r - Refresh current search"),
             Line::from(""),
@@ -297,6 +267,7 @@ impl HelpScreen {
             Line::from("│ ESC         │ Go back / Main menu             │"),
             Line::from("│ q           │ Quit application                │"),
             Line::from("│ F1 or ?     │ Toggle help popup               │"),
+            Line::from("│ F12         │ Toggle debug panel              │"),
             Line::from("└─────────────┴─────────────────────────────────┘"),
             Line::from(""),
             Line::from(Span::styled("Main Menu:", Styles::info())),
@@ -327,20 +298,6 @@ impl HelpScreen {
         ]
     }
 
-    /// Draw the help screen
-    pub fn draw(&mut self, f: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
-            .split(area);
-
-        // Draw section list
-        self.draw_section_list(f, chunks[0]);
-
-        // Draw content
-        self.draw_content(f, chunks[1]);
-    }
-
     fn draw_section_list(&mut self, f: &mut Frame, area: Rect) {
         let items: Vec<ListItem> = self
             .sections
@@ -390,3 +347,65 @@ impl HelpScreen {
     }
 }
 
+#[async_trait::async_trait(?Send)]
+impl traits::Screen for HelpScreen {
+    /// Draw the help screen
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
+            .split(area);
+
+        // Draw section list
+        self.draw_section_list(f, chunks[0]);
+
+        // Draw content
+        self.draw_content(f, chunks[1]);
+    }
+
+    /// Handle key events for the help screen
+    async fn handle_event(
+        &mut self,
+        key: KeyEvent,
+        app: &mut super::super::app::App,
+    ) -> Result<()> {
+        match key.code {
+            KeyCode::Up if self.current_section > 0 => {
+                self.current_section -= 1;
+                self.section_state.select(Some(self.current_section));
+                self.scroll_offset = 0;
+            }
+            KeyCode::Down if self.current_section < self.sections.len() - 1 => {
+                self.current_section += 1;
+                self.section_state.select(Some(self.current_section));
+                self.scroll_offset = 0;
+            }
+            KeyCode::PageUp => {
+                self.scroll_offset = self.scroll_offset.saturating_sub(10);
+            }
+            KeyCode::PageDown => {
+                self.scroll_offset += 10;
+            }
+            KeyCode::Home => {
+                self.scroll_offset = 0;
+            }
+            KeyCode::Esc => {
+                // Help screen: ESC goes back to Main Menu
+                app.navigate_to_screen(Screen::MainMenu);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn title(&self) -> &str {
+        "Help"
+    }
+
+    fn help(&self) -> &str {
+        "Help Screen:\n\
+        ↑/↓ - Scroll help content\n\
+        Tab - Switch help sections"
+    }
+}
+
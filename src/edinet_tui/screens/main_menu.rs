@@ -11,7 +11,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::edinet_tui::{app::Screen, ui::Styles};
+use crate::edinet_tui::{app::Screen, traits, ui::Styles};
 
 /// Main menu options
 #[derive(Debug, Clone)]
@@ -20,6 +20,8 @@ pub struct MenuOption {
     pub description: String,
     pub shortcut: char,
     pub screen: Screen,
+    /// Open the Database screen's static data form on arrival
+    pub loads_static_data: bool,
 }
 
 impl MenuOption {
@@ -29,6 +31,22 @@ impl MenuOption {
             description: description.to_string(),
             shortcut,
             screen,
+            loads_static_data: false,
+        }
+    }
+
+    pub fn with_static_data_form(mut self) -> Self {
+        self.loads_static_data = true;
+        self
+    }
+
+    /// Go to this option's screen
+    fn select(&self, app: &mut super::super::app::App) {
+        app.navigate_to_screen(self.screen.clone());
+        if self.loads_static_data {
+            if let Some(database) = app.screen_mut::<super::DatabaseScreen>() {
+                database.open_static_data_form();
+            }
         }
     }
 }
@@ -54,6 +72,13 @@ impl MainMenuScreen {
                 'D',
                 Screen::Database,
             ),
+            MenuOption::new(
+                "Load Static Data",
+                "Load EDINET company codes from EdinetcodeDlInfo.csv, needed before downloading",
+                'L',
+                Screen::Database,
+            )
+            .with_static_data_form(),
             MenuOption::new(
                 "Help",
                 "View help and keyboard shortcuts",
@@ -71,74 +96,6 @@ impl MainMenuScreen {
         }
     }
 
-    /// Handle key events for the main menu
-    pub async fn handle_event(
-        &mut self,
-        key: KeyEvent,
-        app: &mut super::super::app::App,
-    ) -> Result<()> {
-        match key.code {
-            KeyCode::Up => {
-                let selected = self.menu_state.selected().unwrap_or(0);
-                let new_selected = if selected == 0 {
-                    self.menu_options.len() - 1
-                } else {
-                    selected - 1
-                };
-                self.menu_state.select(Some(new_selected));
-            }
-            KeyCode::Down => {
-                let selected = self.menu_state.selected().unwrap_or(0);
-                let new_selected = (selected + 1) % self.menu_options.len();
-                self.menu_state.select(Some(new_selected));
-            }
-            KeyCode::Enter => {
-                if let Some(selected) = self.menu_state.selected() {
-                    if let Some(option) = self.menu_options.get(selected) {
-                        app.navigate_to_screen(option.screen.clone());
-                    }
-                }
-            }
-            KeyCode::Char('q') => {
-                app.should_quit = true;
-            }
-            KeyCode::Char(c) => {
-                // Handle shortcut keys (case insensitive)
-                let upper_c = c.to_ascii_uppercase();
-                for option in &self.menu_options {
-                    if option.shortcut == upper_c || option.shortcut == c {
-                        app.navigate_to_screen(option.screen.clone());
-                        break;
-                    }
-                }
-            }
-            _ => {}
-        }
-        Ok(())
-    }
-
-    /// Draw the main menu screen
-    pub fn draw(&mut self, f: &mut Frame, area: Rect) {
-        // Create layout: title at top, menu in center, instructions at bottom
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Min(0),    // Menu
-                Constraint::Length(6), // Instructions
-            ])
-            .split(area);
-
-        // Draw title
-        self.draw_title(f, chunks[0]);
-
-        // Draw menu
-        self.draw_menu(f, chunks[1]);
-
-        // Draw instructions
-        self.draw_instructions(f, chunks[2]);
-    }
-
     fn draw_title(&self, f: &mut Frame, area: Rect) {
         let title = Paragraph::new("EDINET Document Manager")
             .style(Styles::title().add_modifier(Modifier::BOLD))
@@ -199,7 +156,7 @@ impl MainMenuScreen {
             ]),
             Line::from(vec![
                 Span::styled("Shortcuts: ", Styles::info()),
-                Span::styled("S/D/H", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled("S/D/L/H", Style::default().add_modifier(Modifier::BOLD)),
                 Span::raw(" for direct access, "),
                 Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
                 Span::raw(" to quit"),
@@ -224,3 +181,89 @@ impl MainMenuScreen {
     }
 }
 
+#[async_trait::async_trait(?Send)]
+impl traits::Screen for MainMenuScreen {
+    /// Draw the main menu screen
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        // Create layout: title at top, menu in center, instructions at bottom
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Min(0),    // Menu
+                Constraint::Length(6), // Instructions
+            ])
+            .split(area);
+
+        // Draw title
+        self.draw_title(f, chunks[0]);
+
+        // Draw menu
+        self.draw_menu(f, chunks[1]);
+
+        // Draw instructions
+        self.draw_instructions(f, chunks[2]);
+    }
+
+    /// Handle key events for the main menu
+    async fn handle_event(
+        &mut self,
+        key: KeyEvent,
+        app: &mut super::super::app::App,
+    ) -> Result<()> {
+        match key.code {
+            KeyCode::Up => {
+                let selected = self.menu_state.selected().unwrap_or(0);
+                let new_selected = if selected == 0 {
+                    self.menu_options.len() - 1
+                } else {
+                    selected - 1
+                };
+                self.menu_state.select(Some(new_selected));
+            }
+            KeyCode::Down => {
+                let selected = self.menu_state.selected().unwrap_or(0);
+                let new_selected = (selected + 1) % self.menu_options.len();
+                self.menu_state.select(Some(new_selected));
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.menu_state.selected() {
+                    if let Some(option) = self.menu_options.get(selected) {
+                        option.select(app);
+                    }
+                }
+            }
+            KeyCode::Char('q') => {
+                app.should_quit = true;
+            }
+            KeyCode::Char(c) => {
+                // Handle shortcut keys (case insensitive)
+                let upper_c = c.to_ascii_uppercase();
+                for option in &self.menu_options {
+                    if option.shortcut == upper_c || option.shortcut == c {
+                        option.select(app);
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn title(&self) -> &str {
+        "Main Menu"
+    }
+
+    fn help(&self) -> &str {
+        "Main Menu:\n\
+        ↑/↓ - Navigate menu\n\
+        Enter - Select option\n\
+        S/s - Search Documents\n\
+        D/d - Database Management\n\
+        L/l - Load Static Data\n\
+        H/h - Help\n\
+        q - Quit"
+    }
+}
+
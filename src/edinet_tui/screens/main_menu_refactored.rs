@@ -20,7 +20,7 @@ use crate::edinet_tui::{
         status_display::StatusDisplay,
     },
     handlers::{CommonKeyHandler, MenuHandler},
-    traits::{Navigable, Screen, ScreenAction},
+    traits::{ActionScreen, Navigable, ScreenAction},
     ui::Styles,
 };
 
@@ -141,7 +141,7 @@ impl MainMenuScreenRefactored {
     }
 }
 
-impl Screen for MainMenuScreenRefactored {
+impl ActionScreen for MainMenuScreenRefactored {
     fn draw(&mut self, f: &mut Frame, area: Rect) {
         // Create layout: title at top, menu in center, instructions at bottom
         let chunks = Layout::default()
@@ -5,313 +5,437 @@ use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
-use unicode_width::{UnicodeWidthStr, UnicodeWidthChar};
 
 use crate::{
-    downloader,
-    edinet_tui::{app::Screen, ui::Styles},
-    models::{Document, DocumentFormat, DownloadRequest, Source},
+    edinet_tui::{
+        app::Screen,
+        components::{
+            document_table::{DocumentTable, DocumentTableConfig, DownloadState},
+            StatusDisplay,
+        },
+        operations,
+        traits,
+        ui::{centered_rect, split_tags, tag_input, InputField, Styles},
+    },
+    export::{self, ExportFormat},
+    locale::{format_number, DateFormat, DisplayLocale},
+    models::Document,
 };
 
+/// Amount the page size changes per +/- key press
+const RESULTS_PAGE_SIZE_STEP: usize = 5;
+
+/// Metadata fields shown in the preview panel, as (metadata key, label)
+const PREVIEW_FIELDS: [(&str, &str); 5] = [
+    ("doc_description", "Description"),
+    ("period_end", "Period End"),
+    ("edinet_code", "EDINET Code"),
+    ("form_code", "Form Code"),
+    ("parent_doc_id", "Amends"),
+];
+
+/// Which results are shown, by whether they are downloaded locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DownloadFilter {
+    #[default]
+    All,
+    Downloaded,
+    NotDownloaded,
+}
+
+impl DownloadFilter {
+    /// Next filter in the `f` key cycle
+    pub fn next(self) -> Self {
+        match self {
+            DownloadFilter::All => DownloadFilter::Downloaded,
+            DownloadFilter::Downloaded => DownloadFilter::NotDownloaded,
+            DownloadFilter::NotDownloaded => DownloadFilter::All,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DownloadFilter::All => "all",
+            DownloadFilter::Downloaded => "downloaded only",
+            DownloadFilter::NotDownloaded => "not downloaded only",
+        }
+    }
+
+    fn matches(&self, downloaded: bool) -> bool {
+        match self {
+            DownloadFilter::All => true,
+            DownloadFilter::Downloaded => downloaded,
+            DownloadFilter::NotDownloaded => !downloaded,
+        }
+    }
+}
+
+/// First day of a YYYY-MM-DD, YYYY-MM or YYYY date
+fn parse_jump_date(value: &str) -> Option<chrono::NaiveDate> {
+    let value = value.trim();
+    let full = match value.len() {
+        4 => format!("{}-01-01", value),
+        7 => format!("{}-01", value),
+        _ => value.to_string(),
+    };
+    chrono::NaiveDate::parse_from_str(&full, "%Y-%m-%d").ok()
+}
+
 /// Results screen state
 pub struct ResultsScreen {
-    pub documents: Vec<Document>,
-    pub document_state: ListState,
-    pub current_page: usize,
-    pub items_per_page: usize,
-    pub is_downloading: bool,
-    pub download_status: Option<String>,
+    pub table: DocumentTable,
+    /// Document being downloaded in the background, as tracked by the download manager
+    pub pending_download: Option<String>,
+    pub download_status: StatusDisplay,
+    pub locale: DisplayLocale,
+    /// Every document from the last search; the table shows those passing `download_filter`
+    pub all_documents: Vec<Document>,
+    /// Whether the search matched more documents than `FAST10K_MAX_SEARCH_RESULTS` and was cut off
+    pub capped: bool,
+    pub download_filter: DownloadFilter,
+    /// Whether the ✓/↓ indicators need re-checking, e.g. after a page change
+    download_states_stale: bool,
+    /// Whether downloads were running at the last tick, so the indicators update once more after they finish
+    had_active_downloads: bool,
+    /// Whether the selected document's metadata is shown below the table
+    pub show_preview: bool,
+    /// Open "go to row" prompt, if any
+    pub row_jump: Option<InputField>,
+    /// Open "jump to date" prompt, if any
+    pub date_jump: Option<InputField>,
+    /// Open tag editor for the selected document, if any
+    pub tag_editor: Option<InputField>,
 }
 
 impl ResultsScreen {
     pub fn new() -> Self {
         Self {
-            documents: Vec::new(),
-            document_state: ListState::default(),
-            current_page: 0,
-            items_per_page: 20,
-            is_downloading: false,
-            download_status: None,
+            table: DocumentTable::new(
+                Vec::new(),
+                DocumentTableConfig::new("Results").with_row_numbers().with_download_status(),
+            ),
+            pending_download: None,
+            download_status: StatusDisplay::new(),
+            locale: DisplayLocale::default(),
+            all_documents: Vec::new(),
+            capped: false,
+            download_filter: DownloadFilter::All,
+            download_states_stale: true,
+            had_active_downloads: false,
+            show_preview: false,
+            row_jump: None,
+            date_jump: None,
+            tag_editor: None,
         }
     }
 
+    pub fn with_locale(mut self, locale: DisplayLocale) -> Self {
+        self.locale = locale;
+        self.table.config.date_format.locale = locale;
+        self
+    }
+
+    pub fn with_date_format(mut self, date_format: DateFormat) -> Self {
+        self.table.config.date_format = date_format;
+        self
+    }
+
+    pub fn with_items_per_page(mut self, items_per_page: usize) -> Self {
+        self.table = self.table.with_pagination(items_per_page);
+        self
+    }
+
     /// Set new documents from search results
     pub fn set_documents(&mut self, documents: Vec<Document>) {
-        self.documents = documents;
-        self.current_page = 0;
-        self.document_state.select(if self.documents.is_empty() {
-            None
-        } else {
-            Some(0)
-        });
+        self.capped = false;
+        self.all_documents = documents.clone();
+        self.download_filter = DownloadFilter::All;
+        self.table.set_documents(documents);
+        self.download_states_stale = true;
     }
 
-    /// Get current page of documents
-    fn get_current_page_documents(&self) -> Vec<&Document> {
-        let start_idx = self.current_page * self.items_per_page;
-        let end_idx = std::cmp::min(start_idx + self.items_per_page, self.documents.len());
+    /// Update a document's metadata after it was edited in the viewer
+    pub fn update_document_metadata(&mut self, document_id: &str, metadata: &std::collections::HashMap<String, String>) {
+        for document in self.all_documents.iter_mut().chain(self.table.documents.iter_mut()) {
+            if document.id == document_id {
+                document.metadata = metadata.clone();
+            }
+        }
+    }
 
-        if start_idx < self.documents.len() {
-            self.documents[start_idx..end_idx].iter().collect()
-        } else {
-            Vec::new()
+    /// Show changed tags in every copy of the document, e.g. after editing them in the viewer
+    pub fn update_document_tags(&mut self, document_id: &str, tags: &[String]) {
+        for document in self.all_documents.iter_mut().chain(self.table.documents.iter_mut()) {
+            if document.id == document_id {
+                document.tags = tags.to_vec();
+            }
         }
     }
 
-    /// Get total number of pages
-    fn get_total_pages(&self) -> usize {
-        if self.documents.is_empty() {
-            0
-        } else {
-            (self.documents.len() + self.items_per_page - 1) / self.items_per_page
+    /// Show only the results passing `filter`, checking every result's local availability
+    pub fn apply_download_filter(&mut self, filter: DownloadFilter, app: &mut super::super::app::App) {
+        self.download_filter = filter;
+        let mut shown = Vec::new();
+        for document in &self.all_documents {
+            let downloaded = app.download_manager.is_document_downloaded(document);
+            if filter.matches(downloaded) {
+                shown.push(document.clone());
+            }
         }
+
+        let count = shown.len();
+        self.table.set_documents(shown);
+        self.download_states_stale = true;
+        self.preload_current_page(app);
+        app.set_status(format!(
+            "Showing {} of {} results ({})",
+            count,
+            self.all_documents.len(),
+            filter.label()
+        ));
+    }
+
+    /// Re-check which documents on the visible page are downloaded or downloading
+    fn refresh_download_states(&mut self, app: &super::super::app::App) {
+        let states: Vec<(String, DownloadState)> = self
+            .table
+            .get_current_page_documents()
+            .iter()
+            .map(|document| {
+                let state = if app.download_manager.is_document_downloading(document) {
+                    DownloadState::Downloading
+                } else if app.download_manager.is_document_downloaded(document) {
+                    DownloadState::Downloaded
+                } else {
+                    DownloadState::NotDownloaded
+                };
+                (document.id.clone(), state)
+            })
+            .collect();
+        for (document_id, state) in states {
+            self.table.set_download_state(&document_id, state);
+        }
+        self.download_states_stale = false;
     }
 
     /// Get currently selected document
     pub fn get_selected_document(&self) -> Option<&Document> {
-        self.document_state.selected().and_then(|idx| {
-            let page_start = self.current_page * self.items_per_page;
-            self.documents.get(page_start + idx)
-        })
+        self.table.get_selected_document()
     }
 
-    /// Handle key events for the results screen
-    pub async fn handle_event(
+    /// Start downloading the selected document in the background; `on_tick` reports the outcome
+    pub async fn download_document(
         &mut self,
-        key: KeyEvent,
+        document: Document,
         app: &mut super::super::app::App,
     ) -> Result<()> {
-        if self.is_downloading {
-            // Only allow cancellation during download
-            if let KeyCode::Esc = key.code {
-                self.is_downloading = false;
-                self.download_status = None;
-                app.set_status("Download cancelled".to_string());
+        match app.download_document(&document).await {
+            Ok(document_id) => {
+                self.pending_download = Some(document_id);
+                self.download_status.set_loading(format!("Downloading {}...", document.ticker));
+                app.set_loading(format!("Downloading {}", document.ticker));
             }
-            return Ok(());
-        }
-
-        match key.code {
-            KeyCode::Up => {
-                self.navigate_up();
-            }
-            KeyCode::Down => {
-                self.navigate_down();
-            }
-            KeyCode::Left => {
-                self.previous_page();
-            }
-            KeyCode::Right => {
-                self.next_page();
-            }
-            KeyCode::Home => {
-                self.go_to_first_page();
-            }
-            KeyCode::End => {
-                self.go_to_last_page();
-            }
-            KeyCode::Enter => {
-                // View selected document
-                if let Some(document) = self.get_selected_document() {
-                    app.viewer.set_document(document.clone());
-                    app.navigate_to_screen(Screen::Viewer);
-                }
-            }
-            KeyCode::Char('d') => {
-                // Download selected document
-                if let Some(document) = self.get_selected_document() {
-                    self.download_document(document.clone(), app).await?;
-                }
-            }
-            KeyCode::Char('r') => {
-                // Refresh/re-execute last search
-                app.set_status("Refresh functionality not implemented yet".to_string());
-            }
-            KeyCode::Char('/') => {
-                // New search
-                app.navigate_to_screen(Screen::Search);
-            }
-            KeyCode::Char('v') => {
-                // View document (same as Enter)
-                if let Some(document) = self.get_selected_document() {
-                    app.viewer.set_document(document.clone());
-                    app.navigate_to_screen(Screen::Viewer);
-                }
-            }
-            _ => {}
+            Err(e) => app.set_error(format!("Download failed: {}", operations::user_message(&e))),
         }
         Ok(())
     }
 
-    pub fn navigate_up(&mut self) {
-        let page_documents = self.get_current_page_documents();
-        if page_documents.is_empty() {
+    /// Report the pending download once the download manager has finished it
+    fn check_pending_download(&mut self, app: &mut super::super::app::App) {
+        let Some(document_id) = &self.pending_download else {
             return;
-        }
+        };
+        let Some(outcome) = app.download_manager.finished_download(document_id) else {
+            return;
+        };
 
-        let current_selection = self.document_state.selected().unwrap_or(0);
-        if current_selection > 0 {
-            self.document_state.select(Some(current_selection - 1));
-        } else if self.current_page > 0 {
-            // Go to previous page, last item
-            self.current_page -= 1;
-            let new_page_documents = self.get_current_page_documents();
-            if !new_page_documents.is_empty() {
-                self.document_state
-                    .select(Some(new_page_documents.len() - 1));
-            }
+        match outcome {
+            Ok(message) => app.set_success(format!("{} to {}", message, app.config.download_dir_str())),
+            Err(e) => app.set_error(format!("Download failed: {}", e)),
         }
+        self.pending_download = None;
+        self.download_status.clear();
+        self.preload_current_page(app);
     }
 
-    pub fn navigate_down(&mut self) {
-        let page_documents = self.get_current_page_documents();
-        if page_documents.is_empty() {
+    /// Queue every document in the result set for download in the background
+    pub fn download_all(&self, app: &mut super::super::app::App) {
+        if self.table.documents.is_empty() {
+            app.set_error("No results to download".to_string());
             return;
         }
 
-        let current_selection = self.document_state.selected().unwrap_or(0);
-        if current_selection < page_documents.len() - 1 {
-            self.document_state.select(Some(current_selection + 1));
-        } else if self.current_page < self.get_total_pages() - 1 {
-            // Go to next page, first item
-            self.current_page += 1;
-            self.document_state.select(Some(0));
-        }
+        let summary = app.download_manager.queue_documents(&self.table.documents);
+        app.set_status(format!(
+            "Queued {} document(s) for download, {} skipped as already downloaded or queued (F12 shows progress)",
+            summary.queued, summary.skipped
+        ));
     }
 
-    pub fn next_page(&mut self) {
-        if self.current_page < self.get_total_pages() - 1 {
-            self.current_page += 1;
-            self.document_state.select(Some(0));
+    /// Export the documents currently shown to the export directory
+    pub fn export(&self, format: ExportFormat, app: &mut super::super::app::App) {
+        if self.table.documents.is_empty() {
+            app.set_error("No results to export".to_string());
+            return;
         }
-    }
 
-    pub fn previous_page(&mut self) {
-        if self.current_page > 0 {
-            self.current_page -= 1;
-            self.document_state.select(Some(0));
+        let path = export::timestamped_export_path(&app.config.export_dir, format);
+        match export::export_documents(&self.table.documents, &path, format) {
+            Ok(count) => {
+                app.set_status(format!("Exported {} documents to {}", count, path.display()));
+            }
+            Err(e) => {
+                app.set_error(format!("Export failed: {}", e));
+            }
         }
     }
 
-    pub fn go_to_first_page(&mut self) {
-        self.current_page = 0;
-        self.document_state.select(if self.documents.is_empty() {
-            None
-        } else {
-            Some(0)
-        });
-    }
+    /// Handle keys while the "go to row" prompt is open
+    fn handle_row_jump_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some(input) = self.row_jump.as_mut() else {
+            return;
+        };
 
-    pub fn go_to_last_page(&mut self) {
-        if self.get_total_pages() > 0 {
-            self.current_page = self.get_total_pages() - 1;
-            let page_documents = self.get_current_page_documents();
-            self.document_state.select(if page_documents.is_empty() {
-                None
-            } else {
-                Some(0)
-            });
+        match key.code {
+            KeyCode::Enter => {
+                let row = input.value.trim().to_string();
+                self.row_jump = None;
+                self.jump_to_row(&row, app);
+            }
+            KeyCode::Esc => self.row_jump = None,
+            KeyCode::Char(c) if c.is_ascii_digit() => input.insert_char(c),
+            KeyCode::Backspace => input.delete_char(),
+            KeyCode::Delete => input.delete_char_forward(),
+            KeyCode::Left => input.move_cursor_left(),
+            KeyCode::Right => input.move_cursor_right(),
+            _ => {}
         }
     }
 
-    /// Download selected document
-    pub async fn download_document(
-        &mut self,
-        document: Document,
-        app: &mut super::super::app::App,
-    ) -> Result<()> {
-        self.is_downloading = true;
-        self.download_status = Some(format!("Downloading {}...", document.ticker));
-
-        app.set_status(format!("Starting download for {}", document.ticker));
-
-        let download_request = DownloadRequest {
-            source: Source::Edinet,
-            ticker: document.ticker.clone(),
-            filing_type: Some(document.filing_type.clone()),
-            date_from: Some(document.date),
-            date_to: Some(document.date),
-            limit: 1,
-            format: DocumentFormat::Complete,
+    /// Handle keys while the "jump to date" prompt is open
+    fn handle_date_jump_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some(input) = self.date_jump.as_mut() else {
+            return;
         };
 
-        match downloader::download_documents(&download_request, app.config.download_dir_str()).await
-        {
-            Ok(count) => {
-                app.set_status(format!(
-                    "Successfully downloaded {} document(s) to {}",
-                    count,
-                    app.config.download_dir_str()
-                ));
-            }
-            Err(e) => {
-                app.set_error(format!("Download failed: {}", e));
+        match key.code {
+            KeyCode::Enter => {
+                let date = input.value.trim().to_string();
+                self.date_jump = None;
+                self.jump_to_date(&date, app);
             }
+            KeyCode::Esc => self.date_jump = None,
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => input.insert_char(c),
+            KeyCode::Backspace => input.delete_char(),
+            KeyCode::Delete => input.delete_char_forward(),
+            KeyCode::Left => input.move_cursor_left(),
+            KeyCode::Right => input.move_cursor_right(),
+            _ => {}
         }
-
-        self.is_downloading = false;
-        self.download_status = None;
-        Ok(())
     }
 
-    /// Draw the results screen
-    pub fn draw(&mut self, f: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Title with stats
-                Constraint::Min(0),    // Results list
-                Constraint::Length(4), // Instructions and pagination
-            ])
-            .split(area);
+    /// Handle keys while the tag editor is open
+    async fn handle_tag_editor_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some(input) = self.tag_editor.as_mut() else {
+            return;
+        };
 
-        // Calculate items per page based on available height
-        // Subtract 3 for borders (top, bottom, header)
-        let available_height = chunks[1].height.saturating_sub(3);
-        let calculated_items_per_page = (available_height as usize).saturating_sub(1).max(10); // At least 10 items
-        
-        // Update items_per_page if it's significantly different
-        if calculated_items_per_page != self.items_per_page {
-            let old_page = self.current_page;
-            let old_selected = self.document_state.selected();
-            let old_items_per_page = self.items_per_page;
-            
-            self.items_per_page = calculated_items_per_page;
-            
-            // Recalculate current page to maintain selection position
-            if let Some(selected_local_idx) = old_selected {
-                let global_idx = old_page * old_items_per_page + selected_local_idx;
-                self.current_page = global_idx / self.items_per_page;
-                let new_local_idx = global_idx % self.items_per_page;
-                self.document_state.select(Some(new_local_idx));
+        match key.code {
+            KeyCode::Enter => {
+                let tags = split_tags(&input.value);
+                self.tag_editor = None;
+                let Some(id) = self.get_selected_document().map(|document| document.id.clone()) else {
+                    return;
+                };
+                match app.set_document_tags(&id, &tags).await {
+                    Ok(tags) => {
+                        self.update_document_tags(&id, &tags);
+                        app.set_status(format!("Tags of {}: {}", id, if tags.is_empty() { "none".to_string() } else { tags.join(", ") }));
+                    }
+                    Err(e) => app.set_error(format!("Failed to save tags: {}", e)),
+                }
             }
+            KeyCode::Esc => self.tag_editor = None,
+            KeyCode::Char(c) => input.insert_char(c),
+            KeyCode::Backspace => input.delete_char(),
+            KeyCode::Delete => input.delete_char_forward(),
+            KeyCode::Left => input.move_cursor_left(),
+            KeyCode::Right => input.move_cursor_right(),
+            _ => {}
         }
+    }
 
-        // Draw title and stats
-        self.draw_title(f, chunks[0]);
-
-        // Draw results list
-        self.draw_results_list(f, chunks[1]);
+    /// Select the document at a 1-based row number, as shown in the `#` column
+    pub fn jump_to_row(&mut self, row: &str, app: &mut super::super::app::App) {
+        let total = self.table.documents.len();
+        match row.parse::<usize>() {
+            Ok(row) if row >= 1 && self.table.select_index(row - 1) => {
+                self.preload_current_page(app);
+                app.set_status(format!(
+                    "Row {} of {} (page {})",
+                    format_number(row as u64, self.locale),
+                    format_number(total as u64, self.locale),
+                    self.table.current_page + 1
+                ));
+            }
+            _ if total == 0 => app.set_error("No results to jump to".to_string()),
+            _ => app.set_error(format!("Enter a row between 1 and {}", format_number(total as u64, self.locale))),
+        }
+    }
 
-        // Draw instructions and pagination
-        self.draw_bottom_info(f, chunks[2]);
+    /// Select the earliest document on or after a date, given as YYYY-MM-DD, YYYY-MM or YYYY
+    pub fn jump_to_date(&mut self, date: &str, app: &mut super::super::app::App) {
+        let Some(target) = parse_jump_date(date) else {
+            app.set_error(format!("Invalid date '{}': use YYYY-MM-DD, YYYY-MM or YYYY", date));
+            return;
+        };
+        let Some(index) = self.table.index_nearest_date(target) else {
+            app.set_error("No results to jump to".to_string());
+            return;
+        };
 
-        // Draw download status if downloading
-        if self.is_downloading {
-            self.draw_download_status(f, area);
+        self.table.select_index(index);
+        self.preload_current_page(app);
+        let date_format = &self.table.config.date_format;
+        let found = self.table.documents[index].date;
+        let row = format!("row {}, page {}", format_number(index as u64 + 1, self.locale), self.table.current_page + 1);
+        if found >= target {
+            app.set_status(format!("First filing on or after {}: {} ({})", date_format.format(target), date_format.format(found), row));
+        } else {
+            app.set_status(format!("No filings on or after {}; latest is {} ({})", date_format.format(target), date_format.format(found), row));
         }
     }
 
+    /// Preload the documents on the visible page in the background
+    fn preload_current_page(&self, app: &super::super::app::App) {
+        app.preload_documents(self.table.get_current_page_documents().to_vec());
+    }
+
     fn draw_title(&self, f: &mut Frame, area: Rect) {
-        let title_text = if self.documents.is_empty() {
+        let title_text = if self.all_documents.is_empty() {
             "Search Results - No documents found".to_string()
+        } else if self.capped {
+            let total = format_number(self.all_documents.len() as u64, self.locale);
+            format!(
+                "Search Results - showing first {} of {}+; refine the search or raise FAST10K_MAX_SEARCH_RESULTS",
+                total, total
+            )
+        } else if self.download_filter != DownloadFilter::All {
+            format!(
+                "Search Results - {} of {} documents ({})",
+                format_number(self.table.documents.len() as u64, self.locale),
+                format_number(self.all_documents.len() as u64, self.locale),
+                self.download_filter.label()
+            )
         } else {
-            format!("Search Results - {} documents found", self.documents.len())
+            format!(
+                "Search Results - {} documents found",
+                format_number(self.table.documents.len() as u64, self.locale)
+            )
         };
 
         let title = Paragraph::new(title_text)
@@ -321,10 +445,10 @@ impl ResultsScreen {
     }
 
     fn draw_results_list(&mut self, f: &mut Frame, area: Rect) {
-        let page_documents = self.get_current_page_documents();
-
-        if page_documents.is_empty() {
-            let empty_message = if self.documents.is_empty() {
+        if self.table.get_current_page_documents().is_empty() {
+            let empty_message = if !self.all_documents.is_empty() && self.table.documents.is_empty() {
+                "No results match the download filter. Press f to change it."
+            } else if self.table.documents.is_empty() {
                 "No documents found. Try adjusting your search criteria."
             } else {
                 "No documents on this page."
@@ -342,48 +466,37 @@ impl ResultsScreen {
             return;
         }
 
-        // Create header
-        let header = ListItem::new(Line::from(vec![
-            Span::styled("No.  ", Styles::title()),
-            Span::styled("│ Date       ", Styles::title()),
-            Span::styled("│ Symbol   ", Styles::title()),
-            Span::styled("│ Company              ", Styles::title()),  // reduced by 5 chars
-            Span::styled("│ Type                ", Styles::title()),   // increased by 8 chars
-            Span::styled("│ Format     ", Styles::title()),
-        ]));
-
-        // Create document items
-        let items: Vec<ListItem> = std::iter::once(header)
-            .chain(page_documents.iter().enumerate().map(|(i, doc)| {
-                let style = if Some(i) == self.document_state.selected() {
-                    Styles::selected()
-                } else {
-                    Style::default()
-                };
-
-                let row_number = self.current_page * self.items_per_page + i + 1;
-                let content = format!(
-                    "{:4} │ {} │ {} │ {} │ {} │ {}",
-                    row_number,
-                    doc.date,
-                    truncate_string(&doc.ticker, 8),
-                    truncate_string(&doc.company_name, 20),
-                    truncate_string(doc.filing_type.as_str(), 19),
-                    truncate_string(doc.format.as_str(), 10)
-                );
+        self.table.render(f, area);
+    }
 
-                ListItem::new(Line::from(Span::styled(content, style)))
-            }))
-            .collect();
+    /// Key metadata of the selected document, for triage without opening the viewer
+    fn draw_preview(&self, f: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = match self.get_selected_document() {
+            Some(document) => {
+                let tags = if document.tags.is_empty() { "-".to_string() } else { document.tags.join(", ") };
+                PREVIEW_FIELDS
+                    .iter()
+                    .map(|(key, label)| {
+                        let value = document.metadata.get(*key).filter(|v| !v.is_empty()).map(String::as_str).unwrap_or("-");
+                        Line::from(vec![Span::styled(format!("{:<12} ", label), Styles::info()), Span::raw(value.to_string())])
+                    })
+                    .chain(std::iter::once(Line::from(vec![Span::styled(format!("{:<12} ", "Tags"), Styles::info()), Span::raw(tags)])))
+                    .collect()
+            }
+            None => vec![Line::from("No document selected")],
+        };
 
-        let results_list = List::new(items).block(
+        let title = match self.get_selected_document() {
+            Some(document) => format!("Preview - {} ({})", document.company_name, document.id),
+            None => "Preview".to_string(),
+        };
+        let preview = Paragraph::new(lines).block(
             Block::default()
-                .title("Results")
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Styles::active_border()),
+                .border_style(Styles::inactive_border()),
         );
-
-        f.render_stateful_widget(results_list, area, &mut self.document_state);
+        f.render_widget(preview, area);
     }
 
     fn draw_bottom_info(&self, f: &mut Frame, area: Rect) {
@@ -394,8 +507,8 @@ impl ResultsScreen {
 
         // Instructions
         let instructions = vec![
-            Line::from("↑/↓: Navigate | ←/→: Pages | Enter/v: View | d: Download"),
-            Line::from("/: New Search | r: Refresh | ESC: Back"),
+            Line::from("↑/↓: Navigate | ←/→: Pages | Enter/v: View | d/D: Download one/all | e/E: Export"),
+            Line::from("p: Preview | f: Filter | :/g: Go to row | J: Go to date | +/-: Page size | /: New Search | ESC: Back"),
         ];
 
         let instructions_widget = Paragraph::new(instructions).style(Styles::info()).block(
@@ -408,21 +521,18 @@ impl ResultsScreen {
         f.render_widget(instructions_widget, chunks[0]);
 
         // Pagination info
-        let current_page = self.current_page + 1;
-        let total_pages = self.get_total_pages();
-        let selected_idx = self
-            .document_state
-            .selected()
-            .map(|idx| self.current_page * self.items_per_page + idx + 1)
-            .unwrap_or(0);
+        let current_page = self.table.current_page + 1;
+        let total_pages = self.table.get_total_pages();
+        let selected_idx = self.table.selected_index().map(|idx| idx + 1).unwrap_or(0);
 
         let pagination_text = if total_pages > 0 {
             format!(
-                "Page {} of {}\nItem {} of {}",
+                "Page {} of {} ({}/page)\nItem {} of {}",
                 current_page,
                 total_pages,
+                self.table.items_per_page,
                 selected_idx,
-                self.documents.len()
+                self.table.documents.len()
             )
         } else {
             "No pages".to_string()
@@ -443,49 +553,239 @@ impl ResultsScreen {
 
         let popup_area = centered_rect(50, 20, area);
 
-        let default_status = "Downloading...".to_string();
-        let status_text = self.download_status.as_ref().unwrap_or(&default_status);
-
-        let status_widget = Paragraph::new(format!("{}\n\nPress ESC to cancel", status_text))
-            .style(Styles::info())
-            .block(
-                Block::default()
-                    .title("Download Status")
-                    .borders(Borders::ALL)
-                    .border_style(Styles::warning()),
-            );
-
         f.render_widget(ratatui::widgets::Clear, popup_area);
-        f.render_widget(status_widget, popup_area);
+        self.download_status.render_with_title(f, popup_area, "Download Status (Esc: cancel)");
     }
 }
 
-/// Helper function to truncate strings to a specific display width (Unicode-aware)
-fn truncate_string(s: &str, max_width: usize) -> String {
-    let display_width = s.width();
-    if display_width <= max_width {
-        // Pad with spaces to reach exact width
-        let padding = max_width - display_width;
-        format!("{}{}", s, " ".repeat(padding))
-    } else {
-        // Truncate by character until we fit within max_width - 1 (for ellipsis)
-        let target_width = max_width.saturating_sub(1);
-        let mut truncated = String::new();
-        let mut current_width = 0;
-        
-        for ch in s.chars() {
-            let ch_width = ch.width().unwrap_or(0);
-            if current_width + ch_width > target_width {
-                break;
-            }
-            truncated.push(ch);
-            current_width += ch_width;
+#[async_trait::async_trait(?Send)]
+impl traits::Screen for ResultsScreen {
+    /// Draw the results screen
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        // Metadata fields, tags and the borders
+        let preview_height = if self.show_preview { PREVIEW_FIELDS.len() as u16 + 3 } else { 0 };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),              // Title with stats
+                Constraint::Min(0),                 // Results list
+                Constraint::Length(preview_height), // Metadata preview, when expanded
+                Constraint::Length(4),              // Instructions and pagination
+            ])
+            .split(area);
+
+        // Draw title and stats
+        self.draw_title(f, chunks[0]);
+
+        // Draw results list
+        self.draw_results_list(f, chunks[1]);
+
+        if self.show_preview {
+            self.draw_preview(f, chunks[2]);
+        }
+
+        // Draw instructions and pagination
+        self.draw_bottom_info(f, chunks[3]);
+
+        if let Some(input) = self.row_jump.as_ref().or(self.date_jump.as_ref()).or(self.tag_editor.as_ref()) {
+            let popup_area = centered_rect(50, 20, area);
+            let popup_area = Rect { height: popup_area.height.min(3), ..popup_area };
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+            input.render(f, popup_area);
+        }
+
+        // Draw download status if downloading
+        if self.pending_download.is_some() {
+            self.draw_download_status(f, area);
+        }
+    }
+
+    /// Handle key events for the results screen
+    async fn handle_event(
+        &mut self,
+        key: KeyEvent,
+        app: &mut super::super::app::App,
+    ) -> Result<()> {
+        // Paging and downloads change which indicators are visible, so re-check them on the next tick
+        self.download_states_stale = true;
+        if let Some(document_id) = &self.pending_download {
+            // Only allow cancellation during download
+            if let KeyCode::Esc = key.code {
+                app.download_manager.cancel_download(document_id);
+                self.pending_download = None;
+                self.download_status.clear();
+                app.set_status("Download cancelled".to_string());
+            }
+            return Ok(());
         }
-        
-        // Add ellipsis and pad to exact width
-        let ellipsis_width = 1;
-        let padding_needed = max_width - current_width - ellipsis_width;
-        format!("{}…{}", truncated, " ".repeat(padding_needed))
+
+        if self.row_jump.is_some() {
+            self.handle_row_jump_event(key, app);
+            return Ok(());
+        }
+
+        if self.date_jump.is_some() {
+            self.handle_date_jump_event(key, app);
+            return Ok(());
+        }
+
+        if self.tag_editor.is_some() {
+            self.handle_tag_editor_event(key, app).await;
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Up => {
+                self.table.navigate_up();
+                app.set_status("Navigate results with ↑/↓, Enter to view, d to download".to_string());
+            }
+            KeyCode::Down => {
+                self.table.navigate_down();
+                app.set_status("Navigate results with ↑/↓, Enter to view, d to download".to_string());
+            }
+            KeyCode::Left | KeyCode::PageUp => {
+                self.table.previous_page();
+                self.preload_current_page(app);
+                app.set_status("Previous page".to_string());
+            }
+            KeyCode::Right | KeyCode::PageDown => {
+                self.table.next_page();
+                self.preload_current_page(app);
+                app.set_status("Next page".to_string());
+            }
+            KeyCode::Home => {
+                self.table.go_to_first_page();
+                self.preload_current_page(app);
+                app.set_status("First page".to_string());
+            }
+            KeyCode::End => {
+                self.table.go_to_last_page();
+                self.preload_current_page(app);
+                app.set_status("Last page".to_string());
+            }
+            KeyCode::Enter | KeyCode::Char('v') => {
+                // View selected document
+                if let Some(document) = self.get_selected_document() {
+                    let id = document.id.clone();
+                    app.view_document_by_id(&id).await?;
+                } else {
+                    app.set_error("No document selected".to_string());
+                }
+            }
+            KeyCode::Esc => {
+                // Results screen: ESC goes back to Search
+                app.navigate_to_screen(Screen::Search);
+            }
+            KeyCode::Char('d') => {
+                // Download selected document
+                if let Some(document) = self.get_selected_document().cloned() {
+                    self.download_document(document, app).await?;
+                } else {
+                    app.set_error("No document selected".to_string());
+                }
+            }
+            KeyCode::Char('D') => {
+                self.download_all(app);
+            }
+            KeyCode::Char('t') => {
+                // Edit the tags of the selected document
+                match self.get_selected_document() {
+                    Some(document) => self.tag_editor = Some(tag_input(&document.tags)),
+                    None => app.set_error("No document selected".to_string()),
+                }
+            }
+            KeyCode::Char('p') => {
+                self.show_preview = !self.show_preview;
+                app.set_status(if self.show_preview { "Preview shown" } else { "Preview hidden" }.to_string());
+            }
+            KeyCode::Char('f') => {
+                self.apply_download_filter(self.download_filter.next(), app);
+            }
+            KeyCode::Char('e') => {
+                self.export(ExportFormat::Csv, app);
+            }
+            KeyCode::Char('E') => {
+                self.export(ExportFormat::Json, app);
+            }
+            KeyCode::Char(':') | KeyCode::Char('g') => {
+                if self.table.documents.is_empty() {
+                    app.set_error("No results to jump to".to_string());
+                } else {
+                    let placeholder = format!("1-{}", self.table.documents.len());
+                    let mut input = InputField::new("Go to row").with_placeholder(&placeholder);
+                    input.set_focus(true);
+                    self.row_jump = Some(input);
+                }
+            }
+            KeyCode::Char('J') => {
+                if self.table.documents.is_empty() {
+                    app.set_error("No results to jump to".to_string());
+                } else {
+                    let mut input = InputField::new("Jump to date").with_placeholder("YYYY-MM-DD, YYYY-MM or YYYY");
+                    input.set_focus(true);
+                    self.date_jump = Some(input);
+                }
+            }
+            KeyCode::Char('/') => {
+                // New search
+                app.navigate_to_screen(Screen::Search);
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.table
+                    .set_items_per_page(self.table.items_per_page + RESULTS_PAGE_SIZE_STEP);
+                self.preload_current_page(app);
+                app.set_status(format!("Page size: {}", self.table.items_per_page));
+            }
+            KeyCode::Char('-') => {
+                let page_size = self
+                    .table
+                    .items_per_page
+                    .saturating_sub(RESULTS_PAGE_SIZE_STEP)
+                    .max(RESULTS_PAGE_SIZE_STEP);
+                self.table.set_items_per_page(page_size);
+                self.preload_current_page(app);
+                app.set_status(format!("Page size: {}", self.table.items_per_page));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn on_tick(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        self.check_pending_download(app);
+
+        let has_active_downloads = app.download_manager.has_active_downloads();
+        if self.download_states_stale || has_active_downloads || self.had_active_downloads {
+            self.refresh_download_states(app);
+        }
+        self.had_active_downloads = has_active_downloads;
+        Ok(())
+    }
+
+    fn title(&self) -> &str {
+        "Search Results"
+    }
+
+    fn is_capturing_text(&self) -> bool {
+        self.row_jump.is_some() || self.date_jump.is_some() || self.tag_editor.is_some()
+    }
+
+    fn help(&self) -> &str {
+        "Search Results:\n\
+        ↑/↓ - Navigate documents\n\
+        Enter - View document\n\
+        d - Download document\n\
+        D - Download all results\n\
+        p - Show/hide metadata preview of the selected document\n\
+        t - Edit tags of the selected document\n\
+        f - Show all, downloaded or not downloaded results\n\
+        e/E - Export results to CSV/JSON\n\
+        :/g - Go to a row number\n\
+        J - Jump to the first filing on or after a date\n\
+        r - Refresh search\n\
+        / - New search\n\
+        Page Up/Down - Navigate pages\n\
+        +/- - Increase/decrease page size"
     }
 }
 
@@ -10,12 +10,13 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
-use chrono::{NaiveDate, Local};
+use chrono::Local;
+use tokio::task::JoinHandle;
 
 use crate::{
-    models::{SearchQuery, Source, FilingType, DocumentFormat},
+    models::{Document, SearchQuery, Source, FilingType},
     storage,
-    edinet_tui::ui::{Styles, InputField, SelectableList}, edinet_tui::app::Screen,
+    edinet_tui::ui::{Styles, InputField, SelectableList}, edinet_tui::{app::Screen, traits},
 };
 
 /// Search form fields
@@ -24,6 +25,7 @@ pub enum SearchField {
     Ticker,
     CompanyName,
     FilingType,
+    Source,
     DateFrom,
     DateTo,
     TextQuery,
@@ -35,6 +37,7 @@ impl SearchField {
             SearchField::Ticker => "Ticker Symbol",
             SearchField::CompanyName => "Company Name",
             SearchField::FilingType => "Filing Type",
+            SearchField::Source => "Source",
             SearchField::DateFrom => "Date From",
             SearchField::DateTo => "Date To",
             SearchField::TextQuery => "Text Search",
@@ -57,9 +60,14 @@ pub struct SearchScreen {
     // Dropdown selections
     pub filing_type_list: SelectableList<FilingType>,
     pub show_filing_dropdown: bool,
+    /// Source filter; `None` merges EDGAR and EDINET results
+    pub source_filter: Option<Source>,
+    /// Source filter the form starts with and returns to when cleared
+    pub default_source: Option<Source>,
     
     // Search state
-    pub is_searching: bool,
+    /// Search running in the background, picked up by `on_tick` when it finishes
+    pub pending_search: Option<(SearchQuery, JoinHandle<Result<Vec<Document>>>)>,
     pub last_query: Option<SearchQuery>,
 }
 
@@ -69,19 +77,13 @@ impl SearchScreen {
             SearchField::Ticker,
             SearchField::CompanyName,
             SearchField::FilingType,
+            SearchField::Source,
             SearchField::DateFrom,
             SearchField::DateTo,
             SearchField::TextQuery,
         ];
 
-        // Available filing types for EDINET
-        let filing_types = vec![
-            FilingType::AnnualSecuritiesReport,     // 有価証券報告書
-            FilingType::QuarterlySecuritiesReport,  // 四半期報告書
-            FilingType::SemiAnnualSecuritiesReport, // 半期報告書
-            FilingType::ExtraordinaryReport,        // 臨時報告書
-            FilingType::Other("Internal Control Report".to_string()), // 内部統制報告書
-        ];
+        let filing_types = FilingType::all().to_vec();
 
         let mut search_screen = Self {
             current_field: 0,
@@ -104,8 +106,10 @@ impl SearchScreen {
                 list
             },
             show_filing_dropdown: false,
+            source_filter: Some(Source::Edinet),
+            default_source: Some(Source::Edinet),
             
-            is_searching: false,
+            pending_search: None,
             last_query: None,
         };
 
@@ -113,68 +117,30 @@ impl SearchScreen {
         search_screen
     }
 
-    /// Handle key events for the search screen
-    pub async fn handle_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) -> Result<()> {
-        if self.show_filing_dropdown {
-            return self.handle_filing_dropdown_event(key, app).await;
-        }
+    /// Start with `source` selected instead of EDINET; `None` searches every source
+    pub fn with_default_source(mut self, source: Option<Source>) -> Self {
+        self.source_filter = source.clone();
+        self.default_source = source;
+        self
+    }
 
-        match key.code {
-            KeyCode::Tab => {
-                self.current_field = (self.current_field + 1) % self.fields.len();
-                self.update_field_focus();
-            }
-            KeyCode::BackTab => {
-                self.current_field = if self.current_field == 0 {
-                    self.fields.len() - 1
-                } else {
-                    self.current_field - 1
-                };
-                self.update_field_focus();
-            }
-            KeyCode::Up => {
-                if self.current_field > 0 {
-                    self.current_field -= 1;
-                    self.update_field_focus();
-                }
-            }
-            KeyCode::Down => {
-                if self.current_field < self.fields.len() - 1 {
-                    self.current_field += 1;
-                    self.update_field_focus();
-                }
-            }
-            KeyCode::Enter => {
-                if self.fields[self.current_field] == SearchField::FilingType {
-                    self.show_filing_dropdown = true;
-                } else {
-                    self.execute_search(app).await?;
-                }
-            }
-            KeyCode::Char(c) => {
-                self.handle_char_input(c);
-            }
-            KeyCode::Backspace => {
-                self.handle_backspace();
-            }
-            KeyCode::Delete => {
-                self.handle_delete();
-            }
-            KeyCode::Left => {
-                self.handle_cursor_left();
-            }
-            KeyCode::Right => {
-                self.handle_cursor_right();
-            }
-            KeyCode::Home => {
-                self.handle_cursor_home();
-            }
-            KeyCode::End => {
-                self.handle_cursor_end();
-            }
-            _ => {}
-        }
-        Ok(())
+    /// Offer only the filing types indexed for the selected source, or every known type if none are
+    async fn refresh_filing_types(&mut self, app: &super::super::app::App) {
+        let indexed = storage::distinct_filing_types(self.source_filter.as_ref(), app.config.database_path_str())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load indexed filing types: {}", e);
+                Vec::new()
+            });
+        let filing_types = if indexed.is_empty() { FilingType::all().to_vec() } else { indexed };
+
+        // Keep the current choice if it is still on offer
+        let selected = self.filing_type_list.selected().map(|t| t.as_str().to_string());
+        self.filing_type_list = SelectableList::new(filing_types);
+        let index = selected.and_then(|label| {
+            self.filing_type_list.items.iter().position(|t| t.as_str() == label)
+        });
+        self.filing_type_list.select(index);
     }
 
     /// Handle filing type dropdown events
@@ -212,7 +178,7 @@ impl SearchScreen {
             SearchField::DateFrom => self.date_from_input.set_focus(true),
             SearchField::DateTo => self.date_to_input.set_focus(true),
             SearchField::TextQuery => self.text_query_input.set_focus(true),
-            SearchField::FilingType => {} // Handled separately
+            SearchField::FilingType | SearchField::Source => {} // Handled separately
         }
     }
 
@@ -224,6 +190,11 @@ impl SearchScreen {
             SearchField::DateTo => self.date_to_input.insert_char(c),
             SearchField::TextQuery => self.text_query_input.insert_char(c),
             SearchField::FilingType => {} // Handled by dropdown
+            SearchField::Source => {
+                if c == ' ' {
+                    self.cycle_source(true);
+                }
+            }
         }
     }
 
@@ -234,7 +205,7 @@ impl SearchScreen {
             SearchField::DateFrom => self.date_from_input.delete_char(),
             SearchField::DateTo => self.date_to_input.delete_char(),
             SearchField::TextQuery => self.text_query_input.delete_char(),
-            SearchField::FilingType => {}
+            SearchField::FilingType | SearchField::Source => {}
         }
     }
 
@@ -245,7 +216,7 @@ impl SearchScreen {
             SearchField::DateFrom => self.date_from_input.delete_char_forward(),
             SearchField::DateTo => self.date_to_input.delete_char_forward(),
             SearchField::TextQuery => self.text_query_input.delete_char_forward(),
-            SearchField::FilingType => {}
+            SearchField::FilingType | SearchField::Source => {}
         }
     }
 
@@ -257,6 +228,7 @@ impl SearchScreen {
             SearchField::DateTo => self.date_to_input.move_cursor_left(),
             SearchField::TextQuery => self.text_query_input.move_cursor_left(),
             SearchField::FilingType => {}
+            SearchField::Source => self.cycle_source(false),
         }
     }
 
@@ -268,6 +240,7 @@ impl SearchScreen {
             SearchField::DateTo => self.date_to_input.move_cursor_right(),
             SearchField::TextQuery => self.text_query_input.move_cursor_right(),
             SearchField::FilingType => {}
+            SearchField::Source => self.cycle_source(true),
         }
     }
 
@@ -278,7 +251,7 @@ impl SearchScreen {
             SearchField::DateFrom => self.date_from_input.move_cursor_to_start(),
             SearchField::DateTo => self.date_to_input.move_cursor_to_start(),
             SearchField::TextQuery => self.text_query_input.move_cursor_to_start(),
-            SearchField::FilingType => {}
+            SearchField::FilingType | SearchField::Source => {}
         }
     }
 
@@ -289,79 +262,111 @@ impl SearchScreen {
             SearchField::DateFrom => self.date_from_input.move_cursor_to_end(),
             SearchField::DateTo => self.date_to_input.move_cursor_to_end(),
             SearchField::TextQuery => self.text_query_input.move_cursor_to_end(),
-            SearchField::FilingType => {}
+            SearchField::FilingType | SearchField::Source => {}
         }
     }
 
+    /// Step through the source filter: each of `Source::all()`, then Any
+    pub fn cycle_source(&mut self, forward: bool) {
+        let options: Vec<Option<Source>> = Source::all()
+            .iter()
+            .cloned()
+            .map(Some)
+            .chain(std::iter::once(None))
+            .collect();
+        let current = options
+            .iter()
+            .position(|option| *option == self.source_filter)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % options.len()
+        } else {
+            (current + options.len() - 1) % options.len()
+        };
+        self.source_filter = options[next].clone();
+    }
+
     /// Execute search with current form values
     async fn execute_search(&mut self, app: &mut super::super::app::App) -> Result<()> {
-        // Validate date inputs
-        if !self.date_from_input.is_empty() {
-            if NaiveDate::parse_from_str(&self.date_from_input.value, "%Y-%m-%d").is_err() {
-                app.set_error("Invalid 'Date From' format. Please use YYYY-MM-DD".to_string());
-                return Ok(());
-            }
-        }
-        
-        if !self.date_to_input.is_empty() {
-            if NaiveDate::parse_from_str(&self.date_to_input.value, "%Y-%m-%d").is_err() {
-                app.set_error("Invalid 'Date To' format. Please use YYYY-MM-DD".to_string());
+        let search_query = SearchQuery::builder()
+            .ticker(self.ticker_input.value.as_str())
+            .company_name(self.company_input.value.as_str())
+            .filing_type(self.filing_type_list.selected().cloned())
+            .source(self.source_filter.clone())
+            .date_from_str(&self.date_from_input.value)
+            .date_to_str(&self.date_to_input.value)
+            .text_query(self.text_query_input.value.as_str())
+            .build()
+            .and_then(|query| query.validate().map(|_| query));
+        let search_query = match search_query {
+            Ok(query) => query,
+            Err(e) => {
+                app.set_error(e.to_string());
                 return Ok(());
             }
-        }
-
-        // Build search query
-        let search_query = SearchQuery {
-            ticker: if self.ticker_input.is_empty() { None } else { Some(self.ticker_input.value.clone()) },
-            company_name: if self.company_input.is_empty() { None } else { Some(self.company_input.value.clone()) },
-            filing_type: self.filing_type_list.selected().cloned(),
-            source: Some(Source::Edinet),
-            date_from: if self.date_from_input.is_empty() { 
-                None 
-            } else { 
-                NaiveDate::parse_from_str(&self.date_from_input.value, "%Y-%m-%d").ok() 
-            },
-            date_to: if self.date_to_input.is_empty() { 
-                None 
-            } else { 
-                NaiveDate::parse_from_str(&self.date_to_input.value, "%Y-%m-%d").ok() 
-            },
-            text_query: if self.text_query_input.is_empty() { None } else { Some(self.text_query_input.value.clone()) },
         };
 
+        app.set_loading("Searching documents...".to_string());
 
-        // Check if search has any criteria
-        if search_query.ticker.is_none() 
-            && search_query.company_name.is_none()
-            && search_query.filing_type.is_none()
-            && search_query.date_from.is_none() 
-            && search_query.date_to.is_none()
-            && search_query.text_query.is_none() {
-            app.set_error("Please enter at least one search criteria".to_string());
-            return Ok(());
+        let query = search_query.clone();
+        let database_path = app.config.database_path_str().to_string();
+        // One row past the cap tells whether the results were cut off
+        let limit = app.config.display.max_search_results + 1;
+        let handle = tokio::spawn(async move {
+            match query.source {
+                Some(_) => storage::search_documents(&query, &database_path, limit).await,
+                None => storage::search_all_sources(&query, &database_path, limit).await,
+            }
+        });
+        self.pending_search = Some((search_query, handle));
+        Ok(())
+    }
+
+    /// Show the results of the background search once it has finished
+    async fn check_pending_search(&mut self, app: &mut super::super::app::App) {
+        if !self.pending_search.as_ref().is_some_and(|(_, handle)| handle.is_finished()) {
+            return;
         }
+        let Some((search_query, handle)) = self.pending_search.take() else {
+            return;
+        };
+        let results = handle.await.unwrap_or_else(|e| Err(anyhow::anyhow!("Search task failed: {}", e)));
 
-        self.is_searching = true;
-        app.set_status("Searching documents...".to_string());
+        match results {
+            Ok(mut documents) => {
+                let max_results = app.config.display.max_search_results;
+                let capped = documents.len() > max_results;
+                documents.truncate(max_results);
+                let count = documents.len();
 
-        match storage::search_documents(&search_query, app.config.database_path_str(), 100).await {
-            Ok(documents) => {
-                app.set_status(format!("Found {} documents", documents.len()));
-                
                 // Store results in the results screen
-                app.results.set_documents(documents);
+                if let Some(results) = app.screen_mut::<super::ResultsScreen>() {
+                    results.set_documents(documents);
+                    results.capped = capped;
+                }
+                app.preload_visible_results();
                 self.last_query = Some(search_query);
                 
                 // Navigate to results screen
                 app.navigate_to_screen(Screen::Results);
+
+                match app.setup_hint.clone() {
+                    // Explain empty results while nothing has been indexed yet
+                    Some(hint) if count == 0 => app.set_error(format!(
+                        "{} Build an index from Database Management first.",
+                        hint
+                    )),
+                    _ if capped => app.set_status(format!(
+                        "Showing the first {} documents; refine the search or raise FAST10K_MAX_SEARCH_RESULTS",
+                        count
+                    )),
+                    _ => app.set_status(format!("Found {} documents", count)),
+                }
             }
             Err(e) => {
                 app.set_error(format!("Search failed: {}", e));
             }
         }
-
-        self.is_searching = false;
-        Ok(())
     }
 
     /// Clear all search fields
@@ -372,45 +377,20 @@ impl SearchScreen {
         self.date_to_input.clear();
         self.text_query_input.clear();
         self.filing_type_list.select(None);
+        self.source_filter = self.default_source.clone();
         self.current_field = 0;
         self.update_field_focus();
     }
 
-    /// Draw the search screen
-    pub fn draw(&mut self, f: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),  // Title
-                Constraint::Min(0),     // Form
-                Constraint::Length(4),  // Instructions
-            ])
-            .split(area);
-
-        // Draw title
-        self.draw_title(f, chunks[0]);
-        
-        // Draw search form
-        self.draw_form(f, chunks[1]);
-        
-        // Draw instructions
-        self.draw_instructions(f, chunks[2]);
-
-        // Draw filing type dropdown if active
-        if self.show_filing_dropdown {
-            self.draw_filing_dropdown(f, area);
-        }
-    }
-
     fn draw_title(&self, f: &mut Frame, area: Rect) {
-        let title = if self.is_searching {
+        let title = if self.pending_search.is_some() {
             "Document Search - Searching..."
         } else {
             "Document Search"
         };
         
         let title_widget = Paragraph::new(title)
-            .style(if self.is_searching { Styles::warning() } else { Styles::title() })
+            .style(if self.pending_search.is_some() { Styles::warning() } else { Styles::title() })
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title_widget, area);
     }
@@ -422,6 +402,7 @@ impl SearchScreen {
                 Constraint::Length(3), // Ticker
                 Constraint::Length(3), // Company
                 Constraint::Length(3), // Filing Type
+                Constraint::Length(3), // Source
                 Constraint::Length(3), // Date From
                 Constraint::Length(3), // Date To
                 Constraint::Length(3), // Text Query
@@ -434,10 +415,11 @@ impl SearchScreen {
         
         // Filing type field (special handling)
         self.draw_filing_type_field(f, chunks[2]);
+        self.draw_source_field(f, chunks[3]);
         
-        self.date_from_input.render(f, chunks[3]);
-        self.date_to_input.render(f, chunks[4]);
-        self.text_query_input.render(f, chunks[5]);
+        self.date_from_input.render(f, chunks[4]);
+        self.date_to_input.render(f, chunks[5]);
+        self.text_query_input.render(f, chunks[6]);
     }
 
     fn draw_filing_type_field(&self, f: &mut Frame, area: Rect) {
@@ -460,10 +442,31 @@ impl SearchScreen {
         f.render_widget(field, area);
     }
 
+    fn draw_source_field(&self, f: &mut Frame, area: Rect) {
+        let selected_source = self.source_filter
+            .as_ref()
+            .map(|source| source.as_str())
+            .unwrap_or("Any (EDGAR + EDINET)");
+
+        let style = if self.fields[self.current_field] == SearchField::Source {
+            Styles::active_border()
+        } else {
+            Styles::inactive_border()
+        };
+
+        let field = Paragraph::new(selected_source)
+            .block(Block::default()
+                .title("Source (←/→ to change)")
+                .borders(Borders::ALL)
+                .border_style(style));
+
+        f.render_widget(field, area);
+    }
+
     fn draw_instructions(&self, f: &mut Frame, area: Rect) {
         let instructions = vec![
             Line::from("Tab/Shift+Tab: Navigate fields | ↑/↓: Navigate | Enter: Search/Select"),
-            Line::from("Enter on Filing Type: Show dropdown | Clear fields: Ctrl+L"),
+            Line::from("Enter on Filing Type: Show dropdown | ←/→ on Source: Change source | Clear fields: Ctrl+L"),
         ];
 
         let instructions_widget = Paragraph::new(instructions)
@@ -504,4 +507,130 @@ impl SearchScreen {
         f.render_widget(ratatui::widgets::Clear, popup_area);
         f.render_stateful_widget(list, popup_area, &mut self.filing_type_list.state);
     }
-}
\ No newline at end of file
+}
+
+#[async_trait::async_trait(?Send)]
+impl traits::Screen for SearchScreen {
+    /// Draw the search screen
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Title
+                Constraint::Min(0),     // Form
+                Constraint::Length(4),  // Instructions
+            ])
+            .split(area);
+
+        // Draw title
+        self.draw_title(f, chunks[0]);
+        
+        // Draw search form
+        self.draw_form(f, chunks[1]);
+        
+        // Draw instructions
+        self.draw_instructions(f, chunks[2]);
+
+        // Draw filing type dropdown if active
+        if self.show_filing_dropdown {
+            self.draw_filing_dropdown(f, area);
+        }
+    }
+
+    /// Handle key events for the search screen
+    async fn handle_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) -> Result<()> {
+        if let Some((_, handle)) = &self.pending_search {
+            // Only allow cancellation while searching
+            if let KeyCode::Esc = key.code {
+                handle.abort();
+                self.pending_search = None;
+                app.set_status("Search cancelled".to_string());
+            }
+            return Ok(());
+        }
+
+        if self.show_filing_dropdown {
+            return self.handle_filing_dropdown_event(key, app).await;
+        }
+
+        match key.code {
+            KeyCode::Tab => {
+                self.current_field = (self.current_field + 1) % self.fields.len();
+                self.update_field_focus();
+                app.set_status(format!("Focus: {}", self.fields[self.current_field].as_str()));
+            }
+            KeyCode::BackTab => {
+                self.current_field = if self.current_field == 0 {
+                    self.fields.len() - 1
+                } else {
+                    self.current_field - 1
+                };
+                self.update_field_focus();
+                app.set_status(format!("Focus: {}", self.fields[self.current_field].as_str()));
+            }
+            KeyCode::Up if self.current_field > 0 => {
+                self.current_field -= 1;
+                self.update_field_focus();
+            }
+            KeyCode::Down if self.current_field < self.fields.len() - 1 => {
+                self.current_field += 1;
+                self.update_field_focus();
+            }
+            KeyCode::Enter => {
+                if self.fields[self.current_field] == SearchField::FilingType {
+                    self.refresh_filing_types(app).await;
+                    self.show_filing_dropdown = true;
+                } else {
+                    self.execute_search(app).await?;
+                }
+            }
+            KeyCode::Esc => {
+                // Search screen: ESC goes back to Main Menu
+                app.navigate_to_screen(Screen::MainMenu);
+            }
+            KeyCode::Char(c) => {
+                self.handle_char_input(c);
+            }
+            KeyCode::Backspace => {
+                self.handle_backspace();
+            }
+            KeyCode::Delete => {
+                self.handle_delete();
+            }
+            KeyCode::Left => {
+                self.handle_cursor_left();
+            }
+            KeyCode::Right => {
+                self.handle_cursor_right();
+            }
+            KeyCode::Home => {
+                self.handle_cursor_home();
+            }
+            KeyCode::End => {
+                self.handle_cursor_end();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn on_tick(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        self.check_pending_search(app).await;
+        Ok(())
+    }
+
+    fn title(&self) -> &str {
+        "Search Documents"
+    }
+
+    fn help(&self) -> &str {
+        "Search Documents:\n\
+        Tab - Next field\n\
+        Shift+Tab - Previous field\n\
+        Enter - Execute search\n\
+        ←/→ on Source - EDINET, EDGAR or Any\n\
+        Type in text fields\n\
+        ↑/↓ - Navigate dropdowns\n\
+        Space - Toggle selections"
+    }
+}
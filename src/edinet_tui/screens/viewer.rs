@@ -1,24 +1,40 @@
 //! Document viewer screen for the EDINET TUI
 
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::{
-    downloader,
-    edinet::reader::{read_edinet_zip, DocumentSection},
-    edinet_tui::ui::Styles,
-    models::{Document, DocumentFormat, DownloadRequest, Source},
+    config::{Config, DisplayConfig},
+    edinet::reader::{
+        export_financials, financials_section, find_downloaded_financials, find_section,
+        find_section_by_content, load_section_content, read_edinet_financials, table_of_contents, DocumentSection,
+        NoTextSections, TocEntry, FINANCIALS_SECTION_TYPE,
+    },
+    edinet_tui::{
+        app::Screen,
+        components::StatusDisplay,
+        operations::{self, local_files},
+        traits,
+        ui::{centered_rect, copy_to_clipboard, split_tags, tag_input, InputField, SelectableList, Styles},
+    },
+    locale::{format_number, DateFormat, DisplayLocale},
+    models::{Document, Source},
+    storage,
 };
 
+/// Section, File, Size and a blank line precede a section's content
+const SECTION_HEADER_LINES: usize = 4;
+
 /// Document viewer mode
 #[derive(Debug, Clone, PartialEq)]
 pub enum ViewerMode {
@@ -26,6 +42,91 @@ pub enum ViewerMode {
     Content, // Document content sections
 }
 
+/// Text being entered in the metadata editor
+pub enum MetadataInput {
+    /// New value for the selected entry
+    Value(InputField),
+    /// New entry typed as `key=value`
+    NewEntry(InputField),
+}
+
+/// Working copy of a document's metadata, persisted only when saved
+pub struct MetadataEditor {
+    pub entries: SelectableList<(String, String)>,
+    pub input: Option<MetadataInput>,
+    pub is_modified: bool,
+}
+
+impl MetadataEditor {
+    pub fn new(metadata: &HashMap<String, String>) -> Self {
+        let mut entries: Vec<(String, String)> = metadata.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        entries.sort();
+        Self {
+            entries: SelectableList::new(entries),
+            input: None,
+            is_modified: false,
+        }
+    }
+
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        self.entries.items.iter().cloned().collect()
+    }
+
+    /// Set `key` to `value`, adding the entry if needed, and select it
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.entries.items.iter().position(|(k, _)| k == key) {
+            Some(index) => {
+                if self.entries.items[index].1 != value {
+                    self.entries.items[index].1 = value.to_string();
+                    self.is_modified = true;
+                }
+                self.entries.select(Some(index));
+            }
+            None => {
+                self.entries.items.push((key.to_string(), value.to_string()));
+                self.entries.items.sort();
+                let index = self.entries.items.iter().position(|(k, _)| k == key);
+                self.entries.select(index);
+                self.is_modified = true;
+            }
+        }
+    }
+
+    /// Remove the selected entry
+    pub fn remove_selected(&mut self) {
+        if let Some(index) = self.entries.selected_index().filter(|&i| i < self.entries.len()) {
+            self.entries.items.remove(index);
+            self.is_modified = true;
+            let len = self.entries.len();
+            self.entries.select((len > 0).then(|| index.min(len - 1)));
+        }
+    }
+
+    /// Apply the pending input, returning an error message for a malformed new entry
+    fn commit_input(&mut self) -> std::result::Result<(), String> {
+        match self.input.take() {
+            Some(MetadataInput::Value(input)) => {
+                if let Some((key, _)) = self.entries.selected().cloned() {
+                    self.set(&key, &input.value);
+                }
+                Ok(())
+            }
+            Some(MetadataInput::NewEntry(input)) => match input.value.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    self.set(key.trim(), value.trim());
+                    Ok(())
+                }
+                _ => {
+                    let message = format!("Expected key=value, got '{}'", input.value);
+                    self.input = Some(MetadataInput::NewEntry(input));
+                    Err(message)
+                }
+            },
+            None => Ok(()),
+        }
+    }
+}
+
 /// Document viewer screen state
 pub struct ViewerScreen {
     pub current_document: Option<Document>,
@@ -33,11 +134,32 @@ pub struct ViewerScreen {
     pub scroll_offset: usize,
     pub content_sections: Option<Vec<DocumentSection>>,
     pub current_section: usize,
+    /// Downloaded file the content sections were listed from
+    pub content_path: Option<PathBuf>,
+    /// Files and sizes of a downloaded ZIP without any text sections, shown instead of content
+    pub archive_files: Option<Vec<(String, u64)>>,
+    pub max_sections: usize,
+    pub max_content_length: usize,
     pub is_loading: bool,
-    pub is_downloading: bool,
-    pub download_status: Option<String>,
+    /// Document being downloaded in the background, as tracked by the download manager
+    pub pending_download: Option<String>,
+    pub download_status: StatusDisplay,
     pub is_downloaded: bool,
     pub pending_g_key: bool, // For "gg" command
+    pub locale: DisplayLocale,
+    pub date_format: DateFormat,
+    /// Downloaded file of the current document, found when it is opened or downloaded
+    pub downloaded_file: Option<PathBuf>,
+    /// Open metadata editor popup, if any
+    pub metadata_editor: Option<MetadataEditor>,
+    /// Open "jump to section" prompt, if any
+    pub section_search: Option<InputField>,
+    /// Open tag editor, if any
+    pub tag_editor: Option<InputField>,
+    /// Highlighted table of contents entry while the sidebar has focus
+    pub toc_cursor: Option<usize>,
+    /// Selected content lines of the current section as (anchor, cursor), while selecting
+    pub selection: Option<(usize, usize)>,
 }
 
 impl ViewerScreen {
@@ -48,14 +170,43 @@ impl ViewerScreen {
             scroll_offset: 0,
             content_sections: None,
             current_section: 0,
+            content_path: None,
+            archive_files: None,
+            max_sections: DisplayConfig::default().viewer_max_sections,
+            max_content_length: DisplayConfig::default().viewer_max_content_length,
             is_loading: false,
-            is_downloading: false,
-            download_status: None,
+            pending_download: None,
+            download_status: StatusDisplay::new(),
             is_downloaded: false,
             pending_g_key: false,
+            locale: DisplayLocale::default(),
+            date_format: DateFormat::default(),
+            downloaded_file: None,
+            metadata_editor: None,
+            section_search: None,
+            tag_editor: None,
+            toc_cursor: None,
+            selection: None,
         }
     }
 
+    pub fn with_locale(mut self, locale: DisplayLocale) -> Self {
+        self.locale = locale;
+        self.date_format.locale = locale;
+        self
+    }
+
+    pub fn with_date_format(mut self, date_format: DateFormat) -> Self {
+        self.date_format = date_format;
+        self
+    }
+
+    pub fn with_content_limits(mut self, max_sections: usize, max_content_length: usize) -> Self {
+        self.max_sections = max_sections;
+        self.max_content_length = max_content_length;
+        self
+    }
+
     /// Set document to view
     pub fn set_document(&mut self, document: Document) {
         self.current_document = Some(document);
@@ -63,279 +214,505 @@ impl ViewerScreen {
         self.scroll_offset = 0;
         self.content_sections = None;
         self.current_section = 0;
+        self.content_path = None;
+        self.archive_files = None;
         self.is_loading = false;
         self.is_downloaded = false; // Will be updated when checked
+        self.downloaded_file = None;
+        self.metadata_editor = None;
+        self.section_search = None;
+        self.tag_editor = None;
+        self.toc_cursor = None;
+        self.selection = None;
     }
 
-    /// Handle key events for the viewer screen
-    pub async fn handle_event(
-        &mut self,
-        key: KeyEvent,
-        app: &mut super::super::app::App,
-    ) -> Result<()> {
-        if self.is_downloading {
-            // Only allow cancellation during download
-            if let KeyCode::Esc = key.code {
-                self.is_downloading = false;
-                self.download_status = None;
-                app.set_status("Download cancelled".to_string());
-            }
+    /// Show changed tags if the document is the one being viewed
+    pub fn update_document_tags(&mut self, document_id: &str, tags: &[String]) {
+        if let Some(document) = self.current_document.as_mut().filter(|document| document.id == document_id) {
+            document.tags = tags.to_vec();
+        }
+    }
+
+    /// Load document content, preferring sections already preloaded from the results screen
+    async fn load_document_content(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        if self.content_sections.is_some() {
+            return Ok(()); // Already loaded
+        }
+
+        let document = match &self.current_document {
+            Some(doc) => doc.clone(),
+            None => return Ok(()),
+        };
+
+        self.is_loading = true;
+        app.set_loading("Loading document content...".to_string());
+
+        // Content preloaded from the results screen is served from the cache
+        let cached = app
+            .content_loader
+            .try_lock()
+            .ok()
+            .and_then(|loader| loader.get_cached_content(&document).cloned());
+        if let Some(sections) = cached {
+            self.content_sections = Some(sections);
+            self.current_section = 0;
+            self.is_loading = false;
+            app.set_status("Document content loaded".to_string());
             return Ok(());
         }
 
+        match self.load_content(&app.config) {
+            Ok(true) if self.archive_files.is_some() => {
+                app.set_status("Document has no text sections; listing ZIP contents".to_string())
+            }
+            Ok(true) => app.set_status("Document content loaded".to_string()),
+            // If no downloaded file found, suggest downloading
+            Ok(false) => app.set_error("Document not found locally. Use 'd' to download first.".to_string()),
+            Err(e) => app.set_error(format!("Failed to read document {}: {}", document.id, e)),
+        }
+        self.is_loading = false;
+        Ok(())
+    }
+
+    /// Handle keys while the "jump to section" prompt is open
+    fn handle_section_search_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some(input) = self.section_search.as_mut() else {
+            return;
+        };
+
         match key.code {
-            KeyCode::Tab => {
-                // Switch between modes
-                self.mode = match self.mode {
-                    ViewerMode::Info => ViewerMode::Content,
-                    ViewerMode::Content => ViewerMode::Info,
-                };
-                self.scroll_offset = 0;
+            KeyCode::Enter => {
+                let query = input.value.clone();
+                self.section_search = None;
+                self.jump_to_section(&query, app);
             }
-            KeyCode::Up => match self.mode {
-                ViewerMode::Info => {
-                    if self.scroll_offset > 0 {
-                        self.scroll_offset -= 1;
-                    }
-                }
-                ViewerMode::Content => {
-                    if self.content_sections.is_some() && self.current_section > 0 {
-                        self.current_section -= 1;
-                        self.scroll_offset = 0;
-                    }
-                }
-            },
-            KeyCode::Down => match self.mode {
-                ViewerMode::Info => {
-                    self.scroll_offset += 1;
-                }
-                ViewerMode::Content => {
-                    if let Some(ref sections) = self.content_sections {
-                        if self.current_section < sections.len() - 1 {
-                            self.current_section += 1;
-                            self.scroll_offset = 0;
-                        }
-                    }
-                }
-            },
-            KeyCode::PageUp => match self.mode {
-                ViewerMode::Info => {
-                    self.scroll_offset = self.scroll_offset.saturating_sub(10);
-                }
-                ViewerMode::Content => {
-                    self.scroll_offset = self.scroll_offset.saturating_sub(10);
-                }
-            },
-            KeyCode::PageDown => match self.mode {
-                ViewerMode::Info => {
-                    self.scroll_offset += 10;
-                }
-                ViewerMode::Content => {
-                    self.scroll_offset += 10;
-                }
-            },
-            KeyCode::Home => {
+            KeyCode::Esc => self.section_search = None,
+            KeyCode::Char(c) => input.insert_char(c),
+            KeyCode::Backspace => input.delete_char(),
+            KeyCode::Delete => input.delete_char_forward(),
+            KeyCode::Left => input.move_cursor_left(),
+            KeyCode::Right => input.move_cursor_right(),
+            _ => {}
+        }
+    }
+
+    /// Select the first section whose type or file name matches `query`, else the first containing it
+    pub fn jump_to_section(&mut self, query: &str, app: &mut super::super::app::App) {
+        let Some(sections) = self.content_sections.as_mut() else {
+            app.set_error("Load the document content first (Enter)".to_string());
+            return;
+        };
+
+        // Sections served from the preload cache are already loaded and need no path
+        let path = self.content_path.as_ref().map(|path| path.to_string_lossy().to_string()).unwrap_or_default();
+        let found = match find_section(sections, query) {
+            Some(index) => Ok(Some(index)),
+            None => find_section_by_content(&path, sections, query, self.max_content_length),
+        };
+
+        match found {
+            Ok(Some(index)) => {
+                let status = format!("Jumped to {} ({}/{})", sections[index].section_type, index + 1, sections.len());
+                self.current_section = index;
                 self.scroll_offset = 0;
-                if self.mode == ViewerMode::Content {
-                    self.current_section = 0;
-                }
+                self.load_selected_section(app);
+                app.set_status(status);
             }
-            KeyCode::End => {
-                if self.mode == ViewerMode::Content {
-                    if let Some(ref sections) = self.content_sections {
-                        self.current_section = sections.len().saturating_sub(1);
+            Ok(None) => app.set_error(format!("No section matches '{}'", query.trim())),
+            Err(e) => app.set_error(format!("Failed to search sections: {}", e)),
+        }
+    }
+
+    /// Handle keys while the metadata editor is open
+    async fn handle_metadata_editor_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) -> Result<()> {
+        let Some(editor) = self.metadata_editor.as_mut() else {
+            return Ok(());
+        };
+
+        if let Some(MetadataInput::Value(input) | MetadataInput::NewEntry(input)) = editor.input.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    if let Err(message) = editor.commit_input() {
+                        app.set_error(message);
                     }
                 }
-                self.scroll_offset = 0;
+                KeyCode::Esc => editor.input = None,
+                KeyCode::Char(c) => input.insert_char(c),
+                KeyCode::Backspace => input.delete_char(),
+                KeyCode::Delete => input.delete_char_forward(),
+                KeyCode::Left => input.move_cursor_left(),
+                KeyCode::Right => input.move_cursor_right(),
+                KeyCode::Home => input.move_cursor_to_start(),
+                KeyCode::End => input.move_cursor_to_end(),
+                _ => {}
             }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Up => editor.entries.previous(),
+            KeyCode::Down => editor.entries.next(),
             KeyCode::Enter => {
-                match self.mode {
-                    ViewerMode::Content => {
-                        // Load content if not already loaded
-                        self.load_document_content(app).await?;
-                    }
-                    ViewerMode::Info => {
-                        // Switch to content view
-                        self.mode = ViewerMode::Content;
-                        self.load_document_content(app).await?;
-                    }
+                if let Some((key, value)) = editor.entries.selected() {
+                    let mut input = InputField::new(&format!("Edit {}", key)).with_value(value);
+                    input.set_focus(true);
+                    editor.input = Some(MetadataInput::Value(input));
                 }
             }
-            KeyCode::Char('d') => {
-                // Download document
-                self.download_document(app).await?;
+            KeyCode::Char('a') => {
+                let mut input = InputField::new("New entry").with_placeholder("key=value");
+                input.set_focus(true);
+                editor.input = Some(MetadataInput::NewEntry(input));
             }
-            KeyCode::Char('r') => {
-                // Reload/refresh content
-                if self.mode == ViewerMode::Content {
-                    self.content_sections = None;
-                    self.load_document_content(app).await?;
+            KeyCode::Char('x') | KeyCode::Delete => editor.remove_selected(),
+            KeyCode::Char('s') => self.save_metadata(app).await,
+            KeyCode::Esc => {
+                if editor.is_modified {
+                    app.set_status("Metadata changes discarded".to_string());
                 }
-            }
-            KeyCode::Char('s') => {
-                // Save content to file (placeholder)
-                app.set_status("Save functionality not implemented yet".to_string());
+                self.metadata_editor = None;
             }
             _ => {}
         }
         Ok(())
     }
 
-    /// Load document content from downloaded ZIP file
-    async fn load_document_content(&mut self, app: &mut super::super::app::App) -> Result<()> {
-        if self.content_sections.is_some() {
-            return Ok(()); // Already loaded
+    /// Persist the edited metadata and refresh the copies shown by the viewer and results
+    async fn save_metadata(&mut self, app: &mut super::super::app::App) {
+        let (Some(editor), Some(document)) = (&self.metadata_editor, &mut self.current_document) else {
+            return;
+        };
+        if !editor.is_modified {
+            self.metadata_editor = None;
+            return;
         }
 
-        let document = match &self.current_document {
-            Some(doc) => doc,
-            None => return Ok(()),
+        let metadata = editor.to_metadata();
+        match storage::update_document_metadata(&document.id, &metadata, app.config.database_path_str()).await {
+            Ok(true) => {
+                if let Some(results) = app.screen_mut::<super::ResultsScreen>() {
+                    results.update_document_metadata(&document.id, &metadata);
+                }
+                document.metadata = metadata;
+                app.set_status(format!("Saved metadata for {}", document.id));
+                self.metadata_editor = None;
+            }
+            Ok(false) => app.set_error(format!("Document {} is no longer in the database", document.id)),
+            Err(e) => app.set_error(format!("Failed to save metadata: {}", e)),
+        }
+    }
+
+    /// Handle keys while the tag editor is open
+    async fn handle_tag_editor_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some(input) = self.tag_editor.as_mut() else {
+            return;
         };
 
-        self.is_loading = true;
-        app.set_status("Loading document content...".to_string());
-
-        // Construct expected download path
-        let download_dir = PathBuf::from(app.config.download_dir_str());
-        let edinet_dir = download_dir.join("edinet").join(&document.ticker);
-
-        // Look for ZIP files in the directory
-        if let Ok(entries) = std::fs::read_dir(&edinet_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    match read_edinet_zip(path.to_str().unwrap(), usize::MAX, usize::MAX) {
-                        Ok(sections) => {
-                            self.content_sections = Some(sections);
-                            self.current_section = 0;
-                            self.is_loading = false;
-                            app.set_status("Document content loaded".to_string());
-                            return Ok(());
-                        }
-                        Err(e) => {
-                            app.set_error(format!("Failed to read document: {}", e));
-                            self.is_loading = false;
-                            return Ok(());
-                        }
+        match key.code {
+            KeyCode::Enter => {
+                let tags = split_tags(&input.value);
+                self.tag_editor = None;
+                let Some(id) = self.current_document.as_ref().map(|document| document.id.clone()) else {
+                    return;
+                };
+                match app.set_document_tags(&id, &tags).await {
+                    Ok(tags) => {
+                        self.update_document_tags(&id, &tags);
+                        app.set_status(format!("Tags of {}: {}", id, if tags.is_empty() { "none".to_string() } else { tags.join(", ") }));
                     }
+                    Err(e) => app.set_error(format!("Failed to save tags: {}", e)),
                 }
             }
+            KeyCode::Esc => self.tag_editor = None,
+            KeyCode::Char(c) => input.insert_char(c),
+            KeyCode::Backspace => input.delete_char(),
+            KeyCode::Delete => input.delete_char_forward(),
+            KeyCode::Left => input.move_cursor_left(),
+            KeyCode::Right => input.move_cursor_right(),
+            _ => {}
         }
+    }
 
-        // If no downloaded file found, suggest downloading
-        app.set_error("Document not found locally. Use 'd' to download first.".to_string());
-        self.is_loading = false;
-        Ok(())
+    /// Table of contents of the listed sections, empty until content is loaded
+    fn toc(&self) -> Vec<TocEntry> {
+        self.content_sections.as_deref().map(table_of_contents).unwrap_or_default()
     }
 
-    /// Check if document is downloaded
-    pub fn is_document_downloaded(&self, app: &super::super::app::App) -> bool {
-        let document = match &self.current_document {
-            Some(doc) => doc,
-            None => return false,
+    /// Give the table of contents focus, starting from the entry of the current section
+    fn focus_toc(&mut self, app: &mut super::super::app::App) {
+        let toc = self.toc();
+        if toc.is_empty() {
+            app.set_error("Load the document content first (Enter)".to_string());
+            return;
+        }
+        if toc.len() == 1 {
+            app.set_status("The document has a single section".to_string());
+            return;
+        }
+        self.toc_cursor = toc.iter().rposition(|entry| entry.section_index == self.current_section).or(Some(0));
+    }
+
+    /// Handle keys while the table of contents has focus
+    fn handle_toc_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some(cursor) = self.toc_cursor else {
+            return;
         };
+        let toc = self.toc();
+        let last = toc.len().saturating_sub(1);
 
-        // Get the document ID from metadata for precise matching
-        let doc_id = document
-            .metadata
-            .get("doc_id")
-            .or_else(|| document.metadata.get("document_id"))
-            .unwrap_or(&document.id);
-
-        // Check if the specific ZIP file exists in download directory
-        let download_dir = std::path::PathBuf::from(app.config.download_dir_str())
-            .join("edinet")
-            .join(&document.ticker);
-
-        if let Ok(entries) = std::fs::read_dir(&download_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        // Check if this ZIP file matches our document ID
-                        if filename.contains(doc_id) {
-                            return true;
-                        }
-                    }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.toc_cursor = Some(cursor.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.toc_cursor = Some((cursor + 1).min(last)),
+            KeyCode::Home => self.toc_cursor = Some(0),
+            KeyCode::End => self.toc_cursor = Some(last),
+            KeyCode::Enter => {
+                self.toc_cursor = None;
+                if let Some(entry) = toc.get(cursor) {
+                    self.current_section = entry.section_index;
+                    self.scroll_offset = 0;
+                    self.load_selected_section(app);
+                    app.set_status(format!("Jumped to {}", entry.title));
                 }
             }
+            KeyCode::Esc | KeyCode::Char('o') => self.toc_cursor = None,
+            _ => {}
         }
-        false
     }
 
-    /// Download document
-    async fn download_document(&mut self, app: &mut super::super::app::App) -> Result<()> {
-        let document = match &self.current_document {
-            Some(doc) => doc.clone(),
-            None => return Ok(()),
+    /// Read the selected section, reporting failures in the status bar
+    /// Content lines of the section being viewed
+    fn current_section_lines(&self) -> Vec<&str> {
+        self.content_sections
+            .as_ref()
+            .and_then(|sections| sections.get(self.current_section))
+            .map(|section| section.content.lines().collect())
+            .unwrap_or_default()
+    }
+
+    /// Start selecting lines at the first content line on screen
+    fn start_selection(&mut self, app: &mut super::super::app::App) {
+        let line_count = self.current_section_lines().len();
+        if self.mode != ViewerMode::Content || line_count == 0 {
+            app.set_error("Load a section's content to select lines from it".to_string());
+            return;
+        }
+        let line = self.scroll_offset.saturating_sub(SECTION_HEADER_LINES).min(line_count - 1);
+        self.selection = Some((line, line));
+        app.set_status("Selecting lines: ↑/↓ to extend, y to copy, Esc to cancel".to_string());
+    }
+
+    fn handle_selection_event(&mut self, key: KeyEvent, app: &mut super::super::app::App) {
+        let Some((anchor, cursor)) = self.selection else {
+            return;
         };
+        let last = self.current_section_lines().len().saturating_sub(1);
+        let cursor = match key.code {
+            KeyCode::Up | KeyCode::Char('k') => cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => (cursor + 1).min(last),
+            KeyCode::PageUp => cursor.saturating_sub(10),
+            KeyCode::PageDown => (cursor + 10).min(last),
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            KeyCode::Char('y') => {
+                self.copy_selection(app);
+                self.selection = None;
+                return;
+            }
+            KeyCode::Esc | KeyCode::Char('V') => {
+                self.selection = None;
+                app.set_status("Selection cancelled".to_string());
+                return;
+            }
+            _ => return,
+        };
+        self.selection = Some((anchor, cursor));
+
+        // Keep the cursor line on screen
+        let line = cursor + SECTION_HEADER_LINES;
+        if line < self.scroll_offset {
+            self.scroll_offset = line;
+        } else if line >= self.scroll_offset + self.page_size() {
+            self.scroll_offset = line + 1 - self.page_size();
+        }
+    }
+
+    /// Copy the selected lines to the clipboard
+    fn copy_selection(&self, app: &mut super::super::app::App) {
+        let Some((anchor, cursor)) = self.selection else {
+            return;
+        };
+        let (first, last) = (anchor.min(cursor), anchor.max(cursor));
+        let text = self.current_section_lines()[first..=last].join("\n");
+        let count = last - first + 1;
+        match copy_to_clipboard(&text) {
+            Ok(()) => app.set_success(format!("Copied {} line{}", count, if count == 1 { "" } else { "s" })),
+            Err(e) => app.set_error(format!("Failed to copy selection: {}", e)),
+        }
+    }
 
-        self.is_downloading = true;
-        self.download_status = Some(format!("Downloading {}...", document.ticker));
+    fn load_selected_section(&mut self, app: &mut super::super::app::App) {
+        if let Err(e) = self.load_current_section() {
+            app.set_error(format!("Failed to read section: {}", e));
+        }
+    }
 
-        app.set_status(format!("Starting download for {}", document.ticker));
+    /// Lines scrolled by Ctrl+U/Ctrl+D (approximates the visible content height)
+    fn page_size(&self) -> usize {
+        20
+    }
 
-        let download_request = DownloadRequest {
-            source: Source::Edinet,
-            ticker: document.ticker.clone(),
-            filing_type: Some(document.filing_type.clone()),
-            date_from: Some(document.date),
-            date_to: Some(document.date),
-            limit: 1,
-            format: DocumentFormat::Complete,
+    /// Maximum scroll offset so scrolling stops at the end of the content
+    fn max_scroll_offset(&self) -> usize {
+        // Rough estimate of the visible content height; the terminal height varies in practice
+        let available_height = 20;
+        let total_lines = match self.mode {
+            ViewerMode::Content => self
+                .content_sections
+                .as_ref()
+                .and_then(|sections| sections.get(self.current_section))
+                .map(|section| SECTION_HEADER_LINES + section.content.lines().count())
+                .unwrap_or(0),
+            // Basic info, metadata and download status
+            ViewerMode::Info => 25,
         };
+        total_lines.saturating_sub(available_height)
+    }
 
-        match downloader::download_documents(&download_request, app.config.download_dir_str()).await
-        {
-            Ok(count) => {
-                app.set_status(format!("Successfully downloaded {} document(s)", count));
-                // Clear content sections to force reload
-                self.content_sections = None;
-                // Update download status
-                self.is_downloaded = self.is_document_downloaded(app);
-            }
-            Err(e) => {
-                app.set_error(format!("Download failed: {}", e));
+    /// List the sections of the current document from its downloaded ZIP, filing or summary
+    ///
+    /// Returns `Ok(false)` if the document has not been downloaded. Only the first section of an
+    /// EDINET ZIP is read up front; the others are read by `load_current_section` as they are selected.
+    pub fn load_content(&mut self, config: &Config) -> Result<bool> {
+        let Some(document) = &self.current_document else {
+            return Ok(false);
+        };
+
+        let Some(path) = local_files::find_downloaded_file(config, document) else {
+            return Ok(false);
+        };
+
+        // Binary-only filings (e.g. PDF only) list the ZIP's files instead of failing
+        self.archive_files = None;
+        let sections = match local_files::list_sections(document, &path, self.max_sections, self.max_content_length) {
+            Ok(sections) => sections,
+            Err(e) if e.downcast_ref::<NoTextSections>().is_some() => {
+                self.archive_files = Some(self.read_zip_contents(&path).unwrap_or_default());
+                Vec::new()
             }
-        }
+            Err(e) => return Err(e),
+        };
+        self.content_sections = Some(sections);
+        self.content_path = Some(path);
+        self.current_section = 0;
+        self.load_current_section()?;
+        Ok(true)
+    }
 
-        self.is_downloading = false;
-        self.download_status = None;
+    /// Read the content of the selected section if it has only been listed so far
+    pub fn load_current_section(&mut self) -> Result<()> {
+        let (Some(path), Some(sections)) = (&self.content_path, &mut self.content_sections) else {
+            return Ok(());
+        };
+
+        if let Some(section) = sections.get_mut(self.current_section) {
+            load_section_content(&path.to_string_lossy(), section, self.max_content_length)?;
+        }
         Ok(())
     }
 
-    /// Draw the viewer screen
-    pub fn draw(&mut self, f: &mut Frame, area: Rect) {
-        if self.current_document.is_none() {
-            self.draw_no_document(f, area);
+    /// Load the financial data CSVs downloaded for the document, show them as the first content
+    /// section and save them to the export directory
+    fn load_financials(&mut self, app: &mut super::super::app::App) {
+        let Some(document) = self.current_document.clone() else {
+            return;
+        };
+        if document.source != Source::Edinet {
+            app.set_error("Financial data CSVs are only available for EDINET documents".to_string());
             return;
         }
+        let doc_id = local_files::file_key(&document);
 
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Min(0),    // Content
-                Constraint::Length(3), // Mode selector/instructions
-            ])
-            .split(area);
+        let Some(path) = find_downloaded_financials(&local_files::company_dir(&app.config, &document), &doc_id) else {
+            app.set_error(format!(
+                "No CSV data downloaded for this document. Download it with: fast10k get {} --format csv",
+                doc_id
+            ));
+            return;
+        };
+        let lines = match read_edinet_financials(&path.to_string_lossy()) {
+            Ok(lines) => lines,
+            Err(e) => {
+                app.set_error(format!("Failed to read financial data: {}", e));
+                return;
+            }
+        };
 
-        // Draw title
-        self.draw_title(f, chunks[0]);
+        // Keep the document's own sections next to the financials when it is downloaded too
+        if self.content_sections.is_none() {
+            let _ = self.load_content(&app.config);
+        }
+        let sections = self.content_sections.get_or_insert_with(Vec::new);
+        sections.retain(|section| section.section_type != FINANCIALS_SECTION_TYPE);
+        sections.insert(0, financials_section(&lines, &path));
+        self.mode = ViewerMode::Content;
+        self.current_section = 0;
+        self.scroll_offset = 0;
 
-        // Draw content based on mode
-        match self.mode {
-            ViewerMode::Info => self.draw_info_mode(f, chunks[1]),
-            ViewerMode::Content => self.draw_content_mode(f, chunks[1]),
+        let export_path = app.config.export_dir.join(format!("{}-financials.csv", doc_id));
+        match export_financials(&lines, &export_path) {
+            Ok(count) => app.set_success(format!("Loaded {} financial lines; saved to {}", count, export_path.display())),
+            Err(e) => app.set_error(format!("Loaded {} financial lines but could not save them: {}", lines.len(), e)),
         }
+    }
 
-        // Draw mode selector and instructions
-        self.draw_bottom_bar(f, chunks[2]);
+    /// Look up the downloaded file of the current document
+    pub fn refresh_download_status(&mut self, config: &Config) {
+        self.downloaded_file = self
+            .current_document
+            .as_ref()
+            .and_then(|document| local_files::find_downloaded_file(config, document));
+        self.is_downloaded = self.downloaded_file.is_some();
+    }
 
-        // Draw download status if downloading
-        if self.is_downloading {
-            self.draw_download_status(f, area);
+    /// Start downloading the document in the background; `on_tick` reports the outcome
+    async fn download_document(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        let document = match &self.current_document {
+            Some(doc) => doc.clone(),
+            None => return Ok(()),
+        };
+
+        match app.download_document(&document).await {
+            Ok(document_id) => {
+                self.pending_download = Some(document_id);
+                self.download_status.set_loading(format!("Downloading {}...", document.ticker));
+                app.set_loading(format!("Downloading {}", document.ticker));
+            }
+            Err(e) => app.set_error(format!("Download failed: {}", operations::user_message(&e))),
         }
+        Ok(())
+    }
+
+    /// Report the pending download once the download manager has finished it
+    fn check_pending_download(&mut self, app: &mut super::super::app::App) {
+        let Some(document_id) = &self.pending_download else {
+            return;
+        };
+        let Some(outcome) = app.download_manager.finished_download(document_id) else {
+            return;
+        };
+
+        match outcome {
+            Ok(message) => {
+                app.set_success(message);
+                // Clear content sections to force reload
+                self.content_sections = None;
+                // Update download status
+                self.refresh_download_status(&app.config);
+            }
+            Err(e) => app.set_error(format!("Download failed: {}", e)),
+        }
+        self.pending_download = None;
+        self.download_status.clear();
     }
 
     fn draw_no_document(&self, f: &mut Frame, area: Rect) {
@@ -354,7 +731,9 @@ impl ViewerScreen {
         let document = self.current_document.as_ref().unwrap();
         let title_text = format!(
             "{} - {} ({})",
-            document.ticker, document.company_name, document.date
+            document.ticker,
+            document.company_name,
+            self.date_format.format(document.date)
         );
 
         let title = Paragraph::new(title_text)
@@ -381,7 +760,7 @@ impl ViewerScreen {
             ]),
             Line::from(vec![
                 Span::styled("Date: ", Styles::info()),
-                Span::raw(document.date.to_string()),
+                Span::raw(self.date_format.format(document.date)),
             ]),
             Line::from(vec![
                 Span::styled("Source: ", Styles::info()),
@@ -391,6 +770,14 @@ impl ViewerScreen {
                 Span::styled("Format: ", Styles::info()),
                 Span::raw(document.format.as_str()),
             ]),
+            Line::from(vec![
+                Span::styled("URL: ", Styles::info()),
+                Span::raw(document.source_url().unwrap_or_else(|| "-".to_string())),
+            ]),
+            Line::from(vec![
+                Span::styled("Tags: ", Styles::info()),
+                Span::raw(if document.tags.is_empty() { "-".to_string() } else { document.tags.join(", ") }),
+            ]),
             Line::from(""),
             Line::from(vec![
                 Span::styled("Content Path: ", Styles::info()),
@@ -410,7 +797,7 @@ impl ViewerScreen {
 
         // Add download status and file information
         all_lines.push(Line::from(""));
-        self.add_download_info(&mut all_lines, document);
+        self.add_download_info(&mut all_lines);
 
         // Apply scrolling
         let visible_lines: Vec<Line> = all_lines.into_iter().skip(self.scroll_offset).collect();
@@ -427,21 +814,73 @@ impl ViewerScreen {
         f.render_widget(info_widget, area);
     }
 
+    /// Sidebar listing the table of contents with the current section highlighted
+    fn draw_toc(&self, f: &mut Frame, area: Rect, toc: &[TocEntry]) {
+        let current = toc.iter().rposition(|entry| entry.section_index == self.current_section);
+        let items: Vec<ListItem> = toc
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let style = if Some(index) == current {
+                    Styles::title()
+                } else if entry.level == 0 {
+                    Styles::info()
+                } else {
+                    Styles::default()
+                };
+                ListItem::new(Line::from(Span::styled(format!("{}{}", "  ".repeat(entry.level), entry.title), style)))
+            })
+            .collect();
+
+        let focused = self.toc_cursor.is_some();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title("Contents")
+                    .borders(Borders::ALL)
+                    .border_style(if focused { Styles::active_border() } else { Styles::inactive_border() }),
+            )
+            .highlight_style(if focused { Styles::selected() } else { Styles::title() })
+            .highlight_symbol("> ");
+
+        // Keeps the cursor, or else the current section, scrolled into view
+        let mut state = ListState::default();
+        state.select(self.toc_cursor.or(current));
+        f.render_stateful_widget(list, area, &mut state);
+    }
+
     fn draw_content_mode(&self, f: &mut Frame, area: Rect) {
         if let Some(ref sections) = self.content_sections {
             if sections.is_empty() {
-                let empty_widget = Paragraph::new("No content sections found")
-                    .style(Styles::inactive())
+                let lines = match (&self.archive_files, &self.content_path) {
+                    (Some(files), Some(path)) => self.archive_files_lines(files, path),
+                    _ => vec![Line::from(Span::styled("No content sections found", Styles::inactive()))],
+                };
+                let empty_widget = Paragraph::new(lines)
                     .block(
                         Block::default()
                             .title("Document Content")
                             .borders(Borders::ALL)
                             .border_style(Styles::active_border()),
-                    );
+                    )
+                    .wrap(Wrap { trim: true });
                 f.render_widget(empty_widget, area);
                 return;
             }
 
+            // Table of contents on the left once there is more than one entry to choose from
+            let toc = table_of_contents(sections);
+            let area = if toc.len() > 1 {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length((area.width / 3).min(36)), Constraint::Min(0)])
+                    .split(area);
+                self.draw_toc(f, chunks[0], &toc);
+                chunks[1]
+            } else {
+                area
+            };
+
             let current_section = &sections[self.current_section];
 
             let content_lines = vec![
@@ -451,19 +890,27 @@ impl ViewerScreen {
                 ]),
                 Line::from(vec![
                     Span::styled("File: ", Styles::info()),
-                    Span::raw(&current_section.filename),
+                    Span::raw(current_section.display_path()),
                 ]),
                 Line::from(vec![
                     Span::styled("Size: ", Styles::info()),
-                    Span::raw(format!("{} characters", current_section.full_length)),
+                    Span::raw(format!(
+                        "{} characters",
+                        format_number(current_section.full_length as u64, self.locale)
+                    )),
                 ]),
                 Line::from(""),
             ];
 
-            // Add content lines
+            // Add content lines, highlighting any selection
+            let selected = self.selection.map(|(anchor, cursor)| anchor.min(cursor)..=anchor.max(cursor));
             let mut all_lines = content_lines;
-            for line in current_section.content.lines() {
-                all_lines.push(Line::from(Span::raw(line)));
+            for (index, line) in current_section.content.lines().enumerate() {
+                if selected.as_ref().is_some_and(|range| range.contains(&index)) {
+                    all_lines.push(Line::from(Span::styled(line, Styles::selected())));
+                } else {
+                    all_lines.push(Line::from(Span::raw(line)));
+                }
             }
 
             // Apply scrolling
@@ -482,15 +929,7 @@ impl ViewerScreen {
 
             f.render_widget(content_widget, area);
         } else if self.is_loading {
-            let loading_widget = Paragraph::new("Loading content...")
-                .style(Styles::info())
-                .block(
-                    Block::default()
-                        .title("Document Content")
-                        .borders(Borders::ALL)
-                        .border_style(Styles::active_border()),
-                );
-            f.render_widget(loading_widget, area);
+            StatusDisplay::loading("Loading content...").render_with_title(f, area, "Document Content");
         } else {
             // Check if document is downloaded and provide appropriate message
             let message = if self.is_downloaded {
@@ -516,9 +955,10 @@ impl ViewerScreen {
         };
 
         let instructions = match self.mode {
-            ViewerMode::Info => "Tab: Switch mode | ↑/↓: Scroll | Enter: View content",
+            ViewerMode::Content if self.selection.is_some() => "↑/↓: Extend selection | y: Copy lines | Esc: Cancel",
+            ViewerMode::Info => "Tab: Switch mode | ↑/↓: Scroll | Enter: View content | e: Edit metadata | f: Financials | y: Copy URL",
             ViewerMode::Content => {
-                "Tab: Switch mode | ↑/↓: Sections | PgUp/PgDn: Scroll | r: Reload"
+                "Tab: Switch mode | ↑/↓: Sections | PgUp/PgDn: Scroll | /: Jump to section | V: Select lines | r: Reload"
             }
         };
 
@@ -531,62 +971,83 @@ impl ViewerScreen {
         f.render_widget(bottom_widget, area);
     }
 
-    fn draw_download_status(&self, f: &mut Frame, area: Rect) {
-        use crate::edinet_tui::ui::centered_rect;
+    fn draw_metadata_editor(&mut self, f: &mut Frame, area: Rect) {
+        let Some(editor) = self.metadata_editor.as_mut() else {
+            return;
+        };
 
-        let popup_area = centered_rect(50, 20, area);
+        let popup_area = centered_rect(70, 60, area);
+        f.render_widget(ratatui::widgets::Clear, popup_area);
 
-        let default_status = "Downloading...".to_string();
-        let status_text = self.download_status.as_ref().unwrap_or(&default_status);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3), Constraint::Length(1)])
+            .split(popup_area);
+
+        let items: Vec<ListItem> = editor
+            .entries
+            .items
+            .iter()
+            .map(|(key, value)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{}: ", key), Styles::info()),
+                    Span::raw(value.as_str()),
+                ]))
+            })
+            .collect();
+        let title = if editor.is_modified { "Edit Metadata (modified)" } else { "Edit Metadata" };
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL).border_style(Styles::active_border()))
+            .highlight_style(Styles::selected())
+            .highlight_symbol("> ");
+        f.render_stateful_widget(list, chunks[0], &mut editor.entries.state);
+
+        match &editor.input {
+            Some(MetadataInput::Value(input) | MetadataInput::NewEntry(input)) => input.render(f, chunks[1]),
+            None => f.render_widget(
+                Paragraph::new("Select an entry and press Enter to edit it")
+                    .style(Styles::inactive())
+                    .block(Block::default().borders(Borders::ALL)),
+                chunks[1],
+            ),
+        }
 
-        let status_widget = Paragraph::new(format!("{}\n\nPress ESC to cancel", status_text))
-            .style(Styles::info())
-            .block(
-                Block::default()
-                    .title("Download Status")
-                    .borders(Borders::ALL)
-                    .border_style(Styles::warning()),
-            );
+        let instructions = if editor.input.is_some() {
+            "Enter: Apply | Esc: Cancel edit"
+        } else {
+            "↑/↓: Select | Enter: Edit | a: Add | x: Remove | s: Save | Esc: Close"
+        };
+        f.render_widget(Paragraph::new(instructions).style(Styles::info()), chunks[2]);
+    }
+
+    fn draw_download_status(&self, f: &mut Frame, area: Rect) {
+        let popup_area = centered_rect(50, 20, area);
 
         f.render_widget(ratatui::widgets::Clear, popup_area);
-        f.render_widget(status_widget, popup_area);
+        self.download_status.render_with_title(f, popup_area, "Download Status (Esc: cancel)");
     }
 
     /// Add download status and file information to the info display
-    fn add_download_info(&self, lines: &mut Vec<Line>, document: &Document) {
-        // Get the document ID from metadata for precise matching
-        let doc_id = document
-            .metadata
-            .get("doc_id")
-            .or_else(|| document.metadata.get("document_id"))
-            .unwrap_or(&document.id);
-
-        // Check download status and get file path - using default download path
-        // This should ideally use the config, but for now we'll use the default
-        let download_dir = std::path::PathBuf::from("./downloads")
-            .join("edinet")
-            .join(&document.ticker);
-
-        let mut downloaded_file_path = None;
-        let mut zip_contents = Vec::new();
-
-        if let Ok(entries) = std::fs::read_dir(&download_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("zip") {
-                    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                        if filename.contains(doc_id) {
-                            downloaded_file_path = Some(path.clone());
-                            // Try to read ZIP contents
-                            if let Ok(contents) = self.read_zip_contents(&path) {
-                                zip_contents = contents;
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
+    /// Copy the document's EDINET viewer or EDGAR filing index URL to the clipboard
+    fn copy_source_url(&self, app: &mut super::super::app::App) {
+        let Some(url) = self.current_document.as_ref().and_then(|document| document.source_url()) else {
+            app.set_error("This document has no web page to copy".to_string());
+            return;
+        };
+        match copy_to_clipboard(&url) {
+            Ok(()) => app.set_success(format!("Copied {}", url)),
+            Err(e) => app.set_error(format!("Failed to copy URL: {}", e)),
         }
+    }
+
+    fn add_download_info(&self, lines: &mut Vec<Line>) {
+        let downloaded_file_path = self.downloaded_file.as_ref();
+
+        // List what is inside downloaded ZIPs; EDGAR filings are single files
+        let zip_contents = downloaded_file_path
+            .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+            .and_then(|path| self.read_zip_contents(path).ok())
+            .unwrap_or_default();
 
         // Add download status
         if let Some(file_path) = downloaded_file_path {
@@ -619,14 +1080,7 @@ impl ViewerScreen {
                 lines.push(Line::from(""));
                 lines.push(Line::from(Span::styled("ZIP Contents:", Styles::info())));
                 for (filename, size) in zip_contents {
-                    let size_str = if size < 1024 {
-                        format!("{} B", size)
-                    } else if size < 1024 * 1024 {
-                        format!("{:.1} KB", size as f64 / 1024.0)
-                    } else {
-                        format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
-                    };
-                    lines.push(Line::from(format!("  {} ({})", filename, size_str)));
+                    lines.push(Line::from(format!("  {} ({})", filename, format_file_size(size))));
                 }
             }
         } else {
@@ -638,6 +1092,28 @@ impl ViewerScreen {
         }
     }
 
+    /// Explain that a downloaded ZIP has no text sections and list what it does contain
+    fn archive_files_lines(&self, files: &[(String, u64)], path: &Path) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("No text sections, only binaries/images ({} files)", files.len()),
+                Styles::warning(),
+            )),
+            Line::from(""),
+            Line::from(Span::styled("ZIP Contents:", Styles::info())),
+        ];
+        for (filename, size) in files {
+            lines.push(Line::from(format!("  {} ({})", filename, format_file_size(*size))));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "This filing can only be viewed outside the TUI, e.g. with a PDF viewer:",
+            Styles::info(),
+        )));
+        lines.push(Line::from(format!("  {}", path.display())));
+        lines
+    }
+
     /// Read ZIP file contents and return list of files with sizes
     fn read_zip_contents(
         &self,
@@ -661,3 +1137,327 @@ impl ViewerScreen {
     }
 }
 
+/// Human-readable size of a file inside a ZIP
+fn format_file_size(size: u64) -> String {
+    if size < 1024 {
+        format!("{} B", size)
+    } else if size < 1024 * 1024 {
+        format!("{:.1} KB", size as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl traits::Screen for ViewerScreen {
+    /// Draw the viewer screen
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        if self.current_document.is_none() {
+            self.draw_no_document(f, area);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Min(0),    // Content
+                Constraint::Length(3), // Mode selector/instructions
+            ])
+            .split(area);
+
+        // Draw title
+        self.draw_title(f, chunks[0]);
+
+        // Draw content based on mode
+        match self.mode {
+            ViewerMode::Info => self.draw_info_mode(f, chunks[1]),
+            ViewerMode::Content => self.draw_content_mode(f, chunks[1]),
+        }
+
+        // Draw mode selector and instructions
+        self.draw_bottom_bar(f, chunks[2]);
+
+        if self.metadata_editor.is_some() {
+            self.draw_metadata_editor(f, area);
+        }
+
+        if let Some(input) = self.section_search.as_ref().or(self.tag_editor.as_ref()) {
+            let popup_area = centered_rect(50, 20, area);
+            let popup_area = Rect { height: popup_area.height.min(3), ..popup_area };
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+            input.render(f, popup_area);
+        }
+
+        // Draw download status if downloading
+        if self.pending_download.is_some() {
+            self.draw_download_status(f, area);
+        }
+    }
+
+    /// Handle key events for the viewer screen
+    async fn handle_event(
+        &mut self,
+        key: KeyEvent,
+        app: &mut super::super::app::App,
+    ) -> Result<()> {
+        if let Some(document_id) = &self.pending_download {
+            // Only allow cancellation during download
+            if let KeyCode::Esc = key.code {
+                app.download_manager.cancel_download(document_id);
+                self.pending_download = None;
+                self.download_status.clear();
+                app.set_status("Download cancelled".to_string());
+            }
+            return Ok(());
+        }
+
+        if self.metadata_editor.is_some() {
+            return self.handle_metadata_editor_event(key, app).await;
+        }
+
+        if self.section_search.is_some() {
+            self.handle_section_search_event(key, app);
+            return Ok(());
+        }
+
+        if self.tag_editor.is_some() {
+            self.handle_tag_editor_event(key, app).await;
+            return Ok(());
+        }
+
+        if self.toc_cursor.is_some() {
+            self.handle_toc_event(key, app);
+            return Ok(());
+        }
+
+        if self.selection.is_some() {
+            self.handle_selection_event(key, app);
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Tab => {
+                // Switch between modes
+                self.mode = match self.mode {
+                    ViewerMode::Info => ViewerMode::Content,
+                    ViewerMode::Content => ViewerMode::Info,
+                };
+                self.scroll_offset = 0;
+            }
+            KeyCode::Up => {
+                // Scroll up in all modes
+                self.scroll_offset = self.scroll_offset.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                // Scroll down in all modes with bounds checking
+                if self.scroll_offset < self.max_scroll_offset() {
+                    self.scroll_offset += 1;
+                }
+            }
+            KeyCode::Left => {
+                // Previous section in Content mode
+                if self.mode == ViewerMode::Content
+                    && self.content_sections.is_some()
+                    && self.current_section > 0
+                {
+                    self.current_section -= 1;
+                    self.scroll_offset = 0;
+                    self.load_selected_section(app);
+                }
+            }
+            KeyCode::Right => {
+                // Next section in Content mode
+                if self.mode == ViewerMode::Content {
+                    if let Some(ref sections) = self.content_sections {
+                        if self.current_section < sections.len() - 1 {
+                            self.current_section += 1;
+                            self.scroll_offset = 0;
+                            self.load_selected_section(app);
+                        }
+                    }
+                }
+            }
+            KeyCode::PageUp => {
+                self.scroll_offset = self.scroll_offset.saturating_sub(10);
+            }
+            KeyCode::PageDown => {
+                // Page down with bounds checking
+                self.scroll_offset = std::cmp::min(self.scroll_offset + 10, self.max_scroll_offset());
+            }
+            KeyCode::Home => {
+                self.scroll_offset = 0;
+                if self.mode == ViewerMode::Content {
+                    self.current_section = 0;
+                    self.load_selected_section(app);
+                }
+            }
+            KeyCode::End => {
+                if self.mode == ViewerMode::Content {
+                    if let Some(ref sections) = self.content_sections {
+                        self.current_section = sections.len().saturating_sub(1);
+                    }
+                    self.load_selected_section(app);
+                }
+                self.scroll_offset = 0;
+            }
+            KeyCode::Enter => {
+                // Switch to content view and load content if not already loaded
+                self.mode = ViewerMode::Content;
+                self.load_document_content(app).await?;
+            }
+            KeyCode::Char('d') => {
+                // Check for Ctrl+D first (vim-like page down)
+                if key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.scroll_offset =
+                        std::cmp::min(self.scroll_offset + self.page_size(), self.max_scroll_offset());
+                    app.set_status("Scroll down one page".to_string());
+                } else {
+                    // Regular 'd' - Download document
+                    self.download_document(app).await?;
+                }
+            }
+            KeyCode::Char('u') => {
+                // Ctrl+U (vim-like page up); regular 'u' has no function in the viewer
+                if key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.scroll_offset = self.scroll_offset.saturating_sub(self.page_size());
+                    app.set_status("Scroll up one page".to_string());
+                }
+            }
+            KeyCode::Char('r') => {
+                // Reload/refresh content
+                if self.mode == ViewerMode::Content {
+                    self.content_sections = None;
+                    self.load_document_content(app).await?;
+                }
+            }
+            KeyCode::Char('e') => {
+                // Edit metadata from the Info mode
+                if self.mode == ViewerMode::Info {
+                    if let Some(document) = &self.current_document {
+                        self.metadata_editor = Some(MetadataEditor::new(&document.metadata));
+                    }
+                }
+            }
+            KeyCode::Char('/') => {
+                // Jump to a section by name or keyword in Content mode
+                if self.mode == ViewerMode::Content {
+                    let mut input =
+                        InputField::new("Jump to section").with_placeholder("e.g. risk, 事業等のリスク, keyword");
+                    input.set_focus(true);
+                    self.section_search = Some(input);
+                }
+            }
+            KeyCode::Char('o') => {
+                // Focus the table of contents in Content mode
+                if self.mode == ViewerMode::Content {
+                    self.focus_toc(app);
+                }
+            }
+            KeyCode::Char('t') => {
+                // Edit the document's tags
+                if let Some(document) = &self.current_document {
+                    self.tag_editor = Some(tag_input(&document.tags));
+                }
+            }
+            KeyCode::Char('f') => {
+                // Load financials from a CSV-format download
+                self.load_financials(app);
+            }
+            KeyCode::Char('y') => {
+                // Copy the document's web page URL
+                self.copy_source_url(app);
+            }
+            KeyCode::Char('V') => {
+                // Select lines of the current section to copy
+                self.start_selection(app);
+            }
+            KeyCode::Char('s') => {
+                // Save content to file (placeholder)
+                app.set_status("Save functionality not implemented yet".to_string());
+            }
+            KeyCode::Esc => {
+                // Viewer screen: ESC goes back to Results and clears any pending vim command
+                self.pending_g_key = false;
+                app.navigate_to_screen(Screen::Results);
+            }
+            KeyCode::Char('g') => {
+                // Vim-like "gg" command (go to top of content)
+                if self.pending_g_key {
+                    self.scroll_offset = 0;
+                    self.pending_g_key = false;
+                    app.set_status("Top of content".to_string());
+                } else {
+                    self.pending_g_key = true;
+                    app.set_status("Press 'g' again to go to top".to_string());
+                }
+            }
+            KeyCode::Char('G') => {
+                // Vim-like "G" command (go to bottom of content)
+                self.pending_g_key = false;
+                self.scroll_offset = self.max_scroll_offset();
+                app.set_status("Bottom of content".to_string());
+            }
+            _ => {
+                // Clear pending vim commands on any other key
+                if self.pending_g_key {
+                    self.pending_g_key = false;
+                    app.set_status("Command cancelled".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn title(&self) -> &str {
+        "Document Viewer"
+    }
+
+    async fn on_tick(&mut self, app: &mut super::super::app::App) -> Result<()> {
+        self.check_pending_download(app);
+        Ok(())
+    }
+
+    fn is_capturing_text(&self) -> bool {
+        self.section_search.is_some() || self.tag_editor.is_some() || self.metadata_editor.as_ref().is_some_and(|editor| editor.input.is_some())
+    }
+
+    fn help(&self) -> &str {
+        match self.mode {
+            ViewerMode::Info => {
+                "Document Viewer (Info):\n\
+                Enter - Load content\n\
+                Tab - Switch to content\n\
+                d - Download document\n\
+                e - Edit metadata\n\
+                t - Edit tags\n\
+                f - Load financials from a CSV-format download\n\
+                y - Copy the EDINET/EDGAR page URL\n\
+                ↑/↓ - Scroll\n\
+                Page Up/Down - Large scroll jumps\n\
+                Ctrl+U/D - Page scroll (vim-like)\n\
+                gg - Go to top (vim-like)\n\
+                G - Go to bottom (vim-like)"
+            }
+            ViewerMode::Content => {
+                "Document Viewer (Content):\n\
+                ←/→ - Change sections\n\
+                ↑/↓ - Scroll\n\
+                o - Table of contents (↑/↓ select, Enter jump)\n\
+                / - Jump to section by name or keyword\n\
+                V - Select lines (↑/↓ extend, y copy, Esc cancel)\n\
+                Tab - Switch to info\n\
+                t - Edit tags\n\
+                f - Load financials from a CSV-format download\n\
+                y - Copy the EDINET/EDGAR page URL\n\
+                r - Reload content\n\
+                d - Download document\n\
+                Page Up/Down - Large scroll jumps\n\
+                Ctrl+U/D - Page scroll (vim-like)\n\
+                gg - Go to top (vim-like)\n\
+                G - Go to bottom (vim-like)"
+            }
+        }
+    }
+}
+
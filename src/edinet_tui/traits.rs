@@ -6,8 +6,9 @@
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{layout::Rect, Frame};
+use std::any::Any;
 
-use crate::edinet_tui::app::Screen as ScreenType;
+use crate::edinet_tui::app::{App, Screen as ScreenType};
 
 /// Actions that can be returned from screen event handling
 #[derive(Debug, Clone, PartialEq)]
@@ -28,8 +29,58 @@ pub enum ScreenAction {
     None,
 }
 
-/// Core trait for all TUI screens
-pub trait Screen {
+/// Core trait for the screens registered with `App`
+///
+/// `App` keeps each screen as a `Box<dyn Screen>` keyed by its `ScreenType`, so adding a
+/// screen only needs a new `ScreenType` variant and a `register_screen` call.
+#[async_trait::async_trait(?Send)]
+pub trait Screen: AsAny {
+    /// Draw the screen content
+    fn draw(&mut self, f: &mut Frame, area: Rect);
+
+    /// Handle keyboard input, updating the app (status, navigation, other screens) directly
+    async fn handle_event(&mut self, key: KeyEvent, app: &mut App) -> Result<()>;
+
+    /// Name shown in the status bar
+    fn title(&self) -> &str;
+
+    /// Screen-specific shortcuts shown in the F1 help popup
+    ///
+    /// Lines of the form `key - description` also make up the key legend above the status bar,
+    /// most important first, so screens with modes should return the current mode's shortcuts.
+    fn help(&self) -> &str;
+
+    /// Whether typed characters go to a text input, so global letter shortcuts are skipped
+    fn is_capturing_text(&self) -> bool {
+        false
+    }
+
+    /// Called on every main loop iteration, even without input, to pick up finished background work
+    ///
+    /// Every registered screen is ticked, not just the visible one.
+    async fn on_tick(&mut self, _app: &mut App) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Downcasting support so `App` can hand out typed access to its boxed screens
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Standalone screen that reports its effects as `ScreenAction`s instead of updating `App`
+pub trait ActionScreen {
     /// Draw the screen content
     fn draw(&mut self, f: &mut Frame, area: Rect);
     
@@ -1,12 +1,17 @@
 //! Common UI components and utilities for the EDINET TUI
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, ListState, Paragraph, Wrap},
     Frame,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::io::Write;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::models::FilingType;
 
 /// Common UI styles
 pub struct Styles;
@@ -63,6 +68,22 @@ impl Styles {
         Style::default()
             .fg(Color::Gray)
     }
+
+    /// Color by filing category: annual green, quarterly/semi-annual blue, material events red
+    pub fn filing_type(filing_type: &FilingType) -> Style {
+        match filing_type {
+            FilingType::TenK | FilingType::AnnualSecuritiesReport => Style::default().fg(Color::Green),
+            FilingType::TenQ | FilingType::QuarterlySecuritiesReport | FilingType::SemiAnnualSecuritiesReport => {
+                Style::default().fg(Color::LightBlue)
+            }
+            FilingType::EightK | FilingType::ExtraordinaryReport => {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            }
+            FilingType::FundReport => Style::default().fg(Color::Magenta),
+            FilingType::Transcript | FilingType::PressRelease => Style::default().fg(Color::Cyan),
+            FilingType::Other(_) => Style::default(),
+        }
+    }
 }
 
 /// Selectable list widget with state
@@ -171,12 +192,12 @@ impl InputField {
 
     pub fn insert_char(&mut self, c: char) {
         self.value.insert(self.cursor_position, c);
-        self.cursor_position += 1;
+        self.cursor_position += c.len_utf8();
     }
 
     pub fn delete_char(&mut self) {
         if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+            self.move_cursor_left();
             self.value.remove(self.cursor_position);
         }
     }
@@ -187,15 +208,16 @@ impl InputField {
         }
     }
 
+    /// Cursor positions are byte offsets, so move by whole characters for Japanese input
     pub fn move_cursor_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+        if let Some(c) = self.value[..self.cursor_position].chars().next_back() {
+            self.cursor_position -= c.len_utf8();
         }
     }
 
     pub fn move_cursor_right(&mut self) {
-        if self.cursor_position < self.value.len() {
-            self.cursor_position += 1;
+        if let Some(c) = self.value[self.cursor_position..].chars().next() {
+            self.cursor_position += c.len_utf8();
         }
     }
 
@@ -249,7 +271,7 @@ impl InputField {
 
         // Render cursor if focused
         if self.is_focused {
-            let cursor_x = area.x + 1 + self.cursor_position as u16;
+            let cursor_x = area.x + 1 + self.value[..self.cursor_position].width() as u16;
             let cursor_y = area.y + 1;
             if cursor_x < area.x + area.width - 1 {
                 f.set_cursor(cursor_x, cursor_y);
@@ -258,57 +280,37 @@ impl InputField {
     }
 }
 
-/// Table-like display for documents
-pub fn render_document_table(
-    f: &mut Frame,
-    area: Rect,
-    documents: &[crate::models::Document],
-    selected_index: Option<usize>,
-    title: &str,
-) {
-    let items: Vec<ListItem> = documents
-        .iter()
-        .enumerate()
-        .map(|(i, doc)| {
-            let style = if Some(i) == selected_index {
-                Styles::selected()
-            } else {
-                Style::default()
-            };
-
-            let content = format!(
-                "{} | {} | {} | {} | {}",
-                doc.date,
-                doc.ticker.get(0..8).unwrap_or(&doc.ticker),
-                doc.company_name.get(0..20).unwrap_or(&doc.company_name),
-                doc.filing_type.as_str().get(0..8).unwrap_or(doc.filing_type.as_str()),
-                doc.format.as_str()
-            );
-
-            ListItem::new(Line::from(Span::styled(content, style)))
-        })
-        .collect();
-
-    let header = Line::from(vec![
-        Span::styled("Date      ", Styles::title()),
-        Span::styled("| Symbol   ", Styles::title()),
-        Span::styled("| Company             ", Styles::title()),
-        Span::styled("| Type     ", Styles::title()),
-        Span::styled("| Format", Styles::title()),
-    ]);
-
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_style(Styles::active_border());
-
-    // Create list with header
-    let mut list_items = vec![ListItem::new(header)];
-    list_items.extend(items);
-
-    let list = List::new(list_items).block(block);
-
-    f.render_widget(list, area);
+/// Truncate or pad a string to exactly `max_width` terminal columns
+///
+/// Width is measured in display columns, so full-width CJK characters count as two
+/// and combining marks as zero. Truncated strings end with `…`.
+pub fn truncate_string(s: &str, max_width: usize) -> String {
+    let display_width = s.width();
+    if display_width <= max_width {
+        // Pad with spaces to reach exact width
+        let padding = max_width - display_width;
+        format!("{}{}", s, " ".repeat(padding))
+    } else if max_width == 0 {
+        String::new()
+    } else {
+        // Truncate by character until we fit within max_width - 1 (for ellipsis)
+        let target_width = max_width - 1;
+        let mut truncated = String::new();
+        let mut current_width = 0;
+
+        for ch in s.chars() {
+            let ch_width = ch.width().unwrap_or(0);
+            if current_width + ch_width > target_width {
+                break;
+            }
+            truncated.push(ch);
+            current_width += ch_width;
+        }
+
+        // Add ellipsis and pad to exact width (a wide char may leave one column free)
+        let padding_needed = target_width - current_width;
+        format!("{}…{}", truncated, " ".repeat(padding_needed))
+    }
 }
 
 /// Center a rectangle within another rectangle
@@ -337,6 +339,88 @@ pub fn popup_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     centered_rect(percent_x, percent_y, r)
 }
 
+/// Smallest terminal size the full layout of bars, tables and popups is drawn at
+pub const MIN_TERMINAL_WIDTH: u16 = 80;
+pub const MIN_TERMINAL_HEIGHT: u16 = 24;
+
+/// Whether `area` is too small for the full layout
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_TERMINAL_WIDTH || area.height < MIN_TERMINAL_HEIGHT
+}
+
+/// Draw only a notice asking for a larger terminal, in place of a layout that would not fit
+pub fn draw_too_small(f: &mut Frame, area: Rect) {
+    let text = format!(
+        "Terminal too small (need at least {}x{}, have {}x{})",
+        MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT, area.width, area.height
+    );
+    // Vertically centered, wrapping onto more lines when even the message is too wide
+    let mut lines = vec![Line::from(""); (area.height.saturating_sub(1) / 2) as usize];
+    lines.push(Line::from(text));
+    let notice = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(Clear, area);
+    f.render_widget(notice, area);
+}
+
+/// Tags typed as a comma-separated list, e.g. `reviewed, M&A`
+pub fn split_tags(input: &str) -> Vec<String> {
+    input.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect()
+}
+
+/// Input field for editing a document's tags, prefilled with the current ones
+pub fn tag_input(tags: &[String]) -> InputField {
+    let mut input = InputField::new("Tags (comma-separated)")
+        .with_placeholder("e.g. reviewed, M&A, follow-up")
+        .with_value(&tags.join(", "));
+    input.set_focus(true);
+    input
+}
+
+/// OSC 52 escape sequence asking the terminal to put `text` on the system clipboard
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", BASE64.encode(text))
+}
+
+/// Copy `text` to the clipboard through the terminal, which also works over SSH
+///
+/// Terminals without OSC 52 support (or with it disabled) silently ignore the request.
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+/// Shortcuts from a screen's help text, i.e. its `key - description` lines, in order
+///
+/// Parenthesized notes are dropped from the descriptions to keep the legend compact.
+pub fn key_legend(help: &str) -> Vec<(&str, &str)> {
+    help.lines()
+        .filter_map(|line| line.trim().split_once(" - "))
+        .map(|(key, description)| (key, description.split(" (").next().unwrap_or(description)))
+        .collect()
+}
+
+/// One-line legend of the shortcuts in `help` that fit in `width` columns
+pub fn key_legend_line(help: &str, width: usize) -> Line<'_> {
+    let mut spans = Vec::new();
+    let mut used = 0;
+    for (key, description) in key_legend(help) {
+        let separator = if spans.is_empty() { "" } else { "  " };
+        let entry_width = separator.width() + key.width() + 1 + description.width();
+        if used + entry_width > width {
+            break;
+        }
+        used += entry_width;
+        spans.push(Span::raw(separator));
+        spans.push(Span::styled(key, Styles::info().add_modifier(Modifier::BOLD)));
+        spans.push(Span::raw(format!(" {}", description)));
+    }
+    Line::from(spans)
+}
+
 /// Text wrapping utility
 pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
     let mut lines = Vec::new();
@@ -375,4 +459,104 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
         }
     }
     lines
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_tags() {
+        assert_eq!(split_tags(" reviewed, M&A ,,"), vec!["reviewed".to_string(), "M&A".to_string()]);
+        assert!(split_tags("  ").is_empty());
+    }
+
+    #[test]
+    fn test_truncate_string_pads_short_input() {
+        assert_eq!(truncate_string("7203", 6), "7203  ");
+        assert_eq!(truncate_string("トヨタ", 8), "トヨタ  ");
+        assert_eq!(truncate_string("", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_string_exact_width() {
+        assert_eq!(truncate_string("abcdef", 6), "abcdef");
+        assert_eq!(truncate_string("トヨタ", 6), "トヨタ");
+        assert_eq!(truncate_string("abcdefg", 6), "abcde…");
+        assert_eq!(truncate_string("abc", 0), "");
+        assert_eq!(truncate_string("abc", 1), "…");
+    }
+
+    #[test]
+    fn test_truncate_string_full_width() {
+        // Each kana is two columns; a wide char that would straddle the limit is dropped
+        let truncated = truncate_string("トヨタ自動車株式会社", 8);
+        assert_eq!(truncated, "トヨタ… ");
+        assert_eq!(truncated.width(), 8);
+        assert_eq!(truncate_string("トヨタ自動車株式会社", 9), "トヨタ自…");
+    }
+
+    #[test]
+    fn test_key_legend_fits_width() {
+        let help = "Search Results:\n\
+            ↑/↓ - Navigate documents\n\
+            d - Download document\n\
+            p - Show/hide preview (metadata)\n\
+            Type in text fields";
+        assert_eq!(
+            key_legend(help),
+            vec![("↑/↓", "Navigate documents"), ("d", "Download document"), ("p", "Show/hide preview")]
+        );
+
+        let line = key_legend_line(help, 45);
+        let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "↑/↓ Navigate documents  d Download document");
+        assert!(line.width() <= 45);
+    }
+
+    #[test]
+    fn test_input_field_multibyte_editing() {
+        let mut input = InputField::new("Value").with_value("トヨタ");
+        input.insert_char('株');
+        input.move_cursor_left();
+        input.move_cursor_left();
+        input.delete_char();
+        assert_eq!(input.value, "トタ株");
+        input.move_cursor_right();
+        input.delete_char_forward();
+        assert_eq!(input.value, "トタ");
+    }
+
+    #[test]
+    fn test_too_small_terminal_shows_notice() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        assert!(!is_too_small(Rect::new(0, 0, 80, 24)));
+        assert!(is_too_small(Rect::new(0, 0, 79, 40)));
+        assert!(is_too_small(Rect::new(0, 0, 120, 10)));
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 5)).unwrap();
+        terminal.draw(|f| draw_too_small(f, f.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..5)
+            .map(|y| (0..60).map(|x| buffer.get(x, y).symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains("Terminal too small (need at least 80x24,"), "{}", text);
+        assert!(text.contains("have 60x5)"), "{}", text);
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("https://example.com/?S100ABCD,,"), "\x1b]52;c;aHR0cHM6Ly9leGFtcGxlLmNvbS8/UzEwMEFCQ0QsLA==\x07");
+    }
+
+    #[test]
+    fn test_truncate_string_combining_marks() {
+        // "e" + combining acute accent is one column wide
+        let accented = "Cafe\u{301} Société";
+        assert_eq!(truncate_string(accented, 4), "Caf…");
+        assert_eq!(truncate_string(accented, 5), "Cafe\u{301}…");
+        assert_eq!(truncate_string(accented, 12), accented);
+    }
+}
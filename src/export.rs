@@ -0,0 +1,292 @@
+//! Export of document search results to CSV or JSON files
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::models::Document;
+
+/// Output file format for exported documents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn file_extension(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!("Unsupported export format: {}. Supported formats: csv, json", other)),
+        }
+    }
+}
+
+/// CSV header written by `ExportRow`, in field order
+const EXPORT_COLUMNS: [&str; 10] =
+    ["id", "ticker", "company_name", "filing_type", "source", "date", "format", "content_path", "source_url", "tags"];
+
+/// Flat row written for each exported document; keep `EXPORT_COLUMNS` in step with its fields
+#[derive(Debug, Serialize)]
+struct ExportRow<'a> {
+    id: &'a str,
+    ticker: &'a str,
+    company_name: &'a str,
+    filing_type: &'a str,
+    source: &'a str,
+    date: String,
+    format: &'a str,
+    content_path: String,
+    /// Filing page on EDGAR or EDINET, empty when there is none
+    source_url: String,
+    /// Tags separated by `; `
+    tags: String,
+}
+
+impl<'a> From<&'a Document> for ExportRow<'a> {
+    fn from(doc: &'a Document) -> Self {
+        Self {
+            id: &doc.id,
+            ticker: &doc.ticker,
+            company_name: &doc.company_name,
+            filing_type: doc.filing_type.as_str(),
+            source: doc.source.as_str(),
+            date: doc.date.format("%Y-%m-%d").to_string(),
+            format: doc.format.as_str(),
+            content_path: doc.content_path.to_string_lossy().to_string(),
+            source_url: doc.source_url().unwrap_or_default(),
+            tags: doc.tags.join("; "),
+        }
+    }
+}
+
+/// Write documents to `path` in the given format, returning the number of rows written
+pub fn export_documents(documents: &[Document], path: &Path, format: ExportFormat) -> Result<usize> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Cannot create export directory: {}", parent.display()))?;
+    }
+
+    let file = File::create(path)
+        .with_context(|| format!("Cannot create export file: {}", path.display()))?;
+    let rows: Vec<ExportRow> = documents.iter().map(ExportRow::from).collect();
+
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(file);
+            for row in &rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(BufWriter::new(file), &rows)?;
+        }
+    }
+
+    Ok(rows.len())
+}
+
+/// Add documents not already in the export at `path` to it, returning the number of rows added
+///
+/// Rows are matched by document id. A missing file is created as by [`export_documents`]; CSV rows
+/// are appended without repeating the header and JSON files are rewritten with the new rows last.
+/// A CSV file whose header differs from the current columns is refused rather than mixed.
+pub fn append_documents(documents: &[Document], path: &Path, format: ExportFormat) -> Result<usize> {
+    if !path.exists() {
+        return export_documents(documents, path, format);
+    }
+    if format == ExportFormat::Csv {
+        check_csv_columns(path)?;
+    }
+
+    let mut seen = existing_ids(path, format)?;
+    let rows: Vec<ExportRow> = documents
+        .iter()
+        .filter(|doc| seen.insert(doc.id.clone()))
+        .map(ExportRow::from)
+        .collect();
+
+    match format {
+        ExportFormat::Csv => {
+            let file = OpenOptions::new()
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Cannot open export file: {}", path.display()))?;
+            let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+            for row in &rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::Json => {
+            let mut values = read_json_rows(path)?;
+            for row in &rows {
+                values.push(serde_json::to_value(row)?);
+            }
+            let file = File::create(path)
+                .with_context(|| format!("Cannot create export file: {}", path.display()))?;
+            serde_json::to_writer_pretty(BufWriter::new(file), &values)?;
+        }
+    }
+
+    Ok(rows.len())
+}
+
+/// Fail unless the CSV export at `path` has the columns new rows are written with
+fn check_csv_columns(path: &Path) -> Result<()> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Cannot read export file: {}", path.display()))?;
+    let headers = reader.headers()?;
+    if !headers.iter().eq(EXPORT_COLUMNS) {
+        anyhow::bail!(
+            "Cannot append to {}: its columns ({}) differ from the export columns ({}); export to a new file instead",
+            path.display(),
+            headers.iter().collect::<Vec<_>>().join(","),
+            EXPORT_COLUMNS.join(",")
+        );
+    }
+    Ok(())
+}
+
+/// Document ids already in an export file
+fn existing_ids(path: &Path, format: ExportFormat) -> Result<HashSet<String>> {
+    match format {
+        ExportFormat::Csv => {
+            let mut reader = csv::Reader::from_path(path)
+                .with_context(|| format!("Cannot read export file: {}", path.display()))?;
+            let id_column = reader
+                .headers()?
+                .iter()
+                .position(|header| header == "id")
+                .with_context(|| format!("{} has no id column", path.display()))?;
+            let mut ids = HashSet::new();
+            for record in reader.records() {
+                if let Some(id) = record?.get(id_column) {
+                    ids.insert(id.to_string());
+                }
+            }
+            Ok(ids)
+        }
+        ExportFormat::Json => Ok(read_json_rows(path)?
+            .iter()
+            .filter_map(|row| row.get("id").and_then(|id| id.as_str()).map(str::to_string))
+            .collect()),
+    }
+}
+
+fn read_json_rows(path: &Path) -> Result<Vec<serde_json::Value>> {
+    let file = File::open(path).with_context(|| format!("Cannot read export file: {}", path.display()))?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .with_context(|| format!("{} is not a JSON export", path.display()))
+}
+
+/// Build a timestamped export file path inside `dir`, e.g. `exports/fast10k-20240401-120000.csv`
+pub fn timestamped_export_path(dir: &Path, format: ExportFormat) -> PathBuf {
+    dir.join(format!(
+        "fast10k-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.file_extension()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DocumentFormat, FilingType, Source};
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_export_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out").join("results.csv");
+        let documents = vec![Document {
+            id: "S100TEST".to_string(),
+            ticker: "7203".to_string(),
+            company_name: "Toyota, Inc.".to_string(),
+            filing_type: FilingType::AnnualSecuritiesReport,
+            source: Source::Edinet,
+            date: NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+            content_path: PathBuf::new(),
+            metadata: HashMap::new(),
+            format: DocumentFormat::Complete,
+            tags: vec!["reviewed".to_string(), "M&A".to_string()],
+        }];
+
+        assert_eq!(export_documents(&documents, &path, ExportFormat::Csv).unwrap(), 1);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let mut lines = contents.lines();
+        assert_eq!(lines.next(), Some("id,ticker,company_name,filing_type,source,date,format,content_path,source_url,tags"));
+        assert_eq!(
+            lines.next(),
+            Some("S100TEST,7203,\"Toyota, Inc.\",Annual Securities Report,EDINET,2024-04-01,complete,,\"https://disclosure2.edinet-fsa.go.jp/WZEK0040.aspx?S100TEST,,\",reviewed; M&A")
+        );
+    }
+
+    #[test]
+    fn test_append_skips_existing_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let document = |id: &str| Document {
+            id: id.to_string(),
+            ticker: "7203".to_string(),
+            company_name: "Toyota".to_string(),
+            filing_type: FilingType::AnnualSecuritiesReport,
+            source: Source::Edinet,
+            date: NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+            content_path: PathBuf::new(),
+            metadata: HashMap::new(),
+            format: DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+
+        for format in [ExportFormat::Csv, ExportFormat::Json] {
+            let path = dir.path().join(format!("results.{}", format.file_extension()));
+            assert_eq!(append_documents(&[document("S100AAAA")], &path, format).unwrap(), 1);
+            assert_eq!(append_documents(&[document("S100AAAA"), document("S100BBBB")], &path, format).unwrap(), 1);
+            assert_eq!(existing_ids(&path, format).unwrap().len(), 2);
+        }
+
+        let contents = std::fs::read_to_string(dir.path().join("results.csv")).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+        assert_eq!(contents.matches("id,ticker").count(), 1);
+        assert_eq!(contents.lines().next(), Some(EXPORT_COLUMNS.join(",").as_str()));
+    }
+
+    #[test]
+    fn test_append_rejects_other_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.csv");
+        // An export from before source URLs and tags were written
+        let old = "id,ticker,company_name,filing_type,source,date,format,content_path\n\
+                   S100AAAA,7203,Toyota,Annual Securities Report,EDINET,2024-04-01,complete,\n";
+        std::fs::write(&path, old).unwrap();
+
+        let err = append_documents(&[crate::test_support::test_document("S100BBBB")], &path, ExportFormat::Csv).unwrap_err().to_string();
+        assert!(err.contains("Cannot append to"), "{}", err);
+        assert!(err.contains("source_url"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), old);
+    }
+}
@@ -0,0 +1,214 @@
+//! Indexing of downloaded EDGAR filings into the SQLite catalog
+//!
+//! Filings are found by the `{form}-{date}-{accession}` names the EDGAR downloader gives them
+//! under `<ticker>/`. Their text is extracted several files at a time, while the database
+//! inserts run one after another as the extractions finish.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, info};
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::downloader::PARTIAL_DOWNLOAD_SUFFIX;
+use crate::edinet::reader::read_text_file;
+use crate::models::{Document, DocumentFormat, FilingType, Source};
+use crate::storage::Storage;
+
+/// Form, filing date and accession number read from a downloaded filing's file name
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FilingName {
+    pub(crate) form: String,
+    pub(crate) date: NaiveDate,
+    pub(crate) accession_number: String,
+}
+
+/// A downloaded filing found on disk, filed under its ticker's directory
+#[derive(Debug)]
+pub(crate) struct FilingFile {
+    pub(crate) path: PathBuf,
+    pub(crate) ticker: String,
+    pub(crate) name: FilingName,
+}
+
+/// Index the EDGAR filings downloaded under `input`, with the settings from the environment
+pub async fn index_documents(input: &str, database_path: &str) -> Result<usize> {
+    let config = Config::from_env()?;
+    index_documents_with_config(Path::new(input), database_path, &config).await
+}
+
+/// Index the EDGAR filings under `input`, reading `config.indexing.file_concurrency` files at once
+///
+/// EDINET ZIPs and JSON summaries are left to their own indexers. Returns the number of filings indexed.
+pub async fn index_documents_with_config(input: &Path, database_path: &str, config: &Config) -> Result<usize> {
+    let files = find_filings(input);
+    info!("Indexing {} filings from {}", files.len(), input.display());
+    let indexed = index_filing_files(files, database_path, config).await?;
+
+    info!("Indexed {} EDGAR filings from {}", indexed, input.display());
+    Ok(indexed)
+}
+
+/// Index downloaded filings, reading `config.indexing.file_concurrency` files at once
+pub(crate) async fn index_filing_files(files: Vec<FilingFile>, database_path: &str, config: &Config) -> Result<usize> {
+    let concurrency = config.indexing.file_concurrency.max(1);
+    debug!("Extracting {} filings ({} at a time)", files.len(), concurrency);
+
+    let storage = Storage::new(database_path).await?;
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let preview_length = config.indexing.content_preview_length;
+    let mut extractions = JoinSet::new();
+    for file in files {
+        let semaphore = Arc::clone(&semaphore);
+        extractions.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("indexing semaphore is never closed");
+            tokio::task::spawn_blocking(move || filing_document(file, preview_length)).await
+        });
+    }
+
+    // Only this task writes to the database, so inserts never contend with each other
+    let mut indexed = 0;
+    while let Some(result) = extractions.join_next().await {
+        let document = result??;
+        storage.insert_document(&document).await?;
+        indexed += 1;
+    }
+    Ok(indexed)
+}
+
+/// Downloaded filings under `input`, in path order
+pub(crate) fn find_filings(input: &Path) -> Vec<FilingFile> {
+    let mut files: Vec<FilingFile> = WalkDir::new(input)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| filing_file(entry.into_path()))
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// The filing at `path`, if it is named like a finished EDGAR download
+pub(crate) fn filing_file(path: PathBuf) -> Option<FilingFile> {
+    let file_name = path.file_name()?.to_str()?;
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    if file_name.ends_with(PARTIAL_DOWNLOAD_SUFFIX) || matches!(extension.as_str(), "zip" | "json") {
+        return None;
+    }
+    let name = parse_filing_name(path.file_stem()?.to_str()?)?;
+    let ticker = path.parent()?.file_name()?.to_str()?.to_string();
+    Some(FilingFile { path, ticker, name })
+}
+
+/// Parse `{form}-{date}-{accession}`, e.g. `10-K-2024-11-01-000032019324000123`
+fn parse_filing_name(stem: &str) -> Option<FilingName> {
+    let mut parts = stem.rsplitn(5, '-');
+    let accession = parts.next()?;
+    let (day, month, year) = (parts.next()?, parts.next()?, parts.next()?);
+    let form = parts.next().filter(|form| !form.is_empty())?;
+    if accession.len() != 18 || !accession.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(FilingName {
+        form: form.to_string(),
+        date: NaiveDate::parse_from_str(&format!("{}-{}-{}", year, month, day), "%Y-%m-%d").ok()?,
+        accession_number: format!("{}-{}-{}", &accession[..10], &accession[10..12], &accession[12..]),
+    })
+}
+
+/// Document for a downloaded filing, with the start of its text as the content preview
+fn filing_document(file: FilingFile, preview_length: usize) -> Document {
+    let FilingFile { path, ticker, name } = file;
+    let mut metadata = HashMap::from([("accession_number".to_string(), name.accession_number.clone())]);
+    if preview_length > 0 {
+        match read_text_file(&path, &name.form, preview_length) {
+            Ok(sections) => {
+                if let Some(section) = sections.into_iter().next() {
+                    metadata.insert("content_preview".to_string(), section.content);
+                }
+            }
+            Err(e) => debug!("No text preview for {}: {}", path.display(), e),
+        }
+    }
+
+    let format = match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("htm" | "html") => DocumentFormat::Html,
+        Some("xml") => DocumentFormat::Xbrl,
+        Some("pdf") => DocumentFormat::Pdf,
+        _ => DocumentFormat::Txt,
+    };
+
+    Document {
+        id: name.accession_number,
+        company_name: ticker.clone(),
+        ticker,
+        filing_type: FilingType::from_label(&name.form),
+        source: Source::Edgar,
+        date: name.date,
+        content_path: path,
+        metadata,
+        format,
+        tags: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_filing_name() {
+        assert_eq!(
+            parse_filing_name("10-K-2024-11-01-000032019324000123"),
+            Some(FilingName {
+                form: "10-K".to_string(),
+                date: NaiveDate::from_ymd_opt(2024, 11, 1).unwrap(),
+                accession_number: "0000320193-24-000123".to_string(),
+            })
+        );
+        assert!(parse_filing_name("notes").is_none());
+        assert!(parse_filing_name("10-K-2024-13-01-000032019324000123").is_none());
+        assert!(parse_filing_name("2024-11-01-000032019324000123").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_index_documents() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let database_path = database_path.to_str().unwrap();
+        let mut config = Config::from_env().unwrap();
+        config.indexing.file_concurrency = 2;
+
+        let company_dir = temp_dir.path().join("downloads").join("edgar").join("AAPL");
+        std::fs::create_dir_all(&company_dir).unwrap();
+        std::fs::write(
+            company_dir.join("10-K-2024-11-01-000032019324000123.htm"),
+            "<html><body><p>Risk Factors: supply chain</p></body></html>",
+        )
+        .unwrap();
+        std::fs::write(company_dir.join("8-K-2024-10-31-000032019324000100.txt"), "CURRENT REPORT").unwrap();
+        std::fs::write(company_dir.join(format!("10-Q-2024-08-02-000032019324000081.htm{}", PARTIAL_DOWNLOAD_SUFFIX)), "<html>").unwrap();
+        std::fs::write(company_dir.join("10-K-2024-11-01-000032019324000123.json"), "{}").unwrap();
+        std::fs::write(company_dir.join("notes.txt"), "not a filing").unwrap();
+
+        let count = index_documents_with_config(&temp_dir.path().join("downloads"), database_path, &config).await.unwrap();
+        assert_eq!(count, 2);
+
+        let document = crate::storage::get_document_by_id("0000320193-24-000123", database_path).await.unwrap().unwrap();
+        assert_eq!(document.ticker, "AAPL");
+        assert_eq!(document.filing_type.as_str(), "10-K");
+        assert_eq!(document.source.as_str(), Source::Edgar.as_str());
+        assert_eq!(document.format.as_str(), "html");
+        assert!(document.metadata["content_preview"].contains("supply chain"));
+
+        let document = crate::storage::get_document_by_id("0000320193-24-000100", database_path).await.unwrap().unwrap();
+        assert_eq!(document.date, NaiveDate::from_ymd_opt(2024, 10, 31).unwrap());
+    }
+}
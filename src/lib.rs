@@ -1,7 +1,14 @@
 pub mod edinet_indexer;
+pub mod indexer;
 pub mod storage;
 pub mod models;
 pub mod downloader;
 pub mod config;
+pub mod locale;
+pub mod export;
+pub mod style;
 pub mod edinet;
 pub mod edinet_tui;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,189 @@
+//! Locale-aware formatting for dates and numbers shown to users
+
+use chrono::{Datelike, NaiveDate};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Display locale for dates and numeric figures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayLocale {
+    /// ISO dates (2024-04-01) and plain decimal numbers
+    #[default]
+    Iso,
+    /// Japanese era dates (令和6年4月1日) and 万/億/兆 number grouping
+    Japanese,
+}
+
+impl DisplayLocale {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DisplayLocale::Iso => "iso",
+            DisplayLocale::Japanese => "ja",
+        }
+    }
+}
+
+impl FromStr for DisplayLocale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "iso" | "en" | "default" => Ok(DisplayLocale::Iso),
+            "ja" | "jp" | "japanese" => Ok(DisplayLocale::Japanese),
+            other => Err(format!("Unsupported display locale: {}. Supported locales: iso, ja", other)),
+        }
+    }
+}
+
+/// Japanese era with its first day and romanized initial
+struct Era {
+    name: &'static str,
+    initial: char,
+    start: (i32, u32, u32),
+}
+
+/// Modern Japanese eras, newest first
+const ERAS: [Era; 4] = [
+    Era { name: "令和", initial: 'R', start: (2019, 5, 1) },
+    Era { name: "平成", initial: 'H', start: (1989, 1, 8) },
+    Era { name: "昭和", initial: 'S', start: (1926, 12, 25) },
+    Era { name: "大正", initial: 'T', start: (1912, 7, 30) },
+];
+
+/// Find the era containing `date` and the year within that era
+fn era_for(date: NaiveDate) -> Option<(&'static Era, i32)> {
+    ERAS.iter().find_map(|era| {
+        let (y, m, d) = era.start;
+        let start = NaiveDate::from_ymd_opt(y, m, d)?;
+        (date >= start).then(|| (era, date.year() - y + 1))
+    })
+}
+
+/// Format a date for display, e.g. `2024-04-01` or `令和6年4月1日`
+pub fn format_date(date: NaiveDate, locale: DisplayLocale) -> String {
+    match (locale, era_for(date)) {
+        (DisplayLocale::Japanese, Some((era, year))) => {
+            let year = if year == 1 { "元".to_string() } else { year.to_string() };
+            format!("{}{}年{}月{}日", era.name, year, date.month(), date.day())
+        }
+        _ => date.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Format a date in a fixed 10-column form for tables, e.g. `2024-04-01` or `R06.04.01 `
+pub fn format_date_short(date: NaiveDate, locale: DisplayLocale) -> String {
+    match (locale, era_for(date)) {
+        (DisplayLocale::Japanese, Some((era, year))) => {
+            format!("{}{:02}.{:02}.{:02} ", era.initial, year, date.month(), date.day())
+        }
+        _ => date.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// How dates are displayed everywhere: the locale's format unless a custom strftime pattern is configured
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DateFormat {
+    pub locale: DisplayLocale,
+    /// strftime pattern such as `%Y/%m/%d`, overriding the locale's date format
+    pub pattern: Option<String>,
+}
+
+impl DateFormat {
+    pub fn new(locale: DisplayLocale, pattern: Option<String>) -> Self {
+        Self { locale, pattern }
+    }
+
+    /// Format a date for detail views and CLI output, e.g. `2024-04-01`, `令和6年4月1日` or the custom pattern
+    pub fn format(&self, date: NaiveDate) -> String {
+        self.format_with_pattern(date).unwrap_or_else(|| format_date(date, self.locale))
+    }
+
+    /// Format a date for table columns, e.g. `2024-04-01`, `R06.04.01 ` or the custom pattern
+    pub fn format_short(&self, date: NaiveDate) -> String {
+        self.format_with_pattern(date).unwrap_or_else(|| format_date_short(date, self.locale))
+    }
+
+    fn format_with_pattern(&self, date: NaiveDate) -> Option<String> {
+        let pattern = self.pattern.as_deref()?;
+        let mut formatted = String::new();
+        // Patterns are validated up front, but never panic on e.g. a time specifier
+        write!(formatted, "{}", date.format(pattern)).ok()?;
+        Some(formatted)
+    }
+}
+
+/// Check that `pattern` is a strftime pattern that can format a date (no time or timezone fields)
+pub fn validate_date_pattern(pattern: &str) -> Result<(), String> {
+    let date = NaiveDate::from_ymd_opt(2024, 4, 1).expect("valid date");
+    let mut formatted = String::new();
+    match write!(formatted, "{}", date.format(pattern)) {
+        Ok(()) if !pattern.trim().is_empty() => Ok(()),
+        _ => Err(format!("Invalid date format: '{}'. Use strftime fields such as %Y/%m/%d", pattern)),
+    }
+}
+
+/// Format an integer for display, e.g. `123456789` or `1億2345万6789`
+pub fn format_number(value: u64, locale: DisplayLocale) -> String {
+    match locale {
+        DisplayLocale::Iso => value.to_string(),
+        DisplayLocale::Japanese => {
+            if value == 0 {
+                return "0".to_string();
+            }
+
+            let units = [("兆", 1_000_000_000_000u64), ("億", 100_000_000), ("万", 10_000)];
+            let mut remaining = value;
+            let mut result = String::new();
+            for (unit, size) in units {
+                if remaining >= size {
+                    result.push_str(&format!("{}{}", remaining / size, unit));
+                    remaining %= size;
+                }
+            }
+            if remaining > 0 {
+                result.push_str(&remaining.to_string());
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_japanese_era_dates() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(format_date(date(2024, 4, 1), DisplayLocale::Japanese), "令和6年4月1日");
+        assert_eq!(format_date(date(2019, 5, 1), DisplayLocale::Japanese), "令和元年5月1日");
+        assert_eq!(format_date(date(2019, 4, 30), DisplayLocale::Japanese), "平成31年4月30日");
+        assert_eq!(format_date(date(2024, 4, 1), DisplayLocale::Iso), "2024-04-01");
+        assert_eq!(format_date_short(date(2024, 4, 1), DisplayLocale::Japanese), "R06.04.01 ");
+    }
+
+    #[test]
+    fn test_custom_date_format() {
+        let date = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let slashes = DateFormat::new(DisplayLocale::Japanese, Some("%Y/%m/%d".to_string()));
+        assert_eq!(slashes.format(date), "2024/04/01");
+        assert_eq!(slashes.format_short(date), "2024/04/01");
+
+        let default = DateFormat::new(DisplayLocale::Japanese, None);
+        assert_eq!(default.format(date), "令和6年4月1日");
+        assert_eq!(default.format_short(date), "R06.04.01 ");
+
+        assert!(validate_date_pattern("%Y年%m月%d日").is_ok());
+        assert!(validate_date_pattern("%Y-%m-%d %H:%M").is_err());
+        assert!(validate_date_pattern("%Q").is_err());
+        assert!(validate_date_pattern("").is_err());
+    }
+
+    #[test]
+    fn test_japanese_number_grouping() {
+        assert_eq!(format_number(123_456_789, DisplayLocale::Japanese), "1億2345万6789");
+        assert_eq!(format_number(300_000_000, DisplayLocale::Japanese), "3億");
+        assert_eq!(format_number(9_999, DisplayLocale::Japanese), "9999");
+        assert_eq!(format_number(123_456_789, DisplayLocale::Iso), "123456789");
+    }
+}
@@ -1,45 +1,21 @@
 use clap::Parser;
 use anyhow::Result;
 use tracing::{info, error};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 mod cli;
-mod models;
-mod storage;
-mod indexer;
-mod edinet_indexer;
 mod tui;
-mod downloader;
+
+use fast10k::{downloader, edinet_indexer, export, indexer, models, storage, style};
 
 use cli::{Cli, Commands};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Set default log level to INFO if not specified
-    if std::env::var("RUST_LOG").is_err() {
-        std::env::set_var("RUST_LOG", "fast10k=info");
-    }
-    
-    // Initialize logging to both console and file
-    use tracing_subscriber::{fmt, EnvFilter, layer::SubscriberExt, util::SubscriberInitExt, Layer};
-    
-    // Create a file appender for logging
-    let file_appender = tracing_appender::rolling::never(".", "fast10k.log");
-    
-    tracing_subscriber::registry()
-        .with(
-            fmt::layer()
-                .with_writer(std::io::stderr)
-                .with_filter(EnvFilter::from_default_env())
-        )
-        .with(
-            fmt::layer()
-                .with_writer(file_appender)
-                .with_ansi(false)
-                .with_filter(EnvFilter::from_default_env())
-        )
-        .init();
-    
     let cli = Cli::parse();
+    style::set_color_enabled(style::should_color(cli.no_color, &std::io::stdout()));
+    init_logging(&cli)?;
     
     match &cli.command {
         Commands::Download { 
@@ -50,7 +26,10 @@ async fn main() -> Result<()> {
             to_date, 
             output,
             limit,
-            format
+            format,
+            dry_run,
+            attachments,
+            output_template
         } => {
             info!("Starting download for ticker: {}", ticker);
             
@@ -68,14 +47,116 @@ async fn main() -> Result<()> {
                 date_to: *to_date,
                 limit: *limit,
                 format: document_format,
+                document_id: None,
+                skip_existing: false,
+                dry_run: *dry_run,
+                include_attachments: *attachments,
+                output_template: output_template.clone(),
+            };
+            
+            match downloader::download_documents(&download_request, output).await {
+                Ok(report) => downloader::print_report(&report, *dry_run),
+                Err(e) => error!("Download failed: {}", e),
+            }
+        }
+        
+        Commands::Get { id, source, ticker, output, format, dry_run, attachments, output_template } => {
+            let source = match source {
+                Some(source) => Commands::parse_source(source)?,
+                None => models::Source::from_document_id(id).ok_or_else(|| anyhow::anyhow!(
+                    "Cannot tell the source of '{}', pass --source edgar or --source edinet", id
+                ))?,
+            };
+            info!("Downloading {} document {}", source.as_str(), id);
+            
+            let download_request = models::DownloadRequest {
+                source,
+                ticker: ticker.clone().unwrap_or_default(),
+                filing_type: None,
+                date_from: None,
+                date_to: None,
+                limit: 1,
+                format: Commands::parse_document_format(format)?,
+                document_id: Some(id.clone()),
+                skip_existing: false,
+                dry_run: *dry_run,
+                include_attachments: *attachments,
+                output_template: output_template.clone(),
             };
             
             match downloader::download_documents(&download_request, output).await {
-                Ok(count) => info!("Successfully downloaded {} documents", count),
+                Ok(report) => downloader::print_report(&report, *dry_run),
                 Err(e) => error!("Download failed: {}", e),
             }
         }
         
+        Commands::BulkDownload {
+            tickers_file,
+            source,
+            filing_type,
+            from,
+            to,
+            output,
+            limit,
+            format,
+            state_file,
+        } => {
+            let tickers = downloader::bulk::read_tickers_file(Path::new(tickers_file))?;
+            if tickers.is_empty() {
+                anyhow::bail!("No tickers found in {}", tickers_file);
+            }
+            
+            let template = models::DownloadRequest {
+                source: Commands::parse_source(source)?,
+                ticker: String::new(),
+                filing_type: filing_type.as_ref()
+                    .map(|ft| Commands::parse_filing_type(ft))
+                    .transpose()?,
+                date_from: *from,
+                date_to: *to,
+                limit: *limit,
+                format: Commands::parse_document_format(format)?,
+                document_id: None,
+                skip_existing: true,
+                dry_run: false,
+                include_attachments: false,
+                output_template: None,
+            };
+            let state_path = state_file
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(output).join("bulk-download-state.json"));
+            
+            info!("Starting bulk download of {} tickers (state: {})", tickers.len(), state_path.display());
+            let max_consecutive_failures = fast10k::config::Config::from_env()?.max_consecutive_failures;
+            let summary = downloader::bulk::bulk_download(&tickers, &template, output, &state_path, max_consecutive_failures).await?;
+            
+            println!("\n📊 Bulk download summary:");
+            for (ticker, outcome) in &summary.results {
+                match outcome {
+                    downloader::bulk::TickerOutcome::Downloaded(report) => println!(
+                        "  {:<12} {} downloaded, {} already on disk, {} failed",
+                        ticker,
+                        report.downloaded,
+                        report.skipped,
+                        report.failed.len()
+                    ),
+                    downloader::bulk::TickerOutcome::Skipped => println!("  {:<12} already completed", ticker),
+                    downloader::bulk::TickerOutcome::Failed(e) => println!("  {:<12} failed: {}", ticker, e),
+                }
+            }
+            println!(
+                "Total: {} documents downloaded, {} failed; {} tickers skipped, {} failed",
+                summary.documents_downloaded(),
+                summary.documents_failed(),
+                summary.skipped(),
+                summary.failed()
+            );
+            if summary.failed() > 0 || summary.documents_failed() > 0 {
+                println!("Re-run the same command to retry failed tickers.");
+            }
+        }
+        
         Commands::Index { input, database } => {
             info!("Starting indexing from: {}", input);
             
@@ -83,6 +164,12 @@ async fn main() -> Result<()> {
                 Ok(count) => info!("Successfully indexed {} documents", count),
                 Err(e) => error!("Indexing failed: {}", e),
             }
+            
+            // EDINET ZIPs carry their own metadata, so they can be indexed without API access
+            match edinet_indexer::index_edinet_archives(Path::new(input), database).await {
+                Ok(count) => info!("Successfully indexed {} EDINET ZIP files", count),
+                Err(e) => error!("EDINET ZIP indexing failed: {}", e),
+            }
         }
         
         Commands::Search {
@@ -93,40 +180,312 @@ async fn main() -> Result<()> {
             from_date,
             to_date,
             query,
+            latest_only,
+            include_withdrawn,
+            tag,
             database,
             limit,
+            interactive,
+            tickers_file,
         } => {
-            let search_query = models::SearchQuery {
-                ticker: ticker.clone(),
-                company_name: company.clone(),
-                filing_type: filing_type.as_ref()
-                    .map(|ft| Commands::parse_filing_type(ft))
-                    .transpose()?,
-                source: source.as_ref()
-                    .map(|s| Commands::parse_source(s))
-                    .transpose()?,
-                date_from: *from_date,
-                date_to: *to_date,
-                text_query: query.clone(),
+            let query_for = |ticker: Option<&str>| -> Result<models::SearchQuery> {
+                Ok(models::SearchQuery::builder()
+                    .ticker(ticker)
+                    .company_name(company.as_deref())
+                    .filing_type(filing_type.as_ref()
+                        .map(|ft| Commands::parse_filing_type(ft))
+                        .transpose()?)
+                    .source(source.as_deref()
+                        .filter(|s| !Commands::is_any_source(Some(*s)))
+                        .map(Commands::parse_source)
+                        .transpose()?)
+                    .date_from(*from_date)
+                    .date_to(*to_date)
+                    .text_query(query.as_deref())
+                    .latest_only(*latest_only)
+                    .include_withdrawn(*include_withdrawn)
+                    .tag(tag.as_deref())
+                    .build()?)
             };
             
-            match storage::search_documents(&search_query, database, *limit).await {
+            let all_sources = Commands::is_any_source(source.as_deref());
+            let date_format = cli_date_format()?;
+            
+            if let Some(tickers_file) = tickers_file {
+                let tickers = downloader::bulk::read_tickers_file(Path::new(tickers_file))?;
+                if tickers.is_empty() {
+                    anyhow::bail!("No tickers found in {}", tickers_file);
+                }
+                let queries = tickers.iter().map(|ticker| query_for(Some(ticker))).collect::<Result<Vec<_>>>()?;
+                match storage::search_databases_batch(&queries, database, all_sources, *limit).await {
+                    Ok(results) => print_ticker_search_results(&tickers, &results, &date_format),
+                    Err(e) => error!("Search failed: {}", e),
+                }
+                return Ok(());
+            }
+            
+            let search_query = query_for(ticker.as_deref())?;
+            
+            // Paging needs a terminal to read keys from; pipes get the plain listing
+            if *interactive && std::io::stdout().is_terminal() {
+                if let Err(e) = page_search_results(&search_query, database, all_sources, *limit, &date_format).await {
+                    error!("Search failed: {}", e);
+                }
+                return Ok(());
+            }
+            
+            match storage::search_databases_page(&search_query, database, all_sources, *limit, 0).await {
                 Ok(documents) => {
                     println!("Found {} documents:", documents.len());
-                    for doc in documents {
-                        println!("{} - {} ({}) - {} - {}", 
-                            doc.ticker, 
-                            doc.company_name, 
-                            doc.filing_type.as_str(),
-                            doc.source.as_str(),
-                            doc.date
-                        );
+                    for doc in &documents {
+                        print_search_result(doc, &date_format);
                     }
                 }
                 Err(e) => error!("Search failed: {}", e),
             }
         }
         
+        Commands::Export {
+            ticker,
+            company,
+            filing_type,
+            source,
+            from_date,
+            to_date,
+            query,
+            latest_only,
+            include_withdrawn,
+            tag,
+            database,
+            limit,
+            output,
+            format,
+            append,
+        } => {
+            let format: export::ExportFormat = format.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            let search_query = models::SearchQuery::builder()
+                .ticker(ticker.as_deref())
+                .company_name(company.as_deref())
+                .filing_type(filing_type.as_ref()
+                    .map(|ft| Commands::parse_filing_type(ft))
+                    .transpose()?)
+                .source(source.as_deref()
+                    .filter(|s| !Commands::is_any_source(Some(*s)))
+                    .map(Commands::parse_source)
+                    .transpose()?)
+                .date_from(*from_date)
+                .date_to(*to_date)
+                .text_query(query.as_deref())
+                .latest_only(*latest_only)
+                .include_withdrawn(*include_withdrawn)
+                .tag(tag.as_deref())
+                .build()?;
+            let path = match output {
+                Some(output) => PathBuf::from(output),
+                None => export::timestamped_export_path(&fast10k::config::Config::from_env()?.export_dir, format),
+            };
+            
+            let results = if Commands::is_any_source(source.as_deref()) {
+                storage::search_all_sources(&search_query, database, *limit).await
+            } else {
+                storage::search_documents(&search_query, database, *limit).await
+            };
+            
+            match results {
+                Ok(documents) if *append => match export::append_documents(&documents, &path, format) {
+                    Ok(count) => info!("Appended {} new of {} documents to {}", count, documents.len(), path.display()),
+                    Err(e) => error!("Export failed: {}", e),
+                },
+                Ok(documents) => match export::export_documents(&documents, &path, format) {
+                    Ok(count) => info!("Exported {} documents to {}", count, path.display()),
+                    Err(e) => error!("Export failed: {}", e),
+                },
+                Err(e) => error!("Search failed: {}", e),
+            }
+        }
+        
+        Commands::Show { id, database, download_dir, open } => {
+            let document = storage::get_document_by_id(id, database)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load document {}: {}", id, e))?
+                .ok_or_else(|| anyhow::anyhow!("Document not found: {}", id))?;
+            let amendments = storage::get_amendments(&document.id, database).await?;
+            show_document(&document, &amendments, download_dir, *open, &cli_date_format()?)?
+        }
+        
+        Commands::Update { incremental, since_last_run, days, database } => {
+            let result = if *since_last_run {
+                info!("Updating EDINET index since the last successful run");
+                edinet_indexer::update_edinet_index_since_last_run(database, *days).await
+            } else if *incremental {
+                info!("Incrementally updating EDINET index");
+                edinet_indexer::update_edinet_index_incremental(database, *days).await
+            } else {
+                info!("Updating EDINET index for the last {} days", days);
+                edinet_indexer::update_edinet_index(database, *days).await
+            };
+            
+            match result {
+                Ok(count) => info!("Successfully indexed {} EDINET documents", count),
+                Err(e) => error!("EDINET index update failed: {}", e),
+            }
+        }
+        
+        Commands::IndexDate { date, doc_types, database } => {
+            let config = fast10k::config::Config::from_env()?;
+            let filter = fast10k::edinet::EdinetIndexFilter::from_code_lists(doc_types.as_deref(), None);
+            let report = fast10k::edinet::index_edinet_date(database, *date, &config, &filter).await?;
+            println!(
+                "{}",
+                style::success(format!(
+                    "✅ {}: {} documents added, {} indexed of {} listed by EDINET",
+                    date, report.added, report.indexed, report.listed
+                ))
+            );
+        }
+        
+        Commands::Watch { interval, watchlist, doc_types, xbrl_only, database } => {
+            let config = fast10k::config::Config::from_env()?;
+            let filter = fast10k::edinet::EdinetIndexFilter::from_code_lists(doc_types.as_deref(), None)
+                .with_xbrl_only(*xbrl_only);
+            let watchlist: Vec<String> = watchlist
+                .as_deref()
+                .unwrap_or("")
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            
+            let date_format = config.display.date_format();
+            info!("Watching EDINET for new filings every {}s (Ctrl+C to stop)", interval);
+            loop {
+                match fast10k::edinet::index_new_edinet_documents(database, &config, &filter).await {
+                    Ok(documents) => {
+                        for doc in documents.iter().filter(|doc| matches_watchlist(doc, &watchlist)) {
+                            println!("🔔 {} {} {} - {} ({})",
+                                date_format.format(doc.date),
+                                doc.ticker,
+                                doc.company_name,
+                                doc.metadata.get("doc_description").map(String::as_str).unwrap_or(doc.filing_type.as_str()),
+                                doc.id
+                            );
+                        }
+                    }
+                    Err(e) => error!("EDINET poll failed: {}", e),
+                }
+                
+                tokio::select! {
+                    _ = tokio::time::sleep(std::time::Duration::from_secs(*interval)) => {}
+                    _ = tokio::signal::ctrl_c() => break,
+                }
+            }
+        }
+        
+        Commands::Stats { source, database } => {
+            let sources = match source {
+                Some(source) => vec![Commands::parse_source(source)?],
+                None => models::Source::all().to_vec(),
+            };
+            
+            for source in &sources {
+                let total = storage::count_documents_by_source(source, database).await?;
+                if total == 0 {
+                    println!("{}: no documents indexed\n", source.as_str());
+                    continue;
+                }
+                let (first, last) = storage::get_date_range_for_source(source, database).await?;
+                println!("{}: {} documents ({} to {})", source.as_str(), total, first, last);
+                for (filing_type, count) in storage::count_documents_by_filing_type(source, database).await? {
+                    println!("  {:<32} {:>8}", filing_type.as_str(), count);
+                }
+                println!();
+            }
+        }
+        
+        Commands::Backup { database, to } => {
+            let backup_path = match to {
+                Some(to) => std::path::PathBuf::from(to),
+                None => storage::default_backup_path(database),
+            };
+            storage::backup_database(database, &backup_path).await?;
+            println!("Backed up {} to {}", database, backup_path.display());
+        }
+        
+        Commands::Restore { backup, database } => {
+            let previous = storage::restore_database(std::path::Path::new(backup), database).await?;
+            println!("Restored {} from {}", database, backup);
+            if let Some(previous) = previous {
+                println!("The replaced database was saved to {}", previous.display());
+            }
+        }
+        
+        Commands::Reconcile { database, downloads, fix } => {
+            let config = fast10k::config::Config::from_env()?;
+            let edinet_dir = config.source_dir(downloads, "edinet");
+            let edgar_dir = config.source_dir(downloads, "edgar");
+            let report = fast10k::edinet::reconcile_downloads(&edinet_dir, &edgar_dir, database).await?;
+            for path in &report.unindexed_files {
+                println!("Not indexed: {}", path.display());
+            }
+            for (id, path) in &report.missing_files {
+                println!("Missing file: {} ({})", id, path.display());
+            }
+            
+            if report.is_consistent() {
+                println!("{} and {} are consistent", downloads, database);
+            } else if *fix {
+                let (indexed, cleared) = fast10k::edinet::fix_reconcile_report(&report, database, &config).await?;
+                println!("Indexed {} of {} files, cleared {} missing content paths", indexed, report.unindexed_files.len(), cleared);
+            } else {
+                println!(
+                    "{} files not indexed, {} indexed documents missing their file; rerun with --fix to repair",
+                    report.unindexed_files.len(),
+                    report.missing_files.len()
+                );
+            }
+        }
+        
+        Commands::Reclassify { database } => {
+            let report = fast10k::edinet::reclassify_edinet_documents(database).await?;
+            for (id, old, new) in &report.changed {
+                println!("{}: {} -> {}", id, old.as_str(), new.as_str());
+            }
+            println!("Reclassified {} of {} EDINET documents", report.changed.len(), report.checked);
+        }
+        
+        Commands::Hash { database, rehash } => {
+            use std::io::Write;
+            
+            let report = storage::backfill_content_hashes(database, *rehash, |done, total| {
+                print!("\r\x1b[2KHashing documents: {} of {}", done, total);
+                let _ = std::io::stdout().flush();
+            })
+            .await?;
+            if report.hashed + report.missing_files.len() > 0 {
+                println!();
+            }
+            
+            for (id, path) in &report.missing_files {
+                println!("Missing file: {} ({})", id, path.display());
+            }
+            println!(
+                "Hashed {} documents ({} changed), {} skipped for missing files",
+                report.hashed,
+                report.changed,
+                report.missing_files.len()
+            );
+        }
+        
+        Commands::Doctor { database, download_dir } => {
+            let mut config = fast10k::config::Config::from_env()?;
+            config.database_path = database.into();
+            config.download_dir = download_dir.into();
+            
+            if !run_doctor(&config).await {
+                std::process::exit(1);
+            }
+        }
+        
         Commands::Tui { database } => {
             info!("Launching TUI interface");
             
@@ -140,4 +499,340 @@ async fn main() -> Result<()> {
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Whether a document belongs to the watchlist of tickers or EDINET codes (empty matches all)
+fn matches_watchlist(document: &fast10k::models::Document, watchlist: &[String]) -> bool {
+    watchlist.is_empty()
+        || watchlist.iter().any(|entry| {
+            document.ticker.eq_ignore_ascii_case(entry)
+                || document.metadata.get("edinet_code").is_some_and(|code| code.eq_ignore_ascii_case(entry))
+        })
+}
+
+/// Run readiness checks and print a pass/fail checklist, returning whether all passed
+async fn run_doctor(config: &fast10k::config::Config) -> bool {
+    let mut all_passed = true;
+    let mut report = |name: &str, result: Result<String>| {
+        match result {
+            Ok(detail) => println!("{}", style::success(format!("✅ {}: {}", name, detail))),
+            Err(e) => {
+                println!("{}", style::failure(format!("❌ {}: {}", name, e)));
+                all_passed = false;
+            }
+        }
+    };
+    
+    report("Configuration", config.validate().map(|_| "valid".to_string()));
+    
+    let health = fast10k::edinet_tui::operations::DatabaseManager::new(config.clone())
+        .health_check()
+        .await;
+    report("Database", match &health {
+        Ok(health) if health.issues.is_empty() && health.status != "Not Found" => {
+            Ok(format!("{} (schema v{})", config.database_path.display(), fast10k::storage::SCHEMA_VERSION))
+        }
+        Ok(health) if health.issues.is_empty() => Err(anyhow::anyhow!("{} not found", config.database_path.display())),
+        Ok(health) => Err(anyhow::anyhow!(health.issues.join("; "))),
+        Err(e) => Err(anyhow::anyhow!("{}", e)),
+    });
+    if let Ok(health) = &health {
+        report("Static data", match health.static_entries_count {
+            0 => Err(anyhow::anyhow!("not loaded, run 'edinet load-static'")),
+            count => Ok(format!("{} entries", count)),
+        });
+        if let Some(warning) = health.stale_static_warning() {
+            println!("{}", style::warning(format!("⚠️  Static data: {}", warning)));
+        }
+        println!("ℹ️  Indexed EDINET documents: {}", health.documents_count);
+    }
+    
+    let database_dir = match config.database_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => std::path::PathBuf::from("."),
+    };
+    report("Database directory", check_writable(&database_dir));
+    report("Download directory", check_writable(&config.download_dir));
+    let source_dirs = &config.source_download_dirs;
+    for (source, dir) in [("EDGAR", &source_dirs.edgar), ("EDINET", &source_dirs.edinet), ("TDNet", &source_dirs.tdnet)] {
+        if let Some(dir) = dir {
+            report(&format!("{} download directory", source), check_writable(dir));
+        }
+    }
+    
+    report("EDINET API key", match &config.edinet_api_key {
+        Some(_) => Ok("set".to_string()),
+        None => Err(anyhow::anyhow!("EDINET_API_KEY is not set")),
+    });
+    if config.edinet_api_key.is_some() {
+        report("EDINET API", fast10k::edinet::check_edinet_api(config)
+            .await
+            .map(|count| format!("reachable ({} documents today)", count))
+            .map_err(Into::into));
+    }
+    
+    report("EDGAR contact", if config.has_edgar_contact() {
+        Ok(config.edgar_user_agent())
+    } else {
+        Err(anyhow::anyhow!("FAST10K_CONTACT_EMAIL is not set; SEC may block the placeholder User-Agent"))
+    });
+    
+    report("EDGAR API", downloader::edgar::check_connectivity(config)
+        .await
+        .map(|encoding| match encoding {
+            Some(encoding) => format!("reachable ({} compressed)", encoding),
+            None => "reachable (uncompressed responses)".to_string(),
+        })
+        .map_err(Into::into));
+    
+    all_passed
+}
+
+/// Log to stderr, and to a file only when one is requested
+fn init_logging(cli: &Cli) -> Result<()> {
+    use tracing_subscriber::{fmt, EnvFilter, layer::SubscriberExt, util::SubscriberInitExt, Layer};
+    
+    let filter = cli.log_filter();
+    let file_layer = match cli.log_file_path() {
+        Some(path) => {
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("Invalid log file path: {}", path.display()))?;
+            let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            std::fs::create_dir_all(dir)?;
+            Some(
+                fmt::layer()
+                    .with_writer(tracing_appender::rolling::never(dir, file_name))
+                    .with_ansi(false)
+                    .with_filter(EnvFilter::new(&filter)),
+            )
+        }
+        None => None,
+    };
+    
+    tracing_subscriber::registry()
+        .with(
+            fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_ansi(style::should_color(cli.no_color, &std::io::stderr()))
+                .with_filter(EnvFilter::new(&filter))
+        )
+        .with(file_layer)
+        .init();
+    
+    Ok(())
+}
+
+/// Check that a directory exists (creating it if needed) and is writable
+fn check_writable(dir: &std::path::Path) -> Result<String> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".fast10k-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)?;
+    Ok(format!("{} is writable", dir.display()))
+}
+
+fn print_search_result(doc: &models::Document, date_format: &fast10k::locale::DateFormat) {
+    println!("{} - {} ({}) - {} - {}", 
+        doc.ticker, 
+        doc.company_name, 
+        doc.filing_type.as_str(),
+        doc.source.as_str(),
+        date_format.format(doc.date)
+    );
+    if !doc.tags.is_empty() {
+        println!("    Tags: {}", doc.tags.join(", "));
+    }
+}
+
+/// Print the results of a `--tickers-file` search in a section per ticker, then the hits per ticker
+fn print_ticker_search_results(tickers: &[String], results: &[Vec<models::Document>], date_format: &fast10k::locale::DateFormat) {
+    for (ticker, documents) in tickers.iter().zip(results) {
+        println!("== {} ({} documents) ==", ticker, documents.len());
+        for doc in documents {
+            print_search_result(doc, date_format);
+        }
+        println!();
+    }
+    
+    println!("Hits per ticker:");
+    for (ticker, documents) in tickers.iter().zip(results) {
+        let hits = format!("{:>5}", documents.len());
+        println!("  {:<10} {}", ticker, if documents.is_empty() { style::dim(hits) } else { hits });
+    }
+    let total: usize = results.iter().map(Vec::len).sum();
+    println!("Found {} documents for {} tickers", total, tickers.len());
+}
+
+/// Print every search result one page at a time, waiting for a key press between pages
+async fn page_search_results(
+    query: &models::SearchQuery,
+    databases: &[String],
+    all_sources: bool,
+    page_size: usize,
+    date_format: &fast10k::locale::DateFormat,
+) -> Result<()> {
+    let total = storage::count_matching_databases(query, databases, all_sources).await? as usize;
+    println!("Found {} documents:", total);
+    
+    let mut offset = 0;
+    while offset < total {
+        let page = storage::search_databases_page(query, databases, all_sources, page_size, offset).await?;
+        // Documents deleted since counting would otherwise leave `offset` short of `total` forever
+        if page.is_empty() {
+            break;
+        }
+        for doc in &page {
+            print_search_result(doc, date_format);
+        }
+        offset += page.len();
+        
+        if offset < total && !wait_for_next_page(offset, total)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Prompt between pages; returns `false` if the user pressed `q`, Esc or Ctrl+C
+fn wait_for_next_page(shown: usize, total: usize) -> Result<bool> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use std::io::Write;
+    
+    print!("-- {} of {} shown: Space/Enter for more, q to quit --", shown, total);
+    std::io::stdout().flush()?;
+    
+    crossterm::terminal::enable_raw_mode()?;
+    let next = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break Ok(false),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Ok(false),
+                KeyCode::Char(' ') | KeyCode::Enter | KeyCode::PageDown | KeyCode::Char('j') => break Ok(true),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    
+    // Erase the prompt so the listing reads continuously
+    print!("\r\x1b[2K");
+    std::io::stdout().flush()?;
+    Ok(next?)
+}
+
+/// Date format for CLI output, from `FAST10K_DISPLAY_LOCALE` and `FAST10K_DATE_FORMAT`
+fn cli_date_format() -> Result<fast10k::locale::DateFormat> {
+    Ok(fast10k::config::Config::from_env()?.display.date_format())
+}
+
+/// Print a document's details and, if it has been downloaded, a content preview
+fn show_document(
+    document: &models::Document,
+    amendments: &[models::Document],
+    download_dir: &str,
+    open: bool,
+    date_format: &fast10k::locale::DateFormat,
+) -> Result<()> {
+    println!("ID:           {}", document.id);
+    println!("Ticker:       {}", document.ticker);
+    println!("Company:      {}", document.company_name);
+    println!("Filing Type:  {}", document.filing_type.as_str());
+    println!("Source:       {}", document.source.as_str());
+    println!("Date:         {}", date_format.format(document.date));
+    println!("Format:       {}", document.format.as_str());
+    println!("Content Path: {}", document.content_path.display());
+    if !document.tags.is_empty() {
+        println!("Tags:         {}", document.tags.join(", "));
+    }
+    if let Some(parent_doc_id) = document.metadata.get("parent_doc_id") {
+        println!("Amends:       {}", parent_doc_id);
+    }
+    for amendment in amendments {
+        println!("Amended by:   {} ({})", amendment.id, date_format.format(amendment.date));
+    }
+    
+    if !document.metadata.is_empty() {
+        println!();
+        println!("Metadata:");
+        let mut keys: Vec<_> = document.metadata.keys().collect();
+        keys.sort();
+        for key in keys {
+            println!("  {}: {}", key, document.metadata[key]);
+        }
+    }
+    
+    println!();
+    let source_dir = fast10k::config::Config::from_env()?.source_dir(download_dir, document.source.as_str());
+    let Some(path) = find_downloaded_file(document, &source_dir) else {
+        println!("Download Status: Not Downloaded");
+        return Ok(());
+    };
+    println!("Download Status: Downloaded ({})", path.display());
+    
+    if path.extension().and_then(|s| s.to_str()) == Some("zip") {
+        match fast10k::edinet::reader::read_edinet_zip(&path.to_string_lossy(), 3, 500) {
+            Ok(sections) => {
+                for section in sections {
+                    println!();
+                    println!("{} ({} chars)", section.section_type, section.full_length);
+                    for line in section.content.lines().take(10) {
+                        if !line.trim().is_empty() {
+                            println!("  {}", line.trim());
+                        }
+                    }
+                }
+            }
+            Err(e) if e.downcast_ref::<fast10k::edinet::NoTextSections>().is_some() => {
+                println!();
+                println!("{}; use --open to view it externally", e);
+            }
+            Err(e) => error!("Failed to read content preview: {}", e),
+        }
+    }
+    
+    if open {
+        open_file(&path)?;
+    }
+    
+    Ok(())
+}
+
+/// Locate the downloaded file for a document, either via its content path or its source's download directory
+fn find_downloaded_file(document: &models::Document, source_dir: &Path) -> Option<std::path::PathBuf> {
+    if document.content_path.is_file() {
+        return Some(document.content_path.clone());
+    }
+    
+    let doc_id = document
+        .metadata
+        .get("doc_id")
+        .or_else(|| document.metadata.get("document_id"))
+        .unwrap_or(&document.id);
+    std::fs::read_dir(source_dir.join(&document.ticker))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.contains(doc_id.as_str()))
+        })
+}
+
+/// Open a file with the platform's default application
+fn open_file(path: &std::path::Path) -> Result<()> {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    
+    std::process::Command::new(program).arg(path).spawn()?;
+    info!("Opened {} with {}", path.display(), program);
+    Ok(())
+}
@@ -2,6 +2,7 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Document {
@@ -14,6 +15,50 @@ pub struct Document {
     pub content_path: PathBuf,
     pub metadata: HashMap<String, String>,
     pub format: DocumentFormat,
+    /// Free-form labels such as "reviewed" or "M&A", kept in the `document_tags` table
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// EDGAR filing archive, followed by `/{CIK}/{accession without dashes}/{accession}-index.htm`
+const EDGAR_ARCHIVES_URL: &str = "https://www.sec.gov/Archives/edgar/data";
+/// EDINET document viewer, followed by `?{doc ID},,`
+const EDINET_VIEWER_URL: &str = "https://disclosure2.edinet-fsa.go.jp/WZEK0040.aspx";
+
+/// EDINET's viewer page of a document, e.g. `https://disclosure2.edinet-fsa.go.jp/WZEK0040.aspx?S100ABCD,,`
+pub fn edinet_viewer_url(doc_id: &str) -> String {
+    format!("{}?{},,", EDINET_VIEWER_URL, doc_id)
+}
+
+impl Document {
+    /// Public web page of the filing, to open in a browser: EDGAR's filing index or EDINET's
+    /// document viewer. None for other sources or when the id is not a doc ID or accession number.
+    ///
+    /// A URL stored in the `source_url` metadata when indexing takes precedence.
+    pub fn source_url(&self) -> Option<String> {
+        if let Some(url) = self.metadata.get("source_url") {
+            return Some(url.clone());
+        }
+        let id = self
+            .metadata
+            .get("doc_id")
+            .or_else(|| self.metadata.get("accession_number"))
+            .unwrap_or(&self.id);
+
+        match (&self.source, Source::from_document_id(id)) {
+            (Source::Edgar, Some(Source::Edgar)) => {
+                let accession_clean = id.replace('-', "");
+                // The accession number starts with the filer's zero-padded CIK
+                let cik = match self.metadata.get("cik") {
+                    Some(cik) => cik.trim_start_matches('0').to_string(),
+                    None => accession_clean[..10].trim_start_matches('0').to_string(),
+                };
+                Some(format!("{}/{}/{}/{}-index.htm", EDGAR_ARCHIVES_URL, cik, accession_clean, id))
+            }
+            (Source::Edinet, Some(Source::Edinet)) => Some(edinet_viewer_url(id)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,10 +73,76 @@ pub enum FilingType {
     QuarterlySecuritiesReport,      // 四半期報告書  
     SemiAnnualSecuritiesReport,     // 半期報告書
     ExtraordinaryReport,            // 臨時報告書
+    FundReport,                     // 投資信託 filings (fund code based)
     Other(String),
 }
 
+/// Phrases in a document title or file name marking an earnings call transcript
+const TRANSCRIPT_KEYWORDS: &[&str] = &["transcript", "earnings call", "conference call", "書き起こし", "質疑応答"];
+
+/// Cover page phrases naming a filing's type, in upper case; 四半期報告書 comes before the
+/// 半期報告書 it contains so that a quarterly report is not taken for a semi-annual one
+const FILING_TYPE_PHRASES: &[(&str, FilingType)] = &[
+    ("四半期報告書", FilingType::QuarterlySecuritiesReport),
+    ("半期報告書", FilingType::SemiAnnualSecuritiesReport),
+    ("有価証券報告書", FilingType::AnnualSecuritiesReport),
+    ("臨時報告書", FilingType::ExtraordinaryReport),
+    ("ANNUAL REPORT PURSUANT TO SECTION 13", FilingType::TenK),
+    ("QUARTERLY REPORT PURSUANT TO SECTION 13", FilingType::TenQ),
+    ("CURRENT REPORT PURSUANT TO SECTION 13", FilingType::EightK),
+];
+
+/// Characters at the start of a document searched for a cover page phrase
+const CLASSIFY_TEXT_CHARS: usize = 3000;
+
 impl FilingType {
+    /// Every user-selectable filing type, in display order
+    pub fn all() -> &'static [FilingType] {
+        const ALL: &[FilingType] = &[
+            FilingType::TenK,
+            FilingType::TenQ,
+            FilingType::EightK,
+            FilingType::Transcript,
+            FilingType::PressRelease,
+            FilingType::AnnualSecuritiesReport,
+            FilingType::QuarterlySecuritiesReport,
+            FilingType::SemiAnnualSecuritiesReport,
+            FilingType::ExtraordinaryReport,
+            FilingType::FundReport,
+        ];
+        ALL
+    }
+
+    /// Parse the label produced by `as_str`, falling back to `Other`
+    pub fn from_label(label: &str) -> FilingType {
+        Self::all()
+            .iter()
+            .find(|filing_type| filing_type.as_str() == label)
+            .cloned()
+            .unwrap_or_else(|| FilingType::Other(label.to_string()))
+    }
+
+    /// Whether a document title or file name looks like an earnings call transcript,
+    /// e.g. `Q2 2024 Earnings Call Transcript` or `7203_earnings-call.txt`
+    pub fn is_transcript_title(title: &str) -> bool {
+        let title = title.to_lowercase().replace(['-', '_'], " ");
+        TRANSCRIPT_KEYWORDS.iter().any(|keyword| title.contains(keyword))
+    }
+
+    /// Filing type named on a document's cover page, for documents whose form code or file name
+    /// says nothing, e.g. `【提出書類】 有価証券報告書` or `ANNUAL REPORT PURSUANT TO SECTION 13 OR 15(d)`
+    ///
+    /// Only the start of the text is searched and the earliest phrase wins, since reports often
+    /// refer to other report types further in.
+    pub fn classify_text(text: &str) -> Option<FilingType> {
+        let head: String = text.chars().take(CLASSIFY_TEXT_CHARS).collect::<String>().to_uppercase();
+        FILING_TYPE_PHRASES
+            .iter()
+            .filter_map(|(phrase, filing_type)| head.find(phrase).map(|position| (position, filing_type)))
+            .min_by_key(|(position, _)| *position)
+            .map(|(_, filing_type)| filing_type.clone())
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             FilingType::TenK => "10-K",
@@ -43,12 +154,13 @@ impl FilingType {
             FilingType::QuarterlySecuritiesReport => "Quarterly Securities Report",
             FilingType::SemiAnnualSecuritiesReport => "Semi-Annual Securities Report",
             FilingType::ExtraordinaryReport => "Extraordinary Report",
+            FilingType::FundReport => "Fund Report",
             FilingType::Other(s) => s,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Source {
     Edgar,
     Edinet,
@@ -57,6 +169,34 @@ pub enum Source {
 }
 
 impl Source {
+    /// Every user-selectable source, in display order
+    pub fn all() -> &'static [Source] {
+        const ALL: &[Source] = &[Source::Edgar, Source::Edinet, Source::Tdnet];
+        ALL
+    }
+
+    /// Source a document identifier belongs to, from its format: EDINET doc IDs look like
+    /// `S100ABCD`, EDGAR accession numbers like `0000320193-24-000123`
+    pub fn from_document_id(id: &str) -> Option<Source> {
+        let digits = id.chars().filter(|c| c.is_ascii_digit()).count();
+        if digits == 18 && id.chars().all(|c| c.is_ascii_digit() || c == '-') {
+            Some(Source::Edgar)
+        } else if id.len() == 8 && id.starts_with('S') && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            Some(Source::Edinet)
+        } else {
+            None
+        }
+    }
+
+    /// Parse the label produced by `as_str`, falling back to `Other`
+    pub fn from_label(label: &str) -> Source {
+        Self::all()
+            .iter()
+            .find(|source| source.as_str() == label)
+            .cloned()
+            .unwrap_or_else(|| Source::Other(label.to_string()))
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             Source::Edgar => "EDGAR",
@@ -74,6 +214,10 @@ pub enum DocumentFormat {
     Xbrl,
     Ixbrl,
     Complete,
+    Pdf,
+    Summary,
+    /// EDINET's XBRL financial data as CSV files, downloaded in a ZIP
+    Csv,
     Other(String),
 }
 
@@ -85,6 +229,9 @@ impl DocumentFormat {
             DocumentFormat::Xbrl => "xbrl",
             DocumentFormat::Ixbrl => "ixbrl",
             DocumentFormat::Complete => "complete",
+            DocumentFormat::Pdf => "pdf",
+            DocumentFormat::Summary => "summary",
+            DocumentFormat::Csv => "csv",
             DocumentFormat::Other(s) => s,
         }
     }
@@ -96,12 +243,15 @@ impl DocumentFormat {
             DocumentFormat::Xbrl => "xml",
             DocumentFormat::Ixbrl => "htm",
             DocumentFormat::Complete => "zip",
+            DocumentFormat::Pdf => "pdf",
+            DocumentFormat::Summary => "json",
+            DocumentFormat::Csv => "zip",
             DocumentFormat::Other(_) => "zip", // Default to zip for mixed formats
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SearchQuery {
     pub ticker: Option<String>,
     pub company_name: Option<String>,
@@ -110,6 +260,158 @@ pub struct SearchQuery {
     pub date_from: Option<NaiveDate>,
     pub date_to: Option<NaiveDate>,
     pub text_query: Option<String>,
+    /// Hide filings superseded by a later amendment
+    pub latest_only: bool,
+    /// Also return EDINET filings that were withdrawn, and their withdrawal notices
+    pub include_withdrawn: bool,
+    /// Only documents with this tag
+    pub tag: Option<String>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SearchQueryError {
+    #[error("Invalid '{field}' date '{value}'. Please use YYYY-MM-DD")]
+    InvalidDate { field: &'static str, value: String },
+
+    #[error("'Date From' ({from}) is after 'Date To' ({to})")]
+    DateRangeReversed { from: NaiveDate, to: NaiveDate },
+
+    #[error("Please enter at least one search criteria")]
+    NoCriteria,
+}
+
+impl SearchQuery {
+    pub fn builder() -> SearchQueryBuilder {
+        SearchQueryBuilder::default()
+    }
+
+    /// Whether no criteria are set; the source alone does not count as a criterion
+    pub fn is_empty(&self) -> bool {
+        self.ticker.is_none()
+            && self.company_name.is_none()
+            && self.filing_type.is_none()
+            && self.date_from.is_none()
+            && self.date_to.is_none()
+            && self.text_query.is_none()
+            && self.tag.is_none()
+    }
+
+    /// Check that the query has at least one criterion and a well-ordered date range
+    pub fn validate(&self) -> Result<(), SearchQueryError> {
+        if self.is_empty() {
+            return Err(SearchQueryError::NoCriteria);
+        }
+        self.validate_date_range()
+    }
+
+    fn validate_date_range(&self) -> Result<(), SearchQueryError> {
+        match (self.date_from, self.date_to) {
+            (Some(from), Some(to)) if from > to => Err(SearchQueryError::DateRangeReversed { from, to }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Builder for [`SearchQuery`]; text setters ignore blank values and every setter accepts an `Option`
+#[derive(Debug, Clone, Default)]
+pub struct SearchQueryBuilder {
+    query: SearchQuery,
+    error: Option<SearchQueryError>,
+}
+
+impl SearchQueryBuilder {
+    pub fn ticker<'a>(mut self, ticker: impl Into<Option<&'a str>>) -> Self {
+        self.query.ticker = non_blank(ticker.into());
+        self
+    }
+
+    pub fn company_name<'a>(mut self, company_name: impl Into<Option<&'a str>>) -> Self {
+        self.query.company_name = non_blank(company_name.into());
+        self
+    }
+
+    pub fn text_query<'a>(mut self, text_query: impl Into<Option<&'a str>>) -> Self {
+        self.query.text_query = non_blank(text_query.into());
+        self
+    }
+
+    pub fn tag<'a>(mut self, tag: impl Into<Option<&'a str>>) -> Self {
+        self.query.tag = non_blank(tag.into());
+        self
+    }
+
+    pub fn filing_type(mut self, filing_type: impl Into<Option<FilingType>>) -> Self {
+        self.query.filing_type = filing_type.into();
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<Option<Source>>) -> Self {
+        self.query.source = source.into();
+        self
+    }
+
+    pub fn latest_only(mut self, latest_only: bool) -> Self {
+        self.query.latest_only = latest_only;
+        self
+    }
+
+    pub fn include_withdrawn(mut self, include_withdrawn: bool) -> Self {
+        self.query.include_withdrawn = include_withdrawn;
+        self
+    }
+
+    pub fn date_from(mut self, date_from: impl Into<Option<NaiveDate>>) -> Self {
+        self.query.date_from = date_from.into();
+        self
+    }
+
+    pub fn date_to(mut self, date_to: impl Into<Option<NaiveDate>>) -> Self {
+        self.query.date_to = date_to.into();
+        self
+    }
+
+    /// Parse a YYYY-MM-DD start date; a blank string clears it
+    pub fn date_from_str(mut self, value: &str) -> Self {
+        self.query.date_from = self.parse_date("Date From", value);
+        self
+    }
+
+    /// Parse a YYYY-MM-DD end date; a blank string clears it
+    pub fn date_to_str(mut self, value: &str) -> Self {
+        self.query.date_to = self.parse_date("Date To", value);
+        self
+    }
+
+    fn parse_date(&mut self, field: &'static str, value: &str) -> Option<NaiveDate> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                // Keep the first error so it is reported in field order
+                self.error.get_or_insert(SearchQueryError::InvalidDate { field, value: value.to_string() });
+                None
+            }
+        }
+    }
+
+    /// Build the query, rejecting unparseable dates and a reversed date range
+    ///
+    /// An empty query is allowed here (it matches everything); call
+    /// [`SearchQuery::validate`] where at least one criterion is required.
+    pub fn build(self) -> Result<SearchQuery, SearchQueryError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.query.validate_date_range()?;
+        Ok(self.query)
+    }
+}
+
+fn non_blank(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
 }
 
 #[derive(Debug, Clone)]
@@ -119,6 +421,169 @@ pub struct DownloadRequest {
     pub filing_type: Option<FilingType>,
     pub date_from: Option<NaiveDate>,
     pub date_to: Option<NaiveDate>,
+    /// Maximum number of documents to download, or 0 for every matching document
     pub limit: usize,
     pub format: DocumentFormat,
-}
\ No newline at end of file
+    /// Download only this document (EDINET doc ID or EDGAR accession number), skipping the ticker lookup
+    pub document_id: Option<String>,
+    /// Leave documents that already exist in the output directory untouched instead of downloading them again
+    pub skip_existing: bool,
+    /// Print each download request (URL, query parameters, redacted headers) instead of sending it
+    pub dry_run: bool,
+    /// Also download each EDINET document's attachments (e.g. articles of incorporation) next to the main ZIP
+    pub include_attachments: bool,
+    /// EDGAR file name template, e.g. `{ticker}_{form}_{date}`; the downloader's default when None
+    pub output_template: Option<String>,
+}
+
+/// Outcome of a download request, per document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadReport {
+    /// Documents downloaded, or in a dry run those that would be
+    pub downloaded: usize,
+    /// Documents left untouched because they were already in the output directory
+    pub skipped: usize,
+    /// Documents that could not be downloaded, as (doc ID or accession number, error)
+    pub failed: Vec<(String, String)>,
+}
+
+impl DownloadReport {
+    /// Documents the request matched, whatever happened to them
+    pub fn requested(&self) -> usize {
+        self.downloaded + self.skipped + self.failed.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_round_trip() {
+        for filing_type in FilingType::all() {
+            assert_eq!(FilingType::from_label(filing_type.as_str()).as_str(), filing_type.as_str());
+        }
+        assert!(matches!(FilingType::from_label("EDINET Form 030000"), FilingType::Other(_)));
+
+        for source in Source::all() {
+            assert_eq!(&Source::from_label(source.as_str()), source);
+        }
+        assert_eq!(Source::from_label("SEDAR"), Source::Other("SEDAR".to_string()));
+    }
+
+    #[test]
+    fn test_is_transcript_title() {
+        assert!(FilingType::is_transcript_title("Q2 2024 Earnings Call Transcript"));
+        assert!(FilingType::is_transcript_title("7203_earnings-call.txt"));
+        assert!(FilingType::is_transcript_title("決算説明会 質疑応答"));
+        assert!(!FilingType::is_transcript_title("Annual Report"));
+        assert!(!FilingType::is_transcript_title("EX-99.1 Press release announcing earnings"));
+    }
+
+    #[test]
+    fn test_classify_text() {
+        let classify = |text: &str| FilingType::classify_text(text).map(|filing_type| filing_type.as_str().to_string());
+        assert_eq!(classify("【表紙】【提出書類】有価証券報告書【根拠条文】").as_deref(), Some("Annual Securities Report"));
+        assert_eq!(classify("【提出書類】四半期報告書 ... 前期の有価証券報告書に記載").as_deref(), Some("Quarterly Securities Report"));
+        assert_eq!(classify("【提出書類】半期報告書").as_deref(), Some("Semi-Annual Securities Report"));
+        assert_eq!(classify("【提出書類】臨時報告書").as_deref(), Some("Extraordinary Report"));
+        assert_eq!(
+            classify("UNITED STATES\nFORM 10-K\nAnnual report pursuant to Section 13 or 15(d)").as_deref(),
+            Some("10-K")
+        );
+        assert_eq!(classify("当社グループは自動車事業を行っています。"), None);
+        // Phrases deep in the document are not the cover page
+        assert_eq!(classify(&format!("{}有価証券報告書", "あ".repeat(CLASSIFY_TEXT_CHARS))), None);
+    }
+
+    #[test]
+    fn test_source_from_document_id() {
+        assert_eq!(Source::from_document_id("S100TEST"), Some(Source::Edinet));
+        assert_eq!(Source::from_document_id("0000320193-24-000123"), Some(Source::Edgar));
+        assert_eq!(Source::from_document_id("000032019324000123"), Some(Source::Edgar));
+        assert_eq!(Source::from_document_id("7203"), None);
+        assert_eq!(Source::from_document_id("S100-TEST"), None);
+    }
+
+    #[test]
+    fn test_source_url() {
+        let document = |id: &str, source: Source| Document {
+            id: id.to_string(),
+            ticker: "TEST".to_string(),
+            company_name: "Test".to_string(),
+            filing_type: FilingType::TenK,
+            source,
+            date: NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+            content_path: PathBuf::new(),
+            metadata: HashMap::new(),
+            format: DocumentFormat::Complete,
+            tags: Vec::new(),
+        };
+
+        assert_eq!(
+            document("0000320193-24-000123", Source::Edgar).source_url().as_deref(),
+            Some("https://www.sec.gov/Archives/edgar/data/320193/000032019324000123/0000320193-24-000123-index.htm")
+        );
+        assert_eq!(
+            document("S100ABCD", Source::Edinet).source_url().as_deref(),
+            Some("https://disclosure2.edinet-fsa.go.jp/WZEK0040.aspx?S100ABCD,,")
+        );
+
+        // Imported ZIPs without a doc ID have no page to link to
+        assert_eq!(document("annual-report", Source::Edinet).source_url(), None);
+        assert_eq!(document("S100ABCD", Source::Tdnet).source_url(), None);
+
+        let mut stored = document("annual-report", Source::Edinet);
+        stored.metadata.insert("source_url".to_string(), edinet_viewer_url("S100WXYZ"));
+        assert_eq!(stored.source_url().as_deref(), Some("https://disclosure2.edinet-fsa.go.jp/WZEK0040.aspx?S100WXYZ,,"));
+    }
+
+    #[test]
+    fn test_download_report_requested() {
+        let report = DownloadReport {
+            downloaded: 2,
+            skipped: 1,
+            failed: vec![("S100FAIL".to_string(), "Not Found".to_string())],
+        };
+        assert_eq!(report.requested(), 4);
+        assert_eq!(DownloadReport::default().requested(), 0);
+    }
+
+    #[test]
+    fn test_search_query_builder() {
+        let query = SearchQuery::builder()
+            .ticker(" 7203 ")
+            .company_name("")
+            .source(Source::Edinet)
+            .date_from_str("2024-01-01")
+            .date_to_str(" ")
+            .build()
+            .unwrap();
+        assert_eq!(query.ticker.as_deref(), Some("7203"));
+        assert_eq!(query.company_name, None);
+        assert_eq!(query.date_from, NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert_eq!(query.date_to, None);
+        assert!(query.validate().is_ok());
+
+        let empty = SearchQuery::builder().source(Source::Edgar).build().unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.validate(), Err(SearchQueryError::NoCriteria));
+    }
+
+    #[test]
+    fn test_search_query_builder_date_errors() {
+        let err = SearchQuery::builder()
+            .date_from_str("2024-13-01")
+            .date_to_str("yesterday")
+            .build()
+            .unwrap_err();
+        assert_eq!(err, SearchQueryError::InvalidDate { field: "Date From", value: "2024-13-01".to_string() });
+
+        let err = SearchQuery::builder()
+            .date_from_str("2024-06-01")
+            .date_to_str("2024-01-01")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, SearchQueryError::DateRangeReversed { .. }));
+    }
+}
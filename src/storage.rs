@@ -1,8 +1,25 @@
 use anyhow::Result;
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use sqlx::{SqlitePool, Row};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 use crate::models::{Document, SearchQuery, FilingType, Source, DocumentFormat};
 
+/// Current schema version, tracked via SQLite's `user_version` pragma
+pub const SCHEMA_VERSION: i64 = 4;
+
+/// Shortest text query the trigram full-text index can match; shorter ones skip the stored text
+const MIN_INDEXED_QUERY_CHARS: usize = 3;
+
+/// Sources merged by a cross-source search
+pub const CROSS_SOURCES: [Source; 2] = [Source::Edgar, Source::Edinet];
+
+/// Columns selected for a `Document`: the documents table plus its tags as a JSON array
+const DOCUMENT_COLUMNS: &str =
+    "*, (SELECT json_group_array(tag) FROM (SELECT tag FROM document_tags WHERE document_id = documents.id ORDER BY tag)) AS tags";
+
 pub struct Storage {
     pool: SqlitePool,
 }
@@ -30,7 +47,9 @@ impl Storage {
                 content_path TEXT NOT NULL,
                 metadata TEXT NOT NULL,
                 content_preview TEXT,
-                format TEXT
+                format TEXT,
+                parent_doc_id TEXT,
+                content_hash TEXT
             );
             
             CREATE INDEX IF NOT EXISTS idx_ticker ON documents(ticker);
@@ -57,23 +76,138 @@ impl Storage {
             
             CREATE INDEX IF NOT EXISTS idx_securities_code ON edinet_static(securities_code);
             CREATE INDEX IF NOT EXISTS idx_submitter_name ON edinet_static(submitter_name);
+            
+            CREATE TABLE IF NOT EXISTS run_state (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS document_content (
+                document_id TEXT PRIMARY KEY,
+                content BLOB NOT NULL,
+                original_length INTEGER NOT NULL
+            );
+            
+            -- Trigram index of the stored text, keyed by document_content's rowid; contentless, so the
+            -- text itself is only kept compressed
+            CREATE VIRTUAL TABLE IF NOT EXISTS document_content_fts USING fts5(
+                content, content='', contentless_delete=1, tokenize='trigram'
+            );
+            
+            CREATE TABLE IF NOT EXISTS document_tags (
+                document_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (document_id, tag)
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_tag ON document_tags(tag);
             "#
         )
         .execute(&pool)
         .await?;
         
-        Ok(Storage { pool })
+        let mut storage = Storage { pool };
+        if storage.migrate().await? {
+            // Other pooled connections may still see the columns from before the migration
+            storage.pool.close().await;
+            storage.pool = SqlitePool::connect(&database_url).await?;
+        }
+        
+        Ok(storage)
+    }
+    
+    /// Apply data migrations for databases created by older versions
+    ///
+    /// Returns whether the database was migrated.
+    async fn migrate(&self) -> Result<bool> {
+        let (version,): (i64,) = sqlx::query_as("PRAGMA user_version")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        if version < 1 {
+            // EDINET PDF-only filings used to be indexed as "html"
+            sqlx::query("UPDATE documents SET format = 'pdf' WHERE source = 'EDINET' AND format = 'html'")
+                .execute(&self.pool)
+                .await?;
+        }
+        
+        if version < 2 {
+            // Amendments reference the filing they amend, kept in a column so searches can hide superseded versions
+            let columns: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info('documents')")
+                .fetch_all(&self.pool)
+                .await?;
+            if !columns.iter().any(|(name,)| name == "parent_doc_id") {
+                sqlx::query("ALTER TABLE documents ADD COLUMN parent_doc_id TEXT")
+                    .execute(&self.pool)
+                    .await?;
+            }
+            sqlx::query("UPDATE documents SET parent_doc_id = json_extract(metadata, '$.parent_doc_id') WHERE parent_doc_id IS NULL")
+                .execute(&self.pool)
+                .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_filing_group ON documents(COALESCE(parent_doc_id, id))")
+                .execute(&self.pool)
+                .await?;
+        }
+        
+        if version < 3 {
+            // SHA-256 of each document's file, filled in by `fast10k hash`
+            let columns: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info('documents')")
+                .fetch_all(&self.pool)
+                .await?;
+            if !columns.iter().any(|(name,)| name == "content_hash") {
+                sqlx::query("ALTER TABLE documents ADD COLUMN content_hash TEXT")
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        
+        if version < 4 {
+            // Stored text is searched through the trigram index, which starts out empty
+            let rows: Vec<(i64, Vec<u8>)> = sqlx::query_as("SELECT rowid, content FROM document_content")
+                .fetch_all(&self.pool)
+                .await?;
+            for (rowid, compressed) in rows {
+                sqlx::query("INSERT INTO document_content_fts (rowid, content) VALUES (?, ?)")
+                    .bind(rowid)
+                    .bind(decompress_content(&compressed)?)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        
+        if version < SCHEMA_VERSION {
+            sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+                .execute(&self.pool)
+                .await?;
+        }
+        
+        Ok(version < SCHEMA_VERSION)
     }
     
     pub async fn insert_document(&self, document: &Document) -> Result<()> {
         let metadata_json = serde_json::to_string(&document.metadata)?;
         let content_preview = document.metadata.get("content_preview").map(|s| s.as_str()).unwrap_or("");
+        let parent_doc_id = document.metadata.get("parent_doc_id").filter(|id| !id.is_empty());
         
+        // Update in place rather than replace, so a re-indexed document keeps its hash while its file is unchanged
         sqlx::query(
             r#"
-            INSERT OR REPLACE INTO documents 
-            (id, ticker, company_name, filing_type, source, date, content_path, metadata, content_preview, format)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO documents 
+            (id, ticker, company_name, filing_type, source, date, content_path, metadata, content_preview, format, parent_doc_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                ticker = excluded.ticker,
+                company_name = excluded.company_name,
+                filing_type = excluded.filing_type,
+                source = excluded.source,
+                date = excluded.date,
+                content_hash = CASE WHEN content_path = excluded.content_path THEN content_hash END,
+                content_path = excluded.content_path,
+                metadata = excluded.metadata,
+                content_preview = excluded.content_preview,
+                format = excluded.format,
+                parent_doc_id = excluded.parent_doc_id
             "#
         )
         .bind(&document.id)
@@ -86,6 +220,7 @@ impl Storage {
         .bind(&metadata_json)
         .bind(content_preview)
         .bind(document.format.as_str())
+        .bind(parent_doc_id)
         .execute(&self.pool)
         .await?;
         
@@ -93,309 +228,1799 @@ impl Storage {
     }
     
     pub async fn search_documents(&self, query: &SearchQuery, limit: usize) -> Result<Vec<Document>> {
-        // Build dynamic SQL query based on provided filters
-        let mut conditions = Vec::new();
-        let mut params: Vec<String> = Vec::new();
-        
-        if let Some(ref ticker) = query.ticker {
-            conditions.push("ticker = ?");
-            params.push(ticker.clone());
-        }
-        
-        if let Some(ref company_name) = query.company_name {
-            conditions.push("company_name LIKE ?");
-            params.push(format!("%{}%", company_name));
-        }
+        self.search_documents_page(query, limit, 0).await
+    }
+    
+    /// Search for up to `limit` documents after skipping the first `offset` matches, newest first
+    ///
+    /// Ties on date are ordered by id so consecutive pages neither repeat nor skip documents.
+    /// A `limit` of 0 returns every remaining match.
+    pub async fn search_documents_page(&self, query: &SearchQuery, limit: usize, offset: usize) -> Result<Vec<Document>> {
+        let (where_clause, params) = search_conditions(query);
+        let sql = format!(
+            "SELECT {} FROM documents{} ORDER BY date DESC, id LIMIT {} OFFSET {}",
+            DOCUMENT_COLUMNS, where_clause, sql_limit(limit), offset
+        );
         
-        if let Some(ref filing_type) = query.filing_type {
-            conditions.push("filing_type = ?");
-            params.push(filing_type.as_str().to_string());
+        // Execute query with parameters
+        let mut query = sqlx::query(&sql);
+        for param in &params {
+            query = query.bind(param);
         }
         
-        if let Some(ref source) = query.source {
-            conditions.push("source = ?");
-            params.push(source.as_str().to_string());
-        }
+        let rows = query.fetch_all(&self.pool).await?;
         
-        if let Some(date_from) = query.date_from {
-            conditions.push("date >= ?");
-            params.push(date_from.format("%Y-%m-%d").to_string());
+        let mut documents = Vec::new();
+        for row in rows {
+            documents.push(document_from_row(&row)?);
         }
         
-        if let Some(date_to) = query.date_to {
-            conditions.push("date <= ?");
-            params.push(date_to.format("%Y-%m-%d").to_string());
-        }
+        Ok(documents)
+    }
+    
+    /// Count the documents matching `query`, ignoring any limit
+    pub async fn count_matching_documents(&self, query: &SearchQuery) -> Result<i64> {
+        let (where_clause, params) = search_conditions(query);
+        let sql = format!("SELECT COUNT(*) FROM documents{}", where_clause);
         
-        if let Some(ref text_query) = query.text_query {
-            conditions.push("(company_name LIKE ? OR content_preview LIKE ?)");
-            params.push(format!("%{}%", text_query));
-            params.push(format!("%{}%", text_query));
+        let mut query = sqlx::query_scalar::<_, i64>(&sql);
+        for param in &params {
+            query = query.bind(param);
         }
         
-        // Build the final SQL query
-        let base_query = "SELECT * FROM documents";
-        let where_clause = if conditions.is_empty() {
-            String::new()
+        Ok(query.fetch_one(&self.pool).await?)
+    }
+    
+    /// Ids of the documents matching `query`; with `all_sources`, of its EDGAR and EDINET matches
+    pub async fn matching_document_ids(&self, query: &SearchQuery, all_sources: bool) -> Result<Vec<String>> {
+        let queries: Vec<SearchQuery> = if all_sources {
+            CROSS_SOURCES.into_iter().map(|source| SearchQuery { source: Some(source), ..query.clone() }).collect()
         } else {
-            format!(" WHERE {}", conditions.join(" AND "))
+            vec![query.clone()]
         };
-        let order_clause = " ORDER BY date DESC";
-        let limit_clause = format!(" LIMIT {}", limit);
-        
-        
-        let sql = format!("{}{}{}{}", base_query, where_clause, order_clause, limit_clause);
         
-        // Execute query with parameters
-        let mut query = sqlx::query(&sql);
-        for param in &params {
-            query = query.bind(param);
+        let mut ids = Vec::new();
+        for query in &queries {
+            let (where_clause, params) = search_conditions(query);
+            let sql = format!("SELECT id FROM documents{}", where_clause);
+            
+            let mut query = sqlx::query_scalar::<_, String>(&sql);
+            for param in &params {
+                query = query.bind(param);
+            }
+            ids.extend(query.fetch_all(&self.pool).await?);
         }
-        
-        let rows = query.fetch_all(&self.pool).await?;
-        
+        Ok(ids)
+    }
+    
+    /// Search EDGAR and EDINET separately and merge the results, newest first
+    ///
+    /// Any `source` set on the query is ignored.
+    pub async fn search_all_sources(&self, query: &SearchQuery, limit: usize) -> Result<Vec<Document>> {
+        self.search_all_sources_page(query, limit, 0).await
+    }
+    
+    /// Page through the merged EDGAR and EDINET results, as `search_documents_page` does for one source
+    pub async fn search_all_sources_page(&self, query: &SearchQuery, limit: usize, offset: usize) -> Result<Vec<Document>> {
         let mut documents = Vec::new();
-        for row in rows {
-            let filing_type_str: String = row.get("filing_type");
-            let source_str: String = row.get("source");
-            let date_str: String = row.get("date");
-            let metadata_str: String = row.get("metadata");
-            let format_str: Option<String> = row.try_get("format").ok();
-            
-            let filing_type = match filing_type_str.as_str() {
-                "10-K" => FilingType::TenK,
-                "10-Q" => FilingType::TenQ,
-                "8-K" => FilingType::EightK,
-                "Transcript" => FilingType::Transcript,
-                "Press Release" => FilingType::PressRelease,
-                other => FilingType::Other(other.to_string()),
-            };
-            
-            let source = match source_str.as_str() {
-                "EDGAR" => Source::Edgar,
-                "EDINET" => Source::Edinet,
-                "TDNet" => Source::Tdnet,
-                other => Source::Other(other.to_string()),
+        for source in CROSS_SOURCES {
+            let source_query = SearchQuery {
+                source: Some(source),
+                ..query.clone()
             };
-            
-            let date = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?;
-            let metadata = serde_json::from_str(&metadata_str)?;
-            
-            let format = match format_str.as_deref() {
-                Some("txt") => DocumentFormat::Txt,
-                Some("html") => DocumentFormat::Html,
-                Some("xbrl") => DocumentFormat::Xbrl,
-                Some("ixbrl") => DocumentFormat::Ixbrl,
-                Some("complete") => DocumentFormat::Complete,
-                Some(other) if other.contains(',') => DocumentFormat::Other(other.to_string()),
-                Some(other) => DocumentFormat::Other(other.to_string()),
-                _ => DocumentFormat::Complete, // Default fallback
+            documents.extend(self.search_documents_page(&source_query, page_end(offset, limit), 0).await?);
+        }
+        
+        documents.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.id.cmp(&b.id)));
+        Ok(documents.into_iter().skip(offset).take(max_results(limit)).collect())
+    }
+    
+    /// Count the EDGAR and EDINET documents matching `query`; any `source` set on the query is ignored
+    pub async fn count_matching_all_sources(&self, query: &SearchQuery) -> Result<i64> {
+        let mut total = 0;
+        for source in CROSS_SOURCES {
+            let source_query = SearchQuery {
+                source: Some(source),
+                ..query.clone()
             };
-            
-            documents.push(Document {
-                id: row.get("id"),
-                ticker: row.get("ticker"),
-                company_name: row.get("company_name"),
-                filing_type,
-                source,
-                date,
-                content_path: row.get::<String, _>("content_path").into(),
-                metadata,
-                format,
-            });
+            total += self.count_matching_documents(&source_query).await?;
         }
+        Ok(total)
+    }
+    
+    pub async fn get_document_by_id(&self, id: &str) -> Result<Option<Document>> {
+        let row = sqlx::query(&format!("SELECT {} FROM documents WHERE id = ?", DOCUMENT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
         
-        Ok(documents)
+        row.map(|row| document_from_row(&row)).transpose()
     }
-}
-
-// Public convenience functions
-pub async fn search_documents(query: &SearchQuery, database_path: &str, limit: usize) -> Result<Vec<Document>> {
-    let storage = Storage::new(database_path).await?;
-    storage.search_documents(query, limit).await
-}
-
-pub async fn insert_document(document: &Document, database_path: &str) -> Result<()> {
-    let storage = Storage::new(database_path).await?;
-    storage.insert_document(document).await
-}
-
-pub async fn count_documents_by_source(source: &Source, database_path: &str) -> Result<i64> {
-    let storage = Storage::new(database_path).await?;
     
-    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM documents WHERE source = ?")
-        .bind(source.as_str())
-        .fetch_one(&storage.pool)
-        .await?;
+    /// Documents amending `id`, oldest first
+    pub async fn get_amendments(&self, id: &str) -> Result<Vec<Document>> {
+        let rows = sqlx::query(&format!("SELECT {} FROM documents WHERE parent_doc_id = ? ORDER BY date, id", DOCUMENT_COLUMNS))
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(document_from_row).collect()
+    }
     
-    Ok(count.0)
-}
-
-pub async fn get_date_range_for_source(source: &Source, database_path: &str) -> Result<(String, String)> {
-    let storage = Storage::new(database_path).await?;
+    /// Id and `content_path` of every document, for checking the catalog against the download directory
+    pub async fn list_content_paths(&self) -> Result<Vec<(String, PathBuf)>> {
+        let rows = sqlx::query("SELECT id, content_path FROM documents ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows
+            .iter()
+            .map(|row| (row.get::<String, _>("id"), PathBuf::from(row.get::<String, _>("content_path"))))
+            .collect())
+    }
     
-    let row = sqlx::query("SELECT MIN(date) as min_date, MAX(date) as max_date FROM documents WHERE source = ?")
-        .bind(source.as_str())
-        .fetch_one(&storage.pool)
-        .await?;
+    /// Id and `content_path` of the documents with a file but no content hash yet
+    pub async fn list_unhashed_content_paths(&self) -> Result<Vec<(String, PathBuf)>> {
+        let rows = sqlx::query("SELECT id, content_path FROM documents WHERE content_hash IS NULL AND content_path != '' ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows
+            .iter()
+            .map(|row| (row.get::<String, _>("id"), PathBuf::from(row.get::<String, _>("content_path"))))
+            .collect())
+    }
     
-    let min_date: String = row.get("min_date");
-    let max_date: String = row.get("max_date");
+    /// Record the hash of a document's file
+    pub async fn set_content_hash(&self, id: &str, hash: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE documents SET content_hash = ? WHERE id = ?")
+            .bind(hash)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
     
-    Ok((min_date, max_date))
-}
-
-pub async fn load_edinet_static_data(database_path: &str, csv_path: &str) -> Result<usize> {
-    let storage = Storage::new(database_path).await?;
+    /// Content hash recorded for a document, if any
+    pub async fn get_content_hash(&self, id: &str) -> Result<Option<String>> {
+        let row: Option<(Option<String>,)> = sqlx::query_as("SELECT content_hash FROM documents WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.and_then(|(hash,)| hash))
+    }
     
-    // Clear existing data
-    sqlx::query("DELETE FROM edinet_static").execute(&storage.pool).await?;
+    /// Forget a document's local file, e.g. after it was deleted from disk
+    pub async fn clear_content_path(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE documents SET content_path = '' WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
     
-    let mut reader = csv::ReaderBuilder::new()
-        .has_headers(false)
-        .flexible(true)
-        .from_path(csv_path)?;
-    let mut count = 0;
+    /// Change a document's filing type, e.g. once its text shows what an unknown form is
+    pub async fn update_filing_type(&self, id: &str, filing_type: &FilingType) -> Result<bool> {
+        let result = sqlx::query("UPDATE documents SET filing_type = ? WHERE id = ?")
+            .bind(filing_type.as_str())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
     
-    // Skip the first two rows (metadata and header rows)
-    let mut records = reader.records();
-    if let Some(_) = records.next() {
-        // Skip metadata row
-    }
-    if let Some(_) = records.next() {
-        // Skip header row
-    }
-    
-    for result in records {
-        let record = result?;
-        
-        if record.len() >= 13 {
-            let securities_code = record.get(11).unwrap_or("").trim_matches('"');
-            // Only include records with securities codes (listed companies)
-            if !securities_code.is_empty() {
-                sqlx::query(
-                    r#"INSERT OR REPLACE INTO edinet_static 
-                       (edinet_code, submitter_type, listed_status, consolidated_status, 
-                        capital_stock, account_closing_date, submitter_name, submitter_name_en,
-                        submitter_name_phonetic, province, industry, securities_code, corporate_number)
-                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
-                )
-                .bind(record.get(0).unwrap_or("").trim_matches('"'))
-                .bind(record.get(1).unwrap_or("").trim_matches('"'))
-                .bind(record.get(2).unwrap_or("").trim_matches('"'))
-                .bind(record.get(3).unwrap_or("").trim_matches('"'))
-                .bind(record.get(4).unwrap_or("").trim_matches('"'))
-                .bind(record.get(5).unwrap_or("").trim_matches('"'))
-                .bind(record.get(6).unwrap_or("").trim_matches('"'))
-                .bind(record.get(7).unwrap_or("").trim_matches('"'))
-                .bind(record.get(8).unwrap_or("").trim_matches('"'))
-                .bind(record.get(9).unwrap_or("").trim_matches('"'))
-                .bind(record.get(10).unwrap_or("").trim_matches('"'))
-                .bind(securities_code)
-                .bind(record.get(12).unwrap_or("").trim_matches('"'))
-                .execute(&storage.pool)
-                .await?;
-                
-                count += 1;
+    /// Replace a document's metadata, logging each changed key
+    ///
+    /// Returns `false` when no document has the given id.
+    pub async fn update_document_metadata(&self, id: &str, metadata: &HashMap<String, String>) -> Result<bool> {
+        let Some(existing) = self.get_document_by_id(id).await? else {
+            return Ok(false);
+        };
+        
+        let mut keys: Vec<&String> = existing.metadata.keys().chain(metadata.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let (old, new) = (existing.metadata.get(key), metadata.get(key));
+            if old != new {
+                info!("Metadata edit on {}: {} changed from {:?} to {:?}", id, key, old, new);
             }
         }
+        
+        let metadata_json = serde_json::to_string(metadata)?;
+        let content_preview = metadata.get("content_preview").map(|s| s.as_str()).unwrap_or("");
+        let parent_doc_id = metadata.get("parent_doc_id").filter(|id| !id.is_empty());
+        sqlx::query("UPDATE documents SET metadata = ?, content_preview = ?, parent_doc_id = ? WHERE id = ?")
+            .bind(&metadata_json)
+            .bind(content_preview)
+            .bind(parent_doc_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(true)
     }
     
-    Ok(count)
-}
-
-pub async fn search_edinet_static(database_path: &str, query: &str, limit: usize) -> Result<Vec<(String, String, String, String, String, String, String)>> {
-    let storage = Storage::new(database_path).await?;
-    
-    let search_pattern = format!("%{}%", query);
-    let rows = sqlx::query(
-        r#"SELECT edinet_code, submitter_name, submitter_name_en, securities_code, 
-                  industry, account_closing_date, province
-           FROM edinet_static 
-           WHERE submitter_name LIKE ? OR submitter_name_en LIKE ? OR securities_code LIKE ?
-           ORDER BY securities_code
-           LIMIT ?"#
-    )
-    .bind(&search_pattern)
-    .bind(&search_pattern)
-    .bind(&search_pattern)
-    .bind(limit as i64)
-    .fetch_all(&storage.pool)
-    .await?;
-    
-    let mut results = Vec::new();
-    for row in rows {
-        results.push((
-            row.get::<String, _>("edinet_code"),
-            row.get::<String, _>("submitter_name"),
-            row.get::<String, _>("submitter_name_en"),
-            row.get::<String, _>("securities_code"),
-            row.get::<String, _>("industry"),
-            row.get::<String, _>("account_closing_date"),
-            row.get::<String, _>("province"),
-        ));
+    /// Replace the tags of a document; blank and repeated tags are dropped
+    pub async fn set_tags(&self, id: &str, tags: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM document_tags WHERE document_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+            sqlx::query("INSERT OR IGNORE INTO document_tags (document_id, tag) VALUES (?, ?)")
+                .bind(id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        info!("Tags of {} set to {:?}", id, tags);
+        Ok(())
     }
     
-    Ok(results)
-}
-
-pub async fn get_edinet_code_by_securities_code(database_path: &str, securities_code: &str) -> Result<Option<String>> {
-    let storage = Storage::new(database_path).await?;
-    
-    // First try exact match
-    let row = sqlx::query("SELECT edinet_code FROM edinet_static WHERE securities_code = ?")
-        .bind(securities_code)
-        .fetch_optional(&storage.pool)
-        .await?;
-    
-    if let Some(row) = row {
-        return Ok(Some(row.get::<String, _>("edinet_code")));
+    /// Tags of a document in alphabetical order
+    pub async fn get_tags(&self, id: &str) -> Result<Vec<String>> {
+        let tags: Vec<(String,)> = sqlx::query_as("SELECT tag FROM document_tags WHERE document_id = ? ORDER BY tag")
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tags.into_iter().map(|(tag,)| tag).collect())
     }
     
-    // If no exact match, try with trailing zero (e.g., 7670 -> 76700)
-    let securities_code_with_zero = format!("{}0", securities_code);
-    let row = sqlx::query("SELECT edinet_code FROM edinet_static WHERE securities_code = ?")
-        .bind(&securities_code_with_zero)
-        .fetch_optional(&storage.pool)
+    /// Store a document's full extracted text, zlib-compressed, for offline full-text search
+    pub async fn store_document_content(&self, document_id: &str, content: &str) -> Result<()> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes())?;
+        let compressed = encoder.finish()?;
+        
+        // Upserted so the row keeps its rowid, which keys its entry in the full-text index
+        let mut tx = self.pool.begin().await?;
+        let (rowid,): (i64,) = sqlx::query_as(
+            "INSERT INTO document_content (document_id, content, original_length) VALUES (?, ?, ?) \
+             ON CONFLICT(document_id) DO UPDATE SET content = excluded.content, original_length = excluded.original_length \
+             RETURNING rowid",
+        )
+        .bind(document_id)
+        .bind(compressed)
+        .bind(content.len() as i64)
+        .fetch_one(&mut *tx)
         .await?;
+        
+        sqlx::query("DELETE FROM document_content_fts WHERE rowid = ?")
+            .bind(rowid)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO document_content_fts (rowid, content) VALUES (?, ?)")
+            .bind(rowid)
+            .bind(content)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
     
-    if let Some(row) = row {
-        return Ok(Some(row.get::<String, _>("edinet_code")));
+    /// Full text stored for a document by `store_document_content`
+    pub async fn get_document_content(&self, document_id: &str) -> Result<Option<String>> {
+        let row: Option<(Vec<u8>,)> = sqlx::query_as("SELECT content FROM document_content WHERE document_id = ?")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        row.map(|(compressed,)| decompress_content(&compressed)).transpose()
     }
     
-    // If still no match, try removing trailing zero (e.g., 76700 -> 7670)
-    if securities_code.len() > 4 && securities_code.ends_with('0') {
-        let securities_code_without_zero = &securities_code[..securities_code.len()-1];
-        let row = sqlx::query("SELECT edinet_code FROM edinet_static WHERE securities_code = ?")
-            .bind(securities_code_without_zero)
-            .fetch_optional(&storage.pool)
+    /// Read a value persisted between runs, e.g. an indexing high-water mark
+    pub async fn get_run_state(&self, key: &str) -> Result<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT value FROM run_state WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
             .await?;
         
-        if let Some(row) = row {
-            return Ok(Some(row.get::<String, _>("edinet_code")));
-        }
+        Ok(row.map(|(value,)| value))
     }
     
-    Ok(None)
+    /// Persist a value between runs, replacing any previous value for `key`
+    pub async fn set_run_state(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO run_state (key, value, updated_at) VALUES (?, ?, ?)")
+            .bind(key)
+            .bind(value)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
 }
 
-pub async fn get_top_companies_for_source(source: &Source, database_path: &str, limit: usize) -> Result<Vec<(String, i64)>> {
-    let storage = Storage::new(database_path).await?;
+/// Convert a `documents` table row into a `Document`
+fn document_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Document> {
+    let filing_type_str: String = row.get("filing_type");
+    let source_str: String = row.get("source");
+    let date_str: String = row.get("date");
+    let metadata_str: String = row.get("metadata");
+    let format_str: Option<String> = row.try_get("format").ok();
+    let tags_json: Option<String> = row.try_get("tags").ok().flatten();
     
-    let rows = sqlx::query(
-        "SELECT company_name, COUNT(*) as doc_count FROM documents WHERE source = ? GROUP BY company_name ORDER BY doc_count DESC LIMIT ?"
-    )
-        .bind(source.as_str())
-        .bind(limit as i64)
-        .fetch_all(&storage.pool)
-        .await?;
+    let filing_type = FilingType::from_label(&filing_type_str);
+    let source = Source::from_label(&source_str);
     
-    let mut companies = Vec::new();
-    for row in rows {
-        let company_name: String = row.get("company_name");
-        let doc_count: i64 = row.get("doc_count");
-        companies.push((company_name, doc_count));
-    }
+    let date = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?;
+    let metadata = serde_json::from_str(&metadata_str)?;
     
-    Ok(companies)
-}
\ No newline at end of file
+    let format = match format_str.as_deref() {
+        Some("txt") => DocumentFormat::Txt,
+        Some("html") => DocumentFormat::Html,
+        Some("xbrl") => DocumentFormat::Xbrl,
+        Some("ixbrl") => DocumentFormat::Ixbrl,
+        Some("complete") => DocumentFormat::Complete,
+        Some("pdf") => DocumentFormat::Pdf,
+        Some("summary") => DocumentFormat::Summary,
+        Some("csv") => DocumentFormat::Csv,
+        Some(other) if other.contains(',') => DocumentFormat::Other(other.to_string()),
+        Some(other) => DocumentFormat::Other(other.to_string()),
+        _ => DocumentFormat::Complete, // Default fallback
+    };
+    let tags = tags_json.map(|json| serde_json::from_str(&json)).transpose()?.unwrap_or_default();
+    
+    Ok(Document {
+        id: row.get("id"),
+        ticker: row.get("ticker"),
+        company_name: row.get("company_name"),
+        filing_type,
+        source,
+        date,
+        content_path: row.get::<String, _>("content_path").into(),
+        metadata,
+        format,
+        tags,
+    })
+}
+
+/// Text of a `document_content` row, as stored by `store_document_content`
+fn decompress_content(compressed: &[u8]) -> Result<String> {
+    let mut content = String::new();
+    ZlibDecoder::new(compressed).read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// SQL `WHERE` clause (empty without filters) and its bound parameters for the filters of `query`
+///
+/// Stored full text is compressed, so it is only searched through the trigram index, which needs
+/// at least `MIN_INDEXED_QUERY_CHARS` characters; shorter queries match names and previews only.
+fn search_conditions(query: &SearchQuery) -> (String, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut params: Vec<String> = Vec::new();
+    
+    if let Some(ref ticker) = query.ticker {
+        conditions.push("ticker = ?");
+        params.push(ticker.clone());
+    }
+    
+    if let Some(ref company_name) = query.company_name {
+        conditions.push("company_name LIKE ?");
+        params.push(format!("%{}%", company_name));
+    }
+    
+    if let Some(ref filing_type) = query.filing_type {
+        conditions.push("filing_type = ?");
+        params.push(filing_type.as_str().to_string());
+    }
+    
+    if let Some(ref source) = query.source {
+        conditions.push("source = ?");
+        params.push(source.as_str().to_string());
+    }
+    
+    if let Some(date_from) = query.date_from {
+        conditions.push("date >= ?");
+        params.push(date_from.format("%Y-%m-%d").to_string());
+    }
+    
+    if let Some(date_to) = query.date_to {
+        conditions.push("date <= ?");
+        params.push(date_to.format("%Y-%m-%d").to_string());
+    }
+    
+    if let Some(ref text_query) = query.text_query {
+        params.push(format!("%{}%", text_query));
+        params.push(format!("%{}%", text_query));
+        if text_query.chars().count() >= MIN_INDEXED_QUERY_CHARS {
+            conditions.push(
+                "(company_name LIKE ? OR content_preview LIKE ? OR id IN (SELECT document_id FROM document_content \
+                 WHERE rowid IN (SELECT rowid FROM document_content_fts WHERE document_content_fts MATCH ?)))",
+            );
+            // Quoted as one phrase so the text is matched as a substring rather than parsed as FTS5 syntax
+            params.push(format!("\"{}\"", text_query.replace('"', "\"\"")));
+        } else {
+            conditions.push("(company_name LIKE ? OR content_preview LIKE ?)");
+        }
+    }
+    
+    if let Some(ref tag) = query.tag {
+        conditions.push("id IN (SELECT document_id FROM document_tags WHERE tag = ?)");
+        params.push(tag.clone());
+    }
+    
+    if !query.include_withdrawn {
+        // EDINET marks withdrawn filings "2" and the withdrawal notices themselves "1"
+        conditions.push("COALESCE(json_extract(metadata, '$.withdrawal_status'), '0') = '0'");
+    }
+    
+    if query.latest_only {
+        // An original filing and its amendments share COALESCE(parent_doc_id, id); keep the newest of each
+        conditions.push(
+            "NOT EXISTS (SELECT 1 FROM documents AS newer \
+             WHERE COALESCE(newer.parent_doc_id, newer.id) = COALESCE(documents.parent_doc_id, documents.id) \
+             AND (newer.date > documents.date OR (newer.date = documents.date AND newer.id > documents.id)))",
+        );
+    }
+    
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    (where_clause, params)
+}
+
+/// SQL `LIMIT` value for a result limit, where 0 means no limit (SQLite treats a negative LIMIT as unbounded)
+fn sql_limit(limit: usize) -> i64 {
+    if limit == 0 { -1 } else { limit as i64 }
+}
+
+/// Number of results to keep for a result limit, where 0 means all of them
+fn max_results(limit: usize) -> usize {
+    if limit == 0 { usize::MAX } else { limit }
+}
+
+/// Matches each source or database must return to fill the page at `offset`, or 0 for an unlimited page
+fn page_end(offset: usize, limit: usize) -> usize {
+    if limit == 0 { 0 } else { offset + limit }
+}
+
+// Public convenience functions
+pub async fn search_documents(query: &SearchQuery, database_path: &str, limit: usize) -> Result<Vec<Document>> {
+    let storage = Storage::new(database_path).await?;
+    storage.search_documents(query, limit).await
+}
+
+pub async fn search_documents_page(query: &SearchQuery, database_path: &str, limit: usize, offset: usize) -> Result<Vec<Document>> {
+    let storage = Storage::new(database_path).await?;
+    storage.search_documents_page(query, limit, offset).await
+}
+
+pub async fn count_matching_documents(query: &SearchQuery, database_path: &str) -> Result<i64> {
+    let storage = Storage::new(database_path).await?;
+    storage.count_matching_documents(query).await
+}
+
+pub async fn search_all_sources(query: &SearchQuery, database_path: &str, limit: usize) -> Result<Vec<Document>> {
+    let storage = Storage::new(database_path).await?;
+    storage.search_all_sources(query, limit).await
+}
+
+pub async fn search_all_sources_page(query: &SearchQuery, database_path: &str, limit: usize, offset: usize) -> Result<Vec<Document>> {
+    let storage = Storage::new(database_path).await?;
+    storage.search_all_sources_page(query, limit, offset).await
+}
+
+pub async fn count_matching_all_sources(query: &SearchQuery, database_path: &str) -> Result<i64> {
+    let storage = Storage::new(database_path).await?;
+    storage.count_matching_all_sources(query).await
+}
+
+/// Search several databases, e.g. one per market or year, and merge the results newest first
+///
+/// With `all_sources` each database is searched as `search_all_sources_page` does. A document
+/// indexed in more than one database is returned once, from the first database listing it.
+pub async fn search_databases_page(
+    query: &SearchQuery,
+    database_paths: &[String],
+    all_sources: bool,
+    limit: usize,
+    offset: usize,
+) -> Result<Vec<Document>> {
+    let mut documents = Vec::new();
+    let mut seen = HashSet::new();
+    for database_path in database_paths {
+        let storage = Storage::new(database_path).await?;
+        let found = if all_sources {
+            storage.search_all_sources_page(query, page_end(offset, limit), 0).await?
+        } else {
+            storage.search_documents_page(query, page_end(offset, limit), 0).await?
+        };
+        documents.extend(found.into_iter().filter(|document| seen.insert(document.id.clone())));
+    }
+    
+    documents.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.id.cmp(&b.id)));
+    Ok(documents.into_iter().skip(offset).take(max_results(limit)).collect())
+}
+
+/// Run several queries against several databases, opening each database once
+///
+/// Returns the results of each query in order, merged like [`search_databases_page`]: newest
+/// first, at most `limit` per query, and a document indexed in more than one database listed once.
+pub async fn search_databases_batch(
+    queries: &[SearchQuery],
+    database_paths: &[String],
+    all_sources: bool,
+    limit: usize,
+) -> Result<Vec<Vec<Document>>> {
+    let mut results: Vec<Vec<Document>> = vec![Vec::new(); queries.len()];
+    let mut seen: Vec<HashSet<String>> = vec![HashSet::new(); queries.len()];
+    for database_path in database_paths {
+        let storage = Storage::new(database_path).await?;
+        for (index, query) in queries.iter().enumerate() {
+            let found = if all_sources {
+                storage.search_all_sources_page(query, limit, 0).await?
+            } else {
+                storage.search_documents_page(query, limit, 0).await?
+            };
+            results[index].extend(found.into_iter().filter(|document| seen[index].insert(document.id.clone())));
+        }
+    }
+    
+    for documents in &mut results {
+        documents.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.id.cmp(&b.id)));
+        documents.truncate(max_results(limit));
+    }
+    Ok(results)
+}
+
+/// Total matches across several databases, counting a document indexed in more than one once
+/// as [`search_databases_page`] lists it once
+pub async fn count_matching_databases(query: &SearchQuery, database_paths: &[String], all_sources: bool) -> Result<i64> {
+    let mut ids = HashSet::new();
+    for database_path in database_paths {
+        let storage = Storage::new(database_path).await?;
+        ids.extend(storage.matching_document_ids(query, all_sources).await?);
+    }
+    Ok(ids.len() as i64)
+}
+
+pub async fn get_document_by_id(id: &str, database_path: &str) -> Result<Option<Document>> {
+    let storage = Storage::new(database_path).await?;
+    storage.get_document_by_id(id).await
+}
+
+pub async fn insert_document(document: &Document, database_path: &str) -> Result<()> {
+    let storage = Storage::new(database_path).await?;
+    storage.insert_document(document).await
+}
+
+pub async fn get_amendments(id: &str, database_path: &str) -> Result<Vec<Document>> {
+    let storage = Storage::new(database_path).await?;
+    storage.get_amendments(id).await
+}
+
+pub async fn update_document_metadata(id: &str, metadata: &HashMap<String, String>, database_path: &str) -> Result<bool> {
+    let storage = Storage::new(database_path).await?;
+    storage.update_document_metadata(id, metadata).await
+}
+
+pub async fn set_tags(id: &str, tags: &[String], database_path: &str) -> Result<()> {
+    let storage = Storage::new(database_path).await?;
+    storage.set_tags(id, tags).await
+}
+
+pub async fn get_tags(id: &str, database_path: &str) -> Result<Vec<String>> {
+    let storage = Storage::new(database_path).await?;
+    storage.get_tags(id).await
+}
+
+pub async fn list_content_paths(database_path: &str) -> Result<Vec<(String, PathBuf)>> {
+    let storage = Storage::new(database_path).await?;
+    storage.list_content_paths().await
+}
+
+pub async fn clear_content_path(id: &str, database_path: &str) -> Result<bool> {
+    let storage = Storage::new(database_path).await?;
+    storage.clear_content_path(id).await
+}
+
+pub async fn update_filing_type(id: &str, filing_type: &FilingType, database_path: &str) -> Result<bool> {
+    let storage = Storage::new(database_path).await?;
+    storage.update_filing_type(id, filing_type).await
+}
+
+pub async fn store_document_content(document_id: &str, content: &str, database_path: &str) -> Result<()> {
+    let storage = Storage::new(database_path).await?;
+    storage.store_document_content(document_id, content).await
+}
+
+pub async fn get_document_content(document_id: &str, database_path: &str) -> Result<Option<String>> {
+    let storage = Storage::new(database_path).await?;
+    storage.get_document_content(document_id).await
+}
+
+pub async fn get_run_state(key: &str, database_path: &str) -> Result<Option<String>> {
+    let storage = Storage::new(database_path).await?;
+    storage.get_run_state(key).await
+}
+
+pub async fn set_run_state(key: &str, value: &str, database_path: &str) -> Result<()> {
+    let storage = Storage::new(database_path).await?;
+    storage.set_run_state(key, value).await
+}
+
+pub async fn count_documents_by_source(source: &Source, database_path: &str) -> Result<i64> {
+    let storage = Storage::new(database_path).await?;
+    
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM documents WHERE source = ?")
+        .bind(source.as_str())
+        .fetch_one(&storage.pool)
+        .await?;
+    
+    Ok(count.0)
+}
+
+/// Document counts per filing type for a source, most common first
+pub async fn count_documents_by_filing_type(source: &Source, database_path: &str) -> Result<Vec<(FilingType, i64)>> {
+    let storage = Storage::new(database_path).await?;
+    
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT filing_type, COUNT(*) as doc_count FROM documents WHERE source = ? GROUP BY filing_type ORDER BY doc_count DESC, filing_type ASC"
+    )
+        .bind(source.as_str())
+        .fetch_all(&storage.pool)
+        .await?;
+    
+    Ok(rows
+        .into_iter()
+        .map(|(filing_type, count)| (FilingType::from_label(&filing_type), count))
+        .collect())
+}
+
+/// Filing types with at least one indexed document, in `FilingType::all()` order with unknown types last
+///
+/// With `source` of `None` documents from every source are considered.
+pub async fn distinct_filing_types(source: Option<&Source>, database_path: &str) -> Result<Vec<FilingType>> {
+    let labels = distinct_column_values("filing_type", source, database_path).await?;
+    
+    let mut filing_types: Vec<FilingType> = labels.iter().map(|label| FilingType::from_label(label)).collect();
+    let position = |filing_type: &FilingType| {
+        FilingType::all()
+            .iter()
+            .position(|known| known.as_str() == filing_type.as_str())
+            .unwrap_or(usize::MAX)
+    };
+    filing_types.sort_by(|a, b| position(a).cmp(&position(b)).then_with(|| a.as_str().cmp(b.as_str())));
+    Ok(filing_types)
+}
+
+/// Sources with at least one indexed document, in `Source::all()` order
+pub async fn distinct_sources(database_path: &str) -> Result<Vec<Source>> {
+    let labels = distinct_column_values("source", None, database_path).await?;
+    Ok(Source::all()
+        .iter()
+        .filter(|source| labels.iter().any(|label| label == source.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// Years with at least one indexed document, newest first
+pub async fn distinct_years(source: Option<&Source>, database_path: &str) -> Result<Vec<i32>> {
+    let storage = Storage::new(database_path).await?;
+    
+    let sql = format!(
+        "SELECT DISTINCT CAST(substr(date, 1, 4) AS INTEGER) AS year FROM documents{} ORDER BY year DESC",
+        if source.is_some() { " WHERE source = ?" } else { "" }
+    );
+    let mut query = sqlx::query_scalar::<_, i64>(&sql);
+    if let Some(source) = source {
+        query = query.bind(source.as_str());
+    }
+    
+    Ok(query.fetch_all(&storage.pool).await?.into_iter().map(|year| year as i32).collect())
+}
+
+/// Distinct values of a `documents` column, optionally restricted to one source
+async fn distinct_column_values(column: &str, source: Option<&Source>, database_path: &str) -> Result<Vec<String>> {
+    let storage = Storage::new(database_path).await?;
+    
+    let sql = format!(
+        "SELECT DISTINCT {column} FROM documents{} ORDER BY {column}",
+        if source.is_some() { " WHERE source = ?" } else { "" }
+    );
+    let mut query = sqlx::query_scalar::<_, String>(&sql);
+    if let Some(source) = source {
+        query = query.bind(source.as_str());
+    }
+    
+    Ok(query.fetch_all(&storage.pool).await?)
+}
+
+pub async fn count_edinet_static(database_path: &str) -> Result<i64> {
+    let storage = Storage::new(database_path).await?;
+    
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM edinet_static")
+        .fetch_one(&storage.pool)
+        .await?;
+    
+    Ok(count.0)
+}
+
+/// Outcome of `backfill_content_hashes`
+#[derive(Debug, Default)]
+pub struct HashBackfillReport {
+    /// Documents whose hash was computed and stored
+    pub hashed: usize,
+    /// Hashed documents that already had a different hash, i.e. whose file changed
+    pub changed: usize,
+    /// Documents whose `content_path` file could not be read, as (id, path)
+    pub missing_files: Vec<(String, PathBuf)>,
+}
+
+/// Hex-encoded SHA-256 of a file's bytes
+pub fn hash_file(path: &Path) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+    
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Compute and store the content hash of every document that has a file but no hash yet
+///
+/// With `rehash`, every document with a file is hashed again, so files replaced at the same path
+/// get their current hash. `on_progress` is called after each document with the number done and
+/// the total. Files that are gone or unreadable are skipped and reported, so the run can be
+/// repeated once they are back.
+pub async fn backfill_content_hashes(
+    database_path: &str,
+    rehash: bool,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<HashBackfillReport> {
+    let storage = Storage::new(database_path).await?;
+    let documents = if rehash {
+        let mut documents = storage.list_content_paths().await?;
+        documents.retain(|(_, path)| !path.as_os_str().is_empty());
+        documents
+    } else {
+        storage.list_unhashed_content_paths().await?
+    };
+    let mut report = HashBackfillReport::default();
+    
+    for (done, (id, path)) in documents.iter().enumerate() {
+        let file = path.clone();
+        match tokio::task::spawn_blocking(move || hash_file(&file)).await? {
+            Ok(hash) => {
+                let previous = storage.get_content_hash(id).await?;
+                if previous.is_some_and(|previous| previous != hash) {
+                    report.changed += 1;
+                }
+                storage.set_content_hash(id, &hash).await?;
+                report.hashed += 1;
+            }
+            Err(e) => {
+                warn!("Could not hash {} for {}: {}", path.display(), id, e);
+                report.missing_files.push((id.clone(), path.clone()));
+            }
+        }
+        on_progress(done + 1, documents.len());
+    }
+    
+    info!(
+        "Hashed {} documents in {} ({} changed), {} files missing",
+        report.hashed,
+        database_path,
+        report.changed,
+        report.missing_files.len()
+    );
+    Ok(report)
+}
+
+pub async fn get_content_hash(id: &str, database_path: &str) -> Result<Option<String>> {
+    let storage = Storage::new(database_path).await?;
+    storage.get_content_hash(id).await
+}
+
+/// Read the schema version of an existing database without migrating it
+pub async fn get_schema_version(database_path: &str) -> Result<i64> {
+    let database_url = format!("sqlite://{}?mode=ro", database_path);
+    let pool = SqlitePool::connect(&database_url).await?;
+    
+    let (version,): (i64,) = sqlx::query_as("PRAGMA user_version")
+        .fetch_one(&pool)
+        .await?;
+    pool.close().await;
+    
+    Ok(version)
+}
+
+/// Default backup file next to the database, e.g. `./fast10k-backup-20240620-153000.db`
+pub fn default_backup_path(database_path: &str) -> PathBuf {
+    let path = Path::new(database_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("fast10k");
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    path.with_file_name(format!("{}-backup-{}.db", stem, timestamp))
+}
+
+/// Write a consistent copy of the database to `backup_path` with `VACUUM INTO`
+///
+/// Safe while other connections are writing; fails rather than overwrite an existing file.
+pub async fn backup_database(database_path: &str, backup_path: &Path) -> Result<()> {
+    if !Path::new(database_path).exists() {
+        anyhow::bail!("Database {} does not exist", database_path);
+    }
+    if backup_path.exists() {
+        anyhow::bail!("Backup file {} already exists", backup_path.display());
+    }
+    if let Some(parent) = backup_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    
+    let storage = Storage::new(database_path).await?;
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup_path.to_string_lossy().as_ref())
+        .execute(&storage.pool)
+        .await?;
+    storage.pool.close().await;
+    
+    info!("Backed up {} to {}", database_path, backup_path.display());
+    Ok(())
+}
+
+/// Replace the database with a backup made by `backup_database`
+///
+/// The current database is backed up first, and the path of that copy returned, so a
+/// restore can itself be undone. Returns `None` when there was no database to replace.
+pub async fn restore_database(backup_path: &Path, database_path: &str) -> Result<Option<PathBuf>> {
+    if !backup_path.exists() {
+        anyhow::bail!("Backup file {} does not exist", backup_path.display());
+    }
+    
+    // Make sure the backup really is a fast10k database before touching the current one
+    let pool = SqlitePool::connect(&format!("sqlite://{}?mode=ro", backup_path.display())).await?;
+    let has_documents: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'documents'")
+            .fetch_optional(&pool)
+            .await?;
+    pool.close().await;
+    if has_documents.is_none() {
+        anyhow::bail!("{} is not a fast10k database (no documents table)", backup_path.display());
+    }
+    
+    let previous = if Path::new(database_path).exists() {
+        let previous = default_backup_path(database_path).with_extension("pre-restore.db");
+        backup_database(database_path, &previous).await?;
+        Some(previous)
+    } else {
+        None
+    };
+    
+    // Copy next to the database first so the swap itself is a rename
+    let database = Path::new(database_path);
+    let staging = database.with_extension("restoring");
+    std::fs::copy(backup_path, &staging)?;
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = PathBuf::from(format!("{}{}", database_path, suffix));
+        if sidecar.exists() {
+            std::fs::remove_file(sidecar)?;
+        }
+    }
+    std::fs::rename(&staging, database)?;
+    
+    info!("Restored {} from {}", database_path, backup_path.display());
+    Ok(previous)
+}
+
+pub async fn get_date_range_for_source(source: &Source, database_path: &str) -> Result<(String, String)> {
+    let storage = Storage::new(database_path).await?;
+    
+    let row = sqlx::query("SELECT MIN(date) as min_date, MAX(date) as max_date FROM documents WHERE source = ?")
+        .bind(source.as_str())
+        .fetch_one(&storage.pool)
+        .await?;
+    
+    let min_date: String = row.get("min_date");
+    let max_date: String = row.get("max_date");
+    
+    Ok((min_date, max_date))
+}
+
+/// Columns of EDINET's code list (EdinetcodeDlInfo.csv), as (English, Japanese) names
+const STATIC_CSV_COLUMNS: [(&str, &str); 13] = [
+    ("EDINET Code", "ＥＤＩＮＥＴコード"),
+    ("Type of Submitter", "提出者種別"),
+    ("Listed company / Unlisted company", "上場区分"),
+    ("Consolidated / NonConsolidated", "連結の有無"),
+    ("Capital stock", "資本金"),
+    ("account closing date", "決算日"),
+    ("Submitter Name", "提出者名"),
+    ("Submitter Name（alphabetic）", "提出者名（英字）"),
+    ("Submitter Name（phonetic）", "提出者名（ヨミ）"),
+    ("Province", "所在地"),
+    ("Submitter's industry", "提出者業種"),
+    ("Securities Identification Code", "証券コード"),
+    ("Submitter's Japan Corporate Number", "提出者法人番号"),
+];
+
+/// Outcome of loading the EDINET code list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StaticLoadSummary {
+    /// Listed companies stored
+    pub loaded: usize,
+    /// Rows without a securities code (unlisted submitters and funds), which are not stored
+    pub skipped: usize,
+    /// Rows that could not be parsed or had too few columns
+    pub rejected: usize,
+}
+
+impl std::fmt::Display for StaticLoadSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} loaded, {} unlisted skipped, {} rejected", self.loaded, self.skipped, self.rejected)
+    }
+}
+
+/// Replace the EDINET static data with the listed companies in an EDINET code list
+///
+/// The header row is checked first, so a wrong or reformatted file fails without touching
+/// the loaded data. Malformed rows are counted as rejected rather than aborting the load.
+pub async fn load_edinet_static_data(database_path: &str, csv_path: &str) -> Result<StaticLoadSummary> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(csv_path)?;
+    let mut records = reader.records();
+    
+    // The first row describes the download (creation date, row count); the second holds the column names
+    records.next().transpose()?;
+    let header = records
+        .next()
+        .transpose()?
+        .ok_or_else(|| anyhow::anyhow!("{} has no header row; expected the EDINET code list (EdinetcodeDlInfo.csv)", csv_path))?;
+    validate_static_header(&header)?;
+    
+    let storage = Storage::new(database_path).await?;
+    
+    // Clear existing data only once the file is known to be an EDINET code list
+    sqlx::query("DELETE FROM edinet_static").execute(&storage.pool).await?;
+    
+    let mut summary = StaticLoadSummary::default();
+    for (index, result) in records.enumerate() {
+        // Line numbers count the two header rows
+        let line = index + 3;
+        let record = match result {
+            Ok(record) if record.len() >= STATIC_CSV_COLUMNS.len() => record,
+            Ok(record) => {
+                warn!("Rejected line {} of {}: {} columns, expected {}", line, csv_path, record.len(), STATIC_CSV_COLUMNS.len());
+                summary.rejected += 1;
+                continue;
+            }
+            Err(e) => {
+                warn!("Rejected line {} of {}: {}", line, csv_path, e);
+                summary.rejected += 1;
+                continue;
+            }
+        };
+        
+        let securities_code = record.get(11).unwrap_or("").trim_matches('"');
+        // Only include records with securities codes (listed companies)
+        if securities_code.is_empty() {
+            summary.skipped += 1;
+            continue;
+        }
+        
+        sqlx::query(
+            r#"INSERT OR REPLACE INTO edinet_static 
+               (edinet_code, submitter_type, listed_status, consolidated_status, 
+                capital_stock, account_closing_date, submitter_name, submitter_name_en,
+                submitter_name_phonetic, province, industry, securities_code, corporate_number)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
+        )
+        .bind(record.get(0).unwrap_or("").trim_matches('"'))
+        .bind(record.get(1).unwrap_or("").trim_matches('"'))
+        .bind(record.get(2).unwrap_or("").trim_matches('"'))
+        .bind(record.get(3).unwrap_or("").trim_matches('"'))
+        .bind(record.get(4).unwrap_or("").trim_matches('"'))
+        .bind(record.get(5).unwrap_or("").trim_matches('"'))
+        .bind(record.get(6).unwrap_or("").trim_matches('"'))
+        .bind(record.get(7).unwrap_or("").trim_matches('"'))
+        .bind(record.get(8).unwrap_or("").trim_matches('"'))
+        .bind(record.get(9).unwrap_or("").trim_matches('"'))
+        .bind(record.get(10).unwrap_or("").trim_matches('"'))
+        .bind(securities_code)
+        .bind(record.get(12).unwrap_or("").trim_matches('"'))
+        .execute(&storage.pool)
+        .await?;
+        
+        summary.loaded += 1;
+    }
+    
+    info!("Loaded EDINET static data from {}: {}", csv_path, summary);
+    Ok(summary)
+}
+
+/// Check the column names of an EDINET code list, in the English or Japanese edition
+fn validate_static_header(header: &csv::StringRecord) -> Result<()> {
+    for (index, (english, japanese)) in STATIC_CSV_COLUMNS.iter().enumerate() {
+        let found = header.get(index).map(|name| name.trim().trim_matches('"').trim_start_matches('\u{feff}'));
+        if found != Some(*english) && found != Some(*japanese) {
+            anyhow::bail!(
+                "Unexpected EDINET code list column {}: expected '{}' ({}), found '{}'. Is this EdinetcodeDlInfo.csv?",
+                index + 1,
+                english,
+                japanese,
+                found.unwrap_or("<missing>")
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn search_edinet_static(database_path: &str, query: &str, limit: usize) -> Result<Vec<(String, String, String, String, String, String, String)>> {
+    let storage = Storage::new(database_path).await?;
+    
+    let search_pattern = format!("%{}%", query);
+    let rows = sqlx::query(
+        r#"SELECT edinet_code, submitter_name, submitter_name_en, securities_code, 
+                  industry, account_closing_date, province
+           FROM edinet_static 
+           WHERE submitter_name LIKE ? OR submitter_name_en LIKE ? OR securities_code LIKE ?
+           ORDER BY securities_code
+           LIMIT ?"#
+    )
+    .bind(&search_pattern)
+    .bind(&search_pattern)
+    .bind(&search_pattern)
+    .bind(sql_limit(limit))
+    .fetch_all(&storage.pool)
+    .await?;
+    
+    let mut results = Vec::new();
+    for row in rows {
+        results.push((
+            row.get::<String, _>("edinet_code"),
+            row.get::<String, _>("submitter_name"),
+            row.get::<String, _>("submitter_name_en"),
+            row.get::<String, _>("securities_code"),
+            row.get::<String, _>("industry"),
+            row.get::<String, _>("account_closing_date"),
+            row.get::<String, _>("province"),
+        ));
+    }
+    
+    Ok(results)
+}
+
+/// Count distinct EDINET codes on indexed documents that have no `edinet_static` entry,
+/// which usually means the static data predates the indexed filings
+pub async fn count_unmapped_edinet_codes(database_path: &str) -> Result<i64> {
+    let storage = Storage::new(database_path).await?;
+    
+    let row = sqlx::query(
+        r#"SELECT COUNT(DISTINCT json_extract(d.metadata, '$.edinet_code')) as count
+           FROM documents d
+           LEFT JOIN edinet_static s ON s.edinet_code = json_extract(d.metadata, '$.edinet_code')
+           WHERE d.source = ? AND json_extract(d.metadata, '$.edinet_code') IS NOT NULL
+             AND s.edinet_code IS NULL"#
+    )
+    .bind(Source::Edinet.as_str())
+    .fetch_one(&storage.pool)
+    .await?;
+    
+    Ok(row.get("count"))
+}
+
+/// Submitter name and securities code (empty for unlisted submitters) for an EDINET code
+pub async fn get_edinet_static_company(database_path: &str, edinet_code: &str) -> Result<Option<(String, String)>> {
+    let storage = Storage::new(database_path).await?;
+    
+    let row: Option<(String, Option<String>)> = sqlx::query_as(
+        "SELECT submitter_name, securities_code FROM edinet_static WHERE edinet_code = ?"
+    )
+        .bind(edinet_code)
+        .fetch_optional(&storage.pool)
+        .await?;
+    
+    Ok(row.map(|(name, securities_code)| (name, securities_code.unwrap_or_default())))
+}
+
+pub async fn get_edinet_code_by_securities_code(database_path: &str, securities_code: &str) -> Result<Option<String>> {
+    let storage = Storage::new(database_path).await?;
+    
+    // First try exact match
+    let row = sqlx::query("SELECT edinet_code FROM edinet_static WHERE securities_code = ?")
+        .bind(securities_code)
+        .fetch_optional(&storage.pool)
+        .await?;
+    
+    if let Some(row) = row {
+        return Ok(Some(row.get::<String, _>("edinet_code")));
+    }
+    
+    // If no exact match, try with trailing zero (e.g., 7670 -> 76700)
+    let securities_code_with_zero = format!("{}0", securities_code);
+    let row = sqlx::query("SELECT edinet_code FROM edinet_static WHERE securities_code = ?")
+        .bind(&securities_code_with_zero)
+        .fetch_optional(&storage.pool)
+        .await?;
+    
+    if let Some(row) = row {
+        return Ok(Some(row.get::<String, _>("edinet_code")));
+    }
+    
+    // If still no match, try removing trailing zero (e.g., 76700 -> 7670)
+    if securities_code.len() > 4 && securities_code.ends_with('0') {
+        let securities_code_without_zero = &securities_code[..securities_code.len()-1];
+        let row = sqlx::query("SELECT edinet_code FROM edinet_static WHERE securities_code = ?")
+            .bind(securities_code_without_zero)
+            .fetch_optional(&storage.pool)
+            .await?;
+        
+        if let Some(row) = row {
+            return Ok(Some(row.get::<String, _>("edinet_code")));
+        }
+    }
+    
+    Ok(None)
+}
+
+pub async fn get_top_companies_for_source(source: &Source, database_path: &str, limit: usize) -> Result<Vec<(String, i64)>> {
+    list_companies_for_source(source, database_path, Some(limit), 0).await
+}
+
+/// List companies for a source with their document counts, most active first.
+/// Ties are ordered by name so paging with `offset` is stable; `limit: None` returns every remaining company.
+pub async fn list_companies_for_source(
+    source: &Source,
+    database_path: &str,
+    limit: Option<usize>,
+    offset: usize,
+) -> Result<Vec<(String, i64)>> {
+    let storage = Storage::new(database_path).await?;
+    
+    // SQLite treats a negative LIMIT as unbounded
+    let limit = limit.map(|l| l as i64).unwrap_or(-1);
+    let rows = sqlx::query(
+        "SELECT company_name, COUNT(*) as doc_count FROM documents WHERE source = ? GROUP BY company_name ORDER BY doc_count DESC, company_name ASC LIMIT ? OFFSET ?"
+    )
+        .bind(source.as_str())
+        .bind(limit)
+        .bind(offset as i64)
+        .fetch_all(&storage.pool)
+        .await?;
+    
+    let mut companies = Vec::new();
+    for row in rows {
+        let company_name: String = row.get("company_name");
+        let doc_count: i64 = row.get("doc_count");
+        companies.push((company_name, doc_count));
+    }
+    
+    Ok(companies)
+}
+
+pub async fn list_all_companies_for_source(source: &Source, database_path: &str) -> Result<Vec<(String, i64)>> {
+    list_companies_for_source(source, database_path, None, 0).await
+}
+
+pub async fn count_companies_for_source(source: &Source, database_path: &str) -> Result<i64> {
+    let storage = Storage::new(database_path).await?;
+    
+    let row = sqlx::query("SELECT COUNT(DISTINCT company_name) as count FROM documents WHERE source = ?")
+        .bind(source.as_str())
+        .fetch_one(&storage.pool)
+        .await?;
+    
+    Ok(row.get("count"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_document;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_get_document_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let document = Document {
+            company_name: "トヨタ自動車株式会社".to_string(),
+            filing_type: FilingType::TenK,
+            metadata: HashMap::from([("edinet_code".to_string(), "E02144".to_string())]),
+            format: DocumentFormat::Pdf,
+            ..test_document("S100TEST")
+        };
+        insert_document(&document, db_path).await.unwrap();
+
+        let found = get_document_by_id("S100TEST", db_path).await.unwrap().unwrap();
+        assert_eq!(found.ticker, "7203");
+        assert_eq!(found.format.as_str(), "pdf");
+        assert_eq!(found.metadata.get("edinet_code").map(String::as_str), Some("E02144"));
+
+        assert!(get_document_by_id("missing", db_path).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_document_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let document = Document {
+            company_name: "トヨタ自動車株式会社".to_string(),
+            filing_type: FilingType::TenK,
+            metadata: HashMap::from([("period_end".to_string(), "2204-03-31".to_string())]),
+            ..test_document("S100META")
+        };
+        insert_document(&document, db_path).await.unwrap();
+
+        let metadata = HashMap::from([
+            ("period_end".to_string(), "2024-03-31".to_string()),
+            ("form_code".to_string(), "030000".to_string()),
+        ]);
+        assert!(update_document_metadata("S100META", &metadata, db_path).await.unwrap());
+        assert!(!update_document_metadata("S100MISSING", &metadata, db_path).await.unwrap());
+
+        let updated = get_document_by_id("S100META", db_path).await.unwrap().unwrap();
+        assert_eq!(updated.metadata, metadata);
+    }
+
+    #[tokio::test]
+    async fn test_run_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        assert!(get_run_state("edinet_index", db_path).await.unwrap().is_none());
+
+        set_run_state("edinet_index", "2024-06-20", db_path).await.unwrap();
+        set_run_state("edinet_index", "2024-06-25", db_path).await.unwrap();
+        assert_eq!(get_run_state("edinet_index", db_path).await.unwrap().as_deref(), Some("2024-06-25"));
+    }
+
+    #[tokio::test]
+    async fn test_search_all_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let filings = [
+            ("0001094517-24-000010", Source::Edgar, FilingType::TenK, (2024, 5, 10)),
+            ("S100EDINET", Source::Edinet, FilingType::AnnualSecuritiesReport, (2024, 6, 20)),
+            ("0001094517-23-000007", Source::Edgar, FilingType::TenQ, (2023, 11, 2)),
+            ("S100TDNET", Source::Tdnet, FilingType::PressRelease, (2024, 7, 1)),
+        ];
+        for (id, source, filing_type, (y, m, d)) in filings {
+            let document = Document {
+                ticker: "TM".to_string(),
+                company_name: "Toyota Motor Corporation".to_string(),
+                filing_type,
+                source,
+                date: chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+                ..test_document(id)
+            };
+            insert_document(&document, db_path).await.unwrap();
+        }
+
+        let query = SearchQuery::builder()
+            .company_name("Toyota")
+            .source(Source::Edinet)
+            .build()
+            .unwrap();
+        let documents = search_all_sources(&query, db_path, 10).await.unwrap();
+        let ids: Vec<&str> = documents.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["S100EDINET", "0001094517-24-000010", "0001094517-23-000007"]);
+
+        assert_eq!(search_all_sources(&query, db_path, 2).await.unwrap().len(), 2);
+
+        // Pages pick up where the previous one stopped
+        let second_page = search_all_sources_page(&query, db_path, 2, 2).await.unwrap();
+        let ids: Vec<&str> = second_page.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["0001094517-23-000007"]);
+        assert_eq!(count_matching_all_sources(&query, db_path).await.unwrap(), 3);
+
+        let edgar_query = SearchQuery::builder().source(Source::Edgar).build().unwrap();
+        assert_eq!(count_matching_documents(&edgar_query, db_path).await.unwrap(), 2);
+        let page = search_documents_page(&edgar_query, db_path, 1, 1).await.unwrap();
+        assert_eq!(page[0].id, "0001094517-23-000007");
+
+        // A limit of 0 returns every match
+        assert_eq!(search_documents(&edgar_query, db_path, 0).await.unwrap().len(), 2);
+        assert_eq!(search_all_sources_page(&query, db_path, 0, 1).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_databases_merges_and_deduplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let databases: Vec<String> = ["us.db", "jp.db"]
+            .iter()
+            .map(|name| dir.path().join(name).to_str().unwrap().to_string())
+            .collect();
+
+        let filings = [
+            (0, "0001094517-24-000010", (2024, 5, 10)),
+            (0, "S100SHARED", (2024, 6, 20)),
+            (1, "S100SHARED", (2024, 6, 20)),
+            (1, "S100JAPAN", (2024, 7, 1)),
+        ];
+        for (database, id, (y, m, d)) in filings {
+            let document = Document {
+                ticker: "TM".to_string(),
+                company_name: "Toyota Motor Corporation".to_string(),
+                filing_type: FilingType::TenK,
+                source: Source::Edgar,
+                date: chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+                ..test_document(id)
+            };
+            insert_document(&document, &databases[database]).await.unwrap();
+        }
+
+        let query = SearchQuery::builder().company_name("Toyota").build().unwrap();
+        let documents = search_databases_page(&query, &databases, false, 10, 0).await.unwrap();
+        let ids: Vec<&str> = documents.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["S100JAPAN", "S100SHARED", "0001094517-24-000010"]);
+
+        let second_page = search_databases_page(&query, &databases, false, 2, 2).await.unwrap();
+        assert_eq!(second_page[0].id, "0001094517-24-000010");
+        assert_eq!(search_databases_page(&query, &databases, false, 0, 1).await.unwrap().len(), 2);
+        assert_eq!(count_matching_databases(&query, &databases, false).await.unwrap(), 3);
+        assert_eq!(count_matching_databases(&query, &databases, true).await.unwrap(), 3);
+
+        let queries = ["TM", "7203"].map(|ticker| SearchQuery::builder().ticker(ticker).build().unwrap());
+        let batch = search_databases_batch(&queries, &databases, false, 2).await.unwrap();
+        let ids: Vec<&str> = batch[0].iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["S100JAPAN", "S100SHARED"]);
+        assert!(batch[1].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_companies_for_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let filings = [
+            ("S100A1", "Beta", Source::Edinet),
+            ("S100A2", "Beta", Source::Edinet),
+            ("S100A3", "Alpha", Source::Edinet),
+            ("S100A4", "Gamma", Source::Edinet),
+            ("S100A5", "Gamma", Source::Edinet),
+            ("S100A6", "Gamma", Source::Edinet),
+            ("0001094517-24-000010", "Delta", Source::Edgar),
+        ];
+        for (id, company_name, source) in filings {
+            let document = Document {
+                ticker: "TM".to_string(),
+                company_name: company_name.to_string(),
+                filing_type: FilingType::TenK,
+                source,
+                ..test_document(id)
+            };
+            insert_document(&document, db_path).await.unwrap();
+        }
+
+        assert_eq!(count_companies_for_source(&Source::Edinet, db_path).await.unwrap(), 3);
+
+        let all = list_all_companies_for_source(&Source::Edinet, db_path).await.unwrap();
+        let expected = [("Gamma".to_string(), 3), ("Beta".to_string(), 2), ("Alpha".to_string(), 1)];
+        assert_eq!(all, expected);
+
+        let page = list_companies_for_source(&Source::Edinet, db_path, Some(1), 1).await.unwrap();
+        assert_eq!(page, expected[1..2]);
+        let rest = list_companies_for_source(&Source::Edinet, db_path, None, 2).await.unwrap();
+        assert_eq!(rest, expected[2..]);
+        assert_eq!(get_top_companies_for_source(&Source::Edinet, db_path, 2).await.unwrap(), expected[..2]);
+    }
+
+    #[tokio::test]
+    async fn test_count_documents_by_filing_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let filings = [
+            ("0001094517-24-000010", Source::Edgar, FilingType::TenQ),
+            ("0001094517-24-000011", Source::Edgar, FilingType::TenK),
+            ("0001094517-24-000012", Source::Edgar, FilingType::TenQ),
+            ("S100A1", Source::Edinet, FilingType::AnnualSecuritiesReport),
+        ];
+        for (id, source, filing_type) in filings {
+            let document = Document {
+                ticker: "TM".to_string(),
+                company_name: "Toyota Motor Corporation".to_string(),
+                filing_type,
+                source,
+                ..test_document(id)
+            };
+            insert_document(&document, db_path).await.unwrap();
+        }
+
+        let edgar = count_documents_by_filing_type(&Source::Edgar, db_path).await.unwrap();
+        let counts: Vec<(&str, i64)> = edgar
+            .iter()
+            .map(|(filing_type, count)| (filing_type.as_str(), *count))
+            .collect();
+        assert_eq!(counts, [("10-Q", 2), ("10-K", 1)]);
+        assert!(count_documents_by_filing_type(&Source::Tdnet, db_path).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_distinct_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let filings = [
+            ("0001094517-23-000010", Source::Edgar, FilingType::TenQ, 2023),
+            ("0001094517-24-000011", Source::Edgar, FilingType::TenK, 2024),
+            ("S100A1", Source::Edinet, FilingType::AnnualSecuritiesReport, 2022),
+            ("S100A2", Source::Edinet, FilingType::Other("臨時報告書".to_string()), 2024),
+        ];
+        for (id, source, filing_type, year) in filings {
+            let document = Document {
+                ticker: "TM".to_string(),
+                company_name: "Toyota Motor Corporation".to_string(),
+                filing_type,
+                source,
+                date: chrono::NaiveDate::from_ymd_opt(year, 6, 20).unwrap(),
+                ..test_document(id)
+            };
+            insert_document(&document, db_path).await.unwrap();
+        }
+
+        let labels = |types: Vec<FilingType>| types.iter().map(|t| t.as_str().to_string()).collect::<Vec<_>>();
+        let edgar_types = distinct_filing_types(Some(&Source::Edgar), db_path).await.unwrap();
+        assert_eq!(labels(edgar_types), ["10-K", "10-Q"]);
+        let all_types = distinct_filing_types(None, db_path).await.unwrap();
+        assert_eq!(labels(all_types).last().unwrap(), "臨時報告書");
+
+        let sources = distinct_sources(db_path).await.unwrap();
+        assert_eq!(sources, [Source::Edgar, Source::Edinet]);
+        assert_eq!(distinct_years(None, db_path).await.unwrap(), [2024, 2023, 2022]);
+        assert_eq!(distinct_years(Some(&Source::Edinet), db_path).await.unwrap(), [2024, 2022]);
+    }
+
+    #[tokio::test]
+    async fn test_count_unmapped_edinet_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let filings = [("S100B1", Some("E02144")), ("S100B2", Some("E99999")), ("S100B3", Some("E99999")), ("S100B4", None)];
+        for (id, edinet_code) in filings {
+            let document = Document {
+                company_name: "トヨタ自動車株式会社".to_string(),
+                metadata: edinet_code
+                    .map(|code| HashMap::from([("edinet_code".to_string(), code.to_string())]))
+                    .unwrap_or_default(),
+                ..test_document(id)
+            };
+            insert_document(&document, db_path).await.unwrap();
+        }
+        assert_eq!(count_unmapped_edinet_codes(db_path).await.unwrap(), 2);
+
+        let storage = Storage::new(db_path).await.unwrap();
+        sqlx::query("INSERT INTO edinet_static (edinet_code) VALUES ('E02144')")
+            .execute(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(count_unmapped_edinet_codes(db_path).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_edinet_static_data_validates_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+        let header = STATIC_CSV_COLUMNS.iter().map(|(english, _)| format!("\"{}\"", english)).collect::<Vec<_>>().join(",");
+        let csv_path = dir.path().join("EdinetcodeDlInfo.csv");
+        std::fs::write(&csv_path, format!(
+            "Date of download data creation,As Of 2025.07.23,Number of data,3\n{}\n{}\n{}\n{}\n",
+            header,
+            r#""E02144","内国法人・組合","Listed company","Consolidated","635401","3.31","トヨタ自動車株式会社","TOYOTA MOTOR CORPORATION","トヨタジドウシャ","豊田市","Transportation Equipment","72030","1180301018771""#,
+            r#""E99999","内国法人・組合","Unlisted company","NonConsolidated","10","3.31","非上場株式会社","UNLISTED","ヒジョウジョウ","東京都","Services","","""#,
+            r#""E00001","truncated""#,
+        )).unwrap();
+
+        let summary = load_edinet_static_data(db_path, csv_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(summary, StaticLoadSummary { loaded: 1, skipped: 1, rejected: 1 });
+        assert_eq!(count_edinet_static(db_path).await.unwrap(), 1);
+
+        // A different file is refused before the loaded data is cleared
+        let wrong_path = dir.path().join("tickers.csv");
+        std::fs::write(&wrong_path, "title\n\"EDINET Code\",\"Ticker\"\n\"E02144\",\"7203\"\n").unwrap();
+        let err = load_edinet_static_data(db_path, wrong_path.to_str().unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("column 2: expected 'Type of Submitter'"), "{}", err);
+        assert_eq!(count_edinet_static(db_path).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_schema_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        Storage::new(db_path).await.unwrap();
+        assert_eq!(get_schema_version(db_path).await.unwrap(), SCHEMA_VERSION);
+        assert_eq!(count_edinet_static(db_path).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("fast10k.db");
+        let db_path = db_path.to_str().unwrap();
+        insert_document(&test_document("S100KEEP"), db_path).await.unwrap();
+
+        let backup = dir.path().join("backups").join("snapshot.db");
+        backup_database(db_path, &backup).await.unwrap();
+        assert!(backup_database(db_path, &backup).await.is_err(), "existing backups are not overwritten");
+
+        insert_document(&test_document("S100LOSE"), db_path).await.unwrap();
+        let previous = restore_database(&backup, db_path).await.unwrap().unwrap();
+
+        assert!(get_document_by_id("S100KEEP", db_path).await.unwrap().is_some());
+        assert!(get_document_by_id("S100LOSE", db_path).await.unwrap().is_none());
+        assert!(get_document_by_id("S100LOSE", previous.to_str().unwrap()).await.unwrap().is_some());
+
+        let not_a_database = dir.path().join("notes.db");
+        std::fs::write(&not_a_database, "").unwrap();
+        assert!(restore_database(&not_a_database, db_path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_latest_only_hides_amended_filings() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let document = |id: &str, parent: Option<&str>, day: u32| Document {
+            date: chrono::NaiveDate::from_ymd_opt(2024, 6, day).unwrap(),
+            metadata: parent
+                .map(|parent| HashMap::from([("parent_doc_id".to_string(), parent.to_string())]))
+                .unwrap_or_default(),
+            ..test_document(id)
+        };
+        for doc in [
+            document("S100ORIG", None, 20),
+            document("S100FIX1", Some("S100ORIG"), 25),
+            document("S100FIX2", Some("S100ORIG"), 28),
+            document("S100SOLO", None, 21),
+        ] {
+            insert_document(&doc, db_path).await.unwrap();
+        }
+
+        let ids = |documents: Vec<Document>| documents.into_iter().map(|d| d.id).collect::<Vec<_>>();
+        let all = SearchQuery::builder().ticker("7203").build().unwrap();
+        assert_eq!(search_documents(&all, db_path, 10).await.unwrap().len(), 4);
+
+        let latest = SearchQuery::builder().ticker("7203").latest_only(true).build().unwrap();
+        assert_eq!(ids(search_documents(&latest, db_path, 10).await.unwrap()), ["S100FIX2", "S100SOLO"]);
+        assert_eq!(count_matching_documents(&latest, db_path).await.unwrap(), 2);
+
+        assert_eq!(ids(get_amendments("S100ORIG", db_path).await.unwrap()), ["S100FIX1", "S100FIX2"]);
+    }
+
+    #[tokio::test]
+    async fn test_withdrawn_filings_are_hidden_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let document = |id: &str, withdrawal_status: &str| Document {
+            metadata: HashMap::from([("withdrawal_status".to_string(), withdrawal_status.to_string())]),
+            ..test_document(id)
+        };
+        for doc in [document("S100KEEP", "0"), document("S100GONE", "2"), document("S100NOTE", "1")] {
+            insert_document(&doc, db_path).await.unwrap();
+        }
+
+        let ids = |documents: Vec<Document>| documents.into_iter().map(|d| d.id).collect::<Vec<_>>();
+        let default = SearchQuery::builder().ticker("7203").build().unwrap();
+        assert_eq!(ids(search_documents(&default, db_path, 10).await.unwrap()), ["S100KEEP"]);
+        assert_eq!(count_matching_documents(&default, db_path).await.unwrap(), 1);
+
+        let all = SearchQuery::builder().ticker("7203").include_withdrawn(true).build().unwrap();
+        assert_eq!(count_matching_documents(&all, db_path).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_stored_and_searchable() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        for doc in [test_document("S100AAAA"), test_document("S100BBBB")] {
+            insert_document(&doc, db_path).await.unwrap();
+        }
+
+        let tags = ["reviewed", " M&A ", "", "reviewed"].map(String::from);
+        set_tags("S100AAAA", &tags, db_path).await.unwrap();
+        assert_eq!(get_tags("S100AAAA", db_path).await.unwrap(), ["M&A", "reviewed"]);
+        assert_eq!(get_document_by_id("S100AAAA", db_path).await.unwrap().unwrap().tags, ["M&A", "reviewed"]);
+        assert!(get_document_by_id("S100BBBB", db_path).await.unwrap().unwrap().tags.is_empty());
+
+        let tagged = SearchQuery::builder().tag("reviewed").build().unwrap();
+        let found = search_documents(&tagged, db_path, 10).await.unwrap();
+        assert_eq!(found.iter().map(|d| d.id.as_str()).collect::<Vec<_>>(), ["S100AAAA"]);
+
+        set_tags("S100AAAA", &[], db_path).await.unwrap();
+        assert_eq!(count_matching_documents(&tagged, db_path).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_migration_marks_edinet_html_as_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        // A version 0 database, from when EDINET PDF-only filings were indexed as "html"
+        std::fs::File::create(db_path).unwrap();
+        let pool = SqlitePool::connect(&format!("sqlite://{}", db_path)).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE documents (id TEXT PRIMARY KEY, ticker TEXT NOT NULL, company_name TEXT NOT NULL, \
+             filing_type TEXT NOT NULL, source TEXT NOT NULL, date TEXT NOT NULL, content_path TEXT NOT NULL, \
+             metadata TEXT NOT NULL, content_preview TEXT, format TEXT); \
+             INSERT INTO documents VALUES ('S100HTML', '7203', 'Toyota', 'Annual Securities Report', 'EDINET', \
+             '2024-06-25', '', '{}', '', 'html'); \
+             INSERT INTO documents VALUES ('0000320193-24-000123', 'AAPL', 'Apple', '10-K', 'EDGAR', \
+             '2024-11-01', '', '{}', '', 'html');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let edinet = get_document_by_id("S100HTML", db_path).await.unwrap().unwrap();
+        assert_eq!(edinet.format.as_str(), "pdf");
+        let edgar = get_document_by_id("0000320193-24-000123", db_path).await.unwrap().unwrap();
+        assert_eq!(edgar.format.as_str(), "html");
+        assert_eq!(get_schema_version(db_path).await.unwrap(), SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_migration_backfills_parent_doc_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        // A version 1 database, from before amendments were tracked in their own column
+        std::fs::File::create(db_path).unwrap();
+        let pool = SqlitePool::connect(&format!("sqlite://{}", db_path)).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE documents (id TEXT PRIMARY KEY, ticker TEXT NOT NULL, company_name TEXT NOT NULL, \
+             filing_type TEXT NOT NULL, source TEXT NOT NULL, date TEXT NOT NULL, content_path TEXT NOT NULL, \
+             metadata TEXT NOT NULL, content_preview TEXT, format TEXT); \
+             INSERT INTO documents VALUES ('S100FIX1', '7203', 'Toyota', 'Annual Securities Report', 'EDINET', \
+             '2024-06-25', '', '{\"parent_doc_id\":\"S100ORIG\"}', '', 'complete'); \
+             PRAGMA user_version = 1;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let amendments = get_amendments("S100ORIG", db_path).await.unwrap();
+        assert_eq!(amendments.len(), 1);
+        assert_eq!(get_schema_version(db_path).await.unwrap(), SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_search_stored_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        for id in ["S100AAAA", "S100BBBB"] {
+            insert_document(&test_document(id), db_path).await.unwrap();
+        }
+        store_document_content("S100AAAA", "Risks from the Supply Chain and 水素 engines", db_path).await.unwrap();
+        store_document_content("S100BBBB", "Dividend \"policy\" unchanged", db_path).await.unwrap();
+
+        let ids = |text: &str| {
+            let query = SearchQuery::builder().text_query(text).build().unwrap();
+            async move {
+                search_documents(&query, db_path, 10).await.unwrap().into_iter().map(|d| d.id).collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(ids("supply chain").await, ["S100AAAA"]);
+        assert_eq!(ids("\"policy\"").await, ["S100BBBB"]);
+        assert_eq!(ids("水素 e").await, ["S100AAAA"]);
+        // Too short for the trigram index, so only names and previews are matched
+        assert!(ids("水素").await.is_empty());
+        assert_eq!(ids("To").await, ["S100AAAA", "S100BBBB"]);
+
+        // Replaced text is no longer found
+        store_document_content("S100AAAA", "Capital expenditure plans", db_path).await.unwrap();
+        assert!(ids("supply chain").await.is_empty());
+        assert_eq!(ids("expenditure").await, ["S100AAAA"]);
+    }
+
+    #[tokio::test]
+    async fn test_migration_indexes_stored_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        insert_document(&test_document("S100FULL"), db_path).await.unwrap();
+        store_document_content("S100FULL", "水素エンジンの開発", db_path).await.unwrap();
+
+        // A version 3 database, from before stored text had a full-text index
+        let pool = SqlitePool::connect(&format!("sqlite://{}", db_path)).await.unwrap();
+        sqlx::query("DELETE FROM document_content_fts; PRAGMA user_version = 3;")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let query = SearchQuery::builder().text_query("水素エンジン").build().unwrap();
+        assert_eq!(count_matching_documents(&query, db_path).await.unwrap(), 1);
+        assert_eq!(get_schema_version(db_path).await.unwrap(), SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_content_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let file = dir.path().join("S100HASH.zip");
+        std::fs::write(&file, "hello").unwrap();
+        for (id, content_path) in [("S100HASH", file), ("S100GONE", dir.path().join("S100GONE.zip")), ("S100NONE", PathBuf::new())] {
+            insert_document(&Document { content_path, ..test_document(id) }, db_path).await.unwrap();
+        }
+
+        let mut calls = Vec::new();
+        let report = backfill_content_hashes(db_path, false, |done, total| calls.push((done, total))).await.unwrap();
+        assert_eq!(report.hashed, 1);
+        assert_eq!(report.missing_files.len(), 1);
+        assert_eq!(report.missing_files[0].0, "S100GONE");
+        assert_eq!(calls, [(1, 2), (2, 2)]);
+        assert_eq!(
+            get_content_hash("S100HASH", db_path).await.unwrap().as_deref(),
+            Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+        );
+
+        // Hashed documents are not read again
+        let report = backfill_content_hashes(db_path, false, |_, _| {}).await.unwrap();
+        assert_eq!((report.hashed, report.missing_files.len()), (0, 1));
+
+        // A file replaced at the same path keeps its old hash until rehashed
+        std::fs::write(dir.path().join("S100HASH.zip"), "hello again").unwrap();
+        let report = backfill_content_hashes(db_path, false, |_, _| {}).await.unwrap();
+        assert_eq!(report.hashed, 0);
+        let report = backfill_content_hashes(db_path, true, |_, _| {}).await.unwrap();
+        assert_eq!((report.hashed, report.changed, report.missing_files.len()), (1, 1, 1));
+        assert_eq!(
+            get_content_hash("S100HASH", db_path).await.unwrap().as_deref(),
+            Some("3908c567feda72bc0dbdb2dff040fe0d3470dcd51b942374378a476930dbf6b3")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reinsert_keeps_content_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut document = Document { content_path: dir.path().join("S100HASH.zip"), ..test_document("S100HASH") };
+        insert_document(&document, db_path).await.unwrap();
+        Storage::new(db_path).await.unwrap().set_content_hash("S100HASH", "abc123").await.unwrap();
+
+        document.company_name = "Toyota Motor Corporation".to_string();
+        insert_document(&document, db_path).await.unwrap();
+        assert_eq!(get_content_hash("S100HASH", db_path).await.unwrap().as_deref(), Some("abc123"));
+        let stored = get_document_by_id("S100HASH", db_path).await.unwrap().unwrap();
+        assert_eq!(stored.company_name, "Toyota Motor Corporation");
+
+        // A different file needs hashing again
+        document.content_path = dir.path().join("S100HASH-v2.zip");
+        insert_document(&document, db_path).await.unwrap();
+        assert_eq!(get_content_hash("S100HASH", db_path).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! Colors for CLI output
+//!
+//! Colors are off unless enabled at startup with [`set_color_enabled`], which the CLI does when
+//! stdout is a terminal, `NO_COLOR` is unset and `--no-color` was not passed. Piped output and
+//! CI logs therefore stay free of escape codes.
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+const GREEN: &str = "32";
+const RED: &str = "31";
+const YELLOW: &str = "33";
+const DIM: &str = "2";
+
+/// Whether output to `stream` should be colored, following `--no-color` and the `NO_COLOR` convention
+pub fn should_color(no_color: bool, stream: &impl IsTerminal) -> bool {
+    !no_color && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && stream.is_terminal()
+}
+
+/// Turn colored CLI output on or off for the rest of the process
+pub fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn color_enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Successful steps, e.g. a passed check or a finished download
+pub fn success(text: impl Display) -> String {
+    paint(text, GREEN, color_enabled())
+}
+
+/// Failed steps
+pub fn failure(text: impl Display) -> String {
+    paint(text, RED, color_enabled())
+}
+
+/// Warnings that do not fail the command
+pub fn warning(text: impl Display) -> String {
+    paint(text, YELLOW, color_enabled())
+}
+
+/// Secondary information such as skipped items
+pub fn dim(text: impl Display) -> String {
+    paint(text, DIM, color_enabled())
+}
+
+fn paint(text: impl Display, code: &str, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_only_when_enabled() {
+        assert_eq!(paint("ok", GREEN, true), "\x1b[32mok\x1b[0m");
+        assert_eq!(paint("ok", GREEN, false), "ok");
+        assert_eq!(success("ok"), "ok");
+    }
+}
@@ -0,0 +1,164 @@
+//! Shared helpers for tests that run the downloaders and indexer against a mock HTTP server
+//!
+//! Tests start a `wiremock::MockServer`, mount canned EDGAR/EDINET responses on it and
+//! build a [`Config`] with [`mock_config`] so every API call goes to the mock instead of the network.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+use crate::config::{ApiEndpoints, Config, RateLimits};
+use crate::models::{Document, DocumentFormat, FilingType, Source};
+
+/// API key the mock EDINET endpoints expect in `Ocp-Apim-Subscription-Key`
+pub const EDINET_TEST_API_KEY: &str = "test-api-key";
+
+/// Configuration pointing every API at `server`, with the database under `temp_dir` and no rate-limit delays
+pub fn mock_config(server: &MockServer, temp_dir: &Path) -> Config {
+    let mut config = Config::from_env().expect("default config");
+    config.database_path = temp_dir.join("test.db");
+    config.download_dir = temp_dir.join("downloads");
+    config.edinet_api_key = Some(EDINET_TEST_API_KEY.to_string());
+    config.rate_limits = RateLimits {
+        edinet_api_delay_ms: 0,
+        edinet_download_delay_ms: 0,
+        edgar_api_delay_ms: 0,
+    };
+    config.http.proxy_url = None;
+    config.http.ca_cert_path = None;
+    config.http.contact_email = Some("tests@example.com".to_string());
+    config.endpoints = ApiEndpoints::all(&server.uri());
+    config
+}
+
+/// EDGAR `company_tickers.json` listing the given (CIK, ticker, title) entries
+pub fn edgar_company_tickers(companies: &[(u64, &str, &str)]) -> Value {
+    let entries: serde_json::Map<String, Value> = companies
+        .iter()
+        .enumerate()
+        .map(|(i, (cik, ticker, title))| {
+            (i.to_string(), json!({ "cik_str": cik, "ticker": ticker, "title": title }))
+        })
+        .collect();
+    Value::Object(entries)
+}
+
+/// EDGAR submissions JSON with recent filings given as (accession number, form, filing date)
+pub fn edgar_submissions(cik: &str, name: &str, filings: &[(&str, &str, &str)]) -> Value {
+    json!({
+        "cik": cik,
+        "entityType": "operating",
+        "sic": "3571",
+        "sicDescription": "Electronic Computers",
+        "insiderTransactionForOwnerExists": 0,
+        "insiderTransactionForIssuerExists": 0,
+        "name": name,
+        "tickers": [],
+        "exchanges": [],
+        "ein": "",
+        "description": "",
+        "website": "",
+        "investorWebsite": "",
+        "category": "",
+        "fiscalYearEnd": "0928",
+        "stateOfIncorporation": "CA",
+        "stateOfIncorporationDescription": "CA",
+        "addresses": {},
+        "phoneNumber": null,
+        "flags": null,
+        "formerNames": [],
+        "filings": {
+            "recent": edgar_filings(filings),
+            "files": [],
+        },
+    })
+}
+
+/// EDGAR filings as parallel arrays, the shape of `filings.recent` and of older submissions files
+pub fn edgar_filings(filings: &[(&str, &str, &str)]) -> Value {
+    let accession_numbers: Vec<&str> = filings.iter().map(|f| f.0).collect();
+    let forms: Vec<&str> = filings.iter().map(|f| f.1).collect();
+    let dates: Vec<&str> = filings.iter().map(|f| f.2).collect();
+    let blank = vec![""; filings.len()];
+    json!({
+        "accessionNumber": accession_numbers,
+        "filingDate": dates,
+        "reportDate": dates,
+        "acceptanceDateTime": blank,
+        "act": vec!["34"; filings.len()],
+        "form": forms,
+        "fileNumber": blank,
+        "filmNumber": blank,
+        "items": blank,
+        "size": vec![1024; filings.len()],
+        "isXBRL": vec![1; filings.len()],
+        "isInlineXBRL": vec![1; filings.len()],
+        "primaryDocument": vec!["primary.htm"; filings.len()],
+        "primaryDocDescription": forms,
+    })
+}
+
+/// EDINET `documents.json` response for `date` containing `results`
+pub fn edinet_documents(date: &str, results: Vec<Value>) -> Value {
+    json!({
+        "metadata": {
+            "title": "提出された書類を把握するためのAPI",
+            "parameter": { "date": date, "type": "2" },
+            "resultset": { "count": results.len() },
+        },
+        "results": results,
+    })
+}
+
+/// A corporate EDINET document entry as returned by `documents.json`
+pub fn edinet_document(doc_id: &str, sec_code: &str, filer_name: &str, submit_date_time: &str) -> Value {
+    json!({
+        "seqNumber": 1,
+        "docID": doc_id,
+        "edinetCode": "E02144",
+        "secCode": sec_code,
+        "filerName": filer_name,
+        "formCode": "030000",
+        "docTypeCode": "120",
+        "submitDateTime": submit_date_time,
+        "docDescription": "有価証券報告書",
+        "xbrlFlag": "1",
+        "pdfFlag": "1",
+    })
+}
+
+/// EDINET error body, as sent with non-200 statuses
+pub fn edinet_error(status_code: u16, message: &str) -> Value {
+    json!({ "statusCode": status_code, "message": message })
+}
+
+/// A ZIP archive of (file name, content) entries, like the EDINET document download payload
+pub fn zip_payload(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (file_name, content) in entries {
+        writer
+            .start_file(*file_name, zip::write::FileOptions::default())
+            .expect("start zip entry");
+        writer.write_all(content.as_bytes()).expect("write zip entry");
+    }
+    writer.finish().expect("finish zip").into_inner()
+}
+
+/// An indexed Toyota annual securities report with id `id`; override fields with `..test_document(id)`
+pub fn test_document(id: &str) -> Document {
+    Document {
+        id: id.to_string(),
+        ticker: "7203".to_string(),
+        company_name: "Toyota".to_string(),
+        filing_type: FilingType::AnnualSecuritiesReport,
+        source: Source::Edinet,
+        date: chrono::NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+        content_path: PathBuf::new(),
+        metadata: HashMap::new(),
+        format: DocumentFormat::Complete,
+        tags: Vec::new(),
+    }
+}
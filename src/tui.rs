@@ -14,6 +14,9 @@ use ratatui::{
 };
 use std::io;
 use tracing::info;
+use fast10k::config::Config;
+use fast10k::edinet_tui::ui::{draw_too_small, is_too_small};
+use fast10k::locale::DateFormat;
 use crate::models::{SearchQuery, Document};
 use crate::storage;
 
@@ -32,6 +35,7 @@ struct App {
     list_state: ListState,
     search_query: String,
     database_path: String,
+    date_format: DateFormat,
 }
 
 impl App {
@@ -46,6 +50,7 @@ impl App {
             list_state,
             search_query: String::new(),
             database_path: database_path.to_string(),
+            date_format: Config::from_env().map(|config| config.display.date_format()).unwrap_or_default(),
         }
     }
     
@@ -100,15 +105,9 @@ impl App {
     }
     
     async fn search_documents(&mut self) -> Result<()> {
-        let query = SearchQuery {
-            ticker: if self.search_query.is_empty() { None } else { Some(self.search_query.clone()) },
-            company_name: None,
-            filing_type: None,
-            source: None,
-            date_from: None,
-            date_to: None,
-            text_query: None,
-        };
+        let query = SearchQuery::builder()
+            .ticker(self.search_query.as_str())
+            .build()?;
         
         self.documents = storage::search_documents(&query, &self.database_path, 100).await?;
         
@@ -193,6 +192,10 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
 
 fn ui(f: &mut Frame, app: &App) {
     let size = f.size();
+    if is_too_small(size) {
+        draw_too_small(f, size);
+        return;
+    }
     
     // Create layout
     let chunks = Layout::default()
@@ -281,7 +284,7 @@ fn render_document_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect, t
                 Cell::from(doc.company_name.clone()),
                 Cell::from(doc.filing_type.as_str()).style(Style::default().fg(Color::Yellow)),
                 Cell::from(doc.source.as_str()).style(Style::default().fg(Color::Green)),
-                Cell::from(doc.date.to_string()),
+                Cell::from(app.date_format.format(doc.date)),
             ]).style(style)
         })
         .collect();